@@ -10,8 +10,25 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .file_descriptor_set_path(out_dir.join("manufacturing_descriptor.bin"))
         .compile_protos_with_config(
             config,
-            &["item.proto"],
-            &["../erponomics/manufacturing/v1", "../googleapis"],
+            &[
+                "item.proto",
+                "bill_of_materials/bill_of_materials_command_service.proto",
+                "bill_of_materials/bill_of_materials_query_service.proto",
+                "location/location_command_service.proto",
+                "location/location_query_service.proto",
+                "production_order/production_order_command_service.proto",
+                "production_order/production_order_query_service.proto",
+                "routing/routing_command_service.proto",
+                "routing/routing_query_service.proto",
+                "stock_movement/stock_movement_command_service.proto",
+                "stock_movement/stock_movement_query_service.proto",
+                "traceability/traceability_command_service.proto",
+                "traceability/traceability_query_service.proto",
+                "unit_of_measure/unit_of_measure_query_service.proto",
+                // Not used by the services, but lets the gateway render error details as JSON.
+                "google/rpc/error_details.proto",
+            ],
+            &["../erponomics/manufacturing/v1", "..", "../googleapis"],
         )?;
 
     Ok(())
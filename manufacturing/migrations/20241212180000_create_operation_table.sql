@@ -0,0 +1,14 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS operation
+(
+    id              TEXT        PRIMARY KEY NOT NULL,
+    metadata_type   TEXT                    NOT NULL,
+    metadata        BLOB                    NOT NULL,
+    done            INTEGER                 NOT NULL,
+    response_type   TEXT,
+    response        BLOB,
+    error_code      INTEGER,
+    error_message   TEXT,
+    create_time     TEXT                    NOT NULL,
+    update_time     TEXT                    NOT NULL
+) STRICT;
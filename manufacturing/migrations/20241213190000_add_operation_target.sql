@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE operation ADD COLUMN target TEXT NOT NULL DEFAULT '';
+
+CREATE INDEX IF NOT EXISTS operation_done_idx ON operation (done, create_time);
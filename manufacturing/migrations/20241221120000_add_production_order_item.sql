@@ -0,0 +1,6 @@
+-- Add migration script here
+ALTER TABLE production_order ADD COLUMN item_id TEXT REFERENCES item (id) ON DELETE SET NULL;
+
+ALTER TABLE production_order ADD COLUMN quantity REAL NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS production_order_item_idx ON production_order (item_id, state);
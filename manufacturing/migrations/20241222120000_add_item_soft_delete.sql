@@ -0,0 +1,5 @@
+-- Add migration script here
+ALTER TABLE item ADD COLUMN delete_time TEXT;
+ALTER TABLE item ADD COLUMN expire_time TEXT;
+
+CREATE INDEX IF NOT EXISTS item_state_idx ON item (state);
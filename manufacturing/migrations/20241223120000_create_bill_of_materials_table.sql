@@ -0,0 +1,35 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS bill_of_materials
+(
+    item_id         TEXT        NOT NULL REFERENCES item (id) ON DELETE CASCADE,
+    id              TEXT        NOT NULL,
+    version         INTEGER     NOT NULL,
+    display_name    TEXT        NOT NULL,
+    description     TEXT        NOT NULL,
+    state           INTEGER     NOT NULL,
+    etag            TEXT        NOT NULL,
+    uid             TEXT        NOT NULL,
+    create_time     TEXT        NOT NULL,
+    update_time     TEXT        NOT NULL,
+    PRIMARY KEY (item_id, id),
+    UNIQUE (item_id, version)
+) STRICT;
+
+CREATE INDEX IF NOT EXISTS bill_of_materials_state_idx ON bill_of_materials (item_id, state);
+
+CREATE TABLE IF NOT EXISTS bill_of_materials_component
+(
+    item_id                 TEXT        NOT NULL,
+    bill_of_materials_id    TEXT        NOT NULL,
+    position                INTEGER     NOT NULL,
+    component_item_id       TEXT        NOT NULL REFERENCES item (id),
+    quantity                REAL        NOT NULL,
+    unit_of_measure         TEXT        NOT NULL,
+    scrap_factor            REAL        NOT NULL,
+    PRIMARY KEY (item_id, bill_of_materials_id, position),
+    FOREIGN KEY (item_id, bill_of_materials_id)
+        REFERENCES bill_of_materials (item_id, id) ON DELETE CASCADE
+) STRICT;
+
+CREATE INDEX IF NOT EXISTS bill_of_materials_component_item_idx
+    ON bill_of_materials_component (component_item_id);
@@ -0,0 +1,36 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS routing
+(
+    item_id         TEXT        NOT NULL PRIMARY KEY REFERENCES item (id) ON DELETE CASCADE,
+    state           INTEGER     NOT NULL,
+    etag            TEXT        NOT NULL,
+    uid             TEXT        NOT NULL,
+    create_time     TEXT        NOT NULL,
+    update_time     TEXT        NOT NULL
+) STRICT;
+
+CREATE TABLE IF NOT EXISTS routing_operation
+(
+    item_id         TEXT        NOT NULL REFERENCES routing (item_id) ON DELETE CASCADE,
+    position        INTEGER     NOT NULL,
+    work_center     TEXT        NOT NULL,
+    setup_time_ms   INTEGER     NOT NULL,
+    run_time_ms     INTEGER     NOT NULL,
+    PRIMARY KEY (item_id, position)
+) STRICT;
+
+CREATE TABLE IF NOT EXISTS production_order_operation
+(
+    production_order_id TEXT        NOT NULL REFERENCES production_order (id) ON DELETE CASCADE,
+    position            INTEGER     NOT NULL,
+    id                  TEXT        NOT NULL,
+    work_center         TEXT        NOT NULL,
+    setup_time_ms       INTEGER     NOT NULL,
+    run_time_ms         INTEGER     NOT NULL,
+    state               INTEGER     NOT NULL,
+    reported_quantity   REAL        NOT NULL,
+    start_time          TEXT        NULL,
+    complete_time       TEXT        NULL,
+    PRIMARY KEY (production_order_id, position),
+    UNIQUE (production_order_id, id)
+) STRICT;
@@ -0,0 +1,37 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS unit_of_measure
+(
+    id              TEXT        PRIMARY KEY NOT NULL,
+    display_name    TEXT                    NOT NULL,
+    dimension       INTEGER                 NOT NULL,
+    factor          TEXT                    NOT NULL
+) STRICT;
+
+-- Factors are exact decimals relative to the SI unit of each dimension.
+INSERT OR IGNORE INTO unit_of_measure (id, display_name, dimension, factor) VALUES
+    ('pcs', 'Pieces', 1, '1'),
+    ('kg', 'Kilogram', 2, '1'),
+    ('g', 'Gram', 2, '0.001'),
+    ('mg', 'Milligram', 2, '0.000001'),
+    ('t', 'Tonne', 2, '1000'),
+    ('lb', 'Pound', 2, '0.45359237'),
+    ('oz', 'Ounce', 2, '0.028349523125'),
+    ('m', 'Metre', 3, '1'),
+    ('mm', 'Millimetre', 3, '0.001'),
+    ('cm', 'Centimetre', 3, '0.01'),
+    ('km', 'Kilometre', 3, '1000'),
+    ('in', 'Inch', 3, '0.0254'),
+    ('ft', 'Foot', 3, '0.3048'),
+    ('m2', 'Square metre', 4, '1'),
+    ('cm2', 'Square centimetre', 4, '0.0001'),
+    ('m3', 'Cubic metre', 5, '1'),
+    ('l', 'Litre', 5, '0.001'),
+    ('ml', 'Millilitre', 5, '0.000001'),
+    ('s', 'Second', 6, '1'),
+    ('min', 'Minute', 6, '60'),
+    ('h', 'Hour', 6, '3600');
+
+-- SQLite refuses a foreign key on an added column with a default, so the command validates the unit.
+ALTER TABLE item ADD COLUMN base_uom TEXT NOT NULL DEFAULT 'pcs';
+
+CREATE INDEX IF NOT EXISTS item_base_uom_idx ON item (base_uom);
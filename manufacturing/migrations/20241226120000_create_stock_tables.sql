@@ -0,0 +1,49 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS location
+(
+    id                      TEXT        PRIMARY KEY NOT NULL,
+    display_name            TEXT                    NOT NULL,
+    allow_negative_stock    INTEGER                 NOT NULL,
+    etag                    TEXT                    NOT NULL,
+    uid                     TEXT                    NOT NULL,
+    create_time             TEXT                    NOT NULL,
+    update_time             TEXT                    NOT NULL
+) STRICT;
+
+CREATE TABLE IF NOT EXISTS stock_movement
+(
+    id                  TEXT        PRIMARY KEY NOT NULL,
+    kind                INTEGER     NOT NULL,
+    item_id             TEXT        NOT NULL REFERENCES item (id),
+    quantity            TEXT        NOT NULL,
+    source_location_id  TEXT        REFERENCES location (id),
+    target_location_id  TEXT        REFERENCES location (id),
+    reason              TEXT        NOT NULL,
+    uid                 TEXT        NOT NULL,
+    create_time         TEXT        NOT NULL
+) STRICT;
+
+CREATE INDEX IF NOT EXISTS stock_movement_item_idx ON stock_movement (item_id);
+
+-- The ledger is append-only, corrections are posted as further movements.
+CREATE TRIGGER IF NOT EXISTS stock_movement_no_update
+BEFORE UPDATE ON stock_movement
+BEGIN
+    SELECT RAISE(ABORT, 'stock movements are append-only');
+END;
+
+CREATE TRIGGER IF NOT EXISTS stock_movement_no_delete
+BEFORE DELETE ON stock_movement
+BEGIN
+    SELECT RAISE(ABORT, 'stock movements are append-only');
+END;
+
+-- On-hand quantities, kept in step with the ledger by the transaction posting each movement.
+CREATE TABLE IF NOT EXISTS stock_balance
+(
+    item_id         TEXT        NOT NULL REFERENCES item (id),
+    location_id     TEXT        NOT NULL REFERENCES location (id),
+    quantity        TEXT        NOT NULL,
+    id              TEXT        GENERATED ALWAYS AS (item_id || '/' || location_id) VIRTUAL,
+    PRIMARY KEY (item_id, location_id)
+) STRICT;
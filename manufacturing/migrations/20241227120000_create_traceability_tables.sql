@@ -0,0 +1,33 @@
+-- Add migration script here
+ALTER TABLE item ADD COLUMN tracking INTEGER NOT NULL DEFAULT 1;
+
+-- Lots and serials are numbered per item and leave with it, movements keep them in use.
+CREATE TABLE IF NOT EXISTS lot
+(
+    item_id         TEXT        NOT NULL REFERENCES item (id) ON DELETE CASCADE,
+    id              TEXT        NOT NULL,
+    description     TEXT        NOT NULL,
+    uid             TEXT        NOT NULL,
+    create_time     TEXT        NOT NULL,
+    PRIMARY KEY (item_id, id)
+) STRICT;
+
+CREATE TABLE IF NOT EXISTS serial
+(
+    item_id         TEXT        NOT NULL REFERENCES item (id) ON DELETE CASCADE,
+    id              TEXT        NOT NULL,
+    location_id     TEXT        REFERENCES location (id),
+    uid             TEXT        NOT NULL,
+    create_time     TEXT        NOT NULL,
+    update_time     TEXT        NOT NULL,
+    PRIMARY KEY (item_id, id)
+) STRICT;
+
+-- A composite foreign key cannot be added to an existing table, so the command validates the
+-- lot and serial. The production order is kept as history even once the order is deleted.
+ALTER TABLE stock_movement ADD COLUMN lot_id TEXT;
+ALTER TABLE stock_movement ADD COLUMN serial_id TEXT;
+ALTER TABLE stock_movement ADD COLUMN production_order_id TEXT;
+
+CREATE INDEX IF NOT EXISTS stock_movement_production_order_idx
+    ON stock_movement (production_order_id);
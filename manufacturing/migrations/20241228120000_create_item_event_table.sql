@@ -0,0 +1,10 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS item_event
+(
+    sequence        INTEGER     PRIMARY KEY AUTOINCREMENT,
+    item_id         TEXT                    NOT NULL,
+    kind            INTEGER                 NOT NULL,
+    item_type       TEXT,
+    item            BLOB,
+    create_time     TEXT                    NOT NULL
+) STRICT;
@@ -0,0 +1,25 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS item_revision
+(
+    sequence                INTEGER     PRIMARY KEY AUTOINCREMENT,
+    item_id                 TEXT                    NOT NULL,
+    revision_id             TEXT                    NOT NULL,
+    operation_id            TEXT                    NOT NULL,
+    actor                   TEXT                    NOT NULL,
+    display_name            TEXT                    NOT NULL,
+    title                   TEXT                    NOT NULL,
+    description             TEXT                    NOT NULL,
+    state                   INTEGER                 NOT NULL,
+    etag                    TEXT                    NOT NULL,
+    uid                     TEXT                    NOT NULL,
+    create_time             TEXT                    NOT NULL,
+    update_time             TEXT                    NOT NULL,
+    base_uom                TEXT                    NOT NULL,
+    tracking                INTEGER                 NOT NULL,
+    delete_time             TEXT,
+    expire_time             TEXT,
+    revision_create_time    TEXT                    NOT NULL,
+    UNIQUE (item_id, revision_id)
+) STRICT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS item_revision_operation_idx ON item_revision (operation_id);
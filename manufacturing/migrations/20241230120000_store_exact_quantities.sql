@@ -0,0 +1,21 @@
+-- Add migration script here
+-- Quantities are kept as exact decimal strings, like those of stock movements.
+ALTER TABLE production_order ADD COLUMN quantity_text TEXT NOT NULL DEFAULT '0';
+UPDATE production_order SET quantity_text = CAST(quantity AS TEXT);
+ALTER TABLE production_order DROP COLUMN quantity;
+ALTER TABLE production_order RENAME COLUMN quantity_text TO quantity;
+
+ALTER TABLE production_order_operation ADD COLUMN reported_quantity_text TEXT NOT NULL DEFAULT '0';
+UPDATE production_order_operation SET reported_quantity_text = CAST(reported_quantity AS TEXT);
+ALTER TABLE production_order_operation DROP COLUMN reported_quantity;
+ALTER TABLE production_order_operation RENAME COLUMN reported_quantity_text TO reported_quantity;
+
+ALTER TABLE bill_of_materials_component ADD COLUMN quantity_text TEXT NOT NULL DEFAULT '0';
+ALTER TABLE bill_of_materials_component ADD COLUMN scrap_factor_text TEXT NOT NULL DEFAULT '0';
+UPDATE bill_of_materials_component SET
+    quantity_text = CAST(quantity AS TEXT),
+    scrap_factor_text = CAST(scrap_factor AS TEXT);
+ALTER TABLE bill_of_materials_component DROP COLUMN quantity;
+ALTER TABLE bill_of_materials_component DROP COLUMN scrap_factor;
+ALTER TABLE bill_of_materials_component RENAME COLUMN quantity_text TO quantity;
+ALTER TABLE bill_of_materials_component RENAME COLUMN scrap_factor_text TO scrap_factor;
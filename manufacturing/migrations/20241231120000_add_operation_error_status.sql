@@ -0,0 +1,3 @@
+-- Add migration script here
+-- The google.rpc.Status of a failed operation, encoded with its details.
+ALTER TABLE operation ADD COLUMN error_status BLOB;
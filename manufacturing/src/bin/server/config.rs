@@ -1,25 +1,134 @@
-use std::env;
+use std::{env, path::PathBuf, time::Duration};
 
 use anyhow::Context;
 
 const SERVER_PORT_KEY: &str = "ERP_MNF_SERVER_PORT";
 
+const GATEWAY_PORT_KEY: &str = "ERP_MNF_GATEWAY_PORT";
+
+const GRPC_WEB_KEY: &str = "ERP_MNF_GRPC_WEB";
+
+const CORS_ALLOWED_ORIGINS_KEY: &str = "ERP_MNF_CORS_ALLOWED_ORIGINS";
+
+const JWKS_FILE_KEY: &str = "ERP_MNF_JWKS_FILE";
+
+const JWT_ISSUER_KEY: &str = "ERP_MNF_JWT_ISSUER";
+
+const JWT_AUDIENCE_KEY: &str = "ERP_MNF_JWT_AUDIENCE";
+
+const ROLES_FILE_KEY: &str = "ERP_MNF_ROLES_FILE";
+
 const DATABASE_URL_KEY: &str = "ERP_MNF_DB_URL";
 
+const TENANT_DATABASE_URL_KEY: &str = "ERP_MNF_TENANT_DB_URL";
+
+const ORGANIZATIONS_KEY: &str = "ERP_MNF_ORGANIZATIONS";
+
+const WORKER_INTERVAL_MS_KEY: &str = "ERP_MNF_WORKER_INTERVAL_MS";
+
+const DEFAULT_WORKER_INTERVAL: Duration = Duration::from_secs(1);
+
+const ITEM_RETENTION_S_KEY: &str = "ERP_MNF_ITEM_RETENTION_S";
+
+const DEFAULT_ITEM_RETENTION: Duration = Duration::from_hours(30 * 24);
+
+const PAGE_TOKEN_SECRET_KEY: &str = "ERP_MNF_PAGE_TOKEN_SECRET";
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Config {
     pub server_port: u16,
+    /// The port of the HTTP/JSON gateway, which is not served without one.
+    pub gateway_port: Option<u16>,
+    /// Whether gRPC-Web requests from browsers are accepted on the server port.
+    pub grpc_web: bool,
+    /// The origins allowed to make cross-origin requests, `*` for any, or none without CORS.
+    pub cors_allowed_origins: Option<Vec<String>>,
+    /// The JSON Web Key Set of Identity Provisioning, without which callers are not
+    /// authenticated.
+    pub jwks_file: Option<PathBuf>,
+    /// The issuer tokens must be issued by, if any.
+    pub jwt_issuer: Option<String>,
+    /// The audience tokens must be issued for, if any.
+    pub jwt_audience: Option<String>,
+    /// The permissions granted by each role, without which authenticated callers are not
+    /// restricted.
+    pub roles_file: Option<PathBuf>,
     pub database_url: String,
+    /// The database of each organization, with `{organization}` for its id, without which
+    /// every caller is served from the default database. With it, authenticated callers
+    /// whose token names no organization are refused.
+    pub tenant_database_url: Option<String>,
+    /// The organizations provisioned with a database, which is opened at startup. Callers of
+    /// any other organization are refused.
+    pub organizations: Vec<String>,
+    pub worker_interval: Duration,
+    pub item_retention: Duration,
+    pub page_token_secret: Vec<u8>,
 }
 
 impl Config {
     pub fn from_env() -> anyhow::Result<Self> {
         let server_port = load_env(SERVER_PORT_KEY)?.parse()?;
+        let gateway_port = load_optional_env(GATEWAY_PORT_KEY)?
+            .map(|port| port.parse())
+            .transpose()?;
+        let grpc_web = load_optional_env(GRPC_WEB_KEY)?
+            .map(|enabled| enabled.parse())
+            .transpose()?
+            .unwrap_or_default();
+        let cors_allowed_origins = load_optional_env(CORS_ALLOWED_ORIGINS_KEY)?.map(|origins| {
+            origins
+                .split(',')
+                .map(str::trim)
+                .filter(|origin| !origin.is_empty())
+                .map(String::from)
+                .collect()
+        });
+        let jwks_file = load_optional_env(JWKS_FILE_KEY)?.map(PathBuf::from);
+        let jwt_issuer = load_optional_env(JWT_ISSUER_KEY)?;
+        let jwt_audience = load_optional_env(JWT_AUDIENCE_KEY)?;
+        let roles_file = load_optional_env(ROLES_FILE_KEY)?.map(PathBuf::from);
         let database_url = load_env(DATABASE_URL_KEY)?;
+        let tenant_database_url = load_optional_env(TENANT_DATABASE_URL_KEY)?;
+        let organizations = load_optional_env(ORGANIZATIONS_KEY)?
+            .map(|organizations| {
+                organizations
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|organization| !organization.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let worker_interval = load_optional_env(WORKER_INTERVAL_MS_KEY)?
+            .map(|ms| ms.parse().map(Duration::from_millis))
+            .transpose()?
+            .unwrap_or(DEFAULT_WORKER_INTERVAL);
+        let item_retention = load_optional_env(ITEM_RETENTION_S_KEY)?
+            .map(|s| s.parse().map(Duration::from_secs))
+            .transpose()?
+            .unwrap_or(DEFAULT_ITEM_RETENTION);
+        // Without a configured secret, page tokens stay valid until the server restarts.
+        let page_token_secret = load_optional_env(PAGE_TOKEN_SECRET_KEY)?.map_or_else(
+            || uuid::Uuid::new_v4().as_bytes().to_vec(),
+            String::into_bytes,
+        );
 
         Ok(Self {
             server_port,
+            gateway_port,
+            grpc_web,
+            cors_allowed_origins,
+            jwks_file,
+            jwt_issuer,
+            jwt_audience,
+            roles_file,
             database_url,
+            tenant_database_url,
+            organizations,
+            worker_interval,
+            item_retention,
+            page_token_secret,
         })
     }
 }
@@ -27,3 +136,11 @@ impl Config {
 fn load_env(key: &str) -> anyhow::Result<String> {
     env::var(key).with_context(|| format!("failed to load environment variable {key}"))
 }
+
+fn load_optional_env(key: &str) -> anyhow::Result<Option<String>> {
+    match env::var(key) {
+        Ok(value) => Ok(Some(value)),
+        Err(env::VarError::NotPresent) => Ok(None),
+        Err(err) => Err(err).with_context(|| format!("failed to load environment variable {key}")),
+    }
+}
@@ -0,0 +1,25 @@
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+
+use axum::extract::{Request, State};
+use axum::response::Response;
+use axum::Router;
+use manufacturing::gateway::Gateway;
+use tokio::net::TcpListener;
+
+use crate::grpc::Services;
+
+/// # Errors
+pub async fn serve(gateway: Gateway<Services>, port: u16) -> anyhow::Result<()> {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let listener = TcpListener::bind(addr).await?;
+
+    let router = Router::new().fallback(handle).with_state(Arc::new(gateway));
+    axum::serve(listener, router).await?;
+
+    Ok(())
+}
+
+async fn handle(State(gateway): State<Arc<Gateway<Services>>>, request: Request) -> Response {
+    gateway.handle(request).await
+}
@@ -1,17 +1,113 @@
 use std::net::{Ipv4Addr, SocketAddr};
 use std::sync::Arc;
+use std::time::Duration;
 
+use anyhow::Context;
+
+use http::{HeaderName, HeaderValue, Method};
+use manufacturing::bill_of_materials::command::Service as BillOfMaterialsCommandService;
+use manufacturing::bill_of_materials::query::Service as BillOfMaterialsQueryService;
+use manufacturing::bill_of_materials::repository::Service as BillOfMaterialsRepositoryService;
+use manufacturing::bill_of_materials::worker::Service as BillOfMaterialsWorkerService;
+use manufacturing::gateway::Gateway;
+use manufacturing::grpc::auth::{Layer as AuthLayer, Service as AuthService, Verifier};
+use manufacturing::grpc::bill_of_materials::{
+    CommandService as GrpcBillOfMaterialsCommandService,
+    QueryService as GrpcBillOfMaterialsQueryService,
+};
+use manufacturing::grpc::iam::Policy;
 use manufacturing::grpc::item::Service as GrpcItemService;
+use manufacturing::grpc::location::{
+    CommandService as GrpcLocationCommandService, QueryService as GrpcLocationQueryService,
+};
+use manufacturing::grpc::production_order::{
+    CommandService as GrpcProductionOrderCommandService,
+    QueryService as GrpcProductionOrderQueryService,
+};
 use manufacturing::grpc::proto::google::longrunning::operations_server::OperationsServer as GoogleOperationsServer;
+use manufacturing::grpc::routing::{
+    CommandService as GrpcRoutingCommandService, QueryService as GrpcRoutingQueryService,
+};
+use manufacturing::grpc::stock_movement::{
+    CommandService as GrpcStockMovementCommandService,
+    QueryService as GrpcStockMovementQueryService,
+};
 use manufacturing::grpc::sync::Service as GrpcSyncService;
+use manufacturing::grpc::tenant::{Layer as TenantLayer, Service as TenantService};
+use manufacturing::grpc::traceability::{
+    CommandService as GrpcTraceabilityCommandService, QueryService as GrpcTraceabilityQueryService,
+};
+use manufacturing::grpc::unit_of_measure::QueryService as GrpcUnitOfMeasureQueryService;
+use manufacturing::grpc::web::Layer as GrpcWebLayer;
 use manufacturing::item::command::Service as ItemCommandService;
 use manufacturing::item::query::Service as ItemQueryService;
 use manufacturing::item::repository::Service as ItemRepositoryService;
+use manufacturing::item::worker::{Purge as ItemPurgeService, Service as ItemWorkerService};
+use manufacturing::location::command::Service as LocationCommandService;
+use manufacturing::location::query::Service as LocationQueryService;
+use manufacturing::location::repository::Service as LocationRepositoryService;
+use manufacturing::page_token::Key as PageTokenKey;
+use manufacturing::production_order::command::Service as ProductionOrderCommandService;
+use manufacturing::production_order::query::Service as ProductionOrderQueryService;
+use manufacturing::production_order::repository::Service as ProductionOrderRepositoryService;
+use manufacturing::production_order::worker::Service as ProductionOrderWorkerService;
+use manufacturing::proto::bill_of_materials::bill_of_materials_command_service_server::BillOfMaterialsCommandServiceServer;
+use manufacturing::proto::bill_of_materials::bill_of_materials_query_service_server::BillOfMaterialsQueryServiceServer;
 use manufacturing::proto::item_service_server::ItemServiceServer;
+use manufacturing::proto::location::location_command_service_server::LocationCommandServiceServer;
+use manufacturing::proto::location::location_query_service_server::LocationQueryServiceServer;
+use manufacturing::proto::production_order::production_order_command_service_server::ProductionOrderCommandServiceServer;
+use manufacturing::proto::production_order::production_order_query_service_server::ProductionOrderQueryServiceServer;
+use manufacturing::proto::routing::routing_command_service_server::RoutingCommandServiceServer;
+use manufacturing::proto::routing::routing_query_service_server::RoutingQueryServiceServer;
+use manufacturing::proto::stock_movement::stock_movement_command_service_server::StockMovementCommandServiceServer;
+use manufacturing::proto::stock_movement::stock_movement_query_service_server::StockMovementQueryServiceServer;
+use manufacturing::proto::traceability::traceability_command_service_server::TraceabilityCommandServiceServer;
+use manufacturing::proto::traceability::traceability_query_service_server::TraceabilityQueryServiceServer;
+use manufacturing::proto::unit_of_measure::unit_of_measure_query_service_server::UnitOfMeasureQueryServiceServer;
+use manufacturing::routing::command::Service as RoutingCommandService;
+use manufacturing::routing::query::Service as RoutingQueryService;
+use manufacturing::routing::repository::Service as RoutingRepositoryService;
+use manufacturing::routing::worker::Service as RoutingWorkerService;
+use manufacturing::stock_movement::command::Service as StockMovementCommandService;
+use manufacturing::stock_movement::query::Service as StockMovementQueryService;
+use manufacturing::stock_movement::repository::Service as StockMovementRepositoryService;
+use manufacturing::sync::command::Service as OperationCommandService;
+use manufacturing::sync::query::Service as OperationQueryService;
+use manufacturing::sync::repository::Service as OperationRepositoryService;
+use manufacturing::traceability::command::Service as TraceabilityCommandService;
+use manufacturing::traceability::query::Service as TraceabilityQueryService;
+use manufacturing::traceability::repository::Service as TraceabilityRepositoryService;
+use manufacturing::unit_of_measure::query::Service as UnitOfMeasureQueryService;
+use manufacturing::unit_of_measure::repository::Service as UnitOfMeasureRepositoryService;
+use tonic::service::Routes;
 use tonic::transport::Server as TonicServer;
+use tower::util::{option_layer, Either};
+use tower::ServiceBuilder;
+use tower_http::cors::{AllowOrigin, CorsLayer};
 
 use crate::config::Config;
+use crate::gateway;
 use crate::sqlite::Connection;
+use crate::worker;
+
+const CORS_ALLOW_HEADERS: [&str; 5] = [
+    "authorization",
+    "content-type",
+    "grpc-timeout",
+    "x-grpc-web",
+    "x-user-agent",
+];
+
+const CORS_EXPOSE_HEADERS: [&str; 3] = ["grpc-status", "grpc-message", "grpc-status-details-bin"];
+
+const CORS_MAX_AGE: Duration = Duration::from_hours(24);
+
+/// The services as called by the gateway, authenticating their callers and serving them
+/// for their organization if configured to.
+pub type Services = Either<AuthService<TenantRoutes>, TenantRoutes>;
+
+type TenantRoutes = Either<TenantService<Routes, Connection>, Routes>;
 
 mod proto {
     pub const MANUFACTURING_DESCRIPTOR_SET: &[u8] =
@@ -19,31 +115,355 @@ mod proto {
 }
 
 /// # Errors
+// Wiring every resource in one place keeps the dependency graph readable.
+#[allow(clippy::too_many_lines)]
 pub async fn serve(config: &Config) -> anyhow::Result<()> {
     let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, config.server_port));
 
-    let sqlite_connection = Arc::new(Connection::new(&config.database_url).await?);
+    let sqlite_connection = Arc::new(
+        Connection::new(
+            &config.database_url,
+            config.tenant_database_url.as_deref(),
+            &config.organizations,
+        )
+        .await?,
+    );
+
+    // MARK: Unit of Measure
+    let page_token_key = PageTokenKey::new(&config.page_token_secret)?;
+    let unit_of_measure_repository = Arc::new(UnitOfMeasureRepositoryService::new(
+        sqlite_connection.clone(),
+        page_token_key.clone(),
+    ));
+    let unit_of_measure_query_service = Arc::new(UnitOfMeasureQueryService::new(
+        unit_of_measure_repository.clone(),
+    ));
+    let grpc_unit_of_measure_query_service =
+        GrpcUnitOfMeasureQueryService::new(unit_of_measure_query_service);
 
     // MARK: Item
-    let item_repository = Arc::new(ItemRepositoryService::new(sqlite_connection));
-    let item_command_service = Arc::new(ItemCommandService::new(item_repository.clone()));
+    let item_repository = Arc::new(ItemRepositoryService::new(
+        sqlite_connection.clone(),
+        page_token_key.clone(),
+    ));
+    let item_command_service = Arc::new(ItemCommandService::new(
+        item_repository.clone(),
+        unit_of_measure_repository.clone(),
+        config.item_retention,
+    ));
     let item_query_service = Arc::new(ItemQueryService::new(item_repository.clone()));
-    let grpc_item_service = GrpcItemService::new(item_command_service, item_query_service);
+    // Without authentication every caller would be denied, or, failing open, allowed.
+    anyhow::ensure!(
+        config.roles_file.is_none() || config.jwks_file.is_some(),
+        "roles file is configured without a JWKS file to authenticate callers"
+    );
+    let policy = match &config.roles_file {
+        Some(path) => {
+            let roles = std::fs::read(path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            Some(Arc::new(Policy::new(&roles)?))
+        }
+        None => None,
+    };
+    let grpc_item_service =
+        GrpcItemService::new(item_command_service, item_query_service, policy.clone());
+
+    // MARK: Routing
+    let routing_repository = Arc::new(RoutingRepositoryService::new(sqlite_connection.clone()));
+    let routing_command_service = Arc::new(RoutingCommandService::new(
+        routing_repository.clone(),
+        item_repository.clone(),
+    ));
+    let routing_query_service = Arc::new(RoutingQueryService::new(routing_repository.clone()));
+    let grpc_routing_command_service = GrpcRoutingCommandService::new(routing_command_service);
+    let grpc_routing_query_service = GrpcRoutingQueryService::new(routing_query_service);
+
+    // MARK: Production Order
+    let production_order_repository = Arc::new(ProductionOrderRepositoryService::new(
+        sqlite_connection.clone(),
+        page_token_key.clone(),
+    ));
+    let production_order_command_service = Arc::new(ProductionOrderCommandService::new(
+        production_order_repository.clone(),
+        item_repository.clone(),
+        routing_repository.clone(),
+    ));
+    let production_order_query_service = Arc::new(ProductionOrderQueryService::new(
+        production_order_repository.clone(),
+    ));
+    let grpc_production_order_command_service =
+        GrpcProductionOrderCommandService::new(production_order_command_service);
+    let grpc_production_order_query_service =
+        GrpcProductionOrderQueryService::new(production_order_query_service);
+
+    // MARK: Bill of Materials
+    let bill_of_materials_repository = Arc::new(BillOfMaterialsRepositoryService::new(
+        sqlite_connection.clone(),
+        page_token_key.clone(),
+    ));
+    let bill_of_materials_command_service = Arc::new(BillOfMaterialsCommandService::new(
+        bill_of_materials_repository.clone(),
+        item_repository.clone(),
+        unit_of_measure_repository.clone(),
+    ));
+    let bill_of_materials_query_service = Arc::new(BillOfMaterialsQueryService::new(
+        bill_of_materials_repository.clone(),
+        item_repository.clone(),
+        unit_of_measure_repository.clone(),
+    ));
+    let grpc_bill_of_materials_command_service =
+        GrpcBillOfMaterialsCommandService::new(bill_of_materials_command_service);
+    let grpc_bill_of_materials_query_service =
+        GrpcBillOfMaterialsQueryService::new(bill_of_materials_query_service);
+
+    // MARK: Location
+    let location_repository = Arc::new(LocationRepositoryService::new(
+        sqlite_connection.clone(),
+        page_token_key.clone(),
+    ));
+    let location_command_service =
+        Arc::new(LocationCommandService::new(location_repository.clone()));
+    let location_query_service = Arc::new(LocationQueryService::new(location_repository.clone()));
+    let grpc_location_command_service = GrpcLocationCommandService::new(location_command_service);
+    let grpc_location_query_service = GrpcLocationQueryService::new(location_query_service);
+
+    // MARK: Traceability
+    let traceability_repository = Arc::new(TraceabilityRepositoryService::new(
+        sqlite_connection.clone(),
+        page_token_key.clone(),
+    ));
+    let traceability_command_service = Arc::new(TraceabilityCommandService::new(
+        traceability_repository.clone(),
+        item_repository.clone(),
+    ));
+    let traceability_query_service = Arc::new(TraceabilityQueryService::new(
+        traceability_repository.clone(),
+        item_repository.clone(),
+    ));
+    let grpc_traceability_command_service =
+        GrpcTraceabilityCommandService::new(traceability_command_service);
+    let grpc_traceability_query_service =
+        GrpcTraceabilityQueryService::new(traceability_query_service);
+
+    // MARK: Stock Movement
+    let stock_movement_repository = Arc::new(StockMovementRepositoryService::new(
+        sqlite_connection.clone(),
+        page_token_key,
+    ));
+    let stock_movement_command_service = Arc::new(StockMovementCommandService::new(
+        stock_movement_repository.clone(),
+        item_repository.clone(),
+        location_repository.clone(),
+        unit_of_measure_repository,
+        traceability_repository,
+        production_order_repository.clone(),
+    ));
+    let stock_movement_query_service = Arc::new(StockMovementQueryService::new(
+        stock_movement_repository,
+        item_repository.clone(),
+        location_repository,
+    ));
+    let grpc_stock_movement_command_service =
+        GrpcStockMovementCommandService::new(stock_movement_command_service);
+    let grpc_stock_movement_query_service =
+        GrpcStockMovementQueryService::new(stock_movement_query_service);
 
     // MARK: Sync
-    let grpc_sync_service = GrpcSyncService;
+    let operation_repository = Arc::new(OperationRepositoryService::new(sqlite_connection.clone()));
+    let operation_command_service =
+        Arc::new(OperationCommandService::new(operation_repository.clone()));
+    let operation_query_service =
+        Arc::new(OperationQueryService::new(operation_repository.clone()));
+    let grpc_sync_service =
+        GrpcSyncService::new(operation_command_service, operation_query_service, policy);
+
+    // MARK: Worker
+    spawn_workers(
+        config,
+        &sqlite_connection,
+        item_repository,
+        production_order_repository,
+        bill_of_materials_repository,
+        routing_repository,
+        operation_repository,
+    );
 
     // MARK: Reflection
     let reflection_service = tonic_reflection::server::Builder::configure()
         .register_encoded_file_descriptor_set(proto::MANUFACTURING_DESCRIPTOR_SET)
         .build_v1()?;
 
-    TonicServer::builder()
-        .add_service(reflection_service)
+    let routes = Routes::new(reflection_service)
         .add_service(ItemServiceServer::new(grpc_item_service))
-        .add_service(GoogleOperationsServer::new(grpc_sync_service))
-        .serve(addr)
-        .await?;
+        .add_service(ProductionOrderCommandServiceServer::new(
+            grpc_production_order_command_service,
+        ))
+        .add_service(ProductionOrderQueryServiceServer::new(
+            grpc_production_order_query_service,
+        ))
+        .add_service(BillOfMaterialsCommandServiceServer::new(
+            grpc_bill_of_materials_command_service,
+        ))
+        .add_service(BillOfMaterialsQueryServiceServer::new(
+            grpc_bill_of_materials_query_service,
+        ))
+        .add_service(RoutingCommandServiceServer::new(
+            grpc_routing_command_service,
+        ))
+        .add_service(RoutingQueryServiceServer::new(grpc_routing_query_service))
+        .add_service(UnitOfMeasureQueryServiceServer::new(
+            grpc_unit_of_measure_query_service,
+        ))
+        .add_service(LocationCommandServiceServer::new(
+            grpc_location_command_service,
+        ))
+        .add_service(LocationQueryServiceServer::new(grpc_location_query_service))
+        .add_service(StockMovementCommandServiceServer::new(
+            grpc_stock_movement_command_service,
+        ))
+        .add_service(StockMovementQueryServiceServer::new(
+            grpc_stock_movement_query_service,
+        ))
+        .add_service(TraceabilityCommandServiceServer::new(
+            grpc_traceability_command_service,
+        ))
+        .add_service(TraceabilityQueryServiceServer::new(
+            grpc_traceability_query_service,
+        ))
+        .add_service(GoogleOperationsServer::new(grpc_sync_service));
+
+    // MARK: Authentication
+    let auth = match &config.jwks_file {
+        Some(path) => {
+            let jwks = std::fs::read(path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            let verifier = Verifier::new(
+                &jwks,
+                config.jwt_issuer.clone(),
+                config.jwt_audience.clone(),
+            )?;
+            Some(AuthLayer::new(verifier))
+        }
+        None => None,
+    };
+
+    // MARK: Tenancy
+    // Callers name their organization themselves only when they are not authenticated.
+    let tenancy = config
+        .tenant_database_url
+        .is_some()
+        .then(|| TenantLayer::new(sqlite_connection.clone(), auth.is_none()));
+
+    // MARK: gRPC-Web
+    let cors = config
+        .cors_allowed_origins
+        .as_deref()
+        .map(cors)
+        .transpose()?;
+    let grpc_web = config.grpc_web.then_some(GrpcWebLayer);
+
+    let grpc = TonicServer::builder()
+        .accept_http1(config.grpc_web)
+        .layer(option_layer(cors))
+        .layer(option_layer(grpc_web))
+        .layer(option_layer(auth.clone()))
+        .layer(option_layer(tenancy.clone()))
+        .add_routes(routes.clone())
+        .serve(addr);
+
+    // MARK: Gateway
+    match config.gateway_port {
+        Some(port) => {
+            let services = ServiceBuilder::new()
+                .layer(option_layer(auth))
+                .layer(option_layer(tenancy))
+                .service(routes);
+            let gateway = Gateway::new(proto::MANUFACTURING_DESCRIPTOR_SET, services)?;
+            tokio::try_join!(
+                async { grpc.await.map_err(anyhow::Error::from) },
+                gateway::serve(gateway, port),
+            )?;
+        }
+        None => grpc.await?,
+    }
 
     Ok(())
 }
+
+/// The CORS policy for browsers calling from `origins`, `*` allowing any origin.
+fn cors(origins: &[String]) -> anyhow::Result<CorsLayer> {
+    let allow_origin = if origins.iter().any(|origin| origin == "*") {
+        AllowOrigin::any()
+    } else {
+        let origins = origins
+            .iter()
+            .map(|origin| HeaderValue::from_str(origin))
+            .collect::<Result<Vec<_>, _>>()?;
+        AllowOrigin::list(origins)
+    };
+
+    Ok(CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods([Method::POST])
+        .allow_headers(CORS_ALLOW_HEADERS.map(HeaderName::from_static))
+        .expose_headers(CORS_EXPOSE_HEADERS.map(HeaderName::from_static))
+        .max_age(CORS_MAX_AGE))
+}
+
+fn spawn_workers(
+    config: &Config,
+    connection: &Arc<Connection>,
+    item_repository: Arc<ItemRepositoryService<Connection>>,
+    production_order_repository: Arc<ProductionOrderRepositoryService<Connection>>,
+    bill_of_materials_repository: Arc<BillOfMaterialsRepositoryService<Connection>>,
+    routing_repository: Arc<RoutingRepositoryService<Connection>>,
+    operation_repository: Arc<OperationRepositoryService<Connection>>,
+) {
+    let item_worker = Arc::new(ItemWorkerService::new(
+        item_repository.clone(),
+        operation_repository.clone(),
+    ));
+    worker::spawn(
+        item_worker,
+        connection.clone(),
+        config.worker_interval,
+        "item",
+    );
+    let item_purge = Arc::new(ItemPurgeService::new(item_repository));
+    worker::spawn(
+        item_purge,
+        connection.clone(),
+        config.worker_interval,
+        "expired item",
+    );
+    let production_order_worker = Arc::new(ProductionOrderWorkerService::new(
+        production_order_repository,
+        operation_repository.clone(),
+    ));
+    worker::spawn(
+        production_order_worker,
+        connection.clone(),
+        config.worker_interval,
+        "production order",
+    );
+    let bill_of_materials_worker = Arc::new(BillOfMaterialsWorkerService::new(
+        bill_of_materials_repository,
+        operation_repository.clone(),
+    ));
+    worker::spawn(
+        bill_of_materials_worker,
+        connection.clone(),
+        config.worker_interval,
+        "bill of materials",
+    );
+    let routing_worker = Arc::new(RoutingWorkerService::new(
+        routing_repository,
+        operation_repository,
+    ));
+    worker::spawn(
+        routing_worker,
+        connection.clone(),
+        config.worker_interval,
+        "routing",
+    );
+}
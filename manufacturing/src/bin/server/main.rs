@@ -1,8 +1,10 @@
 use config::Config;
 
 mod config;
+mod gateway;
 mod grpc;
 mod sqlite;
+mod worker;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -1,32 +1,89 @@
+use std::collections::HashMap;
 use std::str::FromStr;
 
 use anyhow::Context;
+use manufacturing::{sqlx::Tenants, Tenant};
 use sqlx::{sqlite::SqliteConnectOptions, SqlitePool};
 
-#[derive(Debug, Clone)]
+/// The placeholder for the organization of a tenant in the database path of the tenants.
+pub const ORGANIZATION_PLACEHOLDER: &str = "{organization}";
+
+#[derive(Clone, Debug)]
 pub struct Connection {
     pool: SqlitePool,
+    /// A pool that is closed, lent out for a tenant that is not provisioned so that its
+    /// queries fail, as falling back to the default database would serve another's data.
+    closed: SqlitePool,
+    /// The pools of the provisioned tenants, all opened up front.
+    tenants: HashMap<Tenant, SqlitePool>,
 }
 
 impl Connection {
-    pub async fn new(path: &str) -> anyhow::Result<Self> {
-        let connect_options = SqliteConnectOptions::from_str(path)
-            .with_context(|| format!("invalid database path {path}"))?
-            .pragma("foreign_keys", "on")
-            .create_if_missing(true);
-
-        let pool = SqlitePool::connect_with(connect_options)
-            .await
-            .with_context(|| format!("failed to open database at {path}"))?;
+    /// Open the default database at `path` and the database of each of `organizations` at
+    /// `tenant_path`, with [`ORGANIZATION_PLACEHOLDER`] for the organization.
+    pub async fn new(
+        path: &str,
+        tenant_path: Option<&str>,
+        organizations: &[String],
+    ) -> anyhow::Result<Self> {
+        let closed = SqlitePool::connect_lazy_with(SqliteConnectOptions::new());
+        closed.close().await;
 
-        sqlx::migrate!().run(&pool).await?;
+        let mut tenants = HashMap::new();
+        if let Some(tenant_path) = tenant_path {
+            for organization in organizations {
+                let tenant = Tenant::try_from(organization.clone())
+                    .with_context(|| format!("invalid organization {organization:?}"))?;
+                let path = tenant_path.replace(ORGANIZATION_PLACEHOLDER, organization);
+                tenants.insert(tenant, open(&path).await?);
+            }
+        } else {
+            anyhow::ensure!(
+                organizations.is_empty(),
+                "organizations are configured without a database path for them"
+            );
+        }
 
-        Ok(Self { pool })
+        Ok(Self {
+            pool: open(path).await?,
+            closed,
+            tenants,
+        })
     }
 }
 
 impl manufacturing::sqlx::SqliteConnection for Connection {
-    fn pool(&self) -> &SqlitePool {
-        &self.pool
+    fn pool(&self) -> SqlitePool {
+        let Some(tenant) = Tenant::current() else {
+            return self.pool.clone();
+        };
+
+        self.tenants.get(&tenant).unwrap_or(&self.closed).clone()
     }
 }
+
+impl Tenants for Connection {
+    fn serves(&self, tenant: &Tenant) -> bool {
+        self.tenants.contains_key(tenant)
+    }
+
+    fn tenants(&self) -> Vec<Tenant> {
+        self.tenants.keys().cloned().collect()
+    }
+}
+
+/// Open the database at `path`, creating and migrating it as needed.
+async fn open(path: &str) -> anyhow::Result<SqlitePool> {
+    let connect_options = SqliteConnectOptions::from_str(path)
+        .with_context(|| format!("invalid database path {path}"))?
+        .pragma("foreign_keys", "on")
+        .create_if_missing(true);
+
+    let pool = SqlitePool::connect_with(connect_options)
+        .await
+        .with_context(|| format!("failed to open database at {path}"))?;
+
+    sqlx::migrate!().run(&pool).await?;
+
+    Ok(pool)
+}
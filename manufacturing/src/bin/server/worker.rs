@@ -0,0 +1,31 @@
+use std::{sync::Arc, time::Duration};
+
+use manufacturing::{sqlx::Tenants, sync::Reconcile, Tenant};
+use tokio::{task::JoinHandle, time::interval};
+
+use crate::sqlite::Connection;
+
+/// Reconcile the operations of the default database and of every provisioned tenant's database
+/// every `period`.
+pub fn spawn<R: Reconcile>(
+    worker: Arc<R>,
+    connection: Arc<Connection>,
+    period: Duration,
+    kind: &'static str,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = interval(period);
+
+        loop {
+            ticker.tick().await;
+
+            let tenants = std::iter::once(None).chain(connection.tenants().into_iter().map(Some));
+            for tenant in tenants {
+                if let Err(err) = Tenant::scope(tenant.clone(), worker.reconcile()).await {
+                    let tenant = tenant.map(|tenant| tenant.to_string()).unwrap_or_default();
+                    tracing::error!("failed to reconcile {kind} operations of {tenant:?}: {err:?}");
+                }
+            }
+        }
+    })
+}
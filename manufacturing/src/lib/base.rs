@@ -1,16 +1,43 @@
 use chrono::{DateTime, Utc};
 use derive_getters::Dissolve;
 use derive_more::derive::{Deref, Display, From};
+use rust_decimal::Decimal;
 
 pub mod entity_tag;
+pub mod field_mask;
+pub mod filter;
 pub mod id;
+pub mod order_by;
+pub mod page_token;
+pub mod quantity;
+pub mod tenant;
 pub mod timestamp;
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Display, Deref)]
 pub struct Id(String);
 
+/// The organization a request is served for, whose data is kept apart from every other's.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Display, Deref)]
+pub struct Tenant(Id);
+
+/// An exact decimal quantity, kept normalized so that equal amounts compare and print alike.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Display)]
+pub struct Quantity(Decimal);
+
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Display, From, Dissolve)]
 pub struct Timestamp(DateTime<Utc>);
 
 #[derive(Clone, Debug, PartialEq, Eq, Display, From, Deref)]
 pub struct EntityTag(etag::EntityTag);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldMask(Vec<String>);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Filter(filter::Expression);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OrderBy(Vec<order_by::Ordering>);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PageToken(page_token::Payload);
@@ -63,13 +63,13 @@ impl TryFrom<String> for EntityTag {
     fn try_from(value: String) -> Result<Self, Self::Error> {
         let trimmed = value.trim();
         if trimmed.is_empty() {
-            Err(EmptyError.into())
-        } else {
-            Ok(Self(::etag::EntityTag::new(
-                trimmed.starts_with('W'),
-                trimmed,
-            )))
+            return Err(EmptyError.into());
         }
+
+        trimmed
+            .parse::<::etag::EntityTag>()
+            .map(Self)
+            .map_err(|_| InvalidFormatError.into())
     }
 }
 
@@ -86,9 +86,37 @@ pub enum Error {
     #[error(transparent)]
     Empty(#[from] EmptyError),
     #[error(transparent)]
+    InvalidFormat(#[from] InvalidFormatError),
+    #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
 
 #[derive(Clone, Debug, ThisError, From)]
 #[error("name cannot be empty")]
 pub struct EmptyError;
+
+#[derive(Clone, Debug, ThisError, From)]
+#[error("etag format is invalid")]
+pub struct InvalidFormatError;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_round_trips() -> anyhow::Result<()> {
+        let etag = EntityTag::new();
+
+        let parsed = EntityTag::try_from(etag.to_string())?;
+
+        assert_eq!(etag, parsed);
+        assert_eq!(etag.to_string(), parsed.to_string());
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_unquoted_tag() {
+        assert!(EntityTag::try_from(String::from("unquoted")).is_err());
+    }
+}
@@ -0,0 +1,144 @@
+use derive_more::derive::From;
+
+use crate::{FieldMask, ThisError};
+
+const WILDCARD: &str = "*";
+
+impl FieldMask {
+    /// Validate `paths` of an [AIP-134](https://google.aip.dev/134) update mask
+    /// against the `mutable` and `immutable` fields of a resource.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a path is immutable, unknown, or `*` is combined with other paths.
+    pub fn new(paths: Vec<String>, mutable: &[&str], immutable: &[&str]) -> Result<Self, Error> {
+        if paths.is_empty() {
+            return Err(EmptyError.into());
+        }
+
+        for path in &paths {
+            if path == WILDCARD {
+                if paths.len() > 1 {
+                    return Err(InvalidWildcardError.into());
+                }
+            } else if immutable.contains(&path.as_str()) {
+                return Err(ImmutableFieldError::new(path).into());
+            } else if !mutable.contains(&path.as_str()) {
+                return Err(UnknownFieldError::new(path).into());
+            }
+        }
+
+        Ok(Self(paths))
+    }
+
+    #[must_use]
+    pub fn contains(&self, path: &str) -> bool {
+        self.0.iter().any(|p| p == WILDCARD || p == path)
+    }
+
+    /// Select the new value of `path`: fields in the mask are replaced, falling back to
+    /// their default when unset, while fields outside of it are left untouched.
+    #[must_use]
+    pub fn select<T: Default>(&self, path: &str, value: Option<T>) -> Option<T> {
+        self.contains(path).then(|| value.unwrap_or_default())
+    }
+}
+
+#[derive(Debug, ThisError)]
+pub enum Error {
+    #[error(transparent)]
+    Empty(#[from] EmptyError),
+    #[error(transparent)]
+    InvalidWildcard(#[from] InvalidWildcardError),
+    #[error(transparent)]
+    ImmutableField(#[from] ImmutableFieldError),
+    #[error(transparent)]
+    UnknownField(#[from] UnknownFieldError),
+}
+
+#[derive(Clone, Debug, ThisError, From)]
+#[error("update_mask cannot be empty")]
+pub struct EmptyError;
+
+#[derive(Clone, Debug, ThisError, From)]
+#[error("update_mask path `*` cannot be combined with other paths")]
+pub struct InvalidWildcardError;
+
+#[derive(Clone, Debug, ThisError)]
+#[error("update_mask path `{path}` cannot be updated")]
+pub struct ImmutableFieldError {
+    path: String,
+}
+
+impl ImmutableFieldError {
+    #[must_use]
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, ThisError)]
+#[error("update_mask path `{path}` is unknown")]
+pub struct UnknownFieldError {
+    path: String,
+}
+
+impl UnknownFieldError {
+    #[must_use]
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MUTABLE: &[&str] = &["display_name", "title"];
+    const IMMUTABLE: &[&str] = &["state"];
+
+    fn paths(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn selects_only_listed_paths() -> anyhow::Result<()> {
+        let mask = FieldMask::new(paths(&["title"]), MUTABLE, IMMUTABLE)?;
+
+        assert_eq!(
+            None,
+            mask.select("display_name", Some(String::from("Bike")))
+        );
+        assert_eq!(Some(String::new()), mask.select::<String>("title", None));
+
+        Ok(())
+    }
+
+    #[test]
+    fn wildcard_replaces_every_field() -> anyhow::Result<()> {
+        let mask = FieldMask::new(paths(&["*"]), MUTABLE, IMMUTABLE)?;
+
+        assert_eq!(
+            Some(String::new()),
+            mask.select::<String>("display_name", None)
+        );
+        assert_eq!(
+            Some(String::from("Bike")),
+            mask.select("title", Some(String::from("Bike")))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_invalid_paths() {
+        assert!(FieldMask::new(vec![], MUTABLE, IMMUTABLE).is_err());
+        assert!(FieldMask::new(paths(&["state"]), MUTABLE, IMMUTABLE).is_err());
+        assert!(FieldMask::new(paths(&["color"]), MUTABLE, IMMUTABLE).is_err());
+        assert!(FieldMask::new(paths(&["*", "title"]), MUTABLE, IMMUTABLE).is_err());
+    }
+}
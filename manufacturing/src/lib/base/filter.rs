@@ -0,0 +1,580 @@
+use std::{fmt, iter::Peekable, str::CharIndices};
+
+use derive_getters::Getters;
+use derive_more::derive::From;
+
+use crate::{Filter, ThisError};
+
+/// The longest filter accepted, in bytes, so that clients cannot make the server parse
+/// arbitrarily large input.
+pub const MAX_LENGTH: usize = 4096;
+
+/// The deepest nesting of parentheses accepted, so that parsing a filter cannot exhaust the
+/// stack.
+pub const MAX_DEPTH: usize = 64;
+
+/// An expression of the [AIP-160](https://google.aip.dev/160) filter grammar.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expression {
+    And(Vec<Self>),
+    Or(Vec<Self>),
+    Not(Box<Self>),
+    Restriction(Restriction),
+}
+
+/// A comparison of a field against a value, e.g. `state = ACTIVE`.
+#[derive(Clone, Debug, PartialEq, Eq, Getters)]
+pub struct Restriction {
+    field: String,
+    position: usize,
+    comparator: Comparator,
+    value: String,
+    value_position: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparator {
+    Equals,
+    NotEquals,
+    LessThan,
+    LessEquals,
+    GreaterThan,
+    GreaterEquals,
+    Has,
+}
+
+impl fmt::Display for Comparator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let comparator = match self {
+            Self::Equals => "=",
+            Self::NotEquals => "!=",
+            Self::LessThan => "<",
+            Self::LessEquals => "<=",
+            Self::GreaterThan => ">",
+            Self::GreaterEquals => ">=",
+            Self::Has => ":",
+        };
+
+        f.write_str(comparator)
+    }
+}
+
+impl Filter {
+    #[must_use]
+    pub const fn expression(&self) -> &Expression {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for Filter {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if value.trim().is_empty() {
+            return Err(EmptyError.into());
+        }
+        if value.len() > MAX_LENGTH {
+            return Err(TooLongError::new(value.len()).into());
+        }
+
+        let tokens = tokenize(&value)?;
+        let mut parser = Parser {
+            tokens,
+            index: 0,
+            end: value.chars().count() + 1,
+            depth: 0,
+        };
+
+        let expression = parser.expression()?;
+
+        if let Some(token) = parser.peek() {
+            return Err(InvalidSyntaxError::new(token.position, "unexpected token").into());
+        }
+
+        Ok(Self(expression))
+    }
+}
+
+impl TryFrom<Option<String>> for Filter {
+    type Error = Error;
+
+    fn try_from(value: Option<String>) -> Result<Self, Self::Error> {
+        value.map_or_else(|| Err(EmptyError.into()), TryInto::try_into)
+    }
+}
+
+// MARK: Tokenizer
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Kind {
+    LeftParen,
+    RightParen,
+    Minus,
+    And,
+    Or,
+    Not,
+    Comparator(Comparator),
+    Text(String),
+    String(String),
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+    kind: Kind,
+    position: usize,
+}
+
+fn tokenize(value: &str) -> Result<Vec<Token>, Error> {
+    let mut tokens = vec![];
+    let mut chars = value.char_indices().peekable();
+    let mut position = 0;
+
+    while let Some((_, c)) = chars.next() {
+        position += 1;
+        let start = position;
+
+        let kind = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Kind::LeftParen,
+            ')' => Kind::RightParen,
+            '=' => Kind::Comparator(Comparator::Equals),
+            ':' => Kind::Comparator(Comparator::Has),
+            '!' => {
+                if chars.next_if(|(_, c)| *c == '=').is_none() {
+                    return Err(InvalidSyntaxError::new(start, "expected `=` after `!`").into());
+                }
+                position += 1;
+                Kind::Comparator(Comparator::NotEquals)
+            }
+            '<' | '>' => {
+                let equals = chars.next_if(|(_, c)| *c == '=').is_some();
+                if equals {
+                    position += 1;
+                }
+                Kind::Comparator(match (c, equals) {
+                    ('<', false) => Comparator::LessThan,
+                    ('<', true) => Comparator::LessEquals,
+                    (_, false) => Comparator::GreaterThan,
+                    (_, true) => Comparator::GreaterEquals,
+                })
+            }
+            '"' | '\'' => Kind::String(read_string(&mut chars, &mut position, c, start)?),
+            '-' if chars
+                .peek()
+                .is_some_and(|(_, c)| *c == '(' || c.is_alphabetic()) =>
+            {
+                Kind::Minus
+            }
+            c => {
+                let mut text = String::from(c);
+                while let Some((_, c)) = chars.next_if(|(_, c)| is_text(*c)) {
+                    text.push(c);
+                    position += 1;
+                }
+
+                match text.as_str() {
+                    "AND" => Kind::And,
+                    "OR" => Kind::Or,
+                    "NOT" => Kind::Not,
+                    _ => Kind::Text(text),
+                }
+            }
+        };
+
+        tokens.push(Token {
+            kind,
+            position: start,
+        });
+    }
+
+    Ok(tokens)
+}
+
+const fn is_text(c: char) -> bool {
+    !c.is_whitespace() && !matches!(c, '(' | ')' | '=' | '!' | '<' | '>' | ':' | '"' | '\'')
+}
+
+fn read_string(
+    chars: &mut Peekable<CharIndices<'_>>,
+    position: &mut usize,
+    quote: char,
+    start: usize,
+) -> Result<String, Error> {
+    let mut text = String::new();
+
+    while let Some((_, c)) = chars.next() {
+        *position += 1;
+
+        match c {
+            '\\' => {
+                let Some((_, escaped)) = chars.next() else {
+                    break;
+                };
+                *position += 1;
+                text.push(escaped);
+            }
+            c if c == quote => return Ok(text),
+            c => text.push(c),
+        }
+    }
+
+    Err(InvalidSyntaxError::new(start, "unterminated string").into())
+}
+
+// MARK: Parser
+
+struct Parser {
+    tokens: Vec<Token>,
+    index: usize,
+    end: usize,
+    /// The number of parentheses open at the current token.
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.index).cloned();
+        self.index += 1;
+        token
+    }
+
+    fn next_if(&mut self, kind: &Kind) -> bool {
+        if self.peek().is_some_and(|t| t.kind == *kind) {
+            self.index += 1;
+            return true;
+        }
+
+        false
+    }
+
+    fn position(&self) -> usize {
+        self.peek().map_or(self.end, |t| t.position)
+    }
+
+    /// `expression : sequence { AND sequence }`
+    fn expression(&mut self) -> Result<Expression, Error> {
+        let mut sequences = vec![self.sequence()?];
+
+        while self.next_if(&Kind::And) {
+            sequences.push(self.sequence()?);
+        }
+
+        Ok(flatten(sequences, Expression::And))
+    }
+
+    /// `sequence : factor { factor }`
+    fn sequence(&mut self) -> Result<Expression, Error> {
+        let mut factors = vec![self.factor()?];
+
+        while self
+            .peek()
+            .is_some_and(|t| !matches!(t.kind, Kind::And | Kind::RightParen))
+        {
+            factors.push(self.factor()?);
+        }
+
+        Ok(flatten(factors, Expression::And))
+    }
+
+    /// `factor : term { OR term }`
+    fn factor(&mut self) -> Result<Expression, Error> {
+        let mut terms = vec![self.term()?];
+
+        while self.next_if(&Kind::Or) {
+            terms.push(self.term()?);
+        }
+
+        Ok(flatten(terms, Expression::Or))
+    }
+
+    /// `term : [ NOT | - ] simple`
+    fn term(&mut self) -> Result<Expression, Error> {
+        if self.next_if(&Kind::Not) || self.next_if(&Kind::Minus) {
+            return Ok(Expression::Not(Box::new(self.simple()?)));
+        }
+
+        self.simple()
+    }
+
+    /// `simple : restriction | ( expression )`
+    fn simple(&mut self) -> Result<Expression, Error> {
+        let position = self.position();
+        if self.next_if(&Kind::LeftParen) {
+            if self.depth == MAX_DEPTH {
+                return Err(
+                    InvalidSyntaxError::new(position, "parentheses are nested too deeply").into(),
+                );
+            }
+
+            self.depth += 1;
+            let expression = self.expression()?;
+            self.depth -= 1;
+
+            if !self.next_if(&Kind::RightParen) {
+                return Err(InvalidSyntaxError::new(self.position(), "expected `)`").into());
+            }
+
+            return Ok(expression);
+        }
+
+        self.restriction()
+    }
+
+    /// `restriction : field comparator value`
+    fn restriction(&mut self) -> Result<Expression, Error> {
+        let position = self.position();
+        let Some(Token {
+            kind: Kind::Text(field),
+            ..
+        }) = self.next()
+        else {
+            return Err(InvalidSyntaxError::new(position, "expected field").into());
+        };
+
+        let comparator_position = self.position();
+        let Some(Token {
+            kind: Kind::Comparator(comparator),
+            ..
+        }) = self.next()
+        else {
+            return Err(InvalidSyntaxError::new(comparator_position, "expected comparator").into());
+        };
+
+        let value_position = self.position();
+        let Some(Token {
+            kind: Kind::Text(value) | Kind::String(value),
+            ..
+        }) = self.next()
+        else {
+            return Err(InvalidSyntaxError::new(value_position, "expected value").into());
+        };
+
+        Ok(Expression::Restriction(Restriction {
+            field,
+            position,
+            comparator,
+            value,
+            value_position,
+        }))
+    }
+}
+
+fn flatten(
+    mut expressions: Vec<Expression>,
+    group: fn(Vec<Expression>) -> Expression,
+) -> Expression {
+    if expressions.len() == 1 {
+        if let Some(expression) = expressions.pop() {
+            return expression;
+        }
+    }
+
+    group(expressions)
+}
+
+#[derive(Debug, ThisError)]
+pub enum Error {
+    #[error(transparent)]
+    Empty(#[from] EmptyError),
+    #[error(transparent)]
+    TooLong(#[from] TooLongError),
+    #[error(transparent)]
+    InvalidSyntax(#[from] InvalidSyntaxError),
+    #[error(transparent)]
+    UnknownField(#[from] UnknownFieldError),
+    #[error(transparent)]
+    InvalidComparator(#[from] InvalidComparatorError),
+    #[error(transparent)]
+    InvalidValue(#[from] InvalidValueError),
+}
+
+#[derive(Clone, Debug, ThisError, From)]
+#[error("filter cannot be empty")]
+pub struct EmptyError;
+
+#[derive(Clone, Debug, ThisError)]
+#[error("filter is {length} bytes long, longer than {MAX_LENGTH}")]
+pub struct TooLongError {
+    length: usize,
+}
+
+impl TooLongError {
+    #[must_use]
+    pub const fn new(length: usize) -> Self {
+        Self { length }
+    }
+}
+
+#[derive(Clone, Debug, ThisError)]
+#[error("filter is invalid at position {position}: {message}")]
+pub struct InvalidSyntaxError {
+    position: usize,
+    message: String,
+}
+
+impl InvalidSyntaxError {
+    #[must_use]
+    pub fn new(position: usize, message: &str) -> Self {
+        Self {
+            position,
+            message: message.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, ThisError)]
+#[error("filter is invalid at position {position}: field `{field}` is unknown")]
+pub struct UnknownFieldError {
+    position: usize,
+    field: String,
+}
+
+impl UnknownFieldError {
+    #[must_use]
+    pub fn new(restriction: &Restriction) -> Self {
+        Self {
+            position: restriction.position,
+            field: restriction.field.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, ThisError)]
+#[error(
+    "filter is invalid at position {position}: field `{field}` does not support `{comparator}`"
+)]
+pub struct InvalidComparatorError {
+    position: usize,
+    field: String,
+    comparator: Comparator,
+}
+
+impl InvalidComparatorError {
+    #[must_use]
+    pub fn new(restriction: &Restriction) -> Self {
+        Self {
+            position: restriction.position,
+            field: restriction.field.clone(),
+            comparator: restriction.comparator,
+        }
+    }
+}
+
+#[derive(Clone, Debug, ThisError)]
+#[error(
+    "filter is invalid at position {position}: value `{value}` is not valid for field `{field}`"
+)]
+pub struct InvalidValueError {
+    position: usize,
+    field: String,
+    value: String,
+}
+
+impl InvalidValueError {
+    #[must_use]
+    pub fn new(restriction: &Restriction) -> Self {
+        Self {
+            position: restriction.value_position,
+            field: restriction.field.clone(),
+            value: restriction.value.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn restriction(field: &str, comparator: Comparator, value: &str) -> Expression {
+        Expression::Restriction(Restriction {
+            field: field.to_string(),
+            position: 0,
+            comparator,
+            value: value.to_string(),
+            value_position: 0,
+        })
+    }
+
+    fn without_positions(expression: Expression) -> Expression {
+        match expression {
+            Expression::And(e) => Expression::And(e.into_iter().map(without_positions).collect()),
+            Expression::Or(e) => Expression::Or(e.into_iter().map(without_positions).collect()),
+            Expression::Not(e) => Expression::Not(Box::new(without_positions(*e))),
+            Expression::Restriction(r) => Expression::Restriction(Restriction {
+                position: 0,
+                value_position: 0,
+                ..r
+            }),
+        }
+    }
+
+    #[test]
+    fn parses_conjunction_of_restrictions() -> anyhow::Result<()> {
+        let filter =
+            Filter::try_from(String::from(r#"state = ACTIVE AND display_name : "bike*""#))?;
+
+        assert_eq!(
+            Expression::And(vec![
+                restriction("state", Comparator::Equals, "ACTIVE"),
+                restriction("display_name", Comparator::Has, "bike*"),
+            ]),
+            without_positions(filter.expression().clone())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parses_negation_disjunction_and_parentheses() -> anyhow::Result<()> {
+        let filter = Filter::try_from(String::from(
+            r#"NOT (state = BLOCKED OR create_time < "2024-01-01T00:00:00Z") -title = x"#,
+        ))?;
+
+        assert_eq!(
+            Expression::And(vec![
+                Expression::Not(Box::new(Expression::Or(vec![
+                    restriction("state", Comparator::Equals, "BLOCKED"),
+                    restriction("create_time", Comparator::LessThan, "2024-01-01T00:00:00Z"),
+                ]))),
+                Expression::Not(Box::new(restriction("title", Comparator::Equals, "x"))),
+            ]),
+            without_positions(filter.expression().clone())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reports_position_of_syntax_error() {
+        let err = Filter::try_from(String::from("state = ACTIVE AND (title != ")).err();
+
+        assert!(matches!(
+            err,
+            Some(Error::InvalidSyntax(InvalidSyntaxError {
+                position: 30,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn rejects_deep_nesting_and_long_filters() -> anyhow::Result<()> {
+        let nested = |depth| format!("{}state = ACTIVE{}", "(".repeat(depth), ")".repeat(depth));
+
+        Filter::try_from(nested(MAX_DEPTH))?;
+        assert!(matches!(
+            Filter::try_from(nested(MAX_DEPTH + 1)),
+            Err(Error::InvalidSyntax(_))
+        ));
+        assert!(matches!(
+            Filter::try_from("(".repeat(200_000)),
+            Err(Error::TooLong(_))
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,152 @@
+use derive_getters::Getters;
+use derive_more::derive::From;
+
+use crate::{OrderBy, ThisError};
+
+/// A single field of an [AIP-132](https://google.aip.dev/132#ordering) `order_by`
+/// clause, e.g. `display_name desc`.
+#[derive(Clone, Debug, PartialEq, Eq, Getters)]
+pub struct Ordering {
+    field: String,
+    descending: bool,
+}
+
+impl OrderBy {
+    #[must_use]
+    pub fn orderings(&self) -> &[Ordering] {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for OrderBy {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if value.trim().is_empty() {
+            return Err(EmptyError.into());
+        }
+
+        let mut orderings: Vec<Ordering> = vec![];
+
+        for clause in value.split(',') {
+            let mut words = clause.split_whitespace();
+
+            let Some(field) = words.next() else {
+                return Err(InvalidSyntaxError::new(clause, "expected field").into());
+            };
+
+            let descending = match words.next() {
+                None | Some("asc") => false,
+                Some("desc") => true,
+                Some(_) => {
+                    return Err(InvalidSyntaxError::new(clause, "expected `asc` or `desc`").into());
+                }
+            };
+
+            if words.next().is_some() {
+                return Err(InvalidSyntaxError::new(clause, "unexpected token").into());
+            }
+
+            if orderings.iter().any(|o| o.field == field) {
+                return Err(InvalidSyntaxError::new(clause, "duplicate field").into());
+            }
+
+            orderings.push(Ordering {
+                field: field.to_string(),
+                descending,
+            });
+        }
+
+        Ok(Self(orderings))
+    }
+}
+
+impl TryFrom<Option<String>> for OrderBy {
+    type Error = Error;
+
+    fn try_from(value: Option<String>) -> Result<Self, Self::Error> {
+        value.map_or_else(|| Err(EmptyError.into()), TryInto::try_into)
+    }
+}
+
+#[derive(Debug, ThisError)]
+pub enum Error {
+    #[error(transparent)]
+    Empty(#[from] EmptyError),
+    #[error(transparent)]
+    InvalidSyntax(#[from] InvalidSyntaxError),
+    #[error(transparent)]
+    UnknownField(#[from] UnknownFieldError),
+}
+
+#[derive(Clone, Debug, ThisError, From)]
+#[error("order_by cannot be empty")]
+pub struct EmptyError;
+
+#[derive(Clone, Debug, ThisError)]
+#[error("order_by is invalid at `{clause}`: {message}")]
+pub struct InvalidSyntaxError {
+    clause: String,
+    message: String,
+}
+
+impl InvalidSyntaxError {
+    #[must_use]
+    pub fn new(clause: &str, message: &str) -> Self {
+        Self {
+            clause: clause.trim().to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, ThisError)]
+#[error("order_by field `{field}` is unknown")]
+pub struct UnknownFieldError {
+    field: String,
+}
+
+impl UnknownFieldError {
+    #[must_use]
+    pub fn new(ordering: &Ordering) -> Self {
+        Self {
+            field: ordering.field.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ordering(field: &str, descending: bool) -> Ordering {
+        Ordering {
+            field: field.to_string(),
+            descending,
+        }
+    }
+
+    #[test]
+    fn parses_multiple_fields() -> anyhow::Result<()> {
+        let order_by = OrderBy::try_from(String::from(" display_name desc ,create_time"))?;
+
+        assert_eq!(
+            order_by.orderings(),
+            [
+                ordering("display_name", true),
+                ordering("create_time", false)
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_invalid_clauses() {
+        assert!(OrderBy::try_from(String::new()).is_err());
+        assert!(OrderBy::try_from(String::from("title,")).is_err());
+        assert!(OrderBy::try_from(String::from("title up")).is_err());
+        assert!(OrderBy::try_from(String::from("title desc state")).is_err());
+        assert!(OrderBy::try_from(String::from("title, title desc")).is_err());
+    }
+}
@@ -0,0 +1,171 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use derive_more::derive::From;
+use hmac::{Hmac, Mac};
+use prost::Message;
+use sha2::{Digest, Sha256};
+
+use crate::{PageToken, ThisError};
+
+type HmacSha256 = Hmac<Sha256>;
+
+const SIGNATURE_LEN: usize = 32;
+
+/// The signed content of a [`PageToken`].
+#[derive(Clone, PartialEq, Eq, Message)]
+pub struct Payload {
+    /// Digest of the request parameters the token was issued for.
+    #[prost(bytes = "vec", tag = "1")]
+    parameters: Vec<u8>,
+    /// Sort key of the last element of the previous page.
+    #[prost(string, repeated, tag = "2")]
+    cursor: Vec<String>,
+}
+
+impl PageToken {
+    #[must_use]
+    pub fn new(parameters: &[&str], cursor: Vec<String>) -> Self {
+        Self(Payload {
+            parameters: digest(parameters),
+            cursor,
+        })
+    }
+
+    #[must_use]
+    pub fn cursor(&self) -> &[String] {
+        &self.0.cursor
+    }
+
+    /// Encode the token as an opaque, URL safe string signed with `key`.
+    #[must_use]
+    pub fn encode(&self, key: &Key) -> String {
+        let mut bytes = self.0.encode_to_vec();
+        bytes.extend_from_slice(&key.sign(&bytes).finalize().into_bytes());
+
+        URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if the token was not issued with `key` or has been modified.
+    pub fn decode(value: &str, key: &Key) -> Result<Self, Error> {
+        let bytes = URL_SAFE_NO_PAD.decode(value).map_err(|_| InvalidError)?;
+
+        let Some(split) = bytes.len().checked_sub(SIGNATURE_LEN) else {
+            return Err(InvalidError.into());
+        };
+        let (payload, signature) = bytes.split_at(split);
+
+        key.sign(payload)
+            .verify_slice(signature)
+            .map_err(|_| InvalidError)?;
+
+        let payload = Payload::decode(payload).map_err(|_| InvalidError)?;
+
+        Ok(Self(payload))
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if the token was issued for different request parameters.
+    pub fn verify(&self, parameters: &[&str]) -> Result<(), Error> {
+        if self.0.parameters == digest(parameters) {
+            Ok(())
+        } else {
+            Err(MismatchError.into())
+        }
+    }
+}
+
+fn digest(parameters: &[&str]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+
+    for parameter in parameters {
+        hasher.update((parameter.len() as u64).to_be_bytes());
+        hasher.update(parameter.as_bytes());
+    }
+
+    hasher.finalize().to_vec()
+}
+
+/// Secret used to sign and verify [`PageToken`]s.
+#[derive(Clone)]
+pub struct Key(HmacSha256);
+
+impl Key {
+    /// # Errors
+    ///
+    /// Returns an error if `secret` cannot be used as an HMAC key.
+    pub fn new(secret: &[u8]) -> Result<Self, Error> {
+        <HmacSha256 as Mac>::new_from_slice(secret)
+            .map(Self)
+            .map_err(|_| InvalidKeyError.into())
+    }
+
+    fn sign(&self, payload: &[u8]) -> HmacSha256 {
+        let mut mac = self.0.clone();
+        mac.update(payload);
+        mac
+    }
+}
+
+impl std::fmt::Debug for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Key(..)")
+    }
+}
+
+#[derive(Debug, ThisError)]
+pub enum Error {
+    #[error(transparent)]
+    Invalid(#[from] InvalidError),
+    #[error(transparent)]
+    Mismatch(#[from] MismatchError),
+    #[error(transparent)]
+    InvalidKey(#[from] InvalidKeyError),
+}
+
+#[derive(Clone, Debug, ThisError, From)]
+#[error("page token is invalid")]
+pub struct InvalidError;
+
+#[derive(Clone, Debug, ThisError, From)]
+#[error("page token was issued for a request with different filter or order_by")]
+pub struct MismatchError;
+
+#[derive(Clone, Debug, ThisError, From)]
+#[error("page token key is invalid")]
+pub struct InvalidKeyError;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_signed_token() -> anyhow::Result<()> {
+        let key = Key::new(b"secret")?;
+        let token = PageToken::new(&["state = ACTIVE", ""], vec![String::from("b-max")]);
+
+        let decoded = PageToken::decode(&token.encode(&key), &key)?;
+
+        assert_eq!(token, decoded);
+        assert!(decoded.verify(&["state = ACTIVE", ""]).is_ok());
+        assert!(decoded.verify(&["", "state = ACTIVE"]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_tampered_token() -> anyhow::Result<()> {
+        let key = Key::new(b"secret")?;
+        let token = PageToken::new(&[""], vec![String::from("b-max")]).encode(&key);
+        let mut tampered = token.clone().into_bytes();
+        tampered[0] = if tampered[0] == b'A' { b'B' } else { b'A' };
+        let tampered = String::from_utf8_lossy(&tampered);
+
+        assert!(PageToken::decode(&token, &Key::new(b"other")?).is_err());
+        assert!(PageToken::decode(&tampered, &key).is_err());
+        assert!(PageToken::decode("not a token", &key).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+use derive_more::derive::From;
+use rust_decimal::Decimal;
+
+use crate::ThisError;
+
+use super::Quantity;
+
+impl Quantity {
+    #[must_use]
+    pub const fn new(value: Decimal) -> Self {
+        Self(value)
+    }
+
+    #[must_use]
+    pub const fn zero() -> Self {
+        Self(Decimal::ZERO)
+    }
+
+    #[must_use]
+    pub const fn value(&self) -> &Decimal {
+        &self.0
+    }
+
+    #[must_use]
+    pub fn is_positive(&self) -> bool {
+        self.0 > Decimal::ZERO
+    }
+
+    #[must_use]
+    pub fn is_negative(&self) -> bool {
+        self.0 < Decimal::ZERO
+    }
+
+    #[must_use]
+    pub const fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    /// The same amount with the opposite sign.
+    #[must_use]
+    pub fn negated(&self) -> Self {
+        Self::new((-self.0).normalize())
+    }
+
+    /// # Errors
+    ///
+    /// Returns [`Error::Overflow`] if the sum cannot be represented.
+    pub fn checked_add(&self, other: &Self) -> Result<Self, Error> {
+        self.0
+            .checked_add(other.0)
+            .map(|value| Self::new(value.normalize()))
+            .ok_or_else(|| OverflowError.into())
+    }
+
+    /// # Errors
+    ///
+    /// Returns [`Error::Overflow`] if the difference cannot be represented.
+    pub fn checked_sub(&self, other: &Self) -> Result<Self, Error> {
+        self.0
+            .checked_sub(other.0)
+            .map(|value| Self::new(value.normalize()))
+            .ok_or_else(|| OverflowError.into())
+    }
+
+    /// # Errors
+    ///
+    /// Returns [`Error::Overflow`] if the product cannot be represented.
+    pub fn checked_mul(&self, other: &Self) -> Result<Self, Error> {
+        self.0
+            .checked_mul(other.0)
+            .map(|value| Self::new(value.normalize()))
+            .ok_or_else(|| OverflowError.into())
+    }
+
+    /// # Errors
+    ///
+    /// Returns [`Error::Overflow`] if `other` is zero or the quotient cannot be represented.
+    pub fn checked_div(&self, other: &Self) -> Result<Self, Error> {
+        self.0
+            .checked_div(other.0)
+            .map(|value| Self::new(value.normalize()))
+            .ok_or_else(|| OverflowError.into())
+    }
+}
+
+impl TryFrom<String> for Quantity {
+    type Error = Error;
+
+    /// Parse a quantity written as a plain decimal number, e.g. `12.5`. Values that would
+    /// lose precision are rejected rather than rounded.
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(EmptyError.into());
+        }
+
+        Decimal::from_str_exact(value).map_or_else(
+            |_| {
+                Err(InvalidFormatError {
+                    raw: value.to_string(),
+                }
+                .into())
+            },
+            |value| Ok(Self::new(value.normalize())),
+        )
+    }
+}
+
+impl From<Quantity> for String {
+    fn from(value: Quantity) -> Self {
+        value.to_string()
+    }
+}
+
+#[derive(Debug, ThisError)]
+pub enum Error {
+    #[error(transparent)]
+    Empty(#[from] EmptyError),
+    #[error(transparent)]
+    InvalidFormat(#[from] InvalidFormatError),
+    #[error(transparent)]
+    Overflow(#[from] OverflowError),
+}
+
+#[derive(Clone, Debug, ThisError, From)]
+#[error("quantity cannot be empty")]
+pub struct EmptyError;
+
+#[derive(Clone, Debug, ThisError, From)]
+#[error("quantity format is invalid: expected a decimal number, got {raw}")]
+pub struct InvalidFormatError {
+    raw: String,
+}
+
+#[derive(Clone, Debug, ThisError, From)]
+#[error("quantity is out of range")]
+pub struct OverflowError;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quantity(value: &str) -> anyhow::Result<Quantity> {
+        Ok(Quantity::try_from(value.to_string())?)
+    }
+
+    #[test]
+    fn parses_exact_decimals() -> anyhow::Result<()> {
+        assert_eq!("12.5", quantity(" 12.50 ")?.to_string());
+        assert_eq!("0.1", quantity("0.1")?.to_string());
+        assert!(quantity("").is_err());
+        assert!(quantity("1e3").is_err());
+        assert!(quantity("0.1234567890123456789012345678901").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn computes_without_rounding() -> anyhow::Result<()> {
+        let sum = quantity("0.1")?.checked_add(&quantity("0.2")?)?;
+        assert_eq!(quantity("0.3")?, sum);
+
+        let product = quantity("2.5")?.checked_mul(&quantity("0.001")?)?;
+        assert_eq!("0.0025", product.to_string());
+
+        assert!(quantity("1")?.checked_div(&Quantity::zero()).is_err());
+
+        let difference = quantity("1")?.checked_sub(&quantity("2.5")?)?;
+        assert!(difference.is_negative());
+        assert_eq!(quantity("1.5")?, difference.negated());
+
+        Ok(())
+    }
+}
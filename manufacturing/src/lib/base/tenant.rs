@@ -0,0 +1,103 @@
+use std::future::Future;
+
+use crate::{id, Id, Tenant};
+
+/// The collection of organizations, whose resources are named
+/// `organizations/{organization}/{name}`.
+pub const ORGANIZATION_COLLECTION: &str = "organizations";
+
+tokio::task_local! {
+    static CURRENT: Tenant;
+}
+
+impl Tenant {
+    #[must_use]
+    pub const fn new(organization: Id) -> Self {
+        Self(organization)
+    }
+
+    /// The tenant the current task serves, if it serves one.
+    #[must_use]
+    pub fn current() -> Option<Self> {
+        CURRENT.try_with(Clone::clone).ok()
+    }
+
+    /// Run `future` for `tenant`, or for the current tenant if there is none.
+    pub async fn scope<F: Future>(tenant: Option<Self>, future: F) -> F::Output {
+        match tenant {
+            Some(tenant) => CURRENT.scope(tenant, future).await,
+            None => future.await,
+        }
+    }
+
+    /// The resource `name` within the organization of [`Tenant::current`], if there is one.
+    #[must_use]
+    pub fn qualify(name: &str) -> String {
+        Self::current().map_or_else(
+            || name.to_string(),
+            |tenant| format!("{ORGANIZATION_COLLECTION}/{tenant}/{name}"),
+        )
+    }
+
+    /// `name` without the organization of [`Tenant::current`]. The name of a resource of
+    /// another organization is kept whole, so that it never refers to a resource of the
+    /// current tenant.
+    #[must_use]
+    pub fn unqualify(name: &str) -> &str {
+        name.strip_prefix(ORGANIZATION_COLLECTION)
+            .and_then(|name| name.strip_prefix('/')?.split_once('/'))
+            .filter(|(organization, _)| {
+                Self::current().is_some_and(|tenant| tenant.value() == organization)
+            })
+            .map_or(name, |(_, name)| name)
+    }
+}
+
+impl TryFrom<String> for Tenant {
+    type Error = id::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Ok(Self(value.try_into()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn scopes_futures_to_a_tenant() -> anyhow::Result<()> {
+        let acme = Tenant::try_from(String::from("acme"))?;
+
+        assert_eq!(None, Tenant::current());
+        let current = Tenant::scope(Some(acme.clone()), async { Tenant::current() }).await;
+        assert_eq!(Some(acme.clone()), current);
+        let nested = Tenant::scope(
+            Some(acme.clone()),
+            Tenant::scope(None, async { Tenant::current() }),
+        )
+        .await;
+        assert_eq!(Some(acme.clone()), nested);
+        assert_eq!("items/anvil", Tenant::qualify("items/anvil"));
+
+        let names = Tenant::scope(Some(acme), async {
+            (
+                Tenant::qualify("items/anvil"),
+                Tenant::unqualify("organizations/acme/items/anvil").to_string(),
+                Tenant::unqualify("organizations/globex/items/anvil").to_string(),
+            )
+        })
+        .await;
+        assert_eq!(
+            (
+                String::from("organizations/acme/items/anvil"),
+                String::from("items/anvil"),
+                String::from("organizations/globex/items/anvil"),
+            ),
+            names
+        );
+        assert!(Tenant::try_from(String::from("Acme Inc")).is_err());
+
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
-use std::str::FromStr;
+use std::{str::FromStr, time::Duration};
 
-use chrono::{DateTime, ParseError, Utc};
+use chrono::{DateTime, ParseError, TimeDelta, Utc};
 use derive_more::derive::From;
 
 use crate::ThisError;
@@ -22,6 +22,17 @@ impl Timestamp {
     pub const fn value(&self) -> &DateTime<Utc> {
         &self.0
     }
+
+    /// The timestamp `duration` after this one, saturating at the latest representable time.
+    #[must_use]
+    pub fn plus(&self, duration: Duration) -> Self {
+        let value = TimeDelta::from_std(duration)
+            .ok()
+            .and_then(|delta| self.0.checked_add_signed(delta))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+
+        Self::new(value)
+    }
 }
 
 impl TryFrom<String> for Timestamp {
@@ -102,6 +113,16 @@ mod tests {
         assert!(before < now && now < after);
     }
 
+    #[test]
+    fn plus_adds_duration_and_saturates() {
+        let now = Timestamp::now();
+        let later = now.plus(Duration::from_mins(1));
+        assert_eq!(60, (*later.value() - *now.value()).num_seconds());
+
+        let never = now.plus(Duration::MAX);
+        assert_eq!(DateTime::<Utc>::MAX_UTC, *never.value());
+    }
+
     #[test]
     fn value_should_return_initial_value() {
         let now = Timestamp::now();
@@ -1,10 +1,20 @@
+use std::time::Duration;
+
 use derive_getters::{Dissolve, Getters};
 use derive_more::From;
+use prost_types::Any;
 use uuid::Uuid;
 
-use crate::{EntityTag, Id, Timestamp};
+use crate::{EntityTag, Id, Quantity, Timestamp};
 
+pub mod bill_of_materials;
 pub mod item;
+pub mod location;
+pub mod production_order;
+pub mod routing;
+pub mod stock_movement;
+pub mod traceability;
+pub mod unit_of_measure;
 
 #[repr(i32)]
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, FromPrimitive, ToPrimitive)]
@@ -15,8 +25,22 @@ pub enum ItemState {
     Annihilating = 4,
     Blocking = 5,
     Unblocking = 6,
+    Undeleting = 7,
     Active = 10,
     Blocked = 11,
+    Deleted = 12,
+}
+
+/// How individual units of an item are told apart in stock.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, FromPrimitive, ToPrimitive)]
+pub enum Tracking {
+    /// Units are interchangeable, only quantities are tracked.
+    None = 1,
+    /// Units are tracked by the lot they were produced or received in.
+    Lot = 2,
+    /// Every unit is tracked by its own serial number.
+    Serial = 3,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, From, Getters, Dissolve)]
@@ -30,4 +54,297 @@ pub struct Item {
     uid: Uuid,
     create_time: Timestamp,
     update_time: Timestamp,
+    /// The unit the item is counted in, which every quantity of the item converts into.
+    base_uom: Id,
+    /// Whether stock of the item is tracked by lot or serial number.
+    tracking: Tracking,
+    /// When the item was soft-deleted, if it is deleted.
+    delete_time: Option<Timestamp>,
+    /// When a soft-deleted item is purged for good.
+    expire_time: Option<Timestamp>,
+}
+
+/// What happened to an item, after the verb of the operation that wrote it.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum ItemEventKind {
+    Created = 1,
+    Updated = 2,
+    Deleted = 3,
+    Annihilated = 4,
+    Blocked = 5,
+    Unblocked = 6,
+    Undeleted = 7,
+}
+
+/// An entry of the item change-event outbox, appended in the same transaction as the write it
+/// records. An operation records one event, when it completes.
+#[derive(Clone, Debug, PartialEq, From, Getters, Dissolve)]
+pub struct ItemEvent {
+    /// Increases with every event, so a watcher can resume after the last one it saw.
+    sequence: i64,
+    item: Id,
+    kind: ItemEventKind,
+    /// The item as written, in its wire representation, or `None` once it is removed for good.
+    snapshot: Option<Any>,
+    create_time: Timestamp,
+}
+
+/// A revision of an item, recorded when an operation starts changing it. The snapshot is the
+/// item as its operation left it, so it stays transitional until the operation completes.
+#[derive(Clone, Debug, PartialEq, Eq, From, Getters, Dissolve)]
+pub struct ItemRevision {
+    item: Item,
+    id: Id,
+    operation: Id,
+    /// Who requested the change, empty for changes the service makes on its own.
+    actor: String,
+    create_time: Timestamp,
+}
+
+#[repr(i32)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, FromPrimitive, ToPrimitive)]
+pub enum ProductionOrderState {
+    Creating = 1,
+    Updating = 2,
+    Deleting = 3,
+    Releasing = 4,
+    Completing = 5,
+    Finishing = 6,
+    Cancelling = 7,
+    Planned = 10,
+    Released = 11,
+    Completed = 12,
+    Finished = 13,
+    Cancelled = 14,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, From, Getters, Dissolve)]
+pub struct ProductionOrder {
+    id: Id,
+    display_name: String,
+    title: String,
+    description: String,
+    /// The item being produced, `None` once a closed production order outlived its item.
+    item: Option<Id>,
+    quantity: Quantity,
+    /// The operations to perform, copied from the item's routing when the order is released.
+    operations: Vec<ProductionOrderOperation>,
+    state: ProductionOrderState,
+    etag: EntityTag,
+    uid: Uuid,
+    create_time: Timestamp,
+    update_time: Timestamp,
+}
+
+#[repr(i32)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, FromPrimitive, ToPrimitive)]
+pub enum ProductionOrderOperationState {
+    Pending = 1,
+    Started = 2,
+    Completed = 3,
+}
+
+/// A work step of a released production order.
+#[derive(Clone, Debug, PartialEq, Eq, From, Getters, Dissolve)]
+pub struct ProductionOrderOperation {
+    /// Numbers the operations of a production order in their order, starting at 1.
+    id: Id,
+    work_center: String,
+    setup_time: Duration,
+    /// The time it takes to process a single unit.
+    run_time: Duration,
+    state: ProductionOrderOperationState,
+    /// The quantity reported as processed so far.
+    reported_quantity: Quantity,
+    start_time: Option<Timestamp>,
+    complete_time: Option<Timestamp>,
+}
+
+#[repr(i32)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, FromPrimitive, ToPrimitive)]
+pub enum BillOfMaterialsState {
+    Creating = 1,
+    Activating = 2,
+    Draft = 10,
+    Active = 11,
+    Obsolete = 12,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, From, Getters, Dissolve)]
+pub struct BillOfMaterials {
+    id: Id,
+    /// The item the bill of materials produces.
+    item: Id,
+    /// Numbers the bills of materials of an item in the order they were created, starting at 1.
+    version: i64,
+    display_name: String,
+    description: String,
+    components: Vec<Component>,
+    state: BillOfMaterialsState,
+    etag: EntityTag,
+    uid: Uuid,
+    create_time: Timestamp,
+    update_time: Timestamp,
+}
+
+/// A line of a bill of materials, consuming `quantity` of another item per unit produced.
+#[derive(Clone, Debug, PartialEq, Eq, From, Getters, Dissolve)]
+pub struct Component {
+    item: Id,
+    quantity: Quantity,
+    unit_of_measure: String,
+    /// The share of `quantity` expected to be lost in production, e.g. `0.05` for 5%.
+    scrap_factor: Quantity,
+}
+
+#[repr(i32)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, FromPrimitive, ToPrimitive)]
+pub enum RoutingState {
+    Creating = 1,
+    Updating = 2,
+    Active = 10,
+}
+
+/// The work steps producing an item, of which every item has at most one.
+#[derive(Clone, Debug, PartialEq, Eq, From, Getters, Dissolve)]
+pub struct Routing {
+    /// The item the routing produces, which also identifies the routing.
+    item: Id,
+    operations: Vec<RoutingOperation>,
+    state: RoutingState,
+    etag: EntityTag,
+    uid: Uuid,
+    create_time: Timestamp,
+    update_time: Timestamp,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, From, Getters, Dissolve)]
+pub struct RoutingOperation {
+    work_center: String,
+    setup_time: Duration,
+    /// The time it takes to process a single unit.
+    run_time: Duration,
+}
+
+/// The physical quantity a unit of measure measures.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, FromPrimitive, ToPrimitive)]
+pub enum Dimension {
+    Count = 1,
+    Mass = 2,
+    Length = 3,
+    Area = 4,
+    Volume = 5,
+    Time = 6,
+}
+
+/// A unit quantities are measured in, convertible into the other units of its dimension.
+#[derive(Clone, Debug, PartialEq, Eq, From, Getters, Dissolve)]
+pub struct UnitOfMeasure {
+    id: Id,
+    display_name: String,
+    dimension: Dimension,
+    /// How many of the dimension's reference unit one of this unit equals, e.g. `0.001`
+    /// for grams, when mass is referenced in kilograms.
+    factor: Quantity,
+}
+
+/// A place stock is kept at, e.g. a warehouse bin or a line-side rack.
+#[derive(Clone, Debug, PartialEq, Eq, From, Getters, Dissolve)]
+pub struct Location {
+    id: Id,
+    display_name: String,
+    /// Whether stock movements may take the on-hand quantity of an item at the location below zero.
+    allow_negative_stock: bool,
+    etag: EntityTag,
+    uid: Uuid,
+    create_time: Timestamp,
+    update_time: Timestamp,
+}
+
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, FromPrimitive, ToPrimitive)]
+pub enum StockMovementKind {
+    /// Stock entering a location from outside, e.g. from a supplier or production.
+    Receipt = 1,
+    /// Stock leaving a location, e.g. consumed by production or shipped.
+    Issue = 2,
+    /// Stock moving from one location to another.
+    Transfer = 3,
+    /// A correction of the on-hand quantity at a location, e.g. after a count.
+    Adjustment = 4,
+}
+
+/// An entry of the append-only stock ledger, which is never changed once posted.
+#[derive(Clone, Debug, PartialEq, Eq, From, Getters, Dissolve)]
+pub struct StockMovement {
+    id: Id,
+    kind: StockMovementKind,
+    item: Id,
+    /// The quantity moved, in the base unit of the item. Only adjustments may be negative.
+    quantity: Quantity,
+    /// The location the stock leaves, for issues and transfers.
+    source_location: Option<Id>,
+    /// The location the stock enters, for receipts and transfers, or the adjusted location.
+    target_location: Option<Id>,
+    /// The lot moved, required for lot-tracked items.
+    lot: Option<Id>,
+    /// The serial moved, required for serial-tracked items.
+    serial: Option<Id>,
+    /// The production order the movement consumes components for or receives output of.
+    production_order: Option<Id>,
+    reason: String,
+    uid: Uuid,
+    create_time: Timestamp,
+}
+
+/// The on-hand quantity of an item at a location, the sum of the stock movements there.
+#[derive(Clone, Debug, PartialEq, Eq, From, Getters, Dissolve)]
+pub struct StockBalance {
+    item: Id,
+    location: Id,
+    /// The on-hand quantity, in the base unit of the item.
+    quantity: Quantity,
+}
+
+/// A quantity of a lot-tracked item produced or received together.
+#[derive(Clone, Debug, PartialEq, Eq, From, Getters, Dissolve)]
+pub struct Lot {
+    item: Id,
+    /// Unique among the lots of the item.
+    id: Id,
+    description: String,
+    uid: Uuid,
+    create_time: Timestamp,
+}
+
+/// A single unit of a serial-tracked item.
+#[derive(Clone, Debug, PartialEq, Eq, From, Getters, Dissolve)]
+pub struct Serial {
+    item: Id,
+    /// Unique among the serials of the item.
+    id: Id,
+    /// Where the unit is on hand, `None` until it is received and after it is issued.
+    location: Option<Id>,
+    uid: Uuid,
+    create_time: Timestamp,
+    update_time: Timestamp,
+}
+
+/// A lot or serial of an item, the unit in which genealogy is traced.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Traced {
+    Lot { item: Id, lot: Id },
+    Serial { item: Id, serial: Id },
+}
+
+/// A lot or serial reached while tracing genealogy, through the production order linking it
+/// to the previous one.
+#[derive(Clone, Debug, PartialEq, Eq, From, Getters, Dissolve)]
+pub struct GenealogyNode {
+    traced: Traced,
+    production_order: Id,
+    /// The number of production orders between the node and the origin of the trace.
+    depth: u32,
 }
@@ -0,0 +1,512 @@
+use derive_getters::Getters;
+use derive_more::derive::From;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::{
+    entity_tag, filter, id, item, order_by, page_token, quantity, sync::OperationState, timestamp,
+    unit_of_measure, BillOfMaterials, BillOfMaterialsState, Component, EntityTag, Id, Item,
+    ItemState, Quantity, ThisError, Timestamp,
+};
+
+pub mod command;
+pub mod query;
+pub mod repository;
+pub mod sync;
+pub mod worker;
+
+impl BillOfMaterialsState {
+    /// Look up a state by its API name, e.g. `ACTIVE`.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "CREATING" => Some(Self::Creating),
+            "ACTIVATING" => Some(Self::Activating),
+            "DRAFT" => Some(Self::Draft),
+            "ACTIVE" => Some(Self::Active),
+            "OBSOLETE" => Some(Self::Obsolete),
+            _ => None,
+        }
+    }
+
+    /// The API name of the state, e.g. `ACTIVE`.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Creating => "CREATING",
+            Self::Activating => "ACTIVATING",
+            Self::Draft => "DRAFT",
+            Self::Active => "ACTIVE",
+            Self::Obsolete => "OBSOLETE",
+        }
+    }
+}
+
+impl BillOfMaterials {
+    /// Draft `version` of the bill of materials producing an active `item`.
+    pub(crate) fn new(
+        id: String,
+        item: &Item,
+        version: i64,
+        display_name: String,
+        description: String,
+        components: Vec<Component>,
+    ) -> Result<Self, Error> {
+        if *item.state() != ItemState::Active {
+            return Err(ItemNotActiveError::new(item).into());
+        }
+
+        if components.is_empty() {
+            return Err(NoComponentsError.into());
+        }
+
+        let now = Timestamp::now();
+
+        Ok(Self {
+            id: id.try_into()?,
+            item: item.id().clone(),
+            version,
+            display_name,
+            description,
+            components,
+            state: BillOfMaterialsState::Creating,
+            etag: EntityTag::new(),
+            uid: Uuid::new_v4(),
+            create_time: now.clone(),
+            update_time: now,
+        })
+    }
+
+    /// The resource name of the bill of materials.
+    #[must_use]
+    pub fn name(&self) -> String {
+        bill_of_materials_name(&self.item, &self.id)
+    }
+
+    /// Only drafts can be activated, activation supersedes the item's active bill of materials.
+    pub(crate) fn activate(self) -> Result<Self, Error> {
+        if self.state != BillOfMaterialsState::Draft {
+            return Err(InvalidStateError::new(&self).into());
+        }
+
+        Ok(self.transition(BillOfMaterialsState::Activating))
+    }
+
+    /// Move a transitioning bill of materials into the state its operation leads to.
+    pub(crate) fn settle(self) -> Result<Self, Error> {
+        match self.state.next() {
+            Some(state) => Ok(self.transition(state)),
+            None => Err(InvalidStateError::new(&self).into()),
+        }
+    }
+
+    fn transition(self, state: BillOfMaterialsState) -> Self {
+        Self {
+            state,
+            etag: EntityTag::new(),
+            update_time: Timestamp::now(),
+            ..self
+        }
+    }
+}
+
+impl Component {
+    /// Consume `quantity` of an active `item`, of which `scrap_factor` is expected to be lost.
+    pub(crate) fn new(
+        item: &Item,
+        quantity: Quantity,
+        unit_of_measure: String,
+        scrap_factor: Quantity,
+    ) -> Result<Self, Error> {
+        if *item.state() != ItemState::Active {
+            return Err(ItemNotActiveError::new(item).into());
+        }
+
+        if !quantity.is_positive() {
+            return Err(InvalidQuantityError::new(quantity).into());
+        }
+
+        if scrap_factor.is_negative() {
+            return Err(InvalidScrapFactorError::new(scrap_factor).into());
+        }
+
+        if unit_of_measure.trim().is_empty() {
+            return Err(EmptyUnitOfMeasureError.into());
+        }
+
+        Ok(Self {
+            item: item.id().clone(),
+            quantity,
+            unit_of_measure,
+            scrap_factor,
+        })
+    }
+
+    /// The quantity to issue per unit produced, including the expected scrap.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`quantity::Error::Overflow`] if the quantity cannot be represented.
+    pub fn gross_quantity(&self) -> Result<Quantity, quantity::Error> {
+        let scrap = Quantity::new(Decimal::ONE).checked_add(&self.scrap_factor)?;
+
+        self.quantity.checked_mul(&scrap)
+    }
+}
+
+/// Convert `quantity` of a component, given in `unit_of_measure`, into `base_uom`, the base
+/// unit of the component's item.
+pub(crate) async fn convert<UR: unit_of_measure::repository::Get>(
+    unit_of_measure_repository: &UR,
+    quantity: &Quantity,
+    unit_of_measure: &str,
+    base_uom: &Id,
+) -> Result<Quantity, Error> {
+    if base_uom.value() == unit_of_measure {
+        return Ok(quantity.clone());
+    }
+
+    let from = Id::try_from(unit_of_measure.to_string())?;
+    let from = unit_of_measure_repository.get(&from).await?;
+    let to = unit_of_measure_repository.get(base_uom).await?;
+
+    Ok(from.convert(quantity, &to)?)
+}
+
+pub(crate) fn bill_of_materials_name(item: &Id, id: &Id) -> String {
+    format!("items/{item}/boms/{id}")
+}
+
+#[derive(Debug, ThisError)]
+pub enum Error {
+    #[error(transparent)]
+    Empty(#[from] EmptyError),
+    #[error(transparent)]
+    InvalidName(#[from] InvalidNameError),
+    #[error(transparent)]
+    NotFound(#[from] NotFoundError),
+    #[error(transparent)]
+    AlreadyExists(#[from] AlreadyExistsError),
+    #[error(transparent)]
+    EtagMismatch(#[from] EtagMismatchError),
+    #[error(transparent)]
+    InvalidState(#[from] InvalidStateError),
+    #[error(transparent)]
+    NoComponents(#[from] NoComponentsError),
+    #[error(transparent)]
+    InvalidQuantity(#[from] InvalidQuantityError),
+    #[error(transparent)]
+    InvalidScrapFactor(#[from] InvalidScrapFactorError),
+    #[error(transparent)]
+    EmptyUnitOfMeasure(#[from] EmptyUnitOfMeasureError),
+    #[error(transparent)]
+    ItemNotActive(#[from] ItemNotActiveError),
+    #[error(transparent)]
+    Cycle(#[from] CycleError),
+    #[error(transparent)]
+    Item(#[from] item::Error),
+    #[error(transparent)]
+    UnitOfMeasure(#[from] unit_of_measure::Error),
+    #[error(transparent)]
+    Quantity(#[from] quantity::Error),
+    #[error(transparent)]
+    Id(#[from] id::Error),
+    #[error(transparent)]
+    Etag(#[from] entity_tag::Error),
+    #[error(transparent)]
+    Timestamp(#[from] timestamp::Error),
+    #[error(transparent)]
+    Filter(#[from] filter::Error),
+    #[error(transparent)]
+    OrderBy(#[from] order_by::Error),
+    #[error(transparent)]
+    PageToken(#[from] page_token::Error),
+    #[error(transparent)]
+    Operation(#[from] crate::sync::Error),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Clone, Debug, ThisError, From)]
+#[error("bill of materials cannot be empty")]
+pub struct EmptyError;
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("{name:?} is not a bill of materials name, expected items/{{item}}/boms/{{bom}}")]
+pub struct InvalidNameError {
+    name: String,
+}
+
+impl InvalidNameError {
+    #[must_use]
+    pub const fn new(name: String) -> Self {
+        Self { name }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("bill of materials {id:?} of item {item:?} not found")]
+pub struct NotFoundError {
+    item: Id,
+    id: Id,
+}
+
+impl NotFoundError {
+    #[must_use]
+    pub const fn new(item: Id, id: Id) -> Self {
+        Self { item, id }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("bill of materials {id:?} of item {item:?} already exists")]
+pub struct AlreadyExistsError {
+    item: Id,
+    id: Id,
+}
+
+impl AlreadyExistsError {
+    #[must_use]
+    pub const fn new(item: Id, id: Id) -> Self {
+        Self { item, id }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error(
+    "etag {etag:?} does not match the current etag of bill of materials {id:?} of item {item:?}"
+)]
+pub struct EtagMismatchError {
+    item: Id,
+    id: Id,
+    etag: String,
+}
+
+impl EtagMismatchError {
+    #[must_use]
+    pub const fn new(item: Id, id: Id, etag: String) -> Self {
+        Self { item, id, etag }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("bill of materials {id:?} of item {item:?} cannot be changed while it is {}", state.name())]
+pub struct InvalidStateError {
+    item: Id,
+    id: Id,
+    state: BillOfMaterialsState,
+}
+
+impl InvalidStateError {
+    #[must_use]
+    pub fn new(bill_of_materials: &BillOfMaterials) -> Self {
+        Self {
+            item: bill_of_materials.item.clone(),
+            id: bill_of_materials.id.clone(),
+            state: bill_of_materials.state.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, From)]
+#[error("bill of materials needs at least one component")]
+pub struct NoComponentsError;
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("quantity {quantity} must be greater than zero")]
+pub struct InvalidQuantityError {
+    quantity: Quantity,
+}
+
+impl InvalidQuantityError {
+    #[must_use]
+    pub const fn new(quantity: Quantity) -> Self {
+        Self { quantity }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("scrap factor {scrap_factor} must not be less than zero")]
+pub struct InvalidScrapFactorError {
+    scrap_factor: Quantity,
+}
+
+impl InvalidScrapFactorError {
+    #[must_use]
+    pub const fn new(scrap_factor: Quantity) -> Self {
+        Self { scrap_factor }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, From)]
+#[error("unit of measure cannot be empty")]
+pub struct EmptyUnitOfMeasureError;
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("item {id:?} cannot be used in a bill of materials while it is {}", state.name())]
+pub struct ItemNotActiveError {
+    id: Id,
+    state: ItemState,
+}
+
+impl ItemNotActiveError {
+    #[must_use]
+    pub fn new(item: &Item) -> Self {
+        Self {
+            id: item.id().clone(),
+            state: item.state().clone(),
+        }
+    }
+}
+
+/// A bill of materials would make an item a component of itself, following `path`
+/// from the item through the active bills of materials of its components.
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("item {:?} would consume itself through {}", path.first(), path.iter().map(Id::value).cloned().collect::<Vec<_>>().join(" -> "))]
+pub struct CycleError {
+    path: Vec<Id>,
+}
+
+impl CycleError {
+    #[must_use]
+    pub const fn new(path: Vec<Id>) -> Self {
+        Self { path }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Tracking;
+
+    use super::*;
+
+    fn item(id: &str, active: bool) -> anyhow::Result<Item> {
+        let item = Item::new(
+            id.to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::from("pcs"),
+            Tracking::None,
+        )?;
+        let item = if active { item.settle()? } else { Some(item) };
+
+        item.ok_or_else(|| anyhow::anyhow!("item was removed"))
+    }
+
+    fn quantity(value: &str) -> anyhow::Result<Quantity> {
+        Ok(Quantity::try_from(value.to_string())?)
+    }
+
+    fn component(quantity: &str, scrap_factor: &str) -> anyhow::Result<Component> {
+        Ok(Component::new(
+            &item("wheel", true)?,
+            self::quantity(quantity)?,
+            String::from("pcs"),
+            self::quantity(scrap_factor)?,
+        )?)
+    }
+
+    fn bill_of_materials() -> anyhow::Result<BillOfMaterials> {
+        Ok(BillOfMaterials::new(
+            String::from("standard"),
+            &item("bike", true)?,
+            1,
+            String::new(),
+            String::new(),
+            vec![component("2", "0")?],
+        )?)
+    }
+
+    #[test]
+    fn follows_the_lifecycle() -> anyhow::Result<()> {
+        let draft = bill_of_materials()?.settle()?;
+        assert_eq!(&BillOfMaterialsState::Draft, draft.state());
+        assert_eq!("items/bike/boms/standard", draft.name());
+
+        let active = draft.activate()?.settle()?;
+        assert_eq!(&BillOfMaterialsState::Active, active.state());
+        assert!(active.clone().activate().is_err());
+
+        let obsolete = active.transition(BillOfMaterialsState::Obsolete);
+        assert_eq!(&BillOfMaterialsState::Obsolete, obsolete.state());
+        assert!(obsolete.clone().activate().is_err());
+        assert!(obsolete.settle().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn validates_components() -> anyhow::Result<()> {
+        let new = |components| {
+            BillOfMaterials::new(
+                String::from("standard"),
+                &item("bike", true)?,
+                1,
+                String::new(),
+                String::new(),
+                components,
+            )
+            .map_err(anyhow::Error::from)
+        };
+
+        assert!(new(vec![]).is_err());
+        assert!(matches!(
+            BillOfMaterials::new(
+                String::from("standard"),
+                &item("bike", false)?,
+                1,
+                String::new(),
+                String::new(),
+                vec![component("1", "0")?],
+            ),
+            Err(Error::ItemNotActive(_))
+        ));
+        let (one, zero) = (quantity("1")?, quantity("0")?);
+        assert!(matches!(
+            Component::new(
+                &item("wheel", false)?,
+                one.clone(),
+                String::from("pcs"),
+                zero.clone()
+            ),
+            Err(Error::ItemNotActive(_))
+        ));
+        assert!(matches!(
+            Component::new(
+                &item("wheel", true)?,
+                zero.clone(),
+                String::from("pcs"),
+                zero.clone()
+            ),
+            Err(Error::InvalidQuantity(_))
+        ));
+        assert!(matches!(
+            Component::new(
+                &item("wheel", true)?,
+                one.clone(),
+                String::from("pcs"),
+                quantity("-0.1")?
+            ),
+            Err(Error::InvalidScrapFactor(_))
+        ));
+        assert!(matches!(
+            Component::new(&item("wheel", true)?, one, String::from(" "), zero),
+            Err(Error::EmptyUnitOfMeasure(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn includes_scrap_in_gross_quantity() -> anyhow::Result<()> {
+        assert_eq!(quantity("2.5")?, component("2", "0.25")?.gross_quantity()?);
+        assert_eq!(quantity("2")?, component("2", "0")?.gross_quantity()?);
+        assert_eq!(
+            quantity("0.33")?,
+            component("0.3", "0.1")?.gross_quantity()?
+        );
+
+        Ok(())
+    }
+}
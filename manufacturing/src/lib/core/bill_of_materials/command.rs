@@ -0,0 +1,307 @@
+use uuid::Uuid;
+
+pub use super::Error;
+use super::{repository, sync::Metadata, AlreadyExistsError, CycleError, EtagMismatchError};
+
+use std::{collections::HashSet, future::Future, sync::Arc};
+
+use crate::{
+    item, sync::Operation, unit_of_measure, BillOfMaterials, Component, EntityTag, Id, Quantity,
+};
+
+// MARK: Create
+
+pub trait Create: Send + Sync + 'static {
+    #[must_use]
+    fn create(
+        &self,
+        request: CreateRequest,
+    ) -> impl Future<Output = Result<Operation<Metadata>, Error>> + Send;
+}
+
+pub struct CreateRequest {
+    item: String,
+    id: Option<String>,
+    display_name: String,
+    description: String,
+    components: Vec<ComponentRequest>,
+}
+
+impl CreateRequest {
+    #[must_use]
+    pub const fn new(
+        item: String,
+        id: Option<String>,
+        display_name: String,
+        description: String,
+        components: Vec<ComponentRequest>,
+    ) -> Self {
+        Self {
+            item,
+            id,
+            display_name,
+            description,
+            components,
+        }
+    }
+}
+
+pub struct ComponentRequest {
+    item: String,
+    quantity: String,
+    unit_of_measure: String,
+    scrap_factor: String,
+}
+
+impl ComponentRequest {
+    /// Consume `quantity` of `item`, without scrap if `scrap_factor` is empty.
+    #[must_use]
+    pub fn new(
+        item: String,
+        quantity: String,
+        unit_of_measure: String,
+        scrap_factor: String,
+    ) -> Self {
+        Self {
+            item,
+            quantity,
+            unit_of_measure,
+            scrap_factor: Some(scrap_factor)
+                .filter(|s| !s.trim().is_empty())
+                .unwrap_or_else(|| String::from("0")),
+        }
+    }
+}
+
+// MARK: Activate
+
+pub trait Activate: Send + Sync + 'static {
+    #[must_use]
+    fn activate(
+        &self,
+        request: ActivateRequest,
+    ) -> impl Future<Output = Result<Operation<Metadata>, Error>> + Send;
+}
+
+pub struct ActivateRequest {
+    item: String,
+    id: String,
+    etag: String,
+}
+
+impl ActivateRequest {
+    #[must_use]
+    pub const fn new(item: String, id: String, etag: String) -> Self {
+        Self { item, id, etag }
+    }
+}
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+#[allow(clippy::struct_field_names)]
+pub struct Service<
+    BR: repository::Get
+        + repository::Create
+        + repository::Update
+        + repository::Active
+        + repository::Latest
+        + Clone,
+    IR: item::repository::Get + Clone,
+    UR: unit_of_measure::repository::Get + Clone,
+> {
+    bill_of_materials_repository: Arc<BR>,
+    item_repository: Arc<IR>,
+    unit_of_measure_repository: Arc<UR>,
+}
+
+impl<BR, IR, UR> Service<BR, IR, UR>
+where
+    BR: repository::Get
+        + repository::Create
+        + repository::Update
+        + repository::Active
+        + repository::Latest
+        + Clone,
+    IR: item::repository::Get + Clone,
+    UR: unit_of_measure::repository::Get + Clone,
+{
+    #[must_use]
+    pub const fn new(
+        bill_of_materials_repository: Arc<BR>,
+        item_repository: Arc<IR>,
+        unit_of_measure_repository: Arc<UR>,
+    ) -> Self {
+        Self {
+            bill_of_materials_repository,
+            item_repository,
+            unit_of_measure_repository,
+        }
+    }
+
+    async fn validate_create_request(
+        &self,
+        request: CreateRequest,
+    ) -> Result<Operation<Metadata>, Error> {
+        let item = self
+            .item_repository
+            .get(&Id::try_from(request.item)?)
+            .await?;
+
+        let id = match request.id {
+            Some(id) => {
+                let id = Id::try_from(id)?;
+                match self.bill_of_materials_repository.get(item.id(), &id).await {
+                    Ok(_) => return Err(AlreadyExistsError::new(item.id().clone(), id).into()),
+                    Err(Error::NotFound(_)) => id.to_string(),
+                    Err(err) => return Err(err),
+                }
+            }
+            None => Uuid::new_v4().to_string(),
+        };
+
+        let mut components = Vec::with_capacity(request.components.len());
+        for component in request.components {
+            let component_item = self
+                .item_repository
+                .get(&Id::try_from(component.item)?)
+                .await?;
+
+            let component = Component::new(
+                &component_item,
+                Quantity::try_from(component.quantity)?,
+                component.unit_of_measure,
+                Quantity::try_from(component.scrap_factor)?,
+            )?;
+
+            // Explosion converts the component into the base unit of its item.
+            super::convert(
+                self.unit_of_measure_repository.as_ref(),
+                component.quantity(),
+                component.unit_of_measure(),
+                component_item.base_uom(),
+            )
+            .await?;
+
+            components.push(component);
+        }
+
+        let version = self.bill_of_materials_repository.latest(item.id()).await? + 1;
+
+        let bill_of_materials = BillOfMaterials::new(
+            id,
+            &item,
+            version,
+            request.display_name,
+            request.description,
+            components,
+        )?;
+        self.detect_cycle(&bill_of_materials).await?;
+
+        Ok(Operation::new(
+            Id::new(),
+            Metadata::new(bill_of_materials),
+            None,
+        ))
+    }
+
+    async fn validate_activate_request(
+        &self,
+        request: ActivateRequest,
+    ) -> Result<(Operation<Metadata>, EntityTag), Error> {
+        let item = Id::try_from(request.item)?;
+        let id = Id::try_from(request.id)?;
+        let bill_of_materials = self.bill_of_materials_repository.get(&item, &id).await?;
+
+        if request.etag != bill_of_materials.etag.to_string() {
+            return Err(EtagMismatchError::new(item, id, request.etag).into());
+        }
+
+        // The components' bills of materials may have changed since this one was drafted.
+        self.detect_cycle(&bill_of_materials).await?;
+
+        let etag = bill_of_materials.etag.clone();
+        let bill_of_materials = bill_of_materials.activate()?;
+
+        Ok((
+            Operation::new(Id::new(), Metadata::new(bill_of_materials), None),
+            etag,
+        ))
+    }
+
+    /// Walk the active bills of materials below the components of `bill_of_materials`,
+    /// failing if any of them leads back to the item it produces.
+    async fn detect_cycle(&self, bill_of_materials: &BillOfMaterials) -> Result<(), Error> {
+        let root = bill_of_materials.item();
+        let mut visited = HashSet::new();
+        let mut stack: Vec<Vec<Id>> = bill_of_materials
+            .components()
+            .iter()
+            .map(|c| vec![root.clone(), c.item().clone()])
+            .collect();
+
+        while let Some(path) = stack.pop() {
+            let Some(item) = path.last() else {
+                continue;
+            };
+
+            if item == root {
+                return Err(CycleError::new(path).into());
+            }
+
+            if !visited.insert(item.clone()) {
+                continue;
+            }
+
+            if let Some(active) = self.bill_of_materials_repository.active(item).await? {
+                for component in active.components() {
+                    let mut path = path.clone();
+                    path.push(component.item().clone());
+                    stack.push(path);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<BR, IR, UR> Create for Service<BR, IR, UR>
+where
+    BR: repository::Get
+        + repository::Create
+        + repository::Update
+        + repository::Active
+        + repository::Latest
+        + Clone,
+    IR: item::repository::Get + Clone,
+    UR: unit_of_measure::repository::Get + Clone,
+{
+    async fn create(&self, request: CreateRequest) -> Result<Operation<Metadata>, Error> {
+        let operation = self.validate_create_request(request).await?;
+        self.bill_of_materials_repository.create(&operation).await?;
+
+        Ok(operation)
+    }
+}
+
+impl<BR, IR, UR> Activate for Service<BR, IR, UR>
+where
+    BR: repository::Get
+        + repository::Create
+        + repository::Update
+        + repository::Active
+        + repository::Latest
+        + Clone,
+    IR: item::repository::Get + Clone,
+    UR: unit_of_measure::repository::Get + Clone,
+{
+    async fn activate(&self, request: ActivateRequest) -> Result<Operation<Metadata>, Error> {
+        let (operation, etag) = self.validate_activate_request(request).await?;
+        self.bill_of_materials_repository
+            .update(&operation, &etag)
+            .await?;
+
+        Ok(operation)
+    }
+}
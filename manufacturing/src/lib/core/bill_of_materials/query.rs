@@ -0,0 +1,295 @@
+use anyhow::anyhow;
+use derive_getters::{Dissolve, Getters};
+
+pub use super::Error;
+
+use std::{future::Future, sync::Arc};
+
+use crate::{item, unit_of_measure, BillOfMaterials, Component, Id, Quantity};
+
+use super::{repository, CycleError, InvalidQuantityError};
+
+// MARK: Get
+
+pub trait Get: Send + Sync + 'static {
+    fn get(
+        &self,
+        request: GetRequest,
+    ) -> impl Future<Output = Result<BillOfMaterials, Error>> + Send;
+}
+
+pub struct GetRequest {
+    item: String,
+    id: String,
+}
+
+impl GetRequest {
+    #[must_use]
+    pub const fn new(item: String, id: String) -> Self {
+        Self { item, id }
+    }
+}
+
+// MARK: List
+
+pub trait List: Send + Sync + 'static {
+    fn list(
+        &self,
+        request: ListRequest,
+    ) -> impl Future<Output = Result<ListResponse, Error>> + Send;
+}
+
+#[derive(Getters)]
+pub struct ListRequest {
+    item: String,
+    page_size: i32,
+    page_token: Option<String>,
+    order_by: Option<String>,
+    filter: Option<String>,
+}
+
+impl ListRequest {
+    #[must_use]
+    pub fn new(
+        item: String,
+        page_size: Option<i32>,
+        page_token: Option<String>,
+        order_by: Option<String>,
+        filter: Option<String>,
+    ) -> Self {
+        Self {
+            item,
+            page_size: page_size.filter(|s| *s > 0).unwrap_or(50).min(1000),
+            page_token,
+            order_by,
+            filter,
+        }
+    }
+}
+
+#[derive(Dissolve)]
+pub struct ListResponse {
+    bills_of_materials: Vec<BillOfMaterials>,
+    next_page_token: Option<String>,
+    total_size: i32,
+}
+
+impl ListResponse {
+    #[must_use]
+    pub const fn new(
+        bills_of_materials: Vec<BillOfMaterials>,
+        next_page_token: Option<String>,
+        total_size: i32,
+    ) -> Self {
+        Self {
+            bills_of_materials,
+            next_page_token,
+            total_size,
+        }
+    }
+}
+
+// MARK: Explode
+
+pub trait Explode: Send + Sync + 'static {
+    fn explode(
+        &self,
+        request: ExplodeRequest,
+    ) -> impl Future<Output = Result<ExplodeResponse, Error>> + Send;
+}
+
+pub struct ExplodeRequest {
+    item: String,
+    id: String,
+    quantity: String,
+}
+
+impl ExplodeRequest {
+    /// Explode the bill of materials for `quantity` of its item, one if empty.
+    #[must_use]
+    pub fn new(item: String, id: String, quantity: String) -> Self {
+        Self {
+            item,
+            id,
+            quantity: Some(quantity)
+                .filter(|q| !q.trim().is_empty())
+                .unwrap_or_else(|| String::from("1")),
+        }
+    }
+}
+
+/// A component required by an exploded bill of materials, `level` 1 being its direct
+/// components, and deeper levels the components of their active bills of materials.
+#[derive(Clone, Debug, PartialEq, Eq, Dissolve, Getters)]
+pub struct Line {
+    level: i32,
+    item: Id,
+    /// The gross quantity in `unit_of_measure`, including the scrap of this and all levels
+    /// above.
+    quantity: Quantity,
+    unit_of_measure: String,
+    /// The active bill of materials the component is exploded by, if it is produced.
+    bill_of_materials: Option<Id>,
+}
+
+#[derive(Dissolve)]
+pub struct ExplodeResponse {
+    lines: Vec<Line>,
+}
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+#[allow(clippy::struct_field_names)]
+pub struct Service<
+    BR: repository::Get + repository::List + repository::Active + Clone,
+    IR: item::repository::Get + Clone,
+    UR: unit_of_measure::repository::Get + Clone,
+> {
+    bill_of_materials_repository: Arc<BR>,
+    item_repository: Arc<IR>,
+    unit_of_measure_repository: Arc<UR>,
+}
+
+impl<BR, IR, UR> Service<BR, IR, UR>
+where
+    BR: repository::Get + repository::List + repository::Active + Clone,
+    IR: item::repository::Get + Clone,
+    UR: unit_of_measure::repository::Get + Clone,
+{
+    #[must_use]
+    pub const fn new(
+        bill_of_materials_repository: Arc<BR>,
+        item_repository: Arc<IR>,
+        unit_of_measure_repository: Arc<UR>,
+    ) -> Self {
+        Self {
+            bill_of_materials_repository,
+            item_repository,
+            unit_of_measure_repository,
+        }
+    }
+
+    /// Expand `bill_of_materials` depth-first, each component directly followed by the
+    /// components of its active bill of materials.
+    async fn explode_lines(
+        &self,
+        bill_of_materials: &BillOfMaterials,
+        quantity: &Quantity,
+    ) -> Result<Vec<Line>, Error> {
+        let mut lines = Vec::new();
+        let mut stack = Vec::new();
+        push_components(
+            &mut stack,
+            &[bill_of_materials.item().clone()],
+            bill_of_materials,
+            quantity,
+        )?;
+
+        while let Some((path, component, quantity)) = stack.pop() {
+            let (item, above) = path.split_last().ok_or_else(|| anyhow!("empty path"))?;
+
+            // Guards against a cycle that slipped in concurrently to its detection.
+            if above.contains(item) {
+                return Err(CycleError::new(path).into());
+            }
+
+            let active = self.bill_of_materials_repository.active(item).await?;
+
+            lines.push(Line {
+                level: i32::try_from(above.len()).unwrap_or(i32::MAX),
+                item: item.clone(),
+                quantity: quantity.clone(),
+                unit_of_measure: component.unit_of_measure().clone(),
+                bill_of_materials: active.as_ref().map(|b| b.id().clone()),
+            });
+
+            // Bills of materials are given per base unit of the item they produce.
+            if let Some(active) = active {
+                let base_uom = self.item_repository.get(item).await?.base_uom().clone();
+                let quantity = super::convert(
+                    self.unit_of_measure_repository.as_ref(),
+                    &quantity,
+                    component.unit_of_measure(),
+                    &base_uom,
+                )
+                .await?;
+                push_components(&mut stack, &path, &active, &quantity)?;
+            }
+        }
+
+        Ok(lines)
+    }
+}
+
+type Pending = (Vec<Id>, Component, Quantity);
+
+/// Queue the components of `bill_of_materials`, reached through `path`, to be exploded
+/// for `quantity` of its item, in reverse so that they are popped in their order.
+fn push_components(
+    stack: &mut Vec<Pending>,
+    path: &[Id],
+    bill_of_materials: &BillOfMaterials,
+    quantity: &Quantity,
+) -> Result<(), Error> {
+    for component in bill_of_materials.components().iter().rev() {
+        let mut path = path.to_vec();
+        path.push(component.item().clone());
+        let quantity = quantity.checked_mul(&component.gross_quantity()?)?;
+        stack.push((path, component.clone(), quantity));
+    }
+
+    Ok(())
+}
+
+fn validate_get_request(item: String, id: String) -> Result<(Id, Id), Error> {
+    Ok((item.try_into()?, id.try_into()?))
+}
+
+impl<BR, IR, UR> Get for Service<BR, IR, UR>
+where
+    BR: repository::Get + repository::List + repository::Active + Clone,
+    IR: item::repository::Get + Clone,
+    UR: unit_of_measure::repository::Get + Clone,
+{
+    async fn get(&self, request: GetRequest) -> Result<BillOfMaterials, Error> {
+        let (item, id) = validate_get_request(request.item, request.id)?;
+
+        self.bill_of_materials_repository.get(&item, &id).await
+    }
+}
+
+impl<BR, IR, UR> List for Service<BR, IR, UR>
+where
+    BR: repository::Get + repository::List + repository::Active + Clone,
+    IR: item::repository::Get + Clone,
+    UR: unit_of_measure::repository::Get + Clone,
+{
+    async fn list(&self, request: ListRequest) -> Result<ListResponse, Error> {
+        let item = Id::try_from(request.item.clone())?;
+
+        self.bill_of_materials_repository
+            .list(&item, &request)
+            .await
+    }
+}
+
+impl<BR, IR, UR> Explode for Service<BR, IR, UR>
+where
+    BR: repository::Get + repository::List + repository::Active + Clone,
+    IR: item::repository::Get + Clone,
+    UR: unit_of_measure::repository::Get + Clone,
+{
+    async fn explode(&self, request: ExplodeRequest) -> Result<ExplodeResponse, Error> {
+        let quantity = Quantity::try_from(request.quantity)?;
+        if !quantity.is_positive() {
+            return Err(InvalidQuantityError::new(quantity).into());
+        }
+
+        let (item, id) = validate_get_request(request.item, request.id)?;
+        let bill_of_materials = self.bill_of_materials_repository.get(&item, &id).await?;
+        let lines = self.explode_lines(&bill_of_materials, &quantity).await?;
+
+        Ok(ExplodeResponse { lines })
+    }
+}
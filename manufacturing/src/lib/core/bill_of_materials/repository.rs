@@ -0,0 +1,865 @@
+use std::{collections::HashMap, future::Future, sync::Arc};
+
+use anyhow::{anyhow, Context};
+use num_traits::ToPrimitive;
+use prost_types::Any;
+use sqlx::{Executor, FromRow, QueryBuilder, Sqlite, Transaction};
+
+use crate::{
+    bill_of_materials, page_token,
+    sqlx::{
+        list::{self, Column, Kind, OrderTerm},
+        Error as SqlxError, SqliteConnection, SqliteError,
+    },
+    sync::{self, Operation, OperationMetadata, OperationRecord},
+    BillOfMaterials, BillOfMaterialsState, Component, EntityTag, Filter, Id, OrderBy, PageToken,
+    Quantity, Timestamp,
+};
+
+use super::{
+    query::{ListRequest, ListResponse},
+    sync::{Metadata, Verb},
+    AlreadyExistsError, Error, EtagMismatchError, NotFoundError,
+};
+
+// MARK: Get
+
+/// `Get` represents a store of bill of materials data.
+pub trait Get: Send + Sync + 'static {
+    /// Get a [`BillOfMaterials`] of an item.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::NotFound`] if the item has no [`BillOfMaterials`] with the given [`Id`].
+    fn get(
+        &self,
+        item: &Id,
+        id: &Id,
+    ) -> impl Future<Output = Result<BillOfMaterials, Error>> + Send;
+}
+
+// MARK: List
+
+/// `List` represents a store of bill of materials data.
+pub trait List: Send + Sync + 'static {
+    /// List the [`BillOfMaterials`]s of an item.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::PageToken`] if the page token does not fit the request.
+    fn list(
+        &self,
+        item: &Id,
+        request: &ListRequest,
+    ) -> impl Future<Output = Result<ListResponse, Error>> + Send;
+}
+
+// MARK: Create
+
+/// `Create` represents a store of bill of materials data.
+pub trait Create: Send + Sync + 'static {
+    /// Persist a new [`BillOfMaterials`] along with its components.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::AlreadyExists`] if the item already has a [`BillOfMaterials`] with
+    ///   the same [`Id`] or version.
+    fn create(
+        &self,
+        operation: &Operation<Metadata>,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+// MARK: Update
+
+/// `Update` represents a store of bill of materials data.
+pub trait Update: Send + Sync + 'static {
+    /// Update a [`BillOfMaterials`], provided its stored etag still equals `etag`.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::NotFound`] if the [`BillOfMaterials`] does not exist.
+    /// - MUST return [`Error::EtagMismatch`] if the stored etag differs from `etag`.
+    fn update(
+        &self,
+        operation: &Operation<Metadata>,
+        etag: &EntityTag,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+// MARK: Complete
+
+/// `Complete` represents a store of bill of materials data.
+pub trait Complete: Send + Sync + 'static {
+    /// Persist the final [`BillOfMaterials`] of a finished operation and mark the operation as done.
+    /// Completing an activation makes the item's previously active bill of materials obsolete.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::Unknown`] if the operation has no successful result.
+    /// - MUST return [`Error::EtagMismatch`] if the stored etag differs from `etag`.
+    /// - MUST return [`crate::id::Error::NotFound`] if the operation does not exist.
+    fn complete(
+        &self,
+        operation: &Operation<Metadata>,
+        etag: &EntityTag,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+// MARK: Active
+
+/// `Active` represents a store of bill of materials data.
+pub trait Active: Send + Sync + 'static {
+    /// Get the active [`BillOfMaterials`] of an item, if it has one.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::Unknown`] if the bill of materials cannot be fetched.
+    fn active(
+        &self,
+        item: &Id,
+    ) -> impl Future<Output = Result<Option<BillOfMaterials>, Error>> + Send;
+}
+
+// MARK: Latest
+
+/// `Latest` represents a store of bill of materials data.
+pub trait Latest: Send + Sync + 'static {
+    /// Get the highest version of the [`BillOfMaterials`]s of an item, 0 if it has none.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::Unknown`] if the versions cannot be fetched.
+    fn latest(&self, item: &Id) -> impl Future<Output = Result<i64, Error>> + Send;
+}
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+pub struct Service<DB: SqliteConnection> {
+    db: Arc<DB>,
+    page_token_key: page_token::Key,
+}
+
+#[derive(FromRow)]
+struct BillOfMaterialsRow {
+    item_id: String,
+    id: String,
+    version: i64,
+    display_name: String,
+    description: String,
+    state: i64,
+    etag: String,
+    uid: String,
+    create_time: String,
+    update_time: String,
+}
+
+#[derive(FromRow)]
+struct ComponentRow {
+    bill_of_materials_id: String,
+    component_item_id: String,
+    quantity: String,
+    unit_of_measure: String,
+    scrap_factor: String,
+}
+
+impl TryFrom<ComponentRow> for Component {
+    type Error = Error;
+
+    fn try_from(value: ComponentRow) -> Result<Self, Self::Error> {
+        Ok(Self::from((
+            Id::try_from(value.component_item_id)?,
+            Quantity::try_from(value.quantity)?,
+            value.unit_of_measure,
+            Quantity::try_from(value.scrap_factor)?,
+        )))
+    }
+}
+
+impl TryFrom<(BillOfMaterialsRow, Vec<Component>)> for BillOfMaterials {
+    type Error = Error;
+
+    fn try_from(value: (BillOfMaterialsRow, Vec<Component>)) -> Result<Self, Self::Error> {
+        let (value, components) = value;
+        let item = Id::try_from(value.item_id)?;
+        let id = Id::try_from(value.id)?;
+        let version = value.version;
+        let display_name = value.display_name;
+        let description = value.description;
+        let state = num_traits::FromPrimitive::from_i64(value.state).ok_or(Error::Unknown(
+            anyhow!(format!("invalid state {0}", value.state)),
+        ))?;
+        let etag = value.etag.try_into()?;
+        let uid = uuid::Uuid::try_parse(&value.uid)
+            .map_err(|e| bill_of_materials::Error::Unknown(anyhow!(e)))?;
+        let create_time = Timestamp::try_from(value.create_time)?;
+        let update_time = Timestamp::try_from(value.update_time)?;
+
+        Ok(Self::from((
+            id,
+            item,
+            version,
+            display_name,
+            description,
+            components,
+            state,
+            etag,
+            uid,
+            create_time,
+            update_time,
+        )))
+    }
+}
+
+// MARK: List
+
+const BILL_OF_MATERIALS_COLUMNS: &[Column] = &[
+    Column::new("name", "id", Kind::Text, true),
+    Column::new("version", "version", Kind::Integer, true),
+    Column::new("display_name", "display_name", Kind::Text, true),
+    Column::new("description", "description", Kind::Text, false),
+    Column::new("state", "state", Kind::State(bill_of_materials_state), true),
+    Column::new("etag", "etag", Kind::Text, false),
+    Column::new("uid", "uid", Kind::Text, false),
+    Column::new("create_time", "create_time", Kind::Timestamp, true),
+    Column::new("update_time", "update_time", Kind::Timestamp, true),
+];
+
+fn bill_of_materials_state(name: &str) -> Option<i64> {
+    BillOfMaterialsState::from_name(name).and_then(|s| s.to_i64())
+}
+
+fn cursor(terms: &[OrderTerm], bill_of_materials: &BillOfMaterials) -> Vec<String> {
+    terms
+        .iter()
+        .map(|(column, _)| match column.name() {
+            "version" => bill_of_materials.version.to_string(),
+            "display_name" => bill_of_materials.display_name.clone(),
+            "state" => bill_of_materials
+                .state
+                .to_i64()
+                .unwrap_or_default()
+                .to_string(),
+            "create_time" => bill_of_materials.create_time.value().to_string(),
+            "update_time" => bill_of_materials.update_time.value().to_string(),
+            _ => bill_of_materials.id.value().clone(),
+        })
+        .collect()
+}
+
+impl<DB> Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    #[must_use]
+    pub const fn new(db: Arc<DB>, page_token_key: page_token::Key) -> Self {
+        Self { db, page_token_key }
+    }
+
+    /// Fetch the components of the bills of materials of an item, keyed by bill of materials.
+    async fn fetch_components(&self, item: &Id) -> Result<HashMap<String, Vec<Component>>, Error> {
+        let value = item.value();
+
+        let query = sqlx::query_as!(
+            ComponentRow,
+            "SELECT
+                bill_of_materials_id,
+                component_item_id,
+                quantity,
+                unit_of_measure,
+                scrap_factor
+            FROM bill_of_materials_component WHERE item_id = $1
+            ORDER BY bill_of_materials_id, position",
+            value
+        );
+
+        let rows = query.fetch_all(&self.db.pool()).await.map_err(|e| {
+            Error::from(anyhow!(e).context(format!(
+                "failed to fetch bill of materials components of item with id {item:?}"
+            )))
+        })?;
+
+        let mut components: HashMap<String, Vec<Component>> = HashMap::new();
+        for row in rows {
+            components
+                .entry(row.bill_of_materials_id.clone())
+                .or_default()
+                .push(row.try_into()?);
+        }
+
+        Ok(components)
+    }
+
+    async fn assemble(
+        &self,
+        item: &Id,
+        rows: Vec<BillOfMaterialsRow>,
+    ) -> Result<Vec<BillOfMaterials>, Error> {
+        if rows.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut components = self.fetch_components(item).await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let lines = components.remove(&row.id).unwrap_or_default();
+                (row, lines).try_into()
+            })
+            .collect()
+    }
+
+    async fn fetch_bill_of_materials(&self, item: &Id, id: &Id) -> Result<BillOfMaterials, Error> {
+        let item_value = item.value();
+        let value = id.value();
+
+        let query = sqlx::query_as!(
+            BillOfMaterialsRow,
+            "SELECT
+                item_id,
+                id,
+                version,
+                display_name,
+                description,
+                state,
+                etag,
+                uid,
+                create_time,
+                update_time
+            FROM bill_of_materials WHERE item_id = $1 AND id = $2",
+            item_value,
+            value
+        );
+
+        let result = query.fetch_optional(&self.db.pool()).await.map_err(|e| {
+            Error::from(anyhow!(e).context(format!(
+                "failed to fetch bill of materials with id {id:?} of item with id {item:?}"
+            )))
+        })?;
+
+        let row = result.ok_or_else(|| NotFoundError::new(item.clone(), id.clone()))?;
+
+        self.assemble(item, vec![row])
+            .await?
+            .pop()
+            .ok_or_else(|| NotFoundError::new(item.clone(), id.clone()).into())
+    }
+
+    async fn fetch_active_bill_of_materials(
+        &self,
+        item: &Id,
+    ) -> Result<Option<BillOfMaterials>, Error> {
+        let item_value = item.value();
+        let active = BillOfMaterialsState::Active.to_i64();
+
+        let query = sqlx::query_as!(
+            BillOfMaterialsRow,
+            "SELECT
+                item_id,
+                id,
+                version,
+                display_name,
+                description,
+                state,
+                etag,
+                uid,
+                create_time,
+                update_time
+            FROM bill_of_materials WHERE item_id = $1 AND state = $2
+            ORDER BY version DESC LIMIT 1",
+            item_value,
+            active
+        );
+
+        let result = query.fetch_optional(&self.db.pool()).await.map_err(|e| {
+            Error::from(anyhow!(e).context(format!(
+                "failed to fetch active bill of materials of item with id {item:?}"
+            )))
+        })?;
+
+        Ok(self
+            .assemble(item, result.into_iter().collect())
+            .await?
+            .pop())
+    }
+
+    async fn fetch_bills_of_materials(
+        &self,
+        item: &Id,
+        request: &ListRequest,
+    ) -> Result<ListResponse, Error> {
+        let page_size = request.page_size();
+        let raw_filter = request.filter().as_deref().unwrap_or_default().trim();
+        let raw_order_by = request.order_by().as_deref().unwrap_or_default().trim();
+        let parameters = [item.value().as_str(), raw_filter, raw_order_by];
+
+        let filter = Some(raw_filter)
+            .filter(|f| !f.is_empty())
+            .map(|f| Filter::try_from(f.to_string()))
+            .transpose()?;
+        let order_by = Some(raw_order_by)
+            .filter(|o| !o.is_empty())
+            .map(|o| OrderBy::try_from(o.to_string()))
+            .transpose()?;
+        let terms = list::resolve_order_by(BILL_OF_MATERIALS_COLUMNS, order_by.as_ref())?;
+        let page_token = request
+            .page_token()
+            .as_deref()
+            .filter(|t| !t.is_empty())
+            .map(|t| PageToken::decode(t, &self.page_token_key))
+            .transpose()?;
+
+        if let Some(page_token) = &page_token {
+            page_token.verify(&parameters)?;
+        }
+
+        let mut query = QueryBuilder::<Sqlite>::new(
+            "SELECT
+                item_id,
+                id,
+                version,
+                display_name,
+                description,
+                state,
+                etag,
+                uid,
+                create_time,
+                update_time
+            FROM bill_of_materials WHERE item_id = ",
+        );
+        query.push_bind(item.value().clone());
+
+        if let Some(filter) = &filter {
+            query.push(" AND ");
+            list::push_filter(&mut query, BILL_OF_MATERIALS_COLUMNS, filter.expression())?;
+        }
+
+        if let Some(page_token) = &page_token {
+            query.push(" AND ");
+            list::push_cursor(&mut query, &terms, page_token.cursor())?;
+        }
+
+        list::push_order_by(&mut query, &terms);
+
+        // Fetch one more bill of materials than requested to learn whether another page follows.
+        query.push(" LIMIT ");
+        query.push_bind(page_size + 1);
+
+        let result = query
+            .build_query_as::<BillOfMaterialsRow>()
+            .fetch_all(&self.db.pool())
+            .await
+            .map_err(|e| Error::from(anyhow!(e).context("failed to fetch bills of materials")))?;
+
+        let mut bills_of_materials = self.assemble(item, result).await?;
+
+        let next_page_token =
+            if bills_of_materials.len() > usize::try_from(*page_size).unwrap_or_default() {
+                bills_of_materials.pop();
+                bills_of_materials.last().map(|bill_of_materials| {
+                    PageToken::new(&parameters, cursor(&terms, bill_of_materials))
+                        .encode(&self.page_token_key)
+                })
+            } else {
+                None
+            };
+
+        let mut query =
+            QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM bill_of_materials WHERE item_id = ");
+        query.push_bind(item.value().clone());
+
+        if let Some(filter) = &filter {
+            query.push(" AND ");
+            list::push_filter(&mut query, BILL_OF_MATERIALS_COLUMNS, filter.expression())?;
+        }
+
+        let total_size: i64 = query
+            .build_query_scalar()
+            .fetch_one(&self.db.pool())
+            .await
+            .map_err(|e| Error::from(anyhow!(e).context("failed to count bills of materials")))?;
+        let total_size = i32::try_from(total_size).unwrap_or(i32::MAX);
+
+        Ok(ListResponse::new(
+            bills_of_materials,
+            next_page_token,
+            total_size,
+        ))
+    }
+
+    async fn save_bill_of_materials(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        bill_of_materials: &BillOfMaterials,
+    ) -> Result<(), Error> {
+        let item_id = &bill_of_materials.item.value();
+        let id = &bill_of_materials.id.value();
+        let version = &bill_of_materials.version;
+        let display_name = &bill_of_materials.display_name;
+        let description = &bill_of_materials.description;
+        let state = &bill_of_materials.state.to_i64();
+        let etag = &bill_of_materials.etag.to_string();
+        let uid = &bill_of_materials.uid.to_string();
+        let create_time = &bill_of_materials.create_time.value().to_string();
+        let update_time = &bill_of_materials.update_time.value().to_string();
+
+        let query = sqlx::query!(
+            "INSERT INTO bill_of_materials (
+                item_id,
+                id,
+                version,
+                display_name,
+                description,
+                state,
+                etag,
+                uid,
+                create_time,
+                update_time
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+            item_id,
+            id,
+            version,
+            display_name,
+            description,
+            state,
+            etag,
+            uid,
+            create_time,
+            update_time,
+        );
+
+        tx.execute(query)
+            .await
+            .map_err(|e| match SqlxError::from(&e) {
+                SqlxError::Sqlite { inner } => match inner {
+                    SqliteError::UniqueConstraintViolationCode => Error::from(
+                        AlreadyExistsError::new(
+                            bill_of_materials.item.clone(),
+                            bill_of_materials.id.clone(),
+                        ),
+                    ),
+                    SqliteError::Unknown { message } => anyhow!(e)
+                        .context(format!(
+                            "failed to insert bill of materials with id {:?}, with message from database: {:?}",
+                            bill_of_materials.id(),
+                            message
+                        ))
+                        .into(),
+                },
+                SqlxError::RowNotFound | SqlxError::Unknown => anyhow!(e)
+                    .context(format!("failed to insert bill of materials with id {:?}", bill_of_materials.id()))
+                    .into(),
+            })?;
+
+        for (position, component) in bill_of_materials.components.iter().enumerate() {
+            let position = i64::try_from(position).unwrap_or(i64::MAX);
+            let component_item_id = &component.item().value();
+            let quantity = component.quantity().to_string();
+            let unit_of_measure = component.unit_of_measure();
+            let scrap_factor = component.scrap_factor().to_string();
+
+            let query = sqlx::query!(
+                "INSERT INTO bill_of_materials_component (
+                    item_id,
+                    bill_of_materials_id,
+                    position,
+                    component_item_id,
+                    quantity,
+                    unit_of_measure,
+                    scrap_factor
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+                item_id,
+                id,
+                position,
+                component_item_id,
+                quantity,
+                unit_of_measure,
+                scrap_factor,
+            );
+
+            tx.execute(query).await.map_err(|e| {
+                Error::from(anyhow!(e).context(format!(
+                    "failed to insert components of bill of materials with id {id:?}"
+                )))
+            })?;
+        }
+
+        Ok(())
+    }
+
+    async fn modify_bill_of_materials(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        bill_of_materials: &BillOfMaterials,
+        expected_etag: &EntityTag,
+    ) -> Result<(), Error> {
+        let item_id = &bill_of_materials.item.value();
+        let id = &bill_of_materials.id.value();
+        let display_name = &bill_of_materials.display_name;
+        let description = &bill_of_materials.description;
+        let state = &bill_of_materials.state.to_i64();
+        let etag = &bill_of_materials.etag.to_string();
+        let update_time = &bill_of_materials.update_time.value().to_string();
+        let expected = &expected_etag.to_string();
+
+        // The version and components are immutable, a change makes a new bill of materials.
+        let query = sqlx::query!(
+            "UPDATE bill_of_materials SET
+                display_name    = $3,
+                description     = $4,
+                state           = $5,
+                etag            = $6,
+                update_time     = $7
+            WHERE item_id = $1 AND id = $2 AND etag = $8",
+            item_id,
+            id,
+            display_name,
+            description,
+            state,
+            etag,
+            update_time,
+            expected,
+        );
+
+        let result = tx.execute(query).await.map_err(|e| {
+            Error::from(
+                anyhow!(e).context(format!("failed to update bill of materials with id {id:?}")),
+            )
+        })?;
+
+        if result.rows_affected() > 0 {
+            return Ok(());
+        }
+
+        // Nothing matched, either the bill of materials is gone or someone else changed it first.
+        let query = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM bill_of_materials WHERE item_id = $1 AND id = $2",
+            item_id,
+            id
+        );
+
+        let count = query.fetch_one(&mut **tx).await.map_err(|e| {
+            Error::from(
+                anyhow!(e).context(format!("failed to fetch bill of materials with id {id:?}")),
+            )
+        })?;
+
+        if count == 0 {
+            Err(
+                NotFoundError::new(bill_of_materials.item.clone(), bill_of_materials.id.clone())
+                    .into(),
+            )
+        } else {
+            Err(EtagMismatchError::new(
+                bill_of_materials.item.clone(),
+                bill_of_materials.id.clone(),
+                expected.clone(),
+            )
+            .into())
+        }
+    }
+
+    /// Make the active bills of materials of the item other than `bill_of_materials` obsolete.
+    async fn supersede(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        bill_of_materials: &BillOfMaterials,
+    ) -> Result<(), Error> {
+        let item_id = &bill_of_materials.item.value();
+        let id = &bill_of_materials.id.value();
+        let active = BillOfMaterialsState::Active.to_i64();
+
+        let query = sqlx::query_scalar!(
+            "SELECT id FROM bill_of_materials WHERE item_id = $1 AND state = $2 AND id <> $3",
+            item_id,
+            active,
+            id,
+        );
+
+        let superseded = query.fetch_all(&mut **tx).await.map_err(|e| {
+            Error::from(anyhow!(e).context(format!(
+                "failed to fetch active bills of materials of item with id {item_id:?}"
+            )))
+        })?;
+
+        for superseded in superseded {
+            let state = BillOfMaterialsState::Obsolete.to_i64();
+            let etag = EntityTag::new().to_string();
+            let update_time = Timestamp::now().value().to_string();
+
+            let query = sqlx::query!(
+                "UPDATE bill_of_materials SET
+                    state           = $3,
+                    etag            = $4,
+                    update_time     = $5
+                WHERE item_id = $1 AND id = $2",
+                item_id,
+                superseded,
+                state,
+                etag,
+                update_time,
+            );
+
+            tx.execute(query).await.map_err(|e| {
+                Error::from(anyhow!(e).context(format!(
+                    "failed to obsolete bill of materials with id {superseded:?}"
+                )))
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<DB> Get for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn get(&self, item: &Id, id: &Id) -> Result<BillOfMaterials, Error> {
+        let bill_of_materials = self.fetch_bill_of_materials(item, id).await?;
+        Ok(bill_of_materials)
+    }
+}
+
+impl<DB> List for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn list(&self, item: &Id, request: &ListRequest) -> Result<ListResponse, Error> {
+        let response = self.fetch_bills_of_materials(item, request).await?;
+        Ok(response)
+    }
+}
+
+impl<DB> Create for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn create(&self, operation: &Operation<Metadata>) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool()
+            .begin()
+            .await
+            .with_context(|| "failed to start SQLite transaction")?;
+
+        self.save_bill_of_materials(&mut tx, operation.metadata().entity())
+            .await?;
+
+        let operation_record = OperationRecord::new(
+            operation.id().clone(),
+            operation.metadata().entity().name(),
+            operation.metadata().into(),
+        );
+        sync::repository::save_operation(&mut tx, &operation_record).await?;
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(())
+    }
+}
+
+impl<DB> Update for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn update(&self, operation: &Operation<Metadata>, etag: &EntityTag) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool()
+            .begin()
+            .await
+            .with_context(|| "failed to start SQLite transaction")?;
+
+        self.modify_bill_of_materials(&mut tx, operation.metadata().entity(), etag)
+            .await?;
+
+        let operation_record = OperationRecord::new(
+            operation.id().clone(),
+            operation.metadata().entity().name(),
+            operation.metadata().into(),
+        );
+        sync::repository::save_operation(&mut tx, &operation_record).await?;
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(())
+    }
+}
+
+impl<DB> Complete for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn complete(
+        &self,
+        operation: &Operation<Metadata>,
+        etag: &EntityTag,
+    ) -> Result<(), Error> {
+        let Some(Ok(response)) = operation.result() else {
+            return Err(Error::Unknown(anyhow!(
+                "operation with id {:?} has no successful result",
+                operation.id().value()
+            )));
+        };
+
+        let mut tx = self
+            .db
+            .pool()
+            .begin()
+            .await
+            .with_context(|| "failed to start SQLite transaction")?;
+
+        self.modify_bill_of_materials(&mut tx, response.bill_of_materials(), etag)
+            .await?;
+
+        if *response.verb() == Verb::Activate {
+            self.supersede(&mut tx, response.bill_of_materials())
+                .await?;
+        }
+
+        let response = Any::from(response.clone());
+        sync::repository::complete_operation(&mut tx, operation.id(), &Ok(response)).await?;
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(())
+    }
+}
+
+impl<DB> Active for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn active(&self, item: &Id) -> Result<Option<BillOfMaterials>, Error> {
+        self.fetch_active_bill_of_materials(item).await
+    }
+}
+
+impl<DB> Latest for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn latest(&self, item: &Id) -> Result<i64, Error> {
+        let value = item.value();
+
+        let query = sqlx::query_scalar!(
+            r#"SELECT COALESCE(MAX(version), 0) AS "version!: i64"
+            FROM bill_of_materials WHERE item_id = $1"#,
+            value
+        );
+
+        query.fetch_one(&self.db.pool()).await.map_err(|e| {
+            Error::from(anyhow!(e).context(format!(
+                "failed to fetch bill of materials versions of item with id {item:?}"
+            )))
+        })
+    }
+}
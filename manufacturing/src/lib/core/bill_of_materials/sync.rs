@@ -0,0 +1,113 @@
+use derive_getters::{Dissolve, Getters};
+
+use crate::{
+    bill_of_materials,
+    sync::{OperationEntity, OperationMetadata, OperationState},
+    BillOfMaterials, BillOfMaterialsState, Id, Timestamp,
+};
+
+impl OperationState for BillOfMaterialsState {
+    type NextState = Option<Self>;
+
+    fn next(&self) -> Self::NextState {
+        match self {
+            Self::Creating => Some(Self::Draft),
+            Self::Activating => Some(Self::Active),
+            Self::Draft | Self::Active | Self::Obsolete => None,
+        }
+    }
+}
+
+impl OperationEntity for BillOfMaterials {
+    type State = BillOfMaterialsState;
+
+    fn id(&self) -> &Id {
+        self.id()
+    }
+
+    fn state(&self) -> &Self::State {
+        self.state()
+    }
+
+    fn name(&self) -> String {
+        Self::name(self)
+    }
+}
+
+/// The action a bill of materials operation performs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verb {
+    Create,
+    Activate,
+}
+
+impl Verb {
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Activate => "activate",
+        }
+    }
+}
+
+impl From<&BillOfMaterialsState> for Verb {
+    /// Operations are only started for bills of materials entering a transitional
+    /// state, so settled states map to the verb that last produced them.
+    fn from(value: &BillOfMaterialsState) -> Self {
+        match value {
+            BillOfMaterialsState::Creating | BillOfMaterialsState::Draft => Self::Create,
+            BillOfMaterialsState::Activating
+            | BillOfMaterialsState::Active
+            | BillOfMaterialsState::Obsolete => Self::Activate,
+        }
+    }
+}
+
+#[derive(Dissolve, Getters)]
+pub struct Metadata {
+    bill_of_materials: BillOfMaterials,
+    verb: Verb,
+    create_time: Timestamp,
+}
+
+impl Metadata {
+    #[must_use]
+    pub fn new(bill_of_materials: BillOfMaterials) -> Self {
+        let verb = Verb::from(bill_of_materials.state());
+
+        Self {
+            bill_of_materials,
+            verb,
+            create_time: Timestamp::now(),
+        }
+    }
+}
+
+impl OperationMetadata for Metadata {
+    type Entity = BillOfMaterials;
+    type Response = Response;
+    type Error = bill_of_materials::Error;
+
+    fn entity(&self) -> &Self::Entity {
+        self.bill_of_materials()
+    }
+}
+
+/// The settled bill of materials of a finished operation, along with the verb
+/// that produced it, as each verb answers with its own response message.
+#[derive(Clone, Dissolve, Getters)]
+pub struct Response {
+    bill_of_materials: BillOfMaterials,
+    verb: Verb,
+}
+
+impl Response {
+    #[must_use]
+    pub const fn new(bill_of_materials: BillOfMaterials, verb: Verb) -> Self {
+        Self {
+            bill_of_materials,
+            verb,
+        }
+    }
+}
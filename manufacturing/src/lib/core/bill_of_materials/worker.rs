@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use tonic::Code;
+
+pub use super::Error;
+
+use crate::{
+    grpc::proto::google::rpc,
+    sync::{self, Operation, OperationRecord, Reconcile},
+    Id,
+};
+
+use super::{
+    repository,
+    sync::{Metadata, Response},
+};
+
+const ITEM_NAME_PREFIX: &str = "items/";
+
+const BILL_OF_MATERIALS_COLLECTION: &str = "/boms/";
+
+/// The targets of the operations on bills of materials.
+const BILL_OF_MATERIALS_TARGETS: &str = "items/*/boms/*";
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+pub struct Service<
+    BR: repository::Get + repository::Complete + Clone,
+    OR: sync::repository::Pending + sync::repository::Update + Clone,
+> {
+    bill_of_materials_repository: Arc<BR>,
+    operation_repository: Arc<OR>,
+}
+
+impl<BR, OR> Service<BR, OR>
+where
+    BR: repository::Get + repository::Complete + Clone,
+    OR: sync::repository::Pending + sync::repository::Update + Clone,
+{
+    #[must_use]
+    pub const fn new(bill_of_materials_repository: Arc<BR>, operation_repository: Arc<OR>) -> Self {
+        Self {
+            bill_of_materials_repository,
+            operation_repository,
+        }
+    }
+
+    async fn reconcile_operation(
+        &self,
+        record: OperationRecord,
+        item_id: Id,
+        bill_of_materials_id: Id,
+    ) -> Result<(), Error> {
+        let bill_of_materials = match self
+            .bill_of_materials_repository
+            .get(&item_id, &bill_of_materials_id)
+            .await
+        {
+            Ok(bill_of_materials) => bill_of_materials,
+            Err(Error::NotFound(err)) => {
+                let record = record.done(Err(rpc::Status {
+                    code: Code::NotFound.into(),
+                    message: err.to_string(),
+                    details: vec![],
+                }));
+
+                return Ok(self.operation_repository.update(&record).await?);
+            }
+            Err(err) => return Err(err),
+        };
+
+        let (id, ..) = record.dissolve();
+        let metadata = Metadata::new(bill_of_materials.clone());
+        let etag = bill_of_materials.etag().clone();
+        let settled = bill_of_materials.settle()?;
+        let response = Response::new(settled, *metadata.verb());
+        let operation = Operation::new(id, metadata, Some(Ok(response)));
+
+        self.bill_of_materials_repository
+            .complete(&operation, &etag)
+            .await
+    }
+}
+
+impl<BR, OR> Reconcile for Service<BR, OR>
+where
+    BR: repository::Get + repository::Complete + Clone,
+    OR: sync::repository::Pending + sync::repository::Update + Clone,
+{
+    type Error = Error;
+
+    async fn reconcile(&self) -> Result<usize, Error> {
+        let mut completed = 0;
+
+        for record in self
+            .operation_repository
+            .pending(BILL_OF_MATERIALS_TARGETS)
+            .await?
+        {
+            let Some((item_id, bill_of_materials_id)) = record
+                .target()
+                .strip_prefix(ITEM_NAME_PREFIX)
+                .and_then(|name| name.split_once(BILL_OF_MATERIALS_COLLECTION))
+            else {
+                continue;
+            };
+
+            let ids = Id::try_from(item_id.to_string())
+                .and_then(|item_id| Ok((item_id, Id::try_from(bill_of_materials_id.to_string())?)));
+            let failed = record.clone();
+
+            let result = match ids {
+                Ok((item_id, bill_of_materials_id)) => {
+                    self.reconcile_operation(record, item_id, bill_of_materials_id)
+                        .await
+                }
+                Err(err) => Err(err.into()),
+            };
+            // An operation that cannot be completed is failed rather than retried, so that it
+            // does not hold up every operation pending after it.
+            if let Err(err) = result {
+                let record = failed.done(Err(err.into()));
+                self.operation_repository.update(&record).await?;
+            }
+            completed += 1;
+        }
+
+        Ok(completed)
+    }
+}
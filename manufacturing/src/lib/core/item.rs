@@ -1,15 +1,56 @@
-use anyhow::anyhow;
+use std::time::Duration;
+
+use derive_getters::Getters;
 use derive_more::derive::From;
 use uuid::Uuid;
 
-use crate::{entity_tag, id, timestamp, EntityTag, Item, ItemState, ThisError, Timestamp};
+use crate::{
+    entity_tag, field_mask, filter, id, order_by, page_token, sync::OperationState, timestamp,
+    unit_of_measure, EntityTag, Id, Item, ItemState, ThisError, Timestamp, Tracking,
+};
 
 pub mod command;
 pub mod query;
 pub mod repository;
 pub mod sync;
+pub mod worker;
 
 impl ItemState {
+    /// Look up a state by its API name, e.g. `ACTIVE`.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "CREATING" => Some(Self::Creating),
+            "UPDATING" => Some(Self::Updating),
+            "DELETING" => Some(Self::Deleting),
+            "ANNIHILATING" => Some(Self::Annihilating),
+            "BLOCKING" => Some(Self::Blocking),
+            "UNBLOCKING" => Some(Self::Unblocking),
+            "UNDELETING" => Some(Self::Undeleting),
+            "ACTIVE" => Some(Self::Active),
+            "BLOCKED" => Some(Self::Blocked),
+            "DELETED" => Some(Self::Deleted),
+            _ => None,
+        }
+    }
+
+    /// The API name of the state, e.g. `ACTIVE`.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Creating => "CREATING",
+            Self::Updating => "UPDATING",
+            Self::Deleting => "DELETING",
+            Self::Annihilating => "ANNIHILATING",
+            Self::Blocking => "BLOCKING",
+            Self::Unblocking => "UNBLOCKING",
+            Self::Undeleting => "UNDELETING",
+            Self::Active => "ACTIVE",
+            Self::Blocked => "BLOCKED",
+            Self::Deleted => "DELETED",
+        }
+    }
+
     #[must_use]
     const fn is_transitioning(&self) -> bool {
         matches!(
@@ -20,16 +61,42 @@ impl ItemState {
                 | Self::Annihilating
                 | Self::Blocking
                 | Self::Unblocking
+                | Self::Undeleting
         )
     }
 }
 
+impl Tracking {
+    /// Look up a tracking mode by its API name, e.g. `LOT`.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "NONE" => Some(Self::None),
+            "LOT" => Some(Self::Lot),
+            "SERIAL" => Some(Self::Serial),
+            _ => None,
+        }
+    }
+
+    /// The API name of the tracking mode, e.g. `LOT`.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::None => "NONE",
+            Self::Lot => "LOT",
+            Self::Serial => "SERIAL",
+        }
+    }
+}
+
 impl Item {
     pub(crate) fn new(
         id: String,
         display_name: String,
         title: String,
         description: String,
+        base_uom: String,
+        tracking: Tracking,
     ) -> Result<Self, Error> {
         let now = Timestamp::now();
 
@@ -43,6 +110,10 @@ impl Item {
             uid: Uuid::new_v4(),
             create_time: now.clone(),
             update_time: now,
+            base_uom: base_uom.try_into()?,
+            tracking,
+            delete_time: None,
+            expire_time: None,
         })
     }
 
@@ -52,134 +123,145 @@ impl Item {
         title: Option<String>,
         description: Option<String>,
     ) -> Result<Self, Error> {
-        if self.state.is_transitioning() {
-            return Err(Error::Unknown(anyhow!("invalid state")));
+        // A blocked item has to be unblocked first, as an update settles as active.
+        if self.state.is_transitioning()
+            || matches!(self.state, ItemState::Blocked | ItemState::Deleted)
+        {
+            return Err(InvalidStateError::new(&self).into());
         }
 
+        let item = self.transition(ItemState::Updating);
+
         Ok(Self {
-            id: self.id,
-            display_name: display_name.unwrap_or(self.display_name),
-            title: title.unwrap_or(self.title),
-            description: description.unwrap_or(self.description),
-            state: ItemState::Updating,
-            etag: EntityTag::new(),
-            uid: self.uid,
-            create_time: self.create_time,
-            update_time: Timestamp::now(),
+            display_name: display_name.unwrap_or(item.display_name),
+            title: title.unwrap_or(item.title),
+            description: description.unwrap_or(item.description),
+            ..item
         })
     }
 
-    pub(crate) fn delete(self) -> Result<Self, Error> {
-        if self.state.is_transitioning() {
-            return Err(Error::Unknown(anyhow!("invalid state")));
+    /// Soft-delete the item, keeping it restorable for `retention`.
+    pub(crate) fn delete(self, retention: Duration) -> Result<Self, Error> {
+        if self.state.is_transitioning() || self.state == ItemState::Deleted {
+            return Err(InvalidStateError::new(&self).into());
         }
 
+        let now = Timestamp::now();
+
         Ok(Self {
-            id: self.id,
-            display_name: self.display_name,
-            title: self.title,
-            description: self.description,
-            state: ItemState::Deleting,
-            etag: EntityTag::new(),
-            uid: self.uid,
-            create_time: self.create_time,
-            update_time: Timestamp::now(),
+            expire_time: Some(now.plus(retention)),
+            delete_time: Some(now),
+            ..self.transition(ItemState::Deleting)
         })
     }
 
+    pub(crate) fn undelete(self) -> Result<Self, Error> {
+        if self.state != ItemState::Deleted || self.is_expired() {
+            return Err(InvalidStateError::new(&self).into());
+        }
+
+        Ok(self.transition(ItemState::Undeleting))
+    }
+
+    /// Start purging a soft-deleted item whose retention has run out.
+    pub(crate) fn expire(self) -> Result<Self, Error> {
+        if self.state != ItemState::Deleted || !self.is_expired() {
+            return Err(InvalidStateError::new(&self).into());
+        }
+
+        Ok(self.transition(ItemState::Deleting))
+    }
+
     pub(crate) fn annihilate(self) -> Result<Self, Error> {
         if self.state.is_transitioning() {
-            return Err(Error::Unknown(anyhow!("invalid state")));
+            return Err(InvalidStateError::new(&self).into());
         }
 
-        Ok(Self {
-            id: self.id,
-            display_name: self.display_name,
-            title: self.title,
-            description: self.description,
-            state: ItemState::Annihilating,
-            etag: EntityTag::new(),
-            uid: self.uid,
-            create_time: self.create_time,
-            update_time: Timestamp::now(),
-        })
+        Ok(self.transition(ItemState::Annihilating))
     }
 
     pub(crate) fn block(self) -> Result<Self, Error> {
-        if self.state.is_transitioning() || self.state == ItemState::Blocked {
-            return Err(Error::Unknown(anyhow!("invalid state")));
+        if self.state.is_transitioning()
+            || matches!(self.state, ItemState::Blocked | ItemState::Deleted)
+        {
+            return Err(InvalidStateError::new(&self).into());
         }
 
-        Ok(Self {
-            id: self.id,
-            display_name: self.display_name,
-            title: self.title,
-            description: self.description,
-            state: ItemState::Blocking,
-            etag: EntityTag::new(),
-            uid: self.uid,
-            create_time: self.create_time,
-            update_time: Timestamp::now(),
-        })
+        Ok(self.transition(ItemState::Blocking))
     }
 
     pub(crate) fn unblock(self) -> Result<Self, Error> {
-        if self.state.is_transitioning() || self.state == ItemState::Active {
-            return Err(Error::Unknown(anyhow!("invalid state")));
+        if self.state != ItemState::Blocked {
+            return Err(InvalidStateError::new(&self).into());
         }
 
-        Ok(Self {
-            id: self.id,
-            display_name: self.display_name,
-            title: self.title,
-            description: self.description,
-            state: ItemState::Unblocking,
-            etag: EntityTag::new(),
-            uid: self.uid,
-            create_time: self.create_time,
-            update_time: Timestamp::now(),
-        })
+        Ok(self.transition(ItemState::Unblocking))
     }
 
-    #[allow(dead_code)]
     pub(crate) fn active(self) -> Result<Self, Error> {
         if !matches!(
             self.state,
-            ItemState::Creating | ItemState::Updating | ItemState::Unblocking,
+            ItemState::Creating
+                | ItemState::Updating
+                | ItemState::Unblocking
+                | ItemState::Undeleting,
         ) {
-            return Err(Error::Unknown(anyhow!("invalid state")));
+            return Err(InvalidStateError::new(&self).into());
         }
 
         Ok(Self {
-            id: self.id,
-            display_name: self.display_name,
-            title: self.title,
-            description: self.description,
-            state: ItemState::Active,
-            etag: EntityTag::new(),
-            uid: self.uid,
-            create_time: self.create_time,
-            update_time: Timestamp::now(),
+            delete_time: None,
+            expire_time: None,
+            ..self.transition(ItemState::Active)
         })
     }
 
-    #[allow(dead_code)]
     pub(crate) fn blocked(self) -> Result<Self, Error> {
         if self.state != ItemState::Blocking {
-            return Err(Error::Unknown(anyhow!("invalid state")));
+            return Err(InvalidStateError::new(&self).into());
         }
 
-        Ok(Self {
-            id: self.id,
-            display_name: self.display_name,
-            title: self.title,
-            description: self.description,
-            state: ItemState::Blocked,
+        Ok(self.transition(ItemState::Blocked))
+    }
+
+    pub(crate) fn deleted(self) -> Result<Self, Error> {
+        if self.state != ItemState::Deleting {
+            return Err(InvalidStateError::new(&self).into());
+        }
+
+        Ok(self.transition(ItemState::Deleted))
+    }
+
+    /// Whether the item is soft-deleted and its retention has run out.
+    #[must_use]
+    pub fn is_expired(&self) -> bool {
+        self.expire_time
+            .as_ref()
+            .is_some_and(|expire_time| expire_time.value() <= Timestamp::now().value())
+    }
+
+    /// Move a transitioning item into the state its operation leads to.
+    /// Returns `None` if the operation ends with the item being removed.
+    pub(crate) fn settle(self) -> Result<Option<Self>, Error> {
+        match self.state.next() {
+            Some(ItemState::Active) => self.active().map(Some),
+            Some(ItemState::Blocked) => self.blocked().map(Some),
+            // A soft-deleted item whose retention has run out is purged for good.
+            Some(ItemState::Deleted) if self.is_expired() => Ok(None),
+            Some(ItemState::Deleted) => self.deleted().map(Some),
+            Some(_) => Err(InvalidStateError::new(&self).into()),
+            None if self.state == ItemState::Annihilating => Ok(None),
+            None => Ok(Some(self)),
+        }
+    }
+
+    fn transition(self, state: ItemState) -> Self {
+        Self {
+            state,
             etag: EntityTag::new(),
-            uid: self.uid,
-            create_time: self.create_time,
             update_time: Timestamp::now(),
-        })
+            ..self
+        }
     }
 }
 
@@ -188,15 +270,230 @@ pub enum Error {
     #[error(transparent)]
     Empty(#[from] EmptyError),
     #[error(transparent)]
+    NotFound(#[from] NotFoundError),
+    #[error(transparent)]
+    RevisionNotFound(#[from] RevisionNotFoundError),
+    #[error(transparent)]
+    AlreadyExists(#[from] AlreadyExistsError),
+    #[error(transparent)]
+    EtagMismatch(#[from] EtagMismatchError),
+    #[error(transparent)]
+    InvalidState(#[from] InvalidStateError),
+    #[error(transparent)]
+    InUse(#[from] InUseError),
+    #[error(transparent)]
+    UnitOfMeasure(#[from] unit_of_measure::Error),
+    #[error(transparent)]
     Id(#[from] id::Error),
     #[error(transparent)]
     Etag(#[from] entity_tag::Error),
     #[error(transparent)]
     Timestamp(#[from] timestamp::Error),
     #[error(transparent)]
+    FieldMask(#[from] field_mask::Error),
+    #[error(transparent)]
+    Filter(#[from] filter::Error),
+    #[error(transparent)]
+    OrderBy(#[from] order_by::Error),
+    #[error(transparent)]
+    PageToken(#[from] page_token::Error),
+    #[error(transparent)]
+    Operation(#[from] crate::sync::Error),
+    #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
 
 #[derive(Clone, Debug, ThisError, From)]
 #[error("item cannot be empty")]
 pub struct EmptyError;
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("item {id:?} not found")]
+pub struct NotFoundError {
+    id: Id,
+}
+
+impl NotFoundError {
+    #[must_use]
+    pub const fn new(id: Id) -> Self {
+        Self { id }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("revision {revision:?} of item {id:?} not found")]
+pub struct RevisionNotFoundError {
+    id: Id,
+    revision: Id,
+}
+
+impl RevisionNotFoundError {
+    #[must_use]
+    pub const fn new(id: Id, revision: Id) -> Self {
+        Self { id, revision }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("item {id:?} already exists")]
+pub struct AlreadyExistsError {
+    id: Id,
+}
+
+impl AlreadyExistsError {
+    #[must_use]
+    pub const fn new(id: Id) -> Self {
+        Self { id }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("etag {etag:?} does not match the current etag of item {id:?}")]
+pub struct EtagMismatchError {
+    id: Id,
+    etag: String,
+}
+
+impl EtagMismatchError {
+    #[must_use]
+    pub const fn new(id: Id, etag: String) -> Self {
+        Self { id, etag }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("item {id:?} cannot be changed while it is {}", state.name())]
+pub struct InvalidStateError {
+    id: Id,
+    state: ItemState,
+}
+
+impl InvalidStateError {
+    #[must_use]
+    pub fn new(item: &Item) -> Self {
+        Self {
+            id: item.id.clone(),
+            state: item.state.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("item {id:?} is produced by {production_orders} open production order(s), a component of {bills_of_materials} bill(s) of materials and moved by {stock_movements} stock movement(s)")]
+pub struct InUseError {
+    id: Id,
+    production_orders: i64,
+    bills_of_materials: i64,
+    stock_movements: i64,
+}
+
+impl InUseError {
+    #[must_use]
+    pub const fn new(
+        id: Id,
+        production_orders: i64,
+        bills_of_materials: i64,
+        stock_movements: i64,
+    ) -> Self {
+        Self {
+            id,
+            production_orders,
+            bills_of_materials,
+            stock_movements,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RETENTION: Duration = Duration::from_mins(1);
+
+    fn settle(item: Item) -> anyhow::Result<Item> {
+        item.settle()?
+            .ok_or_else(|| anyhow::anyhow!("item was removed"))
+    }
+
+    fn active() -> anyhow::Result<Item> {
+        let item = Item::new(
+            String::from("bike"),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::from("pcs"),
+            Tracking::None,
+        )?;
+
+        settle(item)
+    }
+
+    #[test]
+    fn soft_deletes_and_undeletes() -> anyhow::Result<()> {
+        let deleted = settle(active()?.delete(RETENTION)?)?;
+        assert_eq!(&ItemState::Deleted, deleted.state());
+        assert!(deleted.delete_time().is_some());
+        assert!(deleted.expire_time().is_some());
+        assert!(!deleted.is_expired());
+
+        assert!(deleted.clone().update(None, None, None).is_err());
+        assert!(deleted.clone().block().is_err());
+        assert!(deleted.clone().delete(RETENTION).is_err());
+        assert!(deleted.clone().expire().is_err());
+
+        let undeleted = settle(deleted.undelete()?)?;
+        assert_eq!(&ItemState::Active, undeleted.state());
+        assert_eq!(&None, undeleted.delete_time());
+        assert_eq!(&None, undeleted.expire_time());
+        assert!(undeleted.undelete().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn keeps_blocked_items_blocked() -> anyhow::Result<()> {
+        let blocked = settle(active()?.block()?)?;
+        assert_eq!(&ItemState::Blocked, blocked.state());
+
+        assert!(blocked
+            .clone()
+            .update(None, Some(String::from("Bike")), None)
+            .is_err());
+
+        let unblocked = settle(blocked.unblock()?)?;
+        assert_eq!(&ItemState::Active, unblocked.state());
+        assert!(unblocked
+            .update(None, Some(String::from("Bike")), None)
+            .is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn purges_expired_items() -> anyhow::Result<()> {
+        assert_eq!(None, active()?.delete(Duration::ZERO)?.settle()?);
+
+        let deleted = settle(active()?.delete(RETENTION)?)?;
+        let deleted = Item {
+            expire_time: Some(Timestamp::now()),
+            ..deleted
+        };
+        assert!(deleted.is_expired());
+        assert!(deleted.clone().undelete().is_err());
+
+        let expiring = deleted.expire()?;
+        assert_eq!(&ItemState::Deleting, expiring.state());
+        assert_eq!(None, expiring.settle()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn annihilates_deleted_items() -> anyhow::Result<()> {
+        let deleted = settle(active()?.delete(RETENTION)?)?;
+
+        assert_eq!(None, deleted.annihilate()?.settle()?);
+
+        Ok(())
+    }
+}
@@ -1,12 +1,11 @@
-use anyhow::anyhow;
 use uuid::Uuid;
 
 pub use super::Error;
-use super::{repository, sync::Metadata};
+use super::{repository, sync::Metadata, AlreadyExistsError, EtagMismatchError};
 
-use std::{future::Future, sync::Arc};
+use std::{future::Future, sync::Arc, time::Duration};
 
-use crate::{id, sync::Operation, Id, Item};
+use crate::{sync::Operation, unit_of_measure, EntityTag, Id, Item, Tracking};
 
 // MARK: Create
 
@@ -23,21 +22,37 @@ pub struct CreateRequest {
     display_name: String,
     title: String,
     description: String,
+    /// The unit of measure the item is counted in, [`unit_of_measure::DEFAULT_UNIT_OF_MEASURE`] if empty.
+    base_uom: String,
+    tracking: Tracking,
+    actor: String,
 }
 
 impl CreateRequest {
     #[must_use]
-    pub const fn new(
+    pub fn new(
         id: Option<String>,
         display_name: String,
         title: String,
         description: String,
+        base_uom: String,
+        tracking: Tracking,
+        actor: String,
     ) -> Self {
+        let base_uom = if base_uom.is_empty() {
+            unit_of_measure::DEFAULT_UNIT_OF_MEASURE.to_string()
+        } else {
+            base_uom
+        };
+
         Self {
             id,
             display_name,
             title,
             description,
+            base_uom,
+            tracking,
+            actor,
         }
     }
 }
@@ -58,6 +73,7 @@ pub struct UpdateRequest {
     title: Option<String>,
     description: Option<String>,
     etag: String,
+    actor: String,
 }
 
 impl UpdateRequest {
@@ -68,6 +84,7 @@ impl UpdateRequest {
         title: Option<String>,
         description: Option<String>,
         etag: String,
+        actor: String,
     ) -> Self {
         Self {
             id,
@@ -75,6 +92,7 @@ impl UpdateRequest {
             title,
             description,
             etag,
+            actor,
         }
     }
 }
@@ -92,12 +110,36 @@ pub trait Delete: Send + Sync + 'static {
 pub struct DeleteRequest {
     id: String,
     etag: String,
+    actor: String,
 }
 
 impl DeleteRequest {
     #[must_use]
-    pub const fn new(id: String, etag: String) -> Self {
-        Self { id, etag }
+    pub const fn new(id: String, etag: String, actor: String) -> Self {
+        Self { id, etag, actor }
+    }
+}
+
+// MARK: Undelete
+
+pub trait Undelete: Send + Sync + 'static {
+    #[must_use]
+    fn undelete(
+        &self,
+        request: UndeleteRequest,
+    ) -> impl Future<Output = Result<Operation<Metadata>, Error>> + Send;
+}
+
+pub struct UndeleteRequest {
+    id: String,
+    etag: String,
+    actor: String,
+}
+
+impl UndeleteRequest {
+    #[must_use]
+    pub const fn new(id: String, etag: String, actor: String) -> Self {
+        Self { id, etag, actor }
     }
 }
 
@@ -114,12 +156,13 @@ pub trait Annihilate: Send + Sync + 'static {
 pub struct AnnihilateRequest {
     id: String,
     etag: String,
+    actor: String,
 }
 
 impl AnnihilateRequest {
     #[must_use]
-    pub const fn new(id: String, etag: String) -> Self {
-        Self { id, etag }
+    pub const fn new(id: String, etag: String, actor: String) -> Self {
+        Self { id, etag, actor }
     }
 }
 
@@ -136,12 +179,13 @@ pub trait Block: Send + Sync + 'static {
 pub struct BlockRequest {
     id: String,
     etag: String,
+    actor: String,
 }
 
 impl BlockRequest {
     #[must_use]
-    pub const fn new(id: String, etag: String) -> Self {
-        Self { id, etag }
+    pub const fn new(id: String, etag: String, actor: String) -> Self {
+        Self { id, etag, actor }
     }
 }
 
@@ -158,12 +202,44 @@ pub trait Unblock: Send + Sync + 'static {
 pub struct UnblockRequest {
     id: String,
     etag: String,
+    actor: String,
 }
 
 impl UnblockRequest {
     #[must_use]
-    pub const fn new(id: String, etag: String) -> Self {
-        Self { id, etag }
+    pub const fn new(id: String, etag: String, actor: String) -> Self {
+        Self { id, etag, actor }
+    }
+}
+
+// MARK: Rollback
+
+pub trait Rollback: Send + Sync + 'static {
+    #[must_use]
+    fn rollback(
+        &self,
+        request: RollbackRequest,
+    ) -> impl Future<Output = Result<Operation<Metadata>, Error>> + Send;
+}
+
+/// Restores the display name, title and description an item had at `revision_id`, as an
+/// update recording a new revision.
+pub struct RollbackRequest {
+    id: String,
+    revision_id: String,
+    etag: String,
+    actor: String,
+}
+
+impl RollbackRequest {
+    #[must_use]
+    pub const fn new(id: String, revision_id: String, etag: String, actor: String) -> Self {
+        Self {
+            id,
+            revision_id,
+            etag,
+            actor,
+        }
     }
 }
 
@@ -171,18 +247,41 @@ impl UnblockRequest {
 
 #[derive(Debug, Clone)]
 pub struct Service<
-    IR: repository::Create + repository::Update + repository::Get + repository::Delete + Clone,
+    IR: repository::Create
+        + repository::Update
+        + repository::Get
+        + repository::Delete
+        + repository::Revisions
+        + Clone,
+    UR: unit_of_measure::repository::Get + Clone,
 > {
     item_repository: Arc<IR>,
+    unit_of_measure_repository: Arc<UR>,
+    /// How long a deleted item can be restored before it is purged.
+    retention: Duration,
 }
 
-impl<IR> Service<IR>
+impl<IR, UR> Service<IR, UR>
 where
-    IR: repository::Get + repository::Create + repository::Update + repository::Delete + Clone,
+    IR: repository::Get
+        + repository::Create
+        + repository::Update
+        + repository::Delete
+        + repository::Revisions
+        + Clone,
+    UR: unit_of_measure::repository::Get + Clone,
 {
     #[must_use]
-    pub const fn new(item_repository: Arc<IR>) -> Self {
-        Self { item_repository }
+    pub const fn new(
+        item_repository: Arc<IR>,
+        unit_of_measure_repository: Arc<UR>,
+        retention: Duration,
+    ) -> Self {
+        Self {
+            item_repository,
+            unit_of_measure_repository,
+            retention,
+        }
     }
 
     async fn validate_create_request(
@@ -192,9 +291,9 @@ where
         let id = match request.id {
             Some(id) => match Id::try_from(id) {
                 Ok(id) => match self.item_repository.get(&id).await {
-                    Ok(item) => return Err(Error::Id(id::DuplicateError(item.id).into())),
+                    Ok(item) => return Err(AlreadyExistsError::new(item.id).into()),
                     Err(err) => match err {
-                        Error::Id(id::Error::NotFound(_)) => id.to_string(),
+                        Error::NotFound(_) => id.to_string(),
                         _ => return Err(err),
                     },
                 },
@@ -203,95 +302,186 @@ where
             _ => Uuid::new_v4().to_string(),
         };
 
-        let item = Item::new(id, request.display_name, request.title, request.description)?;
+        let base_uom = Id::try_from(request.base_uom)?;
+        self.unit_of_measure_repository.get(&base_uom).await?;
 
-        Ok(Operation::new(Id::new(), Metadata::new(item), None))
+        let item = Item::new(
+            id,
+            request.display_name,
+            request.title,
+            request.description,
+            base_uom.to_string(),
+            request.tracking,
+        )?;
+
+        Ok(Operation::new(
+            Id::new(),
+            Metadata::new(item, request.actor),
+            None,
+        ))
     }
 
     async fn validate_update_request(
         &self,
         request: UpdateRequest,
-    ) -> Result<Operation<Metadata>, Error> {
+    ) -> Result<(Operation<Metadata>, EntityTag), Error> {
         let id = Id::try_from(request.id)?;
         let item = self.item_repository.get(&id).await?;
 
         if request.etag != item.etag.to_string() {
-            return Err(Error::Id(crate::id::EmptyError.into()));
+            return Err(EtagMismatchError::new(id, request.etag).into());
         }
 
+        let etag = item.etag.clone();
         let item = item.update(request.display_name, request.title, request.description)?;
 
-        Ok(Operation::new(Id::new(), Metadata::new(item), None))
+        Ok((
+            Operation::new(Id::new(), Metadata::new(item, request.actor), None),
+            etag,
+        ))
     }
 
     async fn validate_delete_request(
         &self,
         request: DeleteRequest,
-    ) -> Result<Operation<Metadata>, Error> {
+    ) -> Result<(Operation<Metadata>, EntityTag), Error> {
         let id = Id::try_from(request.id)?;
         let item = self.item_repository.get(&id).await?;
 
         if request.etag != item.etag.to_string() {
-            return Err(Error::Id(crate::id::EmptyError.into()));
+            return Err(EtagMismatchError::new(id, request.etag).into());
         }
 
-        let item = item.delete()?;
+        let etag = item.etag.clone();
+        let item = item.delete(self.retention)?;
 
-        Ok(Operation::new(Id::new(), Metadata::new(item), None))
+        Ok((
+            Operation::new(Id::new(), Metadata::new(item, request.actor), None),
+            etag,
+        ))
+    }
+
+    async fn validate_undelete_request(
+        &self,
+        request: UndeleteRequest,
+    ) -> Result<(Operation<Metadata>, EntityTag), Error> {
+        let id = Id::try_from(request.id)?;
+        let item = self.item_repository.get(&id).await?;
+
+        if request.etag != item.etag.to_string() {
+            return Err(EtagMismatchError::new(id, request.etag).into());
+        }
+
+        let etag = item.etag.clone();
+        let item = item.undelete()?;
+
+        Ok((
+            Operation::new(Id::new(), Metadata::new(item, request.actor), None),
+            etag,
+        ))
     }
 
     async fn validate_annihilate_request(
         &self,
         request: AnnihilateRequest,
-    ) -> Result<Operation<Metadata>, Error> {
+    ) -> Result<(Operation<Metadata>, EntityTag), Error> {
         let id = Id::try_from(request.id)?;
         let item = self.item_repository.get(&id).await?;
 
         if request.etag != item.etag.to_string() {
-            return Err(Error::Id(crate::id::EmptyError.into()));
+            return Err(EtagMismatchError::new(id, request.etag).into());
         }
 
+        let etag = item.etag.clone();
         let item = item.annihilate()?;
 
-        Ok(Operation::new(Id::new(), Metadata::new(item), None))
+        Ok((
+            Operation::new(Id::new(), Metadata::new(item, request.actor), None),
+            etag,
+        ))
     }
 
     async fn validate_block_request(
         &self,
         request: BlockRequest,
-    ) -> Result<Operation<Metadata>, Error> {
+    ) -> Result<(Operation<Metadata>, EntityTag), Error> {
         let id = Id::try_from(request.id)?;
         let item = self.item_repository.get(&id).await?;
 
         if request.etag != item.etag.to_string() {
-            return Err(Error::Unknown(anyhow!("invalid etag")));
+            return Err(EtagMismatchError::new(id, request.etag).into());
         }
 
+        let etag = item.etag.clone();
         let item = item.block()?;
 
-        Ok(Operation::new(Id::new(), Metadata::new(item), None))
+        Ok((
+            Operation::new(Id::new(), Metadata::new(item, request.actor), None),
+            etag,
+        ))
     }
 
     async fn validate_unblock_request(
         &self,
         request: UnblockRequest,
-    ) -> Result<Operation<Metadata>, Error> {
+    ) -> Result<(Operation<Metadata>, EntityTag), Error> {
         let id = Id::try_from(request.id)?;
         let item = self.item_repository.get(&id).await?;
 
         if request.etag != item.etag.to_string() {
-            return Err(Error::Unknown(anyhow!("invalid etag")));
+            return Err(EtagMismatchError::new(id, request.etag).into());
         }
 
+        let etag = item.etag.clone();
         let item = item.unblock()?;
 
-        Ok(Operation::new(Id::new(), Metadata::new(item), None))
+        Ok((
+            Operation::new(Id::new(), Metadata::new(item, request.actor), None),
+            etag,
+        ))
+    }
+
+    async fn validate_rollback_request(
+        &self,
+        request: RollbackRequest,
+    ) -> Result<(Operation<Metadata>, EntityTag), Error> {
+        let id = Id::try_from(request.id)?;
+        let revision_id = Id::try_from(request.revision_id)?;
+        let item = self.item_repository.get(&id).await?;
+
+        if request.etag != item.etag.to_string() {
+            return Err(EtagMismatchError::new(id, request.etag).into());
+        }
+
+        let (revision, ..) = self
+            .item_repository
+            .revision(&id, &revision_id)
+            .await?
+            .dissolve();
+
+        let etag = item.etag.clone();
+        let item = item.update(
+            Some(revision.display_name),
+            Some(revision.title),
+            Some(revision.description),
+        )?;
+
+        Ok((
+            Operation::new(Id::new(), Metadata::new(item, request.actor), None),
+            etag,
+        ))
     }
 }
 
-impl<IR> Create for Service<IR>
+impl<IR, UR> Create for Service<IR, UR>
 where
-    IR: repository::Create + repository::Update + repository::Get + repository::Delete + Clone,
+    IR: repository::Create
+        + repository::Update
+        + repository::Get
+        + repository::Delete
+        + repository::Revisions
+        + Clone,
+    UR: unit_of_measure::repository::Get + Clone,
 {
     async fn create(&self, request: CreateRequest) -> Result<Operation<Metadata>, Error> {
         let operation = self.validate_create_request(request).await?;
@@ -301,61 +491,127 @@ where
     }
 }
 
-impl<IR> Update for Service<IR>
+impl<IR, UR> Update for Service<IR, UR>
 where
-    IR: repository::Create + repository::Update + repository::Get + repository::Delete + Clone,
+    IR: repository::Create
+        + repository::Update
+        + repository::Get
+        + repository::Delete
+        + repository::Revisions
+        + Clone,
+    UR: unit_of_measure::repository::Get + Clone,
 {
     async fn update(&self, request: UpdateRequest) -> Result<Operation<Metadata>, Error> {
-        let operation = self.validate_update_request(request).await?;
-        self.item_repository.update(&operation).await?;
+        let (operation, etag) = self.validate_update_request(request).await?;
+        self.item_repository.update(&operation, &etag).await?;
 
         Ok(operation)
     }
 }
 
-impl<IR> Delete for Service<IR>
+impl<IR, UR> Delete for Service<IR, UR>
 where
-    IR: repository::Create + repository::Update + repository::Get + repository::Delete + Clone,
+    IR: repository::Create
+        + repository::Update
+        + repository::Get
+        + repository::Delete
+        + repository::Revisions
+        + Clone,
+    UR: unit_of_measure::repository::Get + Clone,
 {
     async fn delete(&self, request: DeleteRequest) -> Result<Operation<Metadata>, Error> {
-        let operation = self.validate_delete_request(request).await?;
-        self.item_repository.update(&operation).await?;
+        let (operation, etag) = self.validate_delete_request(request).await?;
+        self.item_repository.update(&operation, &etag).await?;
 
         Ok(operation)
     }
 }
 
-impl<IR> Annihilate for Service<IR>
+impl<IR, UR> Undelete for Service<IR, UR>
 where
-    IR: repository::Create + repository::Update + repository::Get + repository::Delete + Clone,
+    IR: repository::Create
+        + repository::Update
+        + repository::Get
+        + repository::Delete
+        + repository::Revisions
+        + Clone,
+    UR: unit_of_measure::repository::Get + Clone,
+{
+    async fn undelete(&self, request: UndeleteRequest) -> Result<Operation<Metadata>, Error> {
+        let (operation, etag) = self.validate_undelete_request(request).await?;
+        self.item_repository.update(&operation, &etag).await?;
+
+        Ok(operation)
+    }
+}
+
+impl<IR, UR> Annihilate for Service<IR, UR>
+where
+    IR: repository::Create
+        + repository::Update
+        + repository::Get
+        + repository::Delete
+        + repository::Revisions
+        + Clone,
+    UR: unit_of_measure::repository::Get + Clone,
 {
     async fn annihilate(&self, request: AnnihilateRequest) -> Result<Operation<Metadata>, Error> {
-        let operation = self.validate_annihilate_request(request).await?;
-        self.item_repository.update(&operation).await?;
+        let (operation, etag) = self.validate_annihilate_request(request).await?;
+        self.item_repository.update(&operation, &etag).await?;
 
         Ok(operation)
     }
 }
 
-impl<IR> Block for Service<IR>
+impl<IR, UR> Block for Service<IR, UR>
 where
-    IR: repository::Get + repository::Create + repository::Update + repository::Delete + Clone,
+    IR: repository::Get
+        + repository::Create
+        + repository::Update
+        + repository::Delete
+        + repository::Revisions
+        + Clone,
+    UR: unit_of_measure::repository::Get + Clone,
 {
     async fn block(&self, request: BlockRequest) -> Result<Operation<Metadata>, Error> {
-        let operation = self.validate_block_request(request).await?;
-        self.item_repository.update(&operation).await?;
+        let (operation, etag) = self.validate_block_request(request).await?;
+        self.item_repository.update(&operation, &etag).await?;
 
         Ok(operation)
     }
 }
 
-impl<IR> Unblock for Service<IR>
+impl<IR, UR> Unblock for Service<IR, UR>
 where
-    IR: repository::Get + repository::Create + repository::Update + repository::Delete + Clone,
+    IR: repository::Get
+        + repository::Create
+        + repository::Update
+        + repository::Delete
+        + repository::Revisions
+        + Clone,
+    UR: unit_of_measure::repository::Get + Clone,
 {
     async fn unblock(&self, request: UnblockRequest) -> Result<Operation<Metadata>, Error> {
-        let operation = self.validate_unblock_request(request).await?;
-        self.item_repository.update(&operation).await?;
+        let (operation, etag) = self.validate_unblock_request(request).await?;
+        self.item_repository.update(&operation, &etag).await?;
+
+        Ok(operation)
+    }
+}
+
+impl<IR, UR> Rollback for Service<IR, UR>
+where
+    IR: repository::Get
+        + repository::Create
+        + repository::Update
+        + repository::Delete
+        + repository::Revisions
+        + Clone,
+    UR: unit_of_measure::repository::Get + Clone,
+{
+    async fn rollback(&self, request: RollbackRequest) -> Result<Operation<Metadata>, Error> {
+        let (operation, etag) = self.validate_rollback_request(request).await?;
+        self.item_repository.update(&operation, &etag).await?;
 
         Ok(operation)
     }
@@ -4,7 +4,7 @@ pub use super::Error;
 
 use std::{future::Future, sync::Arc};
 
-use crate::{Id, Item};
+use crate::{Id, Item, ItemEvent, ItemRevision};
 
 use super::repository;
 
@@ -16,12 +16,14 @@ pub trait Get: Send + Sync + 'static {
 
 pub struct GetRequest {
     id: String,
+    /// The revision to get the item as of, the current item if `None`.
+    revision: Option<String>,
 }
 
 impl GetRequest {
     #[must_use]
-    pub const fn new(id: String) -> Self {
-        Self { id }
+    pub const fn new(id: String, revision: Option<String>) -> Self {
+        Self { id, revision }
     }
 }
 
@@ -40,6 +42,8 @@ pub struct ListRequest {
     page_token: Option<String>,
     order_by: Option<String>,
     filter: Option<String>,
+    /// Whether soft-deleted items are listed too.
+    show_deleted: bool,
 }
 
 impl ListRequest {
@@ -49,12 +53,14 @@ impl ListRequest {
         page_token: Option<String>,
         order_by: Option<String>,
         filter: Option<String>,
+        show_deleted: bool,
     ) -> Self {
         Self {
-            page_size: page_size.unwrap_or(50).min(1000),
+            page_size: page_size.filter(|s| *s > 0).unwrap_or(50).min(1000),
             page_token,
             order_by,
             filter,
+            show_deleted,
         }
     }
 }
@@ -77,16 +83,87 @@ impl ListResponse {
     }
 }
 
+// MARK: ListRevisions
+
+pub trait ListRevisions: Send + Sync + 'static {
+    fn list_revisions(
+        &self,
+        request: ListRevisionsRequest,
+    ) -> impl Future<Output = Result<ListRevisionsResponse, Error>> + Send;
+}
+
+/// Lists the revisions of the item `id`, newest first.
+#[derive(Getters)]
+pub struct ListRevisionsRequest {
+    id: String,
+    page_size: i32,
+    page_token: Option<String>,
+}
+
+impl ListRevisionsRequest {
+    #[must_use]
+    pub fn new(id: String, page_size: Option<i32>, page_token: Option<String>) -> Self {
+        Self {
+            id,
+            page_size: page_size.filter(|s| *s > 0).unwrap_or(50).min(1000),
+            page_token,
+        }
+    }
+}
+
+#[derive(Dissolve)]
+pub struct ListRevisionsResponse {
+    revisions: Vec<ItemRevision>,
+    next_page_token: Option<String>,
+}
+
+impl ListRevisionsResponse {
+    #[must_use]
+    pub const fn new(revisions: Vec<ItemRevision>, next_page_token: Option<String>) -> Self {
+        Self {
+            revisions,
+            next_page_token,
+        }
+    }
+}
+
+// MARK: ListEvents
+
+pub trait ListEvents: Send + Sync + 'static {
+    fn list_events(
+        &self,
+        request: ListEventsRequest,
+    ) -> impl Future<Output = Result<Vec<ItemEvent>, Error>> + Send;
+}
+
+/// Lists the item events after the one with sequence `after_sequence`, oldest first.
+pub struct ListEventsRequest {
+    after_sequence: i64,
+    page_size: i32,
+}
+
+impl ListEventsRequest {
+    #[must_use]
+    pub fn new(after_sequence: i64, page_size: Option<i32>) -> Self {
+        Self {
+            after_sequence: after_sequence.max(0),
+            page_size: page_size.filter(|s| *s > 0).unwrap_or(50).min(1000),
+        }
+    }
+}
+
 // MARK: Service
 
 #[derive(Debug, Clone)]
-pub struct Service<IR: repository::Get + repository::List + Clone> {
+pub struct Service<
+    IR: repository::Get + repository::List + repository::Events + repository::Revisions + Clone,
+> {
     item_repository: Arc<IR>,
 }
 
 impl<IR> Service<IR>
 where
-    IR: repository::Get + repository::List + Clone,
+    IR: repository::Get + repository::List + repository::Events + repository::Revisions + Clone,
 {
     #[must_use]
     pub const fn new(item_repository: Arc<IR>) -> Self {
@@ -94,28 +171,62 @@ where
     }
 }
 
-fn validate_get_request(request: GetRequest) -> Result<Id, Error> {
+fn validate_get_request(request: GetRequest) -> Result<(Id, Option<Id>), Error> {
     let id: Id = request.id.try_into()?;
+    let revision = request.revision.map(Id::try_from).transpose()?;
 
-    Ok(id)
+    Ok((id, revision))
 }
 
 impl<IR> Get for Service<IR>
 where
-    IR: repository::Get + repository::List + Clone,
+    IR: repository::Get + repository::List + repository::Events + repository::Revisions + Clone,
 {
     async fn get(&self, request: GetRequest) -> Result<Item, Error> {
-        let id = validate_get_request(request)?;
-
-        self.item_repository.get(&id).await
+        let (id, revision) = validate_get_request(request)?;
+
+        match revision {
+            Some(revision) => {
+                let revision = self.item_repository.revision(&id, &revision).await?;
+                Ok(revision.item().clone())
+            }
+            None => self.item_repository.get(&id).await,
+        }
     }
 }
 
 impl<IR> List for Service<IR>
 where
-    IR: repository::Get + repository::List + Clone,
+    IR: repository::Get + repository::List + repository::Events + repository::Revisions + Clone,
 {
     async fn list(&self, request: ListRequest) -> Result<ListResponse, Error> {
         self.item_repository.list(&request).await
     }
 }
+
+impl<IR> ListRevisions for Service<IR>
+where
+    IR: repository::Get + repository::List + repository::Events + repository::Revisions + Clone,
+{
+    async fn list_revisions(
+        &self,
+        request: ListRevisionsRequest,
+    ) -> Result<ListRevisionsResponse, Error> {
+        let id = Id::try_from(request.id.clone())?;
+        // Revisions outlive a soft delete, but not the item being removed for good.
+        self.item_repository.get(&id).await?;
+
+        self.item_repository.revisions(&id, &request).await
+    }
+}
+
+impl<IR> ListEvents for Service<IR>
+where
+    IR: repository::Get + repository::List + repository::Events + repository::Revisions + Clone,
+{
+    async fn list_events(&self, request: ListEventsRequest) -> Result<Vec<ItemEvent>, Error> {
+        self.item_repository
+            .events(request.after_sequence, request.page_size.into())
+            .await
+    }
+}
@@ -2,19 +2,24 @@ use std::{future::Future, sync::Arc};
 
 use anyhow::{anyhow, Context};
 use num_traits::ToPrimitive;
-use sqlx::{Executor, Sqlite, Transaction};
+use prost_types::Any;
+use sqlx::{Executor, FromRow, QueryBuilder, Sqlite, Transaction};
 
 use crate::{
-    id, item,
-    sqlx::{Error as SqlxError, SqliteConnection, SqliteError},
-    sync::{Operation, OperationMetadata},
-    Id, Item, Timestamp,
+    item, page_token,
+    sqlx::{
+        list::{self, Column, Kind, OrderTerm},
+        Error as SqlxError, SqliteConnection, SqliteError,
+    },
+    sync::{self, Operation, OperationEntity, OperationMetadata, OperationRecord},
+    EntityTag, Filter, Id, Item, ItemEvent, ItemEventKind, ItemRevision, ItemState, OrderBy,
+    PageToken, ProductionOrderState, Timestamp, Tracking,
 };
 
 use super::{
-    query::{ListRequest, ListResponse},
-    sync::Metadata,
-    Error,
+    query::{ListRequest, ListResponse, ListRevisionsRequest, ListRevisionsResponse},
+    sync::{Metadata, Verb},
+    AlreadyExistsError, Error, EtagMismatchError, InUseError, NotFoundError, RevisionNotFoundError,
 };
 
 // MARK: Get
@@ -25,7 +30,7 @@ pub trait Get: Send + Sync + 'static {
     ///
     /// # Errors
     ///
-    /// - MUST return [`get::Error::NotFound`] if an [`Item`] with the given [`Id`] does not exist.
+    /// - MUST return [`Error::NotFound`] if an [`Item`] with the given [`Id`] does not exist.
     fn get(&self, id: &Id) -> impl Future<Output = Result<Item, Error>> + Send;
 }
 
@@ -52,7 +57,7 @@ pub trait Create: Send + Sync + 'static {
     ///
     /// # Errors
     ///
-    /// - MUST return [`create::Error::Duplicate`] if an [`Item`] with the same [`Code`] already exists.
+    /// - MUST return [`Error::AlreadyExists`] if an [`Item`] with the same [`Id`] already exists.
     fn create(
         &self,
         operation: &Operation<Metadata>,
@@ -63,14 +68,36 @@ pub trait Create: Send + Sync + 'static {
 
 /// `Update` represents a store of item data.
 pub trait Update: Send + Sync + 'static {
-    /// Update an [`Item`].
+    /// Update an [`Item`], provided its stored etag still equals `etag`.
     ///
     /// # Errors
     ///
-    /// - MUST return [`update::Error::Duplicate`] if an [`Item`] with the same [`Code`] already exists.
+    /// - MUST return [`Error::NotFound`] if an [`Item`] with the given [`Id`] does not exist.
+    /// - MUST return [`Error::EtagMismatch`] if the stored etag differs from `etag`.
+    /// - MUST return [`Error::InUse`] if the [`Item`] starts being deleted or annihilated while
+    ///   open production orders, bills of materials or stock movements refer to it.
     fn update(
         &self,
         operation: &Operation<Metadata>,
+        etag: &EntityTag,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+// MARK: Complete
+
+/// `Complete` represents a store of item data.
+pub trait Complete: Send + Sync + 'static {
+    /// Persist the final [`Item`] of a finished operation and mark the operation as done.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::Unknown`] if the operation has no successful result.
+    /// - MUST return [`Error::EtagMismatch`] if the stored etag differs from `etag`.
+    /// - MUST return [`crate::id::Error::NotFound`] if the operation does not exist.
+    fn complete(
+        &self,
+        operation: &Operation<Metadata>,
+        etag: &EntityTag,
     ) -> impl Future<Output = Result<(), Error>> + Send;
 }
 
@@ -78,22 +105,515 @@ pub trait Update: Send + Sync + 'static {
 
 /// `Delete` represents a store of item data.
 pub trait Delete: Send + Sync + 'static {
-    /// Delete an [`Item`].
+    /// Delete an [`Item`] and mark the operation removing it as done.
     ///
     /// # Errors
     ///
-    /// - MUST return [`delete::Error::NotFound`] if an [`Item`] with the given [`Id`] does not exist.
+    /// - MUST return [`Error::NotFound`] if an [`Item`] with the given [`Id`] does not exist.
     fn delete(
         &self,
         operation: &Operation<Metadata>,
     ) -> impl Future<Output = Result<(), Error>> + Send;
 }
 
+// MARK: Purge
+
+/// `Purge` represents a store of item data.
+pub trait Purge: Send + Sync + 'static {
+    /// Record the operation deleting an expired [`Item`] and remove the item along with it, so
+    /// that no worker ever sees the item half-purged.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::NotFound`] if an [`Item`] with the given [`Id`] does not exist.
+    /// - MUST return [`Error::EtagMismatch`] if the stored etag differs from `etag`.
+    /// - MUST return [`Error::InUse`] if open production orders, bills of materials or stock
+    ///   movements refer to the [`Item`].
+    fn purge(
+        &self,
+        operation: &Operation<Metadata>,
+        etag: &EntityTag,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+// MARK: Expired
+
+/// `Expired` represents a store of item data.
+pub trait Expired: Send + Sync + 'static {
+    /// List the soft-deleted [`Item`]s whose retention has run out.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::Unknown`] if the items cannot be fetched.
+    fn expired(&self) -> impl Future<Output = Result<Vec<Item>, Error>> + Send;
+}
+
+// MARK: Events
+
+/// `Events` represents a store of item change events.
+pub trait Events: Send + Sync + 'static {
+    /// List the [`ItemEvent`]s appended after the one with sequence `after`, oldest first and
+    /// at most `limit` of them.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::Unknown`] if the events cannot be fetched.
+    fn events(
+        &self,
+        after: i64,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<ItemEvent>, Error>> + Send;
+}
+
+// MARK: Revisions
+
+/// `Revisions` represents a store of item revisions.
+pub trait Revisions: Send + Sync + 'static {
+    /// Get an [`ItemRevision`].
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::RevisionNotFound`] if the [`Item`] has no revision with the given
+    ///   [`Id`].
+    fn revision(
+        &self,
+        id: &Id,
+        revision: &Id,
+    ) -> impl Future<Output = Result<ItemRevision, Error>> + Send;
+
+    /// List the [`ItemRevision`]s of an [`Item`], newest first.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::PageToken`] if the page token is invalid.
+    fn revisions(
+        &self,
+        id: &Id,
+        request: &ListRevisionsRequest,
+    ) -> impl Future<Output = Result<ListRevisionsResponse, Error>> + Send;
+}
+
 // MARK: Service
 
 #[derive(Debug, Clone)]
 pub struct Service<DB: SqliteConnection> {
     db: Arc<DB>,
+    page_token_key: page_token::Key,
+}
+
+#[derive(FromRow)]
+struct ItemRow {
+    id: String,
+    display_name: String,
+    title: String,
+    description: String,
+    state: i64,
+    etag: String,
+    uid: String,
+    create_time: String,
+    update_time: String,
+    base_uom: String,
+    tracking: i64,
+    delete_time: Option<String>,
+    expire_time: Option<String>,
+}
+
+impl TryFrom<ItemRow> for Item {
+    type Error = Error;
+
+    fn try_from(value: ItemRow) -> Result<Self, Self::Error> {
+        let id = Id::try_from(value.id)?;
+        let display_name = value.display_name;
+        let title = value.title;
+        let description = value.description;
+        let state = num_traits::FromPrimitive::from_i64(value.state).ok_or(Error::Unknown(
+            anyhow!(format!("invalid state {0}", value.state)),
+        ))?;
+        let etag = value.etag.try_into()?;
+        let uid =
+            uuid::Uuid::try_parse(&value.uid).map_err(|e| item::Error::Unknown(anyhow!(e)))?;
+        let create_time = Timestamp::try_from(value.create_time)?;
+        let update_time = Timestamp::try_from(value.update_time)?;
+        let base_uom = Id::try_from(value.base_uom)?;
+        let tracking = num_traits::FromPrimitive::from_i64(value.tracking).ok_or(
+            Error::Unknown(anyhow!(format!("invalid tracking {0}", value.tracking))),
+        )?;
+        let delete_time = value.delete_time.map(Timestamp::try_from).transpose()?;
+        let expire_time = value.expire_time.map(Timestamp::try_from).transpose()?;
+
+        Ok(Self::from((
+            id,
+            display_name,
+            title,
+            description,
+            state,
+            etag,
+            uid,
+            create_time,
+            update_time,
+            base_uom,
+            tracking,
+            delete_time,
+            expire_time,
+        )))
+    }
+}
+
+#[allow(clippy::struct_field_names)]
+struct ItemRevisionRow {
+    sequence: i64,
+    item_id: String,
+    revision_id: String,
+    operation_id: String,
+    actor: String,
+    display_name: String,
+    title: String,
+    description: String,
+    state: i64,
+    etag: String,
+    uid: String,
+    create_time: String,
+    update_time: String,
+    base_uom: String,
+    tracking: i64,
+    delete_time: Option<String>,
+    expire_time: Option<String>,
+    revision_create_time: String,
+}
+
+impl TryFrom<ItemRevisionRow> for ItemRevision {
+    type Error = Error;
+
+    fn try_from(value: ItemRevisionRow) -> Result<Self, Self::Error> {
+        let item = Item::try_from(ItemRow {
+            id: value.item_id,
+            display_name: value.display_name,
+            title: value.title,
+            description: value.description,
+            state: value.state,
+            etag: value.etag,
+            uid: value.uid,
+            create_time: value.create_time,
+            update_time: value.update_time,
+            base_uom: value.base_uom,
+            tracking: value.tracking,
+            delete_time: value.delete_time,
+            expire_time: value.expire_time,
+        })?;
+        let id = Id::try_from(value.revision_id)?;
+        let operation = Id::try_from(value.operation_id)?;
+        let create_time = Timestamp::try_from(value.revision_create_time)?;
+
+        Ok(Self::from((item, id, operation, value.actor, create_time)))
+    }
+}
+
+struct ItemEventRow {
+    sequence: i64,
+    item_id: String,
+    kind: i64,
+    item_type: Option<String>,
+    item: Option<Vec<u8>>,
+    create_time: String,
+}
+
+impl TryFrom<ItemEventRow> for ItemEvent {
+    type Error = Error;
+
+    fn try_from(value: ItemEventRow) -> Result<Self, Self::Error> {
+        let item = Id::try_from(value.item_id)?;
+        let kind: ItemEventKind = num_traits::FromPrimitive::from_i64(value.kind).ok_or(
+            Error::Unknown(anyhow!(format!("invalid event kind {0}", value.kind))),
+        )?;
+        let snapshot = value.item.map(|item| Any {
+            type_url: value.item_type.unwrap_or_default(),
+            value: item,
+        });
+        let create_time = Timestamp::try_from(value.create_time)?;
+
+        Ok(Self::from((
+            value.sequence,
+            item,
+            kind,
+            snapshot,
+            create_time,
+        )))
+    }
+}
+
+/// Append an [`ItemEvent`] for a write of `item` to the outbox, as part of the transaction
+/// performing the write. The item is left out when the write removes it for good.
+async fn append_event(
+    tx: &mut Transaction<'_, Sqlite>,
+    kind: ItemEventKind,
+    item: &Item,
+    removed: bool,
+) -> Result<(), Error> {
+    let item_id = item.id.value();
+    let kind = kind.to_i64();
+    let snapshot = (!removed).then(|| Any::from(item.clone()));
+    let item_type = snapshot.as_ref().map(|s| s.type_url.clone());
+    let snapshot = snapshot.map(|s| s.value);
+    let create_time = Timestamp::now().value().to_string();
+
+    let query = sqlx::query!(
+        "INSERT INTO item_event (item_id, kind, item_type, item, create_time)
+        VALUES ($1, $2, $3, $4, $5)",
+        item_id,
+        kind,
+        item_type,
+        snapshot,
+        create_time,
+    );
+
+    tx.execute(query).await.map_err(|e| {
+        Error::from(anyhow!(e).context(format!(
+            "failed to append event of item with id {:?}",
+            item.id
+        )))
+    })?;
+
+    Ok(())
+}
+
+/// Record a revision of the item an operation starts changing, as part of the transaction
+/// writing the change.
+async fn insert_revision(
+    tx: &mut Transaction<'_, Sqlite>,
+    operation: &Operation<Metadata>,
+) -> Result<(), Error> {
+    let item = operation.metadata().entity();
+    let item_id = item.id.value();
+    // A whole UUID, as a revision id that collides with another of the item would fail the
+    // change it is recorded for.
+    let revision_id = &uuid::Uuid::new_v4().simple().to_string();
+    let operation_id = operation.id().value();
+    let actor = operation.metadata().actor();
+    let display_name = &item.display_name;
+    let title = &item.title;
+    let description = &item.description;
+    let state = item.state.to_i64();
+    let etag = item.etag.to_string();
+    let uid = item.uid.to_string();
+    let create_time = item.create_time.value().to_string();
+    let update_time = item.update_time.value().to_string();
+    let base_uom = item.base_uom.value();
+    let tracking = item.tracking.to_i64();
+    let delete_time = item.delete_time.as_ref().map(|t| t.value().to_string());
+    let expire_time = item.expire_time.as_ref().map(|t| t.value().to_string());
+    let revision_create_time = Timestamp::now().value().to_string();
+
+    let query = sqlx::query!(
+        "INSERT INTO item_revision (
+            item_id,
+            revision_id,
+            operation_id,
+            actor,
+            display_name,
+            title,
+            description,
+            state,
+            etag,
+            uid,
+            create_time,
+            update_time,
+            base_uom,
+            tracking,
+            delete_time,
+            expire_time,
+            revision_create_time
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)",
+        item_id,
+        revision_id,
+        operation_id,
+        actor,
+        display_name,
+        title,
+        description,
+        state,
+        etag,
+        uid,
+        create_time,
+        update_time,
+        base_uom,
+        tracking,
+        delete_time,
+        expire_time,
+        revision_create_time,
+    );
+
+    tx.execute(query).await.map_err(|e| {
+        Error::from(anyhow!(e).context(format!(
+            "failed to insert revision of item with id {:?}",
+            item.id
+        )))
+    })?;
+
+    Ok(())
+}
+
+/// Replace the snapshot of the revision recorded by `operation` with the item it settled into.
+async fn settle_revision(
+    tx: &mut Transaction<'_, Sqlite>,
+    operation: &Id,
+    item: &Item,
+) -> Result<(), Error> {
+    let operation_id = operation.value();
+    let state = item.state.to_i64();
+    let etag = item.etag.to_string();
+    let update_time = item.update_time.value().to_string();
+    let delete_time = item.delete_time.as_ref().map(|t| t.value().to_string());
+    let expire_time = item.expire_time.as_ref().map(|t| t.value().to_string());
+
+    let query = sqlx::query!(
+        "UPDATE item_revision SET
+            state       = $2,
+            etag        = $3,
+            update_time = $4,
+            delete_time = $5,
+            expire_time = $6
+        WHERE operation_id = $1",
+        operation_id,
+        state,
+        etag,
+        update_time,
+        delete_time,
+        expire_time,
+    );
+
+    tx.execute(query).await.map_err(|e| {
+        Error::from(anyhow!(e).context(format!(
+            "failed to settle revision of item with id {:?}",
+            item.id
+        )))
+    })?;
+
+    Ok(())
+}
+
+/// Refuse to remove an item that open production orders, bills of materials or the stock
+/// ledger still refer to.
+async fn ensure_unused(tx: &mut Transaction<'_, Sqlite>, id: &Id) -> Result<(), Error> {
+    let value = id.value();
+    let finished = ProductionOrderState::Finished.to_i64();
+    let cancelled = ProductionOrderState::Cancelled.to_i64();
+
+    let query = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM production_order
+        WHERE item_id = $1 AND state NOT IN ($2, $3)",
+        value,
+        finished,
+        cancelled,
+    );
+    let production_orders = query.fetch_one(&mut **tx).await.map_err(|e| {
+        Error::from(anyhow!(e).context(format!(
+            "failed to count production orders of item with id {id:?}"
+        )))
+    })?;
+
+    let query = sqlx::query_scalar!(
+        "SELECT COUNT(DISTINCT item_id || '/' || bill_of_materials_id)
+        FROM bill_of_materials_component WHERE component_item_id = $1",
+        value,
+    );
+    let bills_of_materials = query.fetch_one(&mut **tx).await.map_err(|e| {
+        Error::from(anyhow!(e).context(format!(
+            "failed to count bills of materials consuming item with id {id:?}"
+        )))
+    })?;
+
+    let query = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM stock_movement WHERE item_id = $1",
+        value,
+    );
+    let stock_movements = query.fetch_one(&mut **tx).await.map_err(|e| {
+        Error::from(anyhow!(e).context(format!(
+            "failed to count stock movements of item with id {id:?}"
+        )))
+    })?;
+
+    if production_orders > 0 || bills_of_materials > 0 || stock_movements > 0 {
+        return Err(InUseError::new(
+            id.clone(),
+            production_orders,
+            bills_of_materials,
+            stock_movements,
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+async fn purge_revisions(tx: &mut Transaction<'_, Sqlite>, id: &Id) -> Result<(), Error> {
+    let item_id = id.value();
+
+    let query = sqlx::query!("DELETE FROM item_revision WHERE item_id = $1", item_id);
+
+    tx.execute(query).await.map_err(|e| {
+        Error::from(anyhow!(e).context(format!("failed to purge revisions of item with id {id:?}")))
+    })?;
+
+    Ok(())
+}
+
+async fn purge_events(tx: &mut Transaction<'_, Sqlite>, id: &Id) -> Result<(), Error> {
+    let item_id = id.value();
+
+    let query = sqlx::query!("DELETE FROM item_event WHERE item_id = $1", item_id);
+
+    tx.execute(query).await.map_err(|e| {
+        Error::from(anyhow!(e).context(format!("failed to purge events of item with id {id:?}")))
+    })?;
+
+    Ok(())
+}
+
+// MARK: List
+
+const ITEM_COLUMNS: &[Column] = &[
+    Column::new("name", "id", Kind::Text, true),
+    Column::new("display_name", "display_name", Kind::Text, true),
+    Column::new("title", "title", Kind::Text, true),
+    Column::new("description", "description", Kind::Text, false),
+    Column::new("state", "state", Kind::State(item_state), true),
+    Column::new("etag", "etag", Kind::Text, false),
+    Column::new("uid", "uid", Kind::Text, false),
+    Column::new("create_time", "create_time", Kind::Timestamp, true),
+    Column::new("update_time", "update_time", Kind::Timestamp, true),
+    Column::new("base_uom", "base_uom", Kind::Text, true),
+    Column::new("tracking", "tracking", Kind::State(item_tracking), true),
+    Column::new("delete_time", "delete_time", Kind::Timestamp, false),
+    Column::new("expire_time", "expire_time", Kind::Timestamp, false),
+];
+
+fn item_state(name: &str) -> Option<i64> {
+    ItemState::from_name(name).and_then(|s| s.to_i64())
+}
+
+fn item_tracking(name: &str) -> Option<i64> {
+    Tracking::from_name(name).and_then(|t| t.to_i64())
+}
+
+fn push_not_deleted(query: &mut QueryBuilder<'_, Sqlite>) {
+    query.push(" AND state <> ");
+    query.push_bind(ItemState::Deleted.to_i64());
+}
+
+fn cursor(terms: &[OrderTerm], item: &Item) -> Vec<String> {
+    terms
+        .iter()
+        .map(|(column, _)| match column.name() {
+            "display_name" => item.display_name.clone(),
+            "title" => item.title.clone(),
+            "state" => item.state.to_i64().unwrap_or_default().to_string(),
+            "create_time" => item.create_time.value().to_string(),
+            "update_time" => item.update_time.value().to_string(),
+            "base_uom" => item.base_uom.value().clone(),
+            "tracking" => item.tracking.to_i64().unwrap_or_default().to_string(),
+            _ => item.id.value().clone(),
+        })
+        .collect()
 }
 
 impl<DB> Service<DB>
@@ -101,14 +621,15 @@ where
     DB: SqliteConnection + Clone,
 {
     #[must_use]
-    pub const fn new(db: Arc<DB>) -> Self {
-        Self { db }
+    pub const fn new(db: Arc<DB>, page_token_key: page_token::Key) -> Self {
+        Self { db, page_token_key }
     }
 
     async fn fetch_item(&self, id: &Id) -> Result<Item, Error> {
-        let id = id.value();
+        let value = id.value();
 
-        let query = sqlx::query!(
+        let query = sqlx::query_as!(
+            ItemRow,
             "SELECT
                 id,
                 display_name,
@@ -118,57 +639,57 @@ where
                 etag,
                 uid,
                 create_time,
-                update_time
+                update_time,
+                base_uom,
+                tracking,
+                delete_time,
+                expire_time
             FROM item WHERE id = $1",
-            id
+            value
         );
 
         let result =
             query
-                .fetch_one(self.db.pool())
+                .fetch_one(&self.db.pool())
                 .await
                 .map_err(|e| match SqlxError::from(&e) {
-                    SqlxError::RowNotFound => Error::Id(id::NotFoundError.into()),
+                    SqlxError::RowNotFound => NotFoundError::new(id.clone()).into(),
                     _ => Error::from(
                         anyhow!(e).context(format!("failed to fetch item with id {id:?}")),
                     ),
                 })?;
 
-        let id = Id::try_from(result.id)?;
-        let display_name = result.display_name;
-        let title = result.title;
-        let description = result.description;
-        let state = num_traits::FromPrimitive::from_i64(result.state).ok_or(Error::Unknown(
-            anyhow!(format!("invalid state {0}", result.state)),
-        ))?;
-        let etag = result.etag.try_into()?;
-        let uid =
-            uuid::Uuid::try_parse(&result.uid).map_err(|e| item::Error::Unknown(anyhow!(e)))?;
-        let create_time = Timestamp::try_from(result.create_time)?;
-        let update_time = Timestamp::try_from(result.update_time)?;
-
-        let item = Item::from((
-            id,
-            display_name,
-            title,
-            description,
-            state,
-            etag,
-            uid,
-            create_time,
-            update_time,
-        ));
-
-        Ok(item)
+        result.try_into()
     }
 
     async fn fetch_items(&self, request: &ListRequest) -> Result<ListResponse, Error> {
         let page_size = request.page_size();
-        let page_token = request.page_token();
-        let order_by = request.order_by();
-        let filter = request.filter();
+        let raw_filter = request.filter().as_deref().unwrap_or_default().trim();
+        let raw_order_by = request.order_by().as_deref().unwrap_or_default().trim();
+        let show_deleted = request.show_deleted().to_string();
+        let parameters = [raw_filter, raw_order_by, &show_deleted];
 
-        let query = sqlx::query!(
+        let filter = Some(raw_filter)
+            .filter(|f| !f.is_empty())
+            .map(|f| Filter::try_from(f.to_string()))
+            .transpose()?;
+        let order_by = Some(raw_order_by)
+            .filter(|o| !o.is_empty())
+            .map(|o| OrderBy::try_from(o.to_string()))
+            .transpose()?;
+        let terms = list::resolve_order_by(ITEM_COLUMNS, order_by.as_ref())?;
+        let page_token = request
+            .page_token()
+            .as_deref()
+            .filter(|t| !t.is_empty())
+            .map(|t| PageToken::decode(t, &self.page_token_key))
+            .transpose()?;
+
+        if let Some(page_token) = &page_token {
+            page_token.verify(&parameters)?;
+        }
+
+        let mut query = QueryBuilder::<Sqlite>::new(
             "SELECT
                 id,
                 display_name,
@@ -178,57 +699,73 @@ where
                 etag,
                 uid,
                 create_time,
-                update_time
-            FROM item WHERE $1
-            ORDER BY $2
-            LIMIT $3 OFFSET $4",
-            filter,
-            order_by,
-            page_size,
-            page_token
+                update_time,
+                base_uom,
+                tracking,
+                delete_time,
+                expire_time
+            FROM item WHERE 1",
         );
 
-        let result =
-            query
-                .fetch_all(self.db.pool())
-                .await
-                .map_err(|e| match SqlxError::from(&e) {
-                    SqlxError::RowNotFound => Error::Id(id::NotFoundError.into()),
-                    _ => Error::from(anyhow!(e).context("failed to fetch items")),
-                })?;
+        if !request.show_deleted() {
+            push_not_deleted(&mut query);
+        }
 
-        let mut items: Vec<Item> = vec![];
-
-        for r in result {
-            let id = Id::try_from(r.id)?;
-            let display_name = r.display_name;
-            let title = r.title;
-            let description = r.description;
-            let state = num_traits::FromPrimitive::from_i64(r.state).ok_or(Error::Unknown(
-                anyhow!(format!("invalid state {0}", r.state)),
-            ))?;
-            let etag = r.etag.try_into()?;
-            let uid =
-                uuid::Uuid::try_parse(&r.uid).map_err(|e| item::Error::Unknown(anyhow!(e)))?;
-            let create_time = Timestamp::try_from(r.create_time)?;
-            let update_time = Timestamp::try_from(r.update_time)?;
-
-            let item = Item::from((
-                id,
-                display_name,
-                title,
-                description,
-                state,
-                etag,
-                uid,
-                create_time,
-                update_time,
-            ));
+        if let Some(filter) = &filter {
+            query.push(" AND ");
+            list::push_filter(&mut query, ITEM_COLUMNS, filter.expression())?;
+        }
 
-            items.push(item);
+        if let Some(page_token) = &page_token {
+            query.push(" AND ");
+            list::push_cursor(&mut query, &terms, page_token.cursor())?;
         }
 
-        Ok(ListResponse::new(items, None, 0))
+        list::push_order_by(&mut query, &terms);
+
+        // Fetch one more item than requested to learn whether another page follows.
+        query.push(" LIMIT ");
+        query.push_bind(page_size + 1);
+
+        let result = query
+            .build_query_as::<ItemRow>()
+            .fetch_all(&self.db.pool())
+            .await
+            .map_err(|e| Error::from(anyhow!(e).context("failed to fetch items")))?;
+
+        let mut items = result
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<Item>, Error>>()?;
+
+        let next_page_token = if items.len() > usize::try_from(*page_size).unwrap_or_default() {
+            items.pop();
+            items.last().map(|item| {
+                PageToken::new(&parameters, cursor(&terms, item)).encode(&self.page_token_key)
+            })
+        } else {
+            None
+        };
+
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM item WHERE 1");
+
+        if !request.show_deleted() {
+            push_not_deleted(&mut query);
+        }
+
+        if let Some(filter) = &filter {
+            query.push(" AND ");
+            list::push_filter(&mut query, ITEM_COLUMNS, filter.expression())?;
+        }
+
+        let total_size: i64 = query
+            .build_query_scalar()
+            .fetch_one(&self.db.pool())
+            .await
+            .map_err(|e| Error::from(anyhow!(e).context("failed to count items")))?;
+        let total_size = i32::try_from(total_size).unwrap_or(i32::MAX);
+
+        Ok(ListResponse::new(items, next_page_token, total_size))
     }
 
     async fn save_item(&self, tx: &mut Transaction<'_, Sqlite>, item: &Item) -> Result<(), Error> {
@@ -241,6 +778,10 @@ where
         let uid = &item.uid.to_string();
         let create_time = &item.create_time.value().to_string();
         let update_time = &item.update_time.value().to_string();
+        let base_uom = &item.base_uom.value();
+        let tracking = &item.tracking.to_i64();
+        let delete_time = &item.delete_time.as_ref().map(|t| t.value().to_string());
+        let expire_time = &item.expire_time.as_ref().map(|t| t.value().to_string());
 
         let query = sqlx::query!(
             "INSERT INTO item (
@@ -252,8 +793,12 @@ where
                 etag,
                 uid,
                 create_time,
-                update_time
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+                update_time,
+                base_uom,
+                tracking,
+                delete_time,
+                expire_time
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)",
             id,
             display_name,
             title,
@@ -263,6 +808,10 @@ where
             uid,
             create_time,
             update_time,
+            base_uom,
+            tracking,
+            delete_time,
+            expire_time,
         );
 
         tx.execute(query)
@@ -270,7 +819,7 @@ where
             .map_err(|e| match SqlxError::from(&e) {
                 SqlxError::Sqlite { inner } => match inner {
                     SqliteError::UniqueConstraintViolationCode => {
-                        Error::Id(id::DuplicateError(item.id.clone()).into())
+                        Error::from(AlreadyExistsError::new(item.id.clone()))
                     }
                     SqliteError::Unknown { message } => anyhow!(e)
                         .context(format!(
@@ -292,6 +841,7 @@ where
         &self,
         tx: &mut Transaction<'_, Sqlite>,
         item: &Item,
+        expected_etag: &EntityTag,
     ) -> Result<(), Error> {
         let id = &item.id.value();
         let display_name = &item.display_name;
@@ -302,10 +852,12 @@ where
         let uid = &item.uid.to_string();
         let create_time = &item.create_time.value().to_string();
         let update_time = &item.update_time.value().to_string();
+        let delete_time = &item.delete_time.as_ref().map(|t| t.value().to_string());
+        let expire_time = &item.expire_time.as_ref().map(|t| t.value().to_string());
+        let expected = &expected_etag.to_string();
 
         let query = sqlx::query!(
             "UPDATE item SET
-                id              = $1,
                 display_name    = $2,
                 title           = $3,
                 description     = $4,
@@ -313,7 +865,10 @@ where
                 etag            = $6,
                 uid             = $7,
                 create_time     = $8,
-                update_time     = $9",
+                update_time     = $9,
+                delete_time     = $10,
+                expire_time     = $11
+            WHERE id = $1 AND etag = $12",
             id,
             display_name,
             title,
@@ -323,29 +878,42 @@ where
             uid,
             create_time,
             update_time,
+            delete_time,
+            expire_time,
+            expected,
         );
 
-        tx.execute(query)
-            .await
-            .map_err(|e| match SqlxError::from(&e) {
-                SqlxError::RowNotFound => Error::Id(id::NotFoundError.into()),
-                _ => {
-                    Error::from(anyhow!(e).context(format!("failed to delete item with id {id:?}")))
-                }
-            })?;
+        let result = tx.execute(query).await.map_err(|e| {
+            Error::from(anyhow!(e).context(format!("failed to update item with id {id:?}")))
+        })?;
 
-        Ok(())
+        if result.rows_affected() > 0 {
+            return Ok(());
+        }
+
+        // Nothing matched, either the item is gone or someone else changed it first.
+        let query = sqlx::query_scalar!("SELECT COUNT(*) FROM item WHERE id = $1", id);
+
+        let count = query.fetch_one(&mut **tx).await.map_err(|e| {
+            Error::from(anyhow!(e).context(format!("failed to fetch item with id {id:?}")))
+        })?;
+
+        if count == 0 {
+            Err(NotFoundError::new(item.id.clone()).into())
+        } else {
+            Err(EtagMismatchError::new(item.id.clone(), expected.clone()).into())
+        }
     }
 
     async fn remove_item(&self, tx: &mut Transaction<'_, Sqlite>, id: &Id) -> Result<(), Error> {
-        let id = &id.to_string();
+        let value = &id.to_string();
 
-        let query = sqlx::query!("DELETE FROM item WHERE id = $1", id);
+        let query = sqlx::query!("DELETE FROM item WHERE id = $1", value);
 
         tx.execute(query)
             .await
             .map_err(|e| match SqlxError::from(&e) {
-                SqlxError::RowNotFound => Error::Id(id::NotFoundError.into()),
+                SqlxError::RowNotFound => NotFoundError::new(id.clone()).into(),
                 _ => {
                     Error::from(anyhow!(e).context(format!("failed to delete item with id {id:?}")))
                 }
@@ -375,6 +943,204 @@ where
     }
 }
 
+impl<DB> Events for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn events(&self, after: i64, limit: i64) -> Result<Vec<ItemEvent>, Error> {
+        let query = sqlx::query_as!(
+            ItemEventRow,
+            "SELECT sequence, item_id, kind, item_type, item, create_time
+            FROM item_event WHERE sequence > $1 ORDER BY sequence LIMIT $2",
+            after,
+            limit,
+        );
+
+        let result = query.fetch_all(&self.db.pool()).await.map_err(|e| {
+            Error::from(anyhow!(e).context(format!("failed to fetch item events after {after}")))
+        })?;
+
+        result.into_iter().map(TryInto::try_into).collect()
+    }
+}
+
+impl<DB> Revisions for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn revision(&self, id: &Id, revision: &Id) -> Result<ItemRevision, Error> {
+        let item_id = id.value();
+        let revision_id = revision.value();
+
+        let query = sqlx::query_as!(
+            ItemRevisionRow,
+            "SELECT
+                sequence AS \"sequence!\",
+                item_id,
+                revision_id,
+                operation_id,
+                actor,
+                display_name,
+                title,
+                description,
+                state,
+                etag,
+                uid,
+                create_time,
+                update_time,
+                base_uom,
+                tracking,
+                delete_time,
+                expire_time,
+                revision_create_time
+            FROM item_revision WHERE item_id = $1 AND revision_id = $2",
+            item_id,
+            revision_id,
+        );
+
+        let result =
+            query
+                .fetch_one(&self.db.pool())
+                .await
+                .map_err(|e| match SqlxError::from(&e) {
+                    SqlxError::RowNotFound => {
+                        RevisionNotFoundError::new(id.clone(), revision.clone()).into()
+                    }
+                    _ => Error::from(anyhow!(e).context(format!(
+                        "failed to fetch revision {revision:?} of item with id {id:?}"
+                    ))),
+                })?;
+
+        result.try_into()
+    }
+
+    async fn revisions(
+        &self,
+        id: &Id,
+        request: &ListRevisionsRequest,
+    ) -> Result<ListRevisionsResponse, Error> {
+        let item_id = id.value();
+        let parameters = [item_id.as_str()];
+
+        let page_token = request
+            .page_token()
+            .as_deref()
+            .filter(|t| !t.is_empty())
+            .map(|t| PageToken::decode(t, &self.page_token_key))
+            .transpose()?;
+
+        // Revisions are listed newest first, so a page starts below the last sequence seen.
+        let before = match &page_token {
+            Some(page_token) => {
+                page_token.verify(&parameters)?;
+                page_token
+                    .cursor()
+                    .first()
+                    .and_then(|c| c.parse::<i64>().ok())
+                    .ok_or_else(|| page_token::Error::from(page_token::InvalidError))?
+            }
+            None => i64::MAX,
+        };
+
+        // Fetch one more revision than requested to learn whether another page follows.
+        let limit = i64::from(*request.page_size()) + 1;
+
+        let query = sqlx::query_as!(
+            ItemRevisionRow,
+            "SELECT
+                sequence AS \"sequence!\",
+                item_id,
+                revision_id,
+                operation_id,
+                actor,
+                display_name,
+                title,
+                description,
+                state,
+                etag,
+                uid,
+                create_time,
+                update_time,
+                base_uom,
+                tracking,
+                delete_time,
+                expire_time,
+                revision_create_time
+            FROM item_revision WHERE item_id = $1 AND sequence < $2
+            ORDER BY sequence DESC LIMIT $3",
+            item_id,
+            before,
+            limit,
+        );
+
+        let mut rows = query.fetch_all(&self.db.pool()).await.map_err(|e| {
+            Error::from(
+                anyhow!(e).context(format!("failed to fetch revisions of item with id {id:?}")),
+            )
+        })?;
+
+        let next_page_token =
+            if rows.len() > usize::try_from(*request.page_size()).unwrap_or_default() {
+                rows.pop();
+                rows.last().map(|row| {
+                    PageToken::new(&parameters, vec![row.sequence.to_string()])
+                        .encode(&self.page_token_key)
+                })
+            } else {
+                None
+            };
+
+        let revisions = rows
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<ItemRevision>, Error>>()?;
+
+        Ok(ListRevisionsResponse::new(revisions, next_page_token))
+    }
+}
+
+impl<DB> Expired for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn expired(&self) -> Result<Vec<Item>, Error> {
+        let deleted = ItemState::Deleted.to_i64();
+
+        let query = sqlx::query_as!(
+            ItemRow,
+            "SELECT
+                id,
+                display_name,
+                title,
+                description,
+                state,
+                etag,
+                uid,
+                create_time,
+                update_time,
+                base_uom,
+                tracking,
+                delete_time,
+                expire_time
+            FROM item WHERE state = $1",
+            deleted,
+        );
+
+        let result = query
+            .fetch_all(&self.db.pool())
+            .await
+            .map_err(|e| Error::from(anyhow!(e).context("failed to fetch deleted items")))?;
+
+        // Stored timestamps do not sort as text, so expiry is checked once parsed.
+        let items = result
+            .into_iter()
+            .map(Item::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(items.into_iter().filter(Item::is_expired).collect())
+    }
+}
+
 impl<DB> Create for Service<DB>
 where
     DB: SqliteConnection + Clone,
@@ -389,6 +1155,14 @@ where
 
         self.save_item(&mut tx, operation.metadata().entity())
             .await?;
+        insert_revision(&mut tx, operation).await?;
+
+        let operation_record = OperationRecord::new(
+            operation.id().clone(),
+            operation.metadata().entity().name(),
+            operation.metadata().into(),
+        );
+        sync::repository::save_operation(&mut tx, &operation_record).await?;
 
         tx.commit()
             .await
@@ -402,7 +1176,7 @@ impl<DB> Update for Service<DB>
 where
     DB: SqliteConnection + Clone,
 {
-    async fn update(&self, operation: &Operation<Metadata>) -> Result<(), Error> {
+    async fn update(&self, operation: &Operation<Metadata>, etag: &EntityTag) -> Result<(), Error> {
         let mut tx = self
             .db
             .pool()
@@ -410,8 +1184,60 @@ where
             .await
             .with_context(|| "failed to start SQLite transaction")?;
 
-        self.modify_item(&mut tx, operation.metadata().entity())
-            .await?;
+        let item = operation.metadata().entity();
+        self.modify_item(&mut tx, item, etag).await?;
+
+        // Checked after the write, which holds the database lock until the transaction ends.
+        if matches!(item.state, ItemState::Deleting | ItemState::Annihilating) {
+            ensure_unused(&mut tx, &item.id).await?;
+        }
+
+        insert_revision(&mut tx, operation).await?;
+
+        let operation_record = OperationRecord::new(
+            operation.id().clone(),
+            operation.metadata().entity().name(),
+            operation.metadata().into(),
+        );
+        sync::repository::save_operation(&mut tx, &operation_record).await?;
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(())
+    }
+}
+
+impl<DB> Complete for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn complete(
+        &self,
+        operation: &Operation<Metadata>,
+        etag: &EntityTag,
+    ) -> Result<(), Error> {
+        let Some(Ok(item)) = operation.result() else {
+            return Err(Error::Unknown(anyhow!(
+                "operation with id {:?} has no successful result",
+                operation.id().value()
+            )));
+        };
+
+        let mut tx = self
+            .db
+            .pool()
+            .begin()
+            .await
+            .with_context(|| "failed to start SQLite transaction")?;
+
+        self.modify_item(&mut tx, item, etag).await?;
+        append_event(&mut tx, (*operation.metadata().verb()).into(), item, false).await?;
+        settle_revision(&mut tx, operation.id(), item).await?;
+
+        let response = Any::from(item.clone());
+        sync::repository::complete_operation(&mut tx, operation.id(), &Ok(response)).await?;
 
         tx.commit()
             .await
@@ -436,6 +1262,62 @@ where
         self.remove_item(&mut tx, operation.metadata().entity().id())
             .await?;
 
+        // Annihilation leaves nothing behind but its own operation and its event.
+        if *operation.metadata().verb() == Verb::Annihilate {
+            let target = operation.metadata().entity().name();
+            sync::repository::purge_operations(&mut tx, &target, operation.id()).await?;
+            purge_revisions(&mut tx, operation.metadata().entity().id()).await?;
+            purge_events(&mut tx, operation.metadata().entity().id()).await?;
+        }
+        append_event(
+            &mut tx,
+            (*operation.metadata().verb()).into(),
+            operation.metadata().entity(),
+            true,
+        )
+        .await?;
+
+        let response = Any::from_msg(&()).context("failed to encode empty response")?;
+        sync::repository::complete_operation(&mut tx, operation.id(), &Ok(response)).await?;
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(())
+    }
+}
+
+impl<DB> Purge for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn purge(&self, operation: &Operation<Metadata>, etag: &EntityTag) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool()
+            .begin()
+            .await
+            .with_context(|| "failed to start SQLite transaction")?;
+
+        let item = operation.metadata().entity();
+        self.modify_item(&mut tx, item, etag).await?;
+        ensure_unused(&mut tx, &item.id).await?;
+        insert_revision(&mut tx, operation).await?;
+
+        let operation_record = OperationRecord::new(
+            operation.id().clone(),
+            item.name(),
+            operation.metadata().into(),
+        );
+        sync::repository::save_operation(&mut tx, &operation_record).await?;
+
+        self.remove_item(&mut tx, item.id()).await?;
+        append_event(&mut tx, (*operation.metadata().verb()).into(), item, true).await?;
+
+        let response = Any::from_msg(&()).context("failed to encode empty response")?;
+        sync::repository::complete_operation(&mut tx, operation.id(), &Ok(response)).await?;
+
         tx.commit()
             .await
             .context("failed to commit SQLite transaction")?;
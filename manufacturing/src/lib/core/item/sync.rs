@@ -3,7 +3,7 @@ use derive_getters::{Dissolve, Getters};
 use crate::{
     item,
     sync::{OperationEntity, OperationMetadata, OperationState},
-    Id, Item, ItemState,
+    Id, Item, ItemEventKind, ItemState, Tenant, Timestamp,
 };
 
 impl OperationState for ItemState {
@@ -11,9 +11,12 @@ impl OperationState for ItemState {
 
     fn next(&self) -> Self::NextState {
         match self {
-            Self::Creating | Self::Updating | Self::Unblocking => Some(Self::Active),
+            Self::Creating | Self::Updating | Self::Unblocking | Self::Undeleting => {
+                Some(Self::Active)
+            }
             Self::Blocking => Some(Self::Blocked),
-            Self::Deleting | Self::Annihilating | Self::Active | Self::Blocked => None,
+            Self::Deleting => Some(Self::Deleted),
+            Self::Annihilating | Self::Active | Self::Blocked | Self::Deleted => None,
         }
     }
 }
@@ -28,17 +31,91 @@ impl OperationEntity for Item {
     fn state(&self) -> &Self::State {
         self.state()
     }
+
+    /// The name of the item, within the organization of the current tenant like the names
+    /// the API returns.
+    fn name(&self) -> String {
+        Tenant::qualify(&format!("items/{}", self.id()))
+    }
+}
+
+/// The action an item operation performs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verb {
+    Create,
+    Update,
+    Delete,
+    Annihilate,
+    Block,
+    Unblock,
+    Undelete,
+}
+
+impl Verb {
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Update => "update",
+            Self::Delete => "delete",
+            Self::Annihilate => "annihilate",
+            Self::Block => "block",
+            Self::Unblock => "unblock",
+            Self::Undelete => "undelete",
+        }
+    }
+}
+
+impl From<&ItemState> for Verb {
+    /// Operations are only started for items entering a transitional state, so
+    /// settled states map to the verb that last produced them.
+    fn from(value: &ItemState) -> Self {
+        match value {
+            ItemState::Creating => Self::Create,
+            ItemState::Updating | ItemState::Active => Self::Update,
+            ItemState::Deleting | ItemState::Deleted => Self::Delete,
+            ItemState::Annihilating => Self::Annihilate,
+            ItemState::Blocking | ItemState::Blocked => Self::Block,
+            ItemState::Unblocking => Self::Unblock,
+            ItemState::Undeleting => Self::Undelete,
+        }
+    }
+}
+
+impl From<Verb> for ItemEventKind {
+    fn from(value: Verb) -> Self {
+        match value {
+            Verb::Create => Self::Created,
+            Verb::Update => Self::Updated,
+            Verb::Delete => Self::Deleted,
+            Verb::Annihilate => Self::Annihilated,
+            Verb::Block => Self::Blocked,
+            Verb::Unblock => Self::Unblocked,
+            Verb::Undelete => Self::Undeleted,
+        }
+    }
 }
 
 #[derive(Dissolve, Getters)]
 pub struct Metadata {
     item: Item,
+    verb: Verb,
+    /// Who requested the operation, empty for operations the service starts on its own.
+    actor: String,
+    create_time: Timestamp,
 }
 
 impl Metadata {
     #[must_use]
-    pub const fn new(item: Item) -> Self {
-        Self { item }
+    pub fn new(item: Item, actor: String) -> Self {
+        let verb = Verb::from(item.state());
+
+        Self {
+            item,
+            verb,
+            actor,
+            create_time: Timestamp::now(),
+        }
     }
 }
 
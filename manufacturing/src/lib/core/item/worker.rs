@@ -0,0 +1,151 @@
+use std::sync::Arc;
+
+use tonic::Code;
+
+pub use super::Error;
+
+use crate::{
+    grpc::proto::google::rpc,
+    sync::{self, Operation, OperationRecord, Reconcile},
+    Id, Tenant,
+};
+
+use super::{repository, sync::Metadata};
+
+const ITEM_NAME_PREFIX: &str = "items/";
+
+/// The targets of the operations on items, leaving out the resources nested below them.
+const ITEM_TARGETS: &str = "items/*";
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+pub struct Service<
+    IR: repository::Get + repository::Complete + repository::Delete + Clone,
+    OR: sync::repository::Pending + sync::repository::Update + Clone,
+> {
+    item_repository: Arc<IR>,
+    operation_repository: Arc<OR>,
+}
+
+impl<IR, OR> Service<IR, OR>
+where
+    IR: repository::Get + repository::Complete + repository::Delete + Clone,
+    OR: sync::repository::Pending + sync::repository::Update + Clone,
+{
+    #[must_use]
+    pub const fn new(item_repository: Arc<IR>, operation_repository: Arc<OR>) -> Self {
+        Self {
+            item_repository,
+            operation_repository,
+        }
+    }
+
+    async fn reconcile_operation(&self, record: OperationRecord, item_id: Id) -> Result<(), Error> {
+        let item = match self.item_repository.get(&item_id).await {
+            Ok(item) => item,
+            Err(Error::NotFound(err)) => {
+                let record = record.done(Err(rpc::Status {
+                    code: Code::NotFound.into(),
+                    message: err.to_string(),
+                    details: vec![],
+                }));
+
+                return Ok(self.operation_repository.update(&record).await?);
+            }
+            Err(err) => return Err(err),
+        };
+
+        let (id, ..) = record.dissolve();
+
+        if let Some(settled) = item.clone().settle()? {
+            let etag = item.etag().clone();
+            let operation =
+                Operation::new(id, Metadata::new(item, String::new()), Some(Ok(settled)));
+            self.item_repository.complete(&operation, &etag).await
+        } else {
+            let operation = Operation::new(id, Metadata::new(item, String::new()), None);
+            self.item_repository.delete(&operation).await
+        }
+    }
+}
+
+impl<IR, OR> Reconcile for Service<IR, OR>
+where
+    IR: repository::Get + repository::Complete + repository::Delete + Clone,
+    OR: sync::repository::Pending + sync::repository::Update + Clone,
+{
+    type Error = Error;
+
+    async fn reconcile(&self) -> Result<usize, Error> {
+        let mut completed = 0;
+
+        let targets = Tenant::qualify(ITEM_TARGETS);
+        for record in self.operation_repository.pending(&targets).await? {
+            let Some(item_id) = Tenant::unqualify(record.target())
+                .strip_prefix(ITEM_NAME_PREFIX)
+                .filter(|id| !id.contains('/'))
+            else {
+                continue;
+            };
+
+            let item_id = Id::try_from(item_id.to_string());
+            let failed = record.clone();
+
+            let result = match item_id {
+                Ok(item_id) => self.reconcile_operation(record, item_id).await,
+                Err(err) => Err(err.into()),
+            };
+            // An operation that cannot be completed is failed rather than retried, so that it
+            // does not hold up every operation pending after it.
+            if let Err(err) = result {
+                let record = failed.done(Err(err.into()));
+                self.operation_repository.update(&record).await?;
+            }
+            completed += 1;
+        }
+
+        Ok(completed)
+    }
+}
+
+// MARK: Purge
+
+/// Purges soft-deleted items once their retention has run out.
+#[derive(Debug, Clone)]
+pub struct Purge<IR: repository::Expired + repository::Purge + Clone> {
+    item_repository: Arc<IR>,
+}
+
+impl<IR> Purge<IR>
+where
+    IR: repository::Expired + repository::Purge + Clone,
+{
+    #[must_use]
+    pub const fn new(item_repository: Arc<IR>) -> Self {
+        Self { item_repository }
+    }
+}
+
+impl<IR> Reconcile for Purge<IR>
+where
+    IR: repository::Expired + repository::Purge + Clone,
+{
+    type Error = Error;
+
+    async fn reconcile(&self) -> Result<usize, Error> {
+        let mut purged = 0;
+
+        for item in self.item_repository.expired().await? {
+            let etag = item.etag().clone();
+            let item = item.expire()?;
+
+            // The purge is recorded like any other deletion, and completed right away.
+            let operation = Operation::new(Id::new(), Metadata::new(item, String::new()), None);
+            self.item_repository.purge(&operation, &etag).await?;
+            purged += 1;
+        }
+
+        Ok(purged)
+    }
+}
@@ -0,0 +1,128 @@
+use derive_getters::Getters;
+use derive_more::derive::From;
+use uuid::Uuid;
+
+use crate::{
+    entity_tag, field_mask, filter, id, order_by, page_token, timestamp, EntityTag, Id, Location,
+    ThisError, Timestamp,
+};
+
+pub mod command;
+pub mod query;
+pub mod repository;
+
+impl Location {
+    pub(crate) fn new(
+        id: String,
+        display_name: String,
+        allow_negative_stock: bool,
+    ) -> Result<Self, Error> {
+        let now = Timestamp::now();
+
+        Ok(Self {
+            id: id.try_into()?,
+            display_name,
+            allow_negative_stock,
+            etag: EntityTag::new(),
+            uid: Uuid::new_v4(),
+            create_time: now.clone(),
+            update_time: now,
+        })
+    }
+
+    pub(crate) fn update(
+        self,
+        display_name: Option<String>,
+        allow_negative_stock: Option<bool>,
+    ) -> Self {
+        Self {
+            display_name: display_name.unwrap_or(self.display_name),
+            allow_negative_stock: allow_negative_stock.unwrap_or(self.allow_negative_stock),
+            etag: EntityTag::new(),
+            update_time: Timestamp::now(),
+            ..self
+        }
+    }
+
+    /// The resource name of the location.
+    #[must_use]
+    pub fn name(&self) -> String {
+        location_name(&self.id)
+    }
+}
+
+pub(crate) fn location_name(id: &Id) -> String {
+    format!("locations/{id}")
+}
+
+#[derive(Debug, ThisError)]
+pub enum Error {
+    #[error(transparent)]
+    Empty(#[from] EmptyError),
+    #[error(transparent)]
+    NotFound(#[from] NotFoundError),
+    #[error(transparent)]
+    AlreadyExists(#[from] AlreadyExistsError),
+    #[error(transparent)]
+    EtagMismatch(#[from] EtagMismatchError),
+    #[error(transparent)]
+    Id(#[from] id::Error),
+    #[error(transparent)]
+    Etag(#[from] entity_tag::Error),
+    #[error(transparent)]
+    Timestamp(#[from] timestamp::Error),
+    #[error(transparent)]
+    FieldMask(#[from] field_mask::Error),
+    #[error(transparent)]
+    Filter(#[from] filter::Error),
+    #[error(transparent)]
+    OrderBy(#[from] order_by::Error),
+    #[error(transparent)]
+    PageToken(#[from] page_token::Error),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Clone, Debug, ThisError, From)]
+#[error("location cannot be empty")]
+pub struct EmptyError;
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("location {id:?} not found")]
+pub struct NotFoundError {
+    id: Id,
+}
+
+impl NotFoundError {
+    #[must_use]
+    pub const fn new(id: Id) -> Self {
+        Self { id }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("location {id:?} already exists")]
+pub struct AlreadyExistsError {
+    id: Id,
+}
+
+impl AlreadyExistsError {
+    #[must_use]
+    pub const fn new(id: Id) -> Self {
+        Self { id }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("etag {etag:?} does not match the current etag of location {id:?}")]
+pub struct EtagMismatchError {
+    id: Id,
+    etag: String,
+}
+
+impl EtagMismatchError {
+    #[must_use]
+    pub const fn new(id: Id, etag: String) -> Self {
+        Self { id, etag }
+    }
+}
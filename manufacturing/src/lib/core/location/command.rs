@@ -0,0 +1,143 @@
+use uuid::Uuid;
+
+pub use super::Error;
+use super::{repository, AlreadyExistsError, EtagMismatchError};
+
+use std::{future::Future, sync::Arc};
+
+use crate::{EntityTag, Id, Location};
+
+// MARK: Create
+
+pub trait Create: Send + Sync + 'static {
+    fn create(
+        &self,
+        request: CreateRequest,
+    ) -> impl Future<Output = Result<Location, Error>> + Send;
+}
+
+pub struct CreateRequest {
+    id: Option<String>,
+    display_name: String,
+    allow_negative_stock: bool,
+}
+
+impl CreateRequest {
+    #[must_use]
+    pub const fn new(id: Option<String>, display_name: String, allow_negative_stock: bool) -> Self {
+        Self {
+            id,
+            display_name,
+            allow_negative_stock,
+        }
+    }
+}
+
+// MARK: Update
+
+pub trait Update: Send + Sync + 'static {
+    fn update(
+        &self,
+        request: UpdateRequest,
+    ) -> impl Future<Output = Result<Location, Error>> + Send;
+}
+
+pub struct UpdateRequest {
+    id: String,
+    display_name: Option<String>,
+    allow_negative_stock: Option<bool>,
+    etag: String,
+}
+
+impl UpdateRequest {
+    #[must_use]
+    pub const fn new(
+        id: String,
+        display_name: Option<String>,
+        allow_negative_stock: Option<bool>,
+        etag: String,
+    ) -> Self {
+        Self {
+            id,
+            display_name,
+            allow_negative_stock,
+            etag,
+        }
+    }
+}
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+pub struct Service<LR: repository::Create + repository::Update + repository::Get + Clone> {
+    location_repository: Arc<LR>,
+}
+
+impl<LR> Service<LR>
+where
+    LR: repository::Create + repository::Update + repository::Get + Clone,
+{
+    #[must_use]
+    pub const fn new(location_repository: Arc<LR>) -> Self {
+        Self {
+            location_repository,
+        }
+    }
+
+    async fn validate_create_request(&self, request: CreateRequest) -> Result<Location, Error> {
+        let id = match request.id {
+            Some(id) => {
+                let id = Id::try_from(id)?;
+                match self.location_repository.get(&id).await {
+                    Ok(location) => return Err(AlreadyExistsError::new(location.id).into()),
+                    Err(Error::NotFound(_)) => id.to_string(),
+                    Err(err) => return Err(err),
+                }
+            }
+            None => Uuid::new_v4().to_string(),
+        };
+
+        Location::new(id, request.display_name, request.allow_negative_stock)
+    }
+
+    async fn validate_update_request(
+        &self,
+        request: UpdateRequest,
+    ) -> Result<(Location, EntityTag), Error> {
+        let id = Id::try_from(request.id)?;
+        let location = self.location_repository.get(&id).await?;
+
+        if request.etag != location.etag.to_string() {
+            return Err(EtagMismatchError::new(id, request.etag).into());
+        }
+
+        let etag = location.etag.clone();
+        let location = location.update(request.display_name, request.allow_negative_stock);
+
+        Ok((location, etag))
+    }
+}
+
+impl<LR> Create for Service<LR>
+where
+    LR: repository::Create + repository::Update + repository::Get + Clone,
+{
+    async fn create(&self, request: CreateRequest) -> Result<Location, Error> {
+        let location = self.validate_create_request(request).await?;
+        self.location_repository.create(&location).await?;
+
+        Ok(location)
+    }
+}
+
+impl<LR> Update for Service<LR>
+where
+    LR: repository::Create + repository::Update + repository::Get + Clone,
+{
+    async fn update(&self, request: UpdateRequest) -> Result<Location, Error> {
+        let (location, etag) = self.validate_update_request(request).await?;
+        self.location_repository.update(&location, &etag).await?;
+
+        Ok(location)
+    }
+}
@@ -0,0 +1,127 @@
+use derive_getters::{Dissolve, Getters};
+
+pub use super::Error;
+
+use std::{future::Future, sync::Arc};
+
+use crate::{Id, Location};
+
+use super::repository;
+
+// MARK: Get
+
+pub trait Get: Send + Sync + 'static {
+    fn get(&self, request: GetRequest) -> impl Future<Output = Result<Location, Error>> + Send;
+}
+
+pub struct GetRequest {
+    id: String,
+}
+
+impl GetRequest {
+    #[must_use]
+    pub const fn new(id: String) -> Self {
+        Self { id }
+    }
+}
+
+// MARK: List
+
+pub trait List: Send + Sync + 'static {
+    fn list(
+        &self,
+        request: ListRequest,
+    ) -> impl Future<Output = Result<ListResponse, Error>> + Send;
+}
+
+#[derive(Getters)]
+pub struct ListRequest {
+    page_size: i32,
+    page_token: Option<String>,
+    order_by: Option<String>,
+    filter: Option<String>,
+}
+
+impl ListRequest {
+    #[must_use]
+    pub fn new(
+        page_size: Option<i32>,
+        page_token: Option<String>,
+        order_by: Option<String>,
+        filter: Option<String>,
+    ) -> Self {
+        Self {
+            page_size: page_size.filter(|s| *s > 0).unwrap_or(50).min(1000),
+            page_token,
+            order_by,
+            filter,
+        }
+    }
+}
+
+#[derive(Dissolve)]
+pub struct ListResponse {
+    locations: Vec<Location>,
+    next_page_token: Option<String>,
+    total_size: i32,
+}
+
+impl ListResponse {
+    #[must_use]
+    pub const fn new(
+        locations: Vec<Location>,
+        next_page_token: Option<String>,
+        total_size: i32,
+    ) -> Self {
+        Self {
+            locations,
+            next_page_token,
+            total_size,
+        }
+    }
+}
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+pub struct Service<LR: repository::Get + repository::List + Clone> {
+    location_repository: Arc<LR>,
+}
+
+impl<LR> Service<LR>
+where
+    LR: repository::Get + repository::List + Clone,
+{
+    #[must_use]
+    pub const fn new(location_repository: Arc<LR>) -> Self {
+        Self {
+            location_repository,
+        }
+    }
+}
+
+fn validate_get_request(request: GetRequest) -> Result<Id, Error> {
+    let id: Id = request.id.try_into()?;
+
+    Ok(id)
+}
+
+impl<LR> Get for Service<LR>
+where
+    LR: repository::Get + repository::List + Clone,
+{
+    async fn get(&self, request: GetRequest) -> Result<Location, Error> {
+        let id = validate_get_request(request)?;
+
+        self.location_repository.get(&id).await
+    }
+}
+
+impl<LR> List for Service<LR>
+where
+    LR: repository::Get + repository::List + Clone,
+{
+    async fn list(&self, request: ListRequest) -> Result<ListResponse, Error> {
+        self.location_repository.list(&request).await
+    }
+}
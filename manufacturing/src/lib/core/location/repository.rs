@@ -0,0 +1,420 @@
+use std::{future::Future, sync::Arc};
+
+use anyhow::{anyhow, Context};
+use sqlx::{FromRow, QueryBuilder, Sqlite};
+
+use crate::{
+    page_token,
+    sqlx::{
+        list::{self, Column, Kind, OrderTerm},
+        Error as SqlxError, SqliteConnection, SqliteError,
+    },
+    EntityTag, Filter, Id, Location, OrderBy, PageToken, Timestamp,
+};
+
+use super::{
+    query::{ListRequest, ListResponse},
+    AlreadyExistsError, Error, EtagMismatchError, NotFoundError,
+};
+
+// MARK: Get
+
+/// `Get` represents a store of location data.
+pub trait Get: Send + Sync + 'static {
+    /// Get a [`Location`].
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::NotFound`] if a [`Location`] with the given [`Id`] does not exist.
+    fn get(&self, id: &Id) -> impl Future<Output = Result<Location, Error>> + Send;
+}
+
+// MARK: List
+
+/// `List` represents a store of location data.
+pub trait List: Send + Sync + 'static {
+    /// List [`Location`]s.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::Filter`] if the filter is invalid.
+    fn list(
+        &self,
+        request: &ListRequest,
+    ) -> impl Future<Output = Result<ListResponse, Error>> + Send;
+}
+
+// MARK: Create
+
+/// `Create` represents a store of location data.
+pub trait Create: Send + Sync + 'static {
+    /// Persist a new [`Location`].
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::AlreadyExists`] if a [`Location`] with the same [`Id`] already exists.
+    fn create(&self, location: &Location) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+// MARK: Update
+
+/// `Update` represents a store of location data.
+pub trait Update: Send + Sync + 'static {
+    /// Update a [`Location`], provided its stored etag still equals `etag`.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::NotFound`] if a [`Location`] with the given [`Id`] does not exist.
+    /// - MUST return [`Error::EtagMismatch`] if the stored etag differs from `etag`.
+    fn update(
+        &self,
+        location: &Location,
+        etag: &EntityTag,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+pub struct Service<DB: SqliteConnection> {
+    db: Arc<DB>,
+    page_token_key: page_token::Key,
+}
+
+#[derive(FromRow)]
+struct LocationRow {
+    id: String,
+    display_name: String,
+    allow_negative_stock: i64,
+    etag: String,
+    uid: String,
+    create_time: String,
+    update_time: String,
+}
+
+impl TryFrom<LocationRow> for Location {
+    type Error = Error;
+
+    fn try_from(value: LocationRow) -> Result<Self, Self::Error> {
+        let id = Id::try_from(value.id)?;
+        let display_name = value.display_name;
+        let allow_negative_stock = value.allow_negative_stock != 0;
+        let etag = value.etag.try_into()?;
+        let uid = uuid::Uuid::try_parse(&value.uid).map_err(|e| Error::Unknown(anyhow!(e)))?;
+        let create_time = Timestamp::try_from(value.create_time)?;
+        let update_time = Timestamp::try_from(value.update_time)?;
+
+        Ok(Self::from((
+            id,
+            display_name,
+            allow_negative_stock,
+            etag,
+            uid,
+            create_time,
+            update_time,
+        )))
+    }
+}
+
+// MARK: List
+
+const LOCATION_COLUMNS: &[Column] = &[
+    Column::new("name", "id", Kind::Text, true),
+    Column::new("display_name", "display_name", Kind::Text, true),
+    Column::new(
+        "allow_negative_stock",
+        "allow_negative_stock",
+        Kind::Integer,
+        false,
+    ),
+    Column::new("etag", "etag", Kind::Text, false),
+    Column::new("uid", "uid", Kind::Text, false),
+    Column::new("create_time", "create_time", Kind::Timestamp, true),
+    Column::new("update_time", "update_time", Kind::Timestamp, true),
+];
+
+fn cursor(terms: &[OrderTerm], location: &Location) -> Vec<String> {
+    terms
+        .iter()
+        .map(|(column, _)| match column.name() {
+            "display_name" => location.display_name.clone(),
+            "create_time" => location.create_time.value().to_string(),
+            "update_time" => location.update_time.value().to_string(),
+            _ => location.id.value().clone(),
+        })
+        .collect()
+}
+
+impl<DB> Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    #[must_use]
+    pub const fn new(db: Arc<DB>, page_token_key: page_token::Key) -> Self {
+        Self { db, page_token_key }
+    }
+
+    async fn fetch_location(&self, id: &Id) -> Result<Location, Error> {
+        let value = id.value();
+
+        let query = sqlx::query_as!(
+            LocationRow,
+            "SELECT
+                id,
+                display_name,
+                allow_negative_stock,
+                etag,
+                uid,
+                create_time,
+                update_time
+            FROM location WHERE id = $1",
+            value
+        );
+
+        let result =
+            query
+                .fetch_one(&self.db.pool())
+                .await
+                .map_err(|e| match SqlxError::from(&e) {
+                    SqlxError::RowNotFound => NotFoundError::new(id.clone()).into(),
+                    _ => Error::from(
+                        anyhow!(e).context(format!("failed to fetch location with id {id:?}")),
+                    ),
+                })?;
+
+        result.try_into()
+    }
+
+    async fn fetch_locations(&self, request: &ListRequest) -> Result<ListResponse, Error> {
+        let page_size = request.page_size();
+        let raw_filter = request.filter().as_deref().unwrap_or_default().trim();
+        let raw_order_by = request.order_by().as_deref().unwrap_or_default().trim();
+        let parameters = [raw_filter, raw_order_by];
+
+        let filter = Some(raw_filter)
+            .filter(|f| !f.is_empty())
+            .map(|f| Filter::try_from(f.to_string()))
+            .transpose()?;
+        let order_by = Some(raw_order_by)
+            .filter(|o| !o.is_empty())
+            .map(|o| OrderBy::try_from(o.to_string()))
+            .transpose()?;
+        let terms = list::resolve_order_by(LOCATION_COLUMNS, order_by.as_ref())?;
+        let page_token = request
+            .page_token()
+            .as_deref()
+            .filter(|t| !t.is_empty())
+            .map(|t| PageToken::decode(t, &self.page_token_key))
+            .transpose()?;
+
+        if let Some(page_token) = &page_token {
+            page_token.verify(&parameters)?;
+        }
+
+        let mut query = QueryBuilder::<Sqlite>::new(
+            "SELECT
+                id,
+                display_name,
+                allow_negative_stock,
+                etag,
+                uid,
+                create_time,
+                update_time
+            FROM location WHERE 1",
+        );
+
+        if let Some(filter) = &filter {
+            query.push(" AND ");
+            list::push_filter(&mut query, LOCATION_COLUMNS, filter.expression())?;
+        }
+
+        if let Some(page_token) = &page_token {
+            query.push(" AND ");
+            list::push_cursor(&mut query, &terms, page_token.cursor())?;
+        }
+
+        list::push_order_by(&mut query, &terms);
+
+        // Fetch one more location than requested to learn whether another page follows.
+        query.push(" LIMIT ");
+        query.push_bind(page_size + 1);
+
+        let result = query
+            .build_query_as::<LocationRow>()
+            .fetch_all(&self.db.pool())
+            .await
+            .map_err(|e| Error::from(anyhow!(e).context("failed to fetch locations")))?;
+
+        let mut locations = result
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<Location>, Error>>()?;
+
+        let next_page_token = if locations.len() > usize::try_from(*page_size).unwrap_or_default() {
+            locations.pop();
+            locations.last().map(|location| {
+                PageToken::new(&parameters, cursor(&terms, location)).encode(&self.page_token_key)
+            })
+        } else {
+            None
+        };
+
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM location WHERE 1");
+
+        if let Some(filter) = &filter {
+            query.push(" AND ");
+            list::push_filter(&mut query, LOCATION_COLUMNS, filter.expression())?;
+        }
+
+        let total_size: i64 = query
+            .build_query_scalar()
+            .fetch_one(&self.db.pool())
+            .await
+            .map_err(|e| Error::from(anyhow!(e).context("failed to count locations")))?;
+        let total_size = i32::try_from(total_size).unwrap_or(i32::MAX);
+
+        Ok(ListResponse::new(locations, next_page_token, total_size))
+    }
+
+    async fn save_location(&self, location: &Location) -> Result<(), Error> {
+        let id = &location.id.value();
+        let display_name = &location.display_name;
+        let allow_negative_stock = &location.allow_negative_stock;
+        let etag = &location.etag.to_string();
+        let uid = &location.uid.to_string();
+        let create_time = &location.create_time.value().to_string();
+        let update_time = &location.update_time.value().to_string();
+
+        let query = sqlx::query!(
+            "INSERT INTO location (
+                id,
+                display_name,
+                allow_negative_stock,
+                etag,
+                uid,
+                create_time,
+                update_time
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            id,
+            display_name,
+            allow_negative_stock,
+            etag,
+            uid,
+            create_time,
+            update_time,
+        );
+
+        query
+            .execute(&self.db.pool())
+            .await
+            .map_err(|e| match SqlxError::from(&e) {
+                SqlxError::Sqlite {
+                    inner: SqliteError::UniqueConstraintViolationCode,
+                } => Error::from(AlreadyExistsError::new(location.id.clone())),
+                _ => Error::from(anyhow!(e).context(format!(
+                    "failed to insert location with id {:?}",
+                    location.id
+                ))),
+            })?;
+
+        Ok(())
+    }
+
+    async fn modify_location(
+        &self,
+        location: &Location,
+        expected_etag: &EntityTag,
+    ) -> Result<(), Error> {
+        let id = &location.id.value();
+        let display_name = &location.display_name;
+        let allow_negative_stock = &location.allow_negative_stock;
+        let etag = &location.etag.to_string();
+        let update_time = &location.update_time.value().to_string();
+        let expected = &expected_etag.to_string();
+
+        let mut tx = self
+            .db
+            .pool()
+            .begin()
+            .await
+            .with_context(|| "failed to start SQLite transaction")?;
+
+        let query = sqlx::query!(
+            "UPDATE location SET
+                display_name            = $2,
+                allow_negative_stock    = $3,
+                etag                    = $4,
+                update_time             = $5
+            WHERE id = $1 AND etag = $6",
+            id,
+            display_name,
+            allow_negative_stock,
+            etag,
+            update_time,
+            expected,
+        );
+
+        let result = query.execute(&mut *tx).await.map_err(|e| {
+            Error::from(anyhow!(e).context(format!("failed to update location with id {id:?}")))
+        })?;
+
+        if result.rows_affected() == 0 {
+            // Nothing matched, either the location is gone or someone else changed it first.
+            let query = sqlx::query_scalar!("SELECT COUNT(*) FROM location WHERE id = $1", id);
+
+            let count = query.fetch_one(&mut *tx).await.map_err(|e| {
+                Error::from(anyhow!(e).context(format!("failed to fetch location with id {id:?}")))
+            })?;
+
+            return if count == 0 {
+                Err(NotFoundError::new(location.id.clone()).into())
+            } else {
+                Err(EtagMismatchError::new(location.id.clone(), expected.clone()).into())
+            };
+        }
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(())
+    }
+}
+
+impl<DB> Get for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn get(&self, id: &Id) -> Result<Location, Error> {
+        let location = self.fetch_location(id).await?;
+        Ok(location)
+    }
+}
+
+impl<DB> List for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn list(&self, request: &ListRequest) -> Result<ListResponse, Error> {
+        let response = self.fetch_locations(request).await?;
+        Ok(response)
+    }
+}
+
+impl<DB> Create for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn create(&self, location: &Location) -> Result<(), Error> {
+        self.save_location(location).await
+    }
+}
+
+impl<DB> Update for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn update(&self, location: &Location, etag: &EntityTag) -> Result<(), Error> {
+        self.modify_location(location, etag).await
+    }
+}
@@ -0,0 +1,734 @@
+use derive_getters::Getters;
+use derive_more::derive::From;
+use uuid::Uuid;
+
+use crate::{
+    entity_tag, field_mask, filter, id, item, order_by, page_token, quantity, routing,
+    sync::OperationState, timestamp, EntityTag, Id, Item, ItemState, ProductionOrder,
+    ProductionOrderOperation, ProductionOrderOperationState, ProductionOrderState, Quantity,
+    Routing, ThisError, Timestamp,
+};
+
+pub mod command;
+pub mod query;
+pub mod repository;
+pub mod sync;
+pub mod worker;
+
+impl ProductionOrderState {
+    /// Look up a state by its API name, e.g. `RELEASED`.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "CREATING" => Some(Self::Creating),
+            "UPDATING" => Some(Self::Updating),
+            "DELETING" => Some(Self::Deleting),
+            "RELEASING" => Some(Self::Releasing),
+            "COMPLETING" => Some(Self::Completing),
+            "FINISHING" => Some(Self::Finishing),
+            "CANCELLING" => Some(Self::Cancelling),
+            "PLANNED" => Some(Self::Planned),
+            "RELEASED" => Some(Self::Released),
+            "COMPLETED" => Some(Self::Completed),
+            "FINISHED" => Some(Self::Finished),
+            "CANCELLED" => Some(Self::Cancelled),
+            _ => None,
+        }
+    }
+
+    /// The API name of the state, e.g. `RELEASED`.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Creating => "CREATING",
+            Self::Updating => "UPDATING",
+            Self::Deleting => "DELETING",
+            Self::Releasing => "RELEASING",
+            Self::Completing => "COMPLETING",
+            Self::Finishing => "FINISHING",
+            Self::Cancelling => "CANCELLING",
+            Self::Planned => "PLANNED",
+            Self::Released => "RELEASED",
+            Self::Completed => "COMPLETED",
+            Self::Finished => "FINISHED",
+            Self::Cancelled => "CANCELLED",
+        }
+    }
+}
+
+impl ProductionOrderOperationState {
+    /// The API name of the state, e.g. `STARTED`.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Pending => "PENDING",
+            Self::Started => "STARTED",
+            Self::Completed => "COMPLETED",
+        }
+    }
+}
+
+impl ProductionOrder {
+    /// Plan the production of `quantity` of an active `item`.
+    pub(crate) fn new(
+        id: String,
+        item: &Item,
+        quantity: Quantity,
+        display_name: String,
+        title: String,
+        description: String,
+    ) -> Result<Self, Error> {
+        if *item.state() != ItemState::Active {
+            return Err(ItemNotActiveError::new(item).into());
+        }
+
+        let now = Timestamp::now();
+
+        Ok(Self {
+            id: id.try_into()?,
+            display_name,
+            title,
+            description,
+            item: Some(item.id().clone()),
+            quantity: validate_quantity(quantity)?,
+            operations: vec![],
+            state: ProductionOrderState::Creating,
+            etag: EntityTag::new(),
+            uid: Uuid::new_v4(),
+            create_time: now.clone(),
+            update_time: now,
+        })
+    }
+
+    /// Only planned production orders can be changed, released ones are locked for execution.
+    pub(crate) fn update(
+        self,
+        display_name: Option<String>,
+        title: Option<String>,
+        description: Option<String>,
+        quantity: Option<Quantity>,
+    ) -> Result<Self, Error> {
+        if self.state != ProductionOrderState::Planned {
+            return Err(InvalidStateError::new(&self).into());
+        }
+
+        let quantity = quantity.map(validate_quantity).transpose()?;
+
+        Ok(Self {
+            id: self.id,
+            display_name: display_name.unwrap_or(self.display_name),
+            title: title.unwrap_or(self.title),
+            description: description.unwrap_or(self.description),
+            item: self.item,
+            quantity: quantity.unwrap_or(self.quantity),
+            operations: self.operations,
+            state: ProductionOrderState::Updating,
+            etag: EntityTag::new(),
+            uid: self.uid,
+            create_time: self.create_time,
+            update_time: Timestamp::now(),
+        })
+    }
+
+    pub(crate) fn delete(self) -> Result<Self, Error> {
+        if !matches!(
+            self.state,
+            ProductionOrderState::Planned | ProductionOrderState::Cancelled
+        ) {
+            return Err(InvalidStateError::new(&self).into());
+        }
+
+        Ok(self.transition(ProductionOrderState::Deleting))
+    }
+
+    /// Release a planned production order, copying the operations of the item's `routing`.
+    /// Later changes to the routing leave the released operations untouched.
+    pub(crate) fn release(self, routing: Option<&Routing>) -> Result<Self, Error> {
+        if self.state != ProductionOrderState::Planned {
+            return Err(InvalidStateError::new(&self).into());
+        }
+
+        let operations = routing
+            .map(|routing| routing.operations().iter().enumerate())
+            .into_iter()
+            .flatten()
+            .map(|(index, operation)| {
+                Ok(ProductionOrderOperation {
+                    id: (index + 1).to_string().try_into()?,
+                    work_center: operation.work_center().clone(),
+                    setup_time: *operation.setup_time(),
+                    run_time: *operation.run_time(),
+                    state: ProductionOrderOperationState::Pending,
+                    reported_quantity: Quantity::zero(),
+                    start_time: None,
+                    complete_time: None,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(Self {
+            operations,
+            ..self.transition(ProductionOrderState::Releasing)
+        })
+    }
+
+    /// Complete a released production order, all of whose operations have been completed.
+    pub(crate) fn complete(self) -> Result<Self, Error> {
+        if self.state != ProductionOrderState::Released {
+            return Err(InvalidStateError::new(&self).into());
+        }
+
+        let pending = self.pending_operations();
+        if pending > 0 {
+            return Err(OperationsPendingError::new(self.id, pending).into());
+        }
+
+        Ok(self.transition(ProductionOrderState::Completing))
+    }
+
+    pub(crate) fn start_operation(self, operation: &Id) -> Result<Self, Error> {
+        self.change_operation(operation, |operation| {
+            if operation.state != ProductionOrderOperationState::Pending {
+                return Ok(None);
+            }
+
+            Ok(Some(ProductionOrderOperation {
+                state: ProductionOrderOperationState::Started,
+                start_time: Some(Timestamp::now()),
+                ..operation
+            }))
+        })
+    }
+
+    /// Add `quantity` to the quantity reported as processed by a started operation.
+    pub(crate) fn report_operation(
+        self,
+        operation: &Id,
+        quantity: Quantity,
+    ) -> Result<Self, Error> {
+        let quantity = validate_quantity(quantity)?;
+
+        self.change_operation(operation, |operation| {
+            if operation.state != ProductionOrderOperationState::Started {
+                return Ok(None);
+            }
+
+            Ok(Some(ProductionOrderOperation {
+                reported_quantity: operation.reported_quantity.checked_add(&quantity)?,
+                ..operation
+            }))
+        })
+    }
+
+    /// Complete a started operation. Completing the last operation starts completing the
+    /// production order itself.
+    pub(crate) fn complete_operation(self, operation: &Id) -> Result<Self, Error> {
+        let production_order = self.change_operation(operation, |operation| {
+            if operation.state != ProductionOrderOperationState::Started {
+                return Ok(None);
+            }
+
+            Ok(Some(ProductionOrderOperation {
+                state: ProductionOrderOperationState::Completed,
+                complete_time: Some(Timestamp::now()),
+                ..operation
+            }))
+        })?;
+
+        if production_order.pending_operations() > 0 {
+            return Ok(production_order);
+        }
+
+        Ok(production_order.transition(ProductionOrderState::Completing))
+    }
+
+    /// Replace an operation of a released production order by the result of `change`,
+    /// which returns `None` if the operation is in the wrong state for the change.
+    fn change_operation(
+        self,
+        operation: &Id,
+        change: impl FnOnce(ProductionOrderOperation) -> Result<Option<ProductionOrderOperation>, Error>,
+    ) -> Result<Self, Error> {
+        if self.state != ProductionOrderState::Released {
+            return Err(InvalidStateError::new(&self).into());
+        }
+
+        let Some(index) = self.operations.iter().position(|o| &o.id == operation) else {
+            return Err(OperationNotFoundError::new(self.id, operation.clone()).into());
+        };
+
+        let mut operations = self.operations;
+        let current = operations.remove(index);
+        let state = current.state.clone();
+        let Some(changed) = change(current)? else {
+            return Err(InvalidOperationStateError::new(self.id, operation.clone(), state).into());
+        };
+        operations.insert(index, changed);
+
+        Ok(Self {
+            operations,
+            etag: EntityTag::new(),
+            update_time: Timestamp::now(),
+            ..self
+        })
+    }
+
+    fn pending_operations(&self) -> usize {
+        self.operations
+            .iter()
+            .filter(|o| o.state != ProductionOrderOperationState::Completed)
+            .count()
+    }
+
+    /// Finish a completed production order, or with `force` one that is still released.
+    pub(crate) fn finish(self, force: bool) -> Result<Self, Error> {
+        match self.state {
+            ProductionOrderState::Completed => {}
+            ProductionOrderState::Released if force => {}
+            _ => return Err(InvalidStateError::new(&self).into()),
+        }
+
+        Ok(self.transition(ProductionOrderState::Finishing))
+    }
+
+    /// Cancel a planned production order, or with `force` one that has already been
+    /// released or completed.
+    pub(crate) fn cancel(self, force: bool) -> Result<Self, Error> {
+        match self.state {
+            ProductionOrderState::Planned => {}
+            ProductionOrderState::Released | ProductionOrderState::Completed if force => {}
+            _ => return Err(InvalidStateError::new(&self).into()),
+        }
+
+        Ok(self.transition(ProductionOrderState::Cancelling))
+    }
+
+    /// Move a transitioning production order into the state its operation leads to.
+    /// Returns `None` if the operation ends with the production order being removed.
+    pub(crate) fn settle(self) -> Result<Option<Self>, Error> {
+        match self.state.next() {
+            Some(state) => Ok(Some(self.transition(state))),
+            None if self.state == ProductionOrderState::Deleting => Ok(None),
+            None => Err(InvalidStateError::new(&self).into()),
+        }
+    }
+
+    fn transition(self, state: ProductionOrderState) -> Self {
+        Self {
+            id: self.id,
+            display_name: self.display_name,
+            title: self.title,
+            description: self.description,
+            item: self.item,
+            quantity: self.quantity,
+            operations: self.operations,
+            state,
+            etag: EntityTag::new(),
+            uid: self.uid,
+            create_time: self.create_time,
+            update_time: Timestamp::now(),
+        }
+    }
+}
+
+fn validate_quantity(quantity: Quantity) -> Result<Quantity, Error> {
+    if !quantity.is_positive() {
+        return Err(InvalidQuantityError::new(quantity).into());
+    }
+
+    Ok(quantity)
+}
+
+#[derive(Debug, ThisError)]
+pub enum Error {
+    #[error(transparent)]
+    Empty(#[from] EmptyError),
+    #[error(transparent)]
+    NotFound(#[from] NotFoundError),
+    #[error(transparent)]
+    AlreadyExists(#[from] AlreadyExistsError),
+    #[error(transparent)]
+    EtagMismatch(#[from] EtagMismatchError),
+    #[error(transparent)]
+    InvalidState(#[from] InvalidStateError),
+    #[error(transparent)]
+    InvalidQuantity(#[from] InvalidQuantityError),
+    #[error(transparent)]
+    Quantity(#[from] quantity::Error),
+    #[error(transparent)]
+    ItemNotActive(#[from] ItemNotActiveError),
+    #[error(transparent)]
+    OperationNotFound(#[from] OperationNotFoundError),
+    #[error(transparent)]
+    InvalidOperationState(#[from] InvalidOperationStateError),
+    #[error(transparent)]
+    OperationsPending(#[from] OperationsPendingError),
+    #[error(transparent)]
+    InvalidOperationName(#[from] InvalidOperationNameError),
+    #[error(transparent)]
+    Item(#[from] item::Error),
+    #[error(transparent)]
+    Routing(#[from] routing::Error),
+    #[error(transparent)]
+    Id(#[from] id::Error),
+    #[error(transparent)]
+    Etag(#[from] entity_tag::Error),
+    #[error(transparent)]
+    Timestamp(#[from] timestamp::Error),
+    #[error(transparent)]
+    FieldMask(#[from] field_mask::Error),
+    #[error(transparent)]
+    Filter(#[from] filter::Error),
+    #[error(transparent)]
+    OrderBy(#[from] order_by::Error),
+    #[error(transparent)]
+    PageToken(#[from] page_token::Error),
+    #[error(transparent)]
+    Operation(#[from] crate::sync::Error),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Clone, Debug, ThisError, From)]
+#[error("production order cannot be empty")]
+pub struct EmptyError;
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("production order {id:?} not found")]
+pub struct NotFoundError {
+    id: Id,
+}
+
+impl NotFoundError {
+    #[must_use]
+    pub const fn new(id: Id) -> Self {
+        Self { id }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("production order {id:?} already exists")]
+pub struct AlreadyExistsError {
+    id: Id,
+}
+
+impl AlreadyExistsError {
+    #[must_use]
+    pub const fn new(id: Id) -> Self {
+        Self { id }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("etag {etag:?} does not match the current etag of production order {id:?}")]
+pub struct EtagMismatchError {
+    id: Id,
+    etag: String,
+}
+
+impl EtagMismatchError {
+    #[must_use]
+    pub const fn new(id: Id, etag: String) -> Self {
+        Self { id, etag }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("production order {id:?} cannot be changed while it is {}", state.name())]
+pub struct InvalidStateError {
+    id: Id,
+    state: ProductionOrderState,
+}
+
+impl InvalidStateError {
+    #[must_use]
+    pub fn new(production_order: &ProductionOrder) -> Self {
+        Self {
+            id: production_order.id.clone(),
+            state: production_order.state.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("quantity {quantity} must be greater than zero")]
+pub struct InvalidQuantityError {
+    quantity: Quantity,
+}
+
+impl InvalidQuantityError {
+    #[must_use]
+    pub const fn new(quantity: Quantity) -> Self {
+        Self { quantity }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("item {id:?} cannot be produced while it is {}", state.name())]
+pub struct ItemNotActiveError {
+    id: Id,
+    state: ItemState,
+}
+
+impl ItemNotActiveError {
+    #[must_use]
+    pub fn new(item: &Item) -> Self {
+        Self {
+            id: item.id().clone(),
+            state: item.state().clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("production order {id:?} has no operation {operation:?}")]
+pub struct OperationNotFoundError {
+    id: Id,
+    operation: Id,
+}
+
+impl OperationNotFoundError {
+    #[must_use]
+    pub const fn new(id: Id, operation: Id) -> Self {
+        Self { id, operation }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("operation {operation:?} of production order {id:?} cannot be changed while it is {}", state.name())]
+pub struct InvalidOperationStateError {
+    id: Id,
+    operation: Id,
+    state: ProductionOrderOperationState,
+}
+
+impl InvalidOperationStateError {
+    #[must_use]
+    pub const fn new(id: Id, operation: Id, state: ProductionOrderOperationState) -> Self {
+        Self {
+            id,
+            operation,
+            state,
+        }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("{name:?} is not an operation name, expected productionOrders/{{production_order}}/operations/{{operation}}")]
+pub struct InvalidOperationNameError {
+    name: String,
+}
+
+impl InvalidOperationNameError {
+    #[must_use]
+    pub const fn new(name: String) -> Self {
+        Self { name }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("production order {id:?} has {pending} operations not yet completed")]
+pub struct OperationsPendingError {
+    id: Id,
+    pending: usize,
+}
+
+impl OperationsPendingError {
+    #[must_use]
+    pub const fn new(id: Id, pending: usize) -> Self {
+        Self { id, pending }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{RoutingOperation, Tracking};
+
+    use super::*;
+
+    fn item(active: bool) -> anyhow::Result<Item> {
+        let item = Item::new(
+            String::from("bike"),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::from("pcs"),
+            Tracking::None,
+        )?;
+        let item = if active { item.settle()? } else { Some(item) };
+
+        item.ok_or_else(|| anyhow::anyhow!("item was removed"))
+    }
+
+    fn quantity(value: &str) -> anyhow::Result<Quantity> {
+        Ok(Quantity::try_from(value.to_string())?)
+    }
+
+    fn production_order(state: ProductionOrderState) -> anyhow::Result<ProductionOrder> {
+        let production_order = ProductionOrder::new(
+            String::from("po-1"),
+            &item(true)?,
+            quantity("1")?,
+            String::new(),
+            String::new(),
+            String::new(),
+        )?;
+
+        Ok(production_order.transition(state))
+    }
+
+    fn settle(production_order: ProductionOrder) -> anyhow::Result<ProductionOrder> {
+        production_order
+            .settle()?
+            .ok_or_else(|| anyhow::anyhow!("production order was removed"))
+    }
+
+    #[test]
+    fn follows_the_lifecycle() -> anyhow::Result<()> {
+        let planned = settle(production_order(ProductionOrderState::Creating)?)?;
+        assert_eq!(&ProductionOrderState::Planned, planned.state());
+
+        let released = settle(planned.release(None)?)?;
+        assert_eq!(&ProductionOrderState::Released, released.state());
+
+        let completed = settle(released.complete()?)?;
+        assert_eq!(&ProductionOrderState::Completed, completed.state());
+
+        let finished = settle(completed.finish(false)?)?;
+        assert_eq!(&ProductionOrderState::Finished, finished.state());
+
+        assert_eq!(None, finished.cancel(true).ok());
+        assert_eq!(
+            None,
+            production_order(ProductionOrderState::Deleting)?.settle()?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn force_skips_states() -> anyhow::Result<()> {
+        let released = production_order(ProductionOrderState::Released)?;
+        assert!(released.clone().finish(false).is_err());
+        assert!(released.clone().cancel(false).is_err());
+        assert!(released.clone().finish(true).is_ok());
+        assert!(released.cancel(true).is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn requires_an_active_item_and_positive_quantity() -> anyhow::Result<()> {
+        let id = || String::from("po-1");
+        let new = |item: &Item, quantity: Quantity| {
+            ProductionOrder::new(
+                id(),
+                item,
+                quantity,
+                String::new(),
+                String::new(),
+                String::new(),
+            )
+        };
+
+        assert!(matches!(
+            new(&item(false)?, quantity("1")?),
+            Err(Error::ItemNotActive(_))
+        ));
+        assert!(matches!(
+            new(&item(true)?, quantity("0")?),
+            Err(Error::InvalidQuantity(_))
+        ));
+        assert!(matches!(
+            production_order(ProductionOrderState::Planned)?.update(
+                None,
+                None,
+                None,
+                Some(quantity("-1")?)
+            ),
+            Err(Error::InvalidQuantity(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_invalid_transitions() -> anyhow::Result<()> {
+        assert!(production_order(ProductionOrderState::Released)?
+            .update(None, None, None, None)
+            .is_err());
+        assert!(production_order(ProductionOrderState::Released)?
+            .delete()
+            .is_err());
+        assert!(production_order(ProductionOrderState::Planned)?
+            .complete()
+            .is_err());
+        assert!(production_order(ProductionOrderState::Finished)?
+            .cancel(true)
+            .is_err());
+        assert!(production_order(ProductionOrderState::Releasing)?
+            .release(None)
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn completes_with_its_last_operation() -> anyhow::Result<()> {
+        let operation = |work_center: &str| {
+            RoutingOperation::new(
+                work_center.to_string(),
+                Duration::from_mins(10),
+                Duration::from_secs(30),
+            )
+        };
+        let routing = Routing::new(&item(true)?, vec![operation("weld")?, operation("paint")?])?;
+
+        let released =
+            settle(production_order(ProductionOrderState::Planned)?.release(Some(&routing))?)?;
+        let ids: Vec<_> = released
+            .operations()
+            .iter()
+            .map(|o| o.id().to_string())
+            .collect();
+        assert_eq!(vec!["1", "2"], ids);
+        assert!(matches!(
+            released.clone().complete(),
+            Err(Error::OperationsPending(_))
+        ));
+
+        let (first, second) = (
+            Id::try_from(String::from("1"))?,
+            Id::try_from(String::from("2"))?,
+        );
+        assert!(matches!(
+            released.clone().report_operation(&first, quantity("1")?),
+            Err(Error::InvalidOperationState(_))
+        ));
+
+        let started = released
+            .start_operation(&first)?
+            .report_operation(&first, quantity("0.1")?)?;
+        let reported = started.report_operation(&first, quantity("0.2")?)?;
+        assert_eq!(
+            &quantity("0.3")?,
+            reported.operations()[0].reported_quantity()
+        );
+
+        let progressed = reported
+            .complete_operation(&first)?
+            .start_operation(&second)?;
+        assert_eq!(&ProductionOrderState::Released, progressed.state());
+
+        let completing = progressed.complete_operation(&second)?;
+        assert_eq!(&ProductionOrderState::Completing, completing.state());
+        assert!(matches!(
+            completing.start_operation(&Id::try_from(String::from("3"))?),
+            Err(Error::InvalidState(_))
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,723 @@
+use uuid::Uuid;
+
+pub use super::Error;
+use super::{repository, sync::Metadata, AlreadyExistsError, EtagMismatchError};
+
+use std::{future::Future, sync::Arc};
+
+use crate::{
+    item, routing, sync::Operation, EntityTag, Id, ProductionOrder, ProductionOrderState, Quantity,
+};
+
+// MARK: Create
+
+pub trait Create: Send + Sync + 'static {
+    #[must_use]
+    fn create(
+        &self,
+        request: CreateRequest,
+    ) -> impl Future<Output = Result<Operation<Metadata>, Error>> + Send;
+}
+
+pub struct CreateRequest {
+    id: Option<String>,
+    item: String,
+    quantity: String,
+    display_name: String,
+    title: String,
+    description: String,
+}
+
+impl CreateRequest {
+    #[must_use]
+    pub const fn new(
+        id: Option<String>,
+        item: String,
+        quantity: String,
+        display_name: String,
+        title: String,
+        description: String,
+    ) -> Self {
+        Self {
+            id,
+            item,
+            quantity,
+            display_name,
+            title,
+            description,
+        }
+    }
+}
+
+// MARK: Update
+
+pub trait Update: Send + Sync + 'static {
+    #[must_use]
+    fn update(
+        &self,
+        request: UpdateRequest,
+    ) -> impl Future<Output = Result<Operation<Metadata>, Error>> + Send;
+}
+
+pub struct UpdateRequest {
+    id: String,
+    display_name: Option<String>,
+    title: Option<String>,
+    description: Option<String>,
+    quantity: Option<String>,
+    etag: String,
+}
+
+impl UpdateRequest {
+    #[must_use]
+    pub const fn new(
+        id: String,
+        display_name: Option<String>,
+        title: Option<String>,
+        description: Option<String>,
+        quantity: Option<String>,
+        etag: String,
+    ) -> Self {
+        Self {
+            id,
+            display_name,
+            title,
+            description,
+            quantity,
+            etag,
+        }
+    }
+}
+
+// MARK: Delete
+
+pub trait Delete: Send + Sync + 'static {
+    #[must_use]
+    fn delete(
+        &self,
+        request: DeleteRequest,
+    ) -> impl Future<Output = Result<Operation<Metadata>, Error>> + Send;
+}
+
+pub struct DeleteRequest {
+    id: String,
+    etag: String,
+}
+
+impl DeleteRequest {
+    #[must_use]
+    pub const fn new(id: String, etag: String) -> Self {
+        Self { id, etag }
+    }
+}
+
+// MARK: Release
+
+pub trait Release: Send + Sync + 'static {
+    #[must_use]
+    fn release(
+        &self,
+        request: ReleaseRequest,
+    ) -> impl Future<Output = Result<Operation<Metadata>, Error>> + Send;
+}
+
+pub struct ReleaseRequest {
+    id: String,
+    etag: String,
+}
+
+impl ReleaseRequest {
+    #[must_use]
+    pub const fn new(id: String, etag: String) -> Self {
+        Self { id, etag }
+    }
+}
+
+// MARK: Complete
+
+pub trait Complete: Send + Sync + 'static {
+    #[must_use]
+    fn complete(
+        &self,
+        request: CompleteRequest,
+    ) -> impl Future<Output = Result<Operation<Metadata>, Error>> + Send;
+}
+
+pub struct CompleteRequest {
+    id: String,
+    etag: String,
+}
+
+impl CompleteRequest {
+    #[must_use]
+    pub const fn new(id: String, etag: String) -> Self {
+        Self { id, etag }
+    }
+}
+
+// MARK: Finish
+
+pub trait Finish: Send + Sync + 'static {
+    #[must_use]
+    fn finish(
+        &self,
+        request: FinishRequest,
+    ) -> impl Future<Output = Result<Operation<Metadata>, Error>> + Send;
+}
+
+pub struct FinishRequest {
+    id: String,
+    etag: String,
+    force: bool,
+}
+
+impl FinishRequest {
+    #[must_use]
+    pub const fn new(id: String, etag: String, force: bool) -> Self {
+        Self { id, etag, force }
+    }
+}
+
+// MARK: Cancel
+
+pub trait Cancel: Send + Sync + 'static {
+    #[must_use]
+    fn cancel(
+        &self,
+        request: CancelRequest,
+    ) -> impl Future<Output = Result<Operation<Metadata>, Error>> + Send;
+}
+
+pub struct CancelRequest {
+    id: String,
+    etag: String,
+    force: bool,
+}
+
+impl CancelRequest {
+    #[must_use]
+    pub const fn new(id: String, etag: String, force: bool) -> Self {
+        Self { id, etag, force }
+    }
+}
+
+// MARK: StartOperation
+
+pub trait StartOperation: Send + Sync + 'static {
+    /// Start a pending operation of a released production order.
+    #[must_use]
+    fn start_operation(
+        &self,
+        request: StartOperationRequest,
+    ) -> impl Future<Output = Result<ProductionOrder, Error>> + Send;
+}
+
+pub struct StartOperationRequest {
+    id: String,
+    operation: String,
+    etag: String,
+}
+
+impl StartOperationRequest {
+    #[must_use]
+    pub const fn new(id: String, operation: String, etag: String) -> Self {
+        Self {
+            id,
+            operation,
+            etag,
+        }
+    }
+}
+
+// MARK: ReportOperation
+
+pub trait ReportOperation: Send + Sync + 'static {
+    /// Report a quantity processed by a started operation of a released production order.
+    #[must_use]
+    fn report_operation(
+        &self,
+        request: ReportOperationRequest,
+    ) -> impl Future<Output = Result<ProductionOrder, Error>> + Send;
+}
+
+pub struct ReportOperationRequest {
+    id: String,
+    operation: String,
+    quantity: String,
+    etag: String,
+}
+
+impl ReportOperationRequest {
+    #[must_use]
+    pub const fn new(id: String, operation: String, quantity: String, etag: String) -> Self {
+        Self {
+            id,
+            operation,
+            quantity,
+            etag,
+        }
+    }
+}
+
+// MARK: CompleteOperation
+
+pub trait CompleteOperation: Send + Sync + 'static {
+    /// Complete a started operation of a released production order. Completing the last
+    /// operation starts completing the production order.
+    #[must_use]
+    fn complete_operation(
+        &self,
+        request: CompleteOperationRequest,
+    ) -> impl Future<Output = Result<ProductionOrder, Error>> + Send;
+}
+
+pub struct CompleteOperationRequest {
+    id: String,
+    operation: String,
+    etag: String,
+}
+
+impl CompleteOperationRequest {
+    #[must_use]
+    pub const fn new(id: String, operation: String, etag: String) -> Self {
+        Self {
+            id,
+            operation,
+            etag,
+        }
+    }
+}
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+#[allow(clippy::struct_field_names)]
+pub struct Service<
+    PR: repository::Create
+        + repository::Update
+        + repository::Get
+        + repository::Delete
+        + repository::Progress
+        + Clone,
+    IR: item::repository::Get + Clone,
+    RR: routing::repository::Get + Clone,
+> {
+    production_order_repository: Arc<PR>,
+    item_repository: Arc<IR>,
+    routing_repository: Arc<RR>,
+}
+
+impl<PR, IR, RR> Service<PR, IR, RR>
+where
+    PR: repository::Create
+        + repository::Update
+        + repository::Get
+        + repository::Delete
+        + repository::Progress
+        + Clone,
+    IR: item::repository::Get + Clone,
+    RR: routing::repository::Get + Clone,
+{
+    #[must_use]
+    pub const fn new(
+        production_order_repository: Arc<PR>,
+        item_repository: Arc<IR>,
+        routing_repository: Arc<RR>,
+    ) -> Self {
+        Self {
+            production_order_repository,
+            item_repository,
+            routing_repository,
+        }
+    }
+
+    async fn validate_create_request(
+        &self,
+        request: CreateRequest,
+    ) -> Result<Operation<Metadata>, Error> {
+        let id = match request.id {
+            Some(id) => match Id::try_from(id) {
+                Ok(id) => match self.production_order_repository.get(&id).await {
+                    Ok(production_order) => {
+                        return Err(AlreadyExistsError::new(production_order.id).into())
+                    }
+                    Err(err) => match err {
+                        Error::NotFound(_) => id.to_string(),
+                        _ => return Err(err),
+                    },
+                },
+                Err(err) => return Err(Error::Id(err)),
+            },
+            _ => Uuid::new_v4().to_string(),
+        };
+
+        let item = self
+            .item_repository
+            .get(&Id::try_from(request.item)?)
+            .await?;
+
+        let production_order = ProductionOrder::new(
+            id,
+            &item,
+            Quantity::try_from(request.quantity)?,
+            request.display_name,
+            request.title,
+            request.description,
+        )?;
+
+        Ok(Operation::new(
+            Id::new(),
+            Metadata::new(production_order),
+            None,
+        ))
+    }
+
+    /// Load the production order a request acts on, provided `etag` is still current.
+    async fn fetch_current(
+        &self,
+        id: String,
+        etag: String,
+    ) -> Result<(ProductionOrder, EntityTag), Error> {
+        let id = Id::try_from(id)?;
+        let production_order = self.production_order_repository.get(&id).await?;
+
+        if etag != production_order.etag.to_string() {
+            return Err(EtagMismatchError::new(id, etag).into());
+        }
+
+        let etag = production_order.etag.clone();
+
+        Ok((production_order, etag))
+    }
+
+    async fn validate_update_request(
+        &self,
+        request: UpdateRequest,
+    ) -> Result<(Operation<Metadata>, EntityTag), Error> {
+        let quantity = request.quantity.map(Quantity::try_from).transpose()?;
+        let (production_order, etag) = self.fetch_current(request.id, request.etag).await?;
+        let production_order = production_order.update(
+            request.display_name,
+            request.title,
+            request.description,
+            quantity,
+        )?;
+
+        Ok((
+            Operation::new(Id::new(), Metadata::new(production_order), None),
+            etag,
+        ))
+    }
+
+    async fn validate_delete_request(
+        &self,
+        request: DeleteRequest,
+    ) -> Result<(Operation<Metadata>, EntityTag), Error> {
+        let (production_order, etag) = self.fetch_current(request.id, request.etag).await?;
+        let production_order = production_order.delete()?;
+
+        Ok((
+            Operation::new(Id::new(), Metadata::new(production_order), None),
+            etag,
+        ))
+    }
+
+    async fn validate_release_request(
+        &self,
+        request: ReleaseRequest,
+    ) -> Result<(Operation<Metadata>, EntityTag), Error> {
+        let (production_order, etag) = self.fetch_current(request.id, request.etag).await?;
+
+        let routing = match &production_order.item {
+            Some(item) => match self.routing_repository.get(item).await {
+                Ok(routing) => Some(routing),
+                Err(routing::Error::NotFound(_)) => None,
+                Err(err) => return Err(err.into()),
+            },
+            None => None,
+        };
+        let production_order = production_order.release(routing.as_ref())?;
+
+        Ok((
+            Operation::new(Id::new(), Metadata::new(production_order), None),
+            etag,
+        ))
+    }
+
+    async fn validate_complete_request(
+        &self,
+        request: CompleteRequest,
+    ) -> Result<(Operation<Metadata>, EntityTag), Error> {
+        let (production_order, etag) = self.fetch_current(request.id, request.etag).await?;
+        let production_order = production_order.complete()?;
+
+        Ok((
+            Operation::new(Id::new(), Metadata::new(production_order), None),
+            etag,
+        ))
+    }
+
+    /// Persist a production order whose operations progressed. Once the last operation
+    /// completes, the production order is completed like through [`Complete`].
+    async fn save_progress(
+        &self,
+        production_order: ProductionOrder,
+        etag: &EntityTag,
+    ) -> Result<ProductionOrder, Error> {
+        if production_order.state == ProductionOrderState::Completing {
+            let operation = Operation::new(Id::new(), Metadata::new(production_order), None);
+            self.production_order_repository
+                .update(&operation, etag)
+                .await?;
+
+            return Ok(operation.metadata().production_order().clone());
+        }
+
+        self.production_order_repository
+            .progress(&production_order, etag)
+            .await?;
+
+        Ok(production_order)
+    }
+
+    async fn validate_finish_request(
+        &self,
+        request: FinishRequest,
+    ) -> Result<(Operation<Metadata>, EntityTag), Error> {
+        let (production_order, etag) = self.fetch_current(request.id, request.etag).await?;
+        let production_order = production_order.finish(request.force)?;
+
+        Ok((
+            Operation::new(Id::new(), Metadata::new(production_order), None),
+            etag,
+        ))
+    }
+
+    async fn validate_cancel_request(
+        &self,
+        request: CancelRequest,
+    ) -> Result<(Operation<Metadata>, EntityTag), Error> {
+        let (production_order, etag) = self.fetch_current(request.id, request.etag).await?;
+        let production_order = production_order.cancel(request.force)?;
+
+        Ok((
+            Operation::new(Id::new(), Metadata::new(production_order), None),
+            etag,
+        ))
+    }
+}
+
+impl<PR, IR, RR> Create for Service<PR, IR, RR>
+where
+    PR: repository::Create
+        + repository::Update
+        + repository::Get
+        + repository::Delete
+        + repository::Progress
+        + Clone,
+    IR: item::repository::Get + Clone,
+    RR: routing::repository::Get + Clone,
+{
+    async fn create(&self, request: CreateRequest) -> Result<Operation<Metadata>, Error> {
+        let operation = self.validate_create_request(request).await?;
+        self.production_order_repository.create(&operation).await?;
+
+        Ok(operation)
+    }
+}
+
+impl<PR, IR, RR> Update for Service<PR, IR, RR>
+where
+    PR: repository::Create
+        + repository::Update
+        + repository::Get
+        + repository::Delete
+        + repository::Progress
+        + Clone,
+    IR: item::repository::Get + Clone,
+    RR: routing::repository::Get + Clone,
+{
+    async fn update(&self, request: UpdateRequest) -> Result<Operation<Metadata>, Error> {
+        let (operation, etag) = self.validate_update_request(request).await?;
+        self.production_order_repository
+            .update(&operation, &etag)
+            .await?;
+
+        Ok(operation)
+    }
+}
+
+impl<PR, IR, RR> Delete for Service<PR, IR, RR>
+where
+    PR: repository::Create
+        + repository::Update
+        + repository::Get
+        + repository::Delete
+        + repository::Progress
+        + Clone,
+    IR: item::repository::Get + Clone,
+    RR: routing::repository::Get + Clone,
+{
+    async fn delete(&self, request: DeleteRequest) -> Result<Operation<Metadata>, Error> {
+        let (operation, etag) = self.validate_delete_request(request).await?;
+        self.production_order_repository
+            .update(&operation, &etag)
+            .await?;
+
+        Ok(operation)
+    }
+}
+
+impl<PR, IR, RR> Release for Service<PR, IR, RR>
+where
+    PR: repository::Create
+        + repository::Update
+        + repository::Get
+        + repository::Delete
+        + repository::Progress
+        + Clone,
+    IR: item::repository::Get + Clone,
+    RR: routing::repository::Get + Clone,
+{
+    async fn release(&self, request: ReleaseRequest) -> Result<Operation<Metadata>, Error> {
+        let (operation, etag) = self.validate_release_request(request).await?;
+        self.production_order_repository
+            .update(&operation, &etag)
+            .await?;
+
+        Ok(operation)
+    }
+}
+
+impl<PR, IR, RR> Complete for Service<PR, IR, RR>
+where
+    PR: repository::Create
+        + repository::Update
+        + repository::Get
+        + repository::Delete
+        + repository::Progress
+        + Clone,
+    IR: item::repository::Get + Clone,
+    RR: routing::repository::Get + Clone,
+{
+    async fn complete(&self, request: CompleteRequest) -> Result<Operation<Metadata>, Error> {
+        let (operation, etag) = self.validate_complete_request(request).await?;
+        self.production_order_repository
+            .update(&operation, &etag)
+            .await?;
+
+        Ok(operation)
+    }
+}
+
+impl<PR, IR, RR> Finish for Service<PR, IR, RR>
+where
+    PR: repository::Create
+        + repository::Update
+        + repository::Get
+        + repository::Delete
+        + repository::Progress
+        + Clone,
+    IR: item::repository::Get + Clone,
+    RR: routing::repository::Get + Clone,
+{
+    async fn finish(&self, request: FinishRequest) -> Result<Operation<Metadata>, Error> {
+        let (operation, etag) = self.validate_finish_request(request).await?;
+        self.production_order_repository
+            .update(&operation, &etag)
+            .await?;
+
+        Ok(operation)
+    }
+}
+
+impl<PR, IR, RR> Cancel for Service<PR, IR, RR>
+where
+    PR: repository::Create
+        + repository::Update
+        + repository::Get
+        + repository::Delete
+        + repository::Progress
+        + Clone,
+    IR: item::repository::Get + Clone,
+    RR: routing::repository::Get + Clone,
+{
+    async fn cancel(&self, request: CancelRequest) -> Result<Operation<Metadata>, Error> {
+        let (operation, etag) = self.validate_cancel_request(request).await?;
+        self.production_order_repository
+            .update(&operation, &etag)
+            .await?;
+
+        Ok(operation)
+    }
+}
+
+impl<PR, IR, RR> StartOperation for Service<PR, IR, RR>
+where
+    PR: repository::Create
+        + repository::Update
+        + repository::Get
+        + repository::Delete
+        + repository::Progress
+        + Clone,
+    IR: item::repository::Get + Clone,
+    RR: routing::repository::Get + Clone,
+{
+    async fn start_operation(
+        &self,
+        request: StartOperationRequest,
+    ) -> Result<ProductionOrder, Error> {
+        let operation = Id::try_from(request.operation)?;
+        let (production_order, etag) = self.fetch_current(request.id, request.etag).await?;
+        let production_order = production_order.start_operation(&operation)?;
+
+        self.save_progress(production_order, &etag).await
+    }
+}
+
+impl<PR, IR, RR> ReportOperation for Service<PR, IR, RR>
+where
+    PR: repository::Create
+        + repository::Update
+        + repository::Get
+        + repository::Delete
+        + repository::Progress
+        + Clone,
+    IR: item::repository::Get + Clone,
+    RR: routing::repository::Get + Clone,
+{
+    async fn report_operation(
+        &self,
+        request: ReportOperationRequest,
+    ) -> Result<ProductionOrder, Error> {
+        let operation = Id::try_from(request.operation)?;
+        let quantity = Quantity::try_from(request.quantity)?;
+        let (production_order, etag) = self.fetch_current(request.id, request.etag).await?;
+        let production_order = production_order.report_operation(&operation, quantity)?;
+
+        self.save_progress(production_order, &etag).await
+    }
+}
+
+impl<PR, IR, RR> CompleteOperation for Service<PR, IR, RR>
+where
+    PR: repository::Create
+        + repository::Update
+        + repository::Get
+        + repository::Delete
+        + repository::Progress
+        + Clone,
+    IR: item::repository::Get + Clone,
+    RR: routing::repository::Get + Clone,
+{
+    async fn complete_operation(
+        &self,
+        request: CompleteOperationRequest,
+    ) -> Result<ProductionOrder, Error> {
+        let operation = Id::try_from(request.operation)?;
+        let (production_order, etag) = self.fetch_current(request.id, request.etag).await?;
+        let production_order = production_order.complete_operation(&operation)?;
+
+        self.save_progress(production_order, &etag).await
+    }
+}
@@ -0,0 +1,130 @@
+use derive_getters::{Dissolve, Getters};
+
+pub use super::Error;
+
+use std::{future::Future, sync::Arc};
+
+use crate::{Id, ProductionOrder};
+
+use super::repository;
+
+// MARK: Get
+
+pub trait Get: Send + Sync + 'static {
+    fn get(
+        &self,
+        request: GetRequest,
+    ) -> impl Future<Output = Result<ProductionOrder, Error>> + Send;
+}
+
+pub struct GetRequest {
+    id: String,
+}
+
+impl GetRequest {
+    #[must_use]
+    pub const fn new(id: String) -> Self {
+        Self { id }
+    }
+}
+
+// MARK: List
+
+pub trait List: Send + Sync + 'static {
+    fn list(
+        &self,
+        request: ListRequest,
+    ) -> impl Future<Output = Result<ListResponse, Error>> + Send;
+}
+
+#[derive(Getters)]
+pub struct ListRequest {
+    page_size: i32,
+    page_token: Option<String>,
+    order_by: Option<String>,
+    filter: Option<String>,
+}
+
+impl ListRequest {
+    #[must_use]
+    pub fn new(
+        page_size: Option<i32>,
+        page_token: Option<String>,
+        order_by: Option<String>,
+        filter: Option<String>,
+    ) -> Self {
+        Self {
+            page_size: page_size.filter(|s| *s > 0).unwrap_or(50).min(1000),
+            page_token,
+            order_by,
+            filter,
+        }
+    }
+}
+
+#[derive(Dissolve)]
+pub struct ListResponse {
+    production_orders: Vec<ProductionOrder>,
+    next_page_token: Option<String>,
+    total_size: i32,
+}
+
+impl ListResponse {
+    #[must_use]
+    pub const fn new(
+        production_orders: Vec<ProductionOrder>,
+        next_page_token: Option<String>,
+        total_size: i32,
+    ) -> Self {
+        Self {
+            production_orders,
+            next_page_token,
+            total_size,
+        }
+    }
+}
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+pub struct Service<PR: repository::Get + repository::List + Clone> {
+    production_order_repository: Arc<PR>,
+}
+
+impl<PR> Service<PR>
+where
+    PR: repository::Get + repository::List + Clone,
+{
+    #[must_use]
+    pub const fn new(production_order_repository: Arc<PR>) -> Self {
+        Self {
+            production_order_repository,
+        }
+    }
+}
+
+fn validate_get_request(request: GetRequest) -> Result<Id, Error> {
+    let id: Id = request.id.try_into()?;
+
+    Ok(id)
+}
+
+impl<PR> Get for Service<PR>
+where
+    PR: repository::Get + repository::List + Clone,
+{
+    async fn get(&self, request: GetRequest) -> Result<ProductionOrder, Error> {
+        let id = validate_get_request(request)?;
+
+        self.production_order_repository.get(&id).await
+    }
+}
+
+impl<PR> List for Service<PR>
+where
+    PR: repository::Get + repository::List + Clone,
+{
+    async fn list(&self, request: ListRequest) -> Result<ListResponse, Error> {
+        self.production_order_repository.list(&request).await
+    }
+}
@@ -0,0 +1,880 @@
+use std::{collections::HashMap, future::Future, sync::Arc, time::Duration};
+
+use anyhow::{anyhow, Context};
+use num_traits::ToPrimitive;
+use prost_types::Any;
+use sqlx::{Executor, FromRow, QueryBuilder, Sqlite, Transaction};
+
+use crate::{
+    page_token, production_order,
+    sqlx::{
+        list::{self, Column, Kind, OrderTerm},
+        Error as SqlxError, SqliteConnection, SqliteError,
+    },
+    sync::{self, Operation, OperationEntity, OperationMetadata, OperationRecord},
+    EntityTag, Filter, Id, OrderBy, PageToken, ProductionOrder, ProductionOrderOperation,
+    ProductionOrderState, Quantity, Timestamp,
+};
+
+use super::{
+    query::{ListRequest, ListResponse},
+    sync::Metadata,
+    AlreadyExistsError, Error, EtagMismatchError, NotFoundError,
+};
+
+// MARK: Get
+
+/// `Get` represents a store of production order data.
+pub trait Get: Send + Sync + 'static {
+    /// Get an [`ProductionOrder`].
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::NotFound`] if an [`ProductionOrder`] with the given [`Id`] does not exist.
+    fn get(&self, id: &Id) -> impl Future<Output = Result<ProductionOrder, Error>> + Send;
+}
+
+// MARK: List
+
+/// `List` represents a store of production order data.
+pub trait List: Send + Sync + 'static {
+    /// List [`ProductionOrder`]s.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`list::Error::NotFound`] if an [`ProductionOrder`] with the given [`Id`] does not exist.
+    fn list(
+        &self,
+        request: &ListRequest,
+    ) -> impl Future<Output = Result<ListResponse, Error>> + Send;
+}
+
+// MARK: Create
+
+/// `Create` represents a store of production order data.
+pub trait Create: Send + Sync + 'static {
+    /// Persist a new [`ProductionOrder`].
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::AlreadyExists`] if an [`ProductionOrder`] with the same [`Id`] already exists.
+    fn create(
+        &self,
+        operation: &Operation<Metadata>,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+// MARK: Update
+
+/// `Update` represents a store of production order data.
+pub trait Update: Send + Sync + 'static {
+    /// Update an [`ProductionOrder`], provided its stored etag still equals `etag`.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::NotFound`] if an [`ProductionOrder`] with the given [`Id`] does not exist.
+    /// - MUST return [`Error::EtagMismatch`] if the stored etag differs from `etag`.
+    fn update(
+        &self,
+        operation: &Operation<Metadata>,
+        etag: &EntityTag,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+// MARK: Progress
+
+/// `Progress` represents a store of production order data.
+pub trait Progress: Send + Sync + 'static {
+    /// Persist a [`ProductionOrder`] whose operations progressed without it changing state,
+    /// provided its stored etag still equals `etag`. No long-running operation is recorded.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::NotFound`] if an [`ProductionOrder`] with the given [`Id`] does not exist.
+    /// - MUST return [`Error::EtagMismatch`] if the stored etag differs from `etag`.
+    fn progress(
+        &self,
+        production_order: &ProductionOrder,
+        etag: &EntityTag,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+// MARK: Complete
+
+/// `Complete` represents a store of production order data.
+pub trait Complete: Send + Sync + 'static {
+    /// Persist the final [`ProductionOrder`] of a finished operation and mark the operation as done.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::Unknown`] if the operation has no successful result.
+    /// - MUST return [`Error::EtagMismatch`] if the stored etag differs from `etag`.
+    /// - MUST return [`crate::id::Error::NotFound`] if the operation does not exist.
+    fn complete(
+        &self,
+        operation: &Operation<Metadata>,
+        etag: &EntityTag,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+// MARK: Delete
+
+/// `Delete` represents a store of production order data.
+pub trait Delete: Send + Sync + 'static {
+    /// Delete an [`ProductionOrder`] and mark the operation removing it as done.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::NotFound`] if an [`ProductionOrder`] with the given [`Id`] does not exist.
+    fn delete(
+        &self,
+        operation: &Operation<Metadata>,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+pub struct Service<DB: SqliteConnection> {
+    db: Arc<DB>,
+    page_token_key: page_token::Key,
+}
+
+#[derive(FromRow)]
+struct ProductionOrderRow {
+    id: String,
+    display_name: String,
+    title: String,
+    description: String,
+    item_id: Option<String>,
+    quantity: String,
+    state: i64,
+    etag: String,
+    uid: String,
+    create_time: String,
+    update_time: String,
+}
+
+#[derive(FromRow)]
+struct OperationRow {
+    production_order_id: String,
+    id: String,
+    work_center: String,
+    setup_time_ms: i64,
+    run_time_ms: i64,
+    state: i64,
+    reported_quantity: String,
+    start_time: Option<String>,
+    complete_time: Option<String>,
+}
+
+impl TryFrom<OperationRow> for ProductionOrderOperation {
+    type Error = Error;
+
+    fn try_from(value: OperationRow) -> Result<Self, Self::Error> {
+        let id = Id::try_from(value.id)?;
+        let state = num_traits::FromPrimitive::from_i64(value.state).ok_or(Error::Unknown(
+            anyhow!(format!("invalid operation state {0}", value.state)),
+        ))?;
+        let start_time = value.start_time.map(Timestamp::try_from).transpose()?;
+        let complete_time = value.complete_time.map(Timestamp::try_from).transpose()?;
+        let reported_quantity = Quantity::try_from(value.reported_quantity)?;
+
+        Ok(Self::from((
+            id,
+            value.work_center,
+            duration_from_millis(value.setup_time_ms),
+            duration_from_millis(value.run_time_ms),
+            state,
+            reported_quantity,
+            start_time,
+            complete_time,
+        )))
+    }
+}
+
+fn duration_from_millis(millis: i64) -> Duration {
+    Duration::from_millis(u64::try_from(millis).unwrap_or_default())
+}
+
+fn duration_to_millis(duration: Duration) -> i64 {
+    i64::try_from(duration.as_millis()).unwrap_or(i64::MAX)
+}
+
+impl TryFrom<(ProductionOrderRow, Vec<ProductionOrderOperation>)> for ProductionOrder {
+    type Error = Error;
+
+    fn try_from(
+        value: (ProductionOrderRow, Vec<ProductionOrderOperation>),
+    ) -> Result<Self, Self::Error> {
+        let (value, operations) = value;
+        let id = Id::try_from(value.id)?;
+        let display_name = value.display_name;
+        let title = value.title;
+        let description = value.description;
+        let item = value.item_id.map(Id::try_from).transpose()?;
+        let quantity = Quantity::try_from(value.quantity)?;
+        let state = num_traits::FromPrimitive::from_i64(value.state).ok_or(Error::Unknown(
+            anyhow!(format!("invalid state {0}", value.state)),
+        ))?;
+        let etag = value.etag.try_into()?;
+        let uid = uuid::Uuid::try_parse(&value.uid)
+            .map_err(|e| production_order::Error::Unknown(anyhow!(e)))?;
+        let create_time = Timestamp::try_from(value.create_time)?;
+        let update_time = Timestamp::try_from(value.update_time)?;
+
+        Ok(Self::from((
+            id,
+            display_name,
+            title,
+            description,
+            item,
+            quantity,
+            operations,
+            state,
+            etag,
+            uid,
+            create_time,
+            update_time,
+        )))
+    }
+}
+
+// MARK: List
+
+const PRODUCTION_ORDER_COLUMNS: &[Column] = &[
+    Column::new("name", "id", Kind::Text, true),
+    Column::new("display_name", "display_name", Kind::Text, true),
+    Column::new("title", "title", Kind::Text, true),
+    Column::new("description", "description", Kind::Text, false),
+    Column::new("item", "item_id", Kind::Text, false),
+    Column::new("state", "state", Kind::State(production_order_state), true),
+    Column::new("etag", "etag", Kind::Text, false),
+    Column::new("uid", "uid", Kind::Text, false),
+    Column::new("create_time", "create_time", Kind::Timestamp, true),
+    Column::new("update_time", "update_time", Kind::Timestamp, true),
+];
+
+fn production_order_state(name: &str) -> Option<i64> {
+    ProductionOrderState::from_name(name).and_then(|s| s.to_i64())
+}
+
+fn cursor(terms: &[OrderTerm], production_order: &ProductionOrder) -> Vec<String> {
+    terms
+        .iter()
+        .map(|(column, _)| match column.name() {
+            "display_name" => production_order.display_name.clone(),
+            "title" => production_order.title.clone(),
+            "state" => production_order
+                .state
+                .to_i64()
+                .unwrap_or_default()
+                .to_string(),
+            "create_time" => production_order.create_time.value().to_string(),
+            "update_time" => production_order.update_time.value().to_string(),
+            _ => production_order.id.value().clone(),
+        })
+        .collect()
+}
+
+impl<DB> Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    #[must_use]
+    pub const fn new(db: Arc<DB>, page_token_key: page_token::Key) -> Self {
+        Self { db, page_token_key }
+    }
+
+    async fn fetch_production_order(&self, id: &Id) -> Result<ProductionOrder, Error> {
+        let value = id.value();
+
+        let query = sqlx::query_as!(
+            ProductionOrderRow,
+            "SELECT
+                id,
+                display_name,
+                title,
+                description,
+                item_id,
+                quantity,
+                state,
+                etag,
+                uid,
+                create_time,
+                update_time
+            FROM production_order WHERE id = $1",
+            value
+        );
+
+        let result =
+            query
+                .fetch_one(&self.db.pool())
+                .await
+                .map_err(|e| match SqlxError::from(&e) {
+                    SqlxError::RowNotFound => NotFoundError::new(id.clone()).into(),
+                    _ => Error::from(
+                        anyhow!(e)
+                            .context(format!("failed to fetch production order with id {id:?}")),
+                    ),
+                })?;
+
+        let mut operations = self.fetch_operations(&[value]).await?;
+        let operations = operations.remove(value).unwrap_or_default();
+
+        (result, operations).try_into()
+    }
+
+    /// Fetch the operations of production orders, keyed by production order.
+    async fn fetch_operations(
+        &self,
+        ids: &[&String],
+    ) -> Result<HashMap<String, Vec<ProductionOrderOperation>>, Error> {
+        let mut operations: HashMap<String, Vec<ProductionOrderOperation>> = HashMap::new();
+        if ids.is_empty() {
+            return Ok(operations);
+        }
+
+        let mut query = QueryBuilder::<Sqlite>::new(
+            "SELECT
+                production_order_id,
+                id,
+                work_center,
+                setup_time_ms,
+                run_time_ms,
+                state,
+                reported_quantity,
+                start_time,
+                complete_time
+            FROM production_order_operation WHERE production_order_id IN (",
+        );
+        let mut separated = query.separated(", ");
+        for id in ids {
+            separated.push_bind(*id);
+        }
+        separated.push_unseparated(") ORDER BY production_order_id, position");
+
+        let rows = query
+            .build_query_as::<OperationRow>()
+            .fetch_all(&self.db.pool())
+            .await
+            .map_err(|e| {
+                Error::from(anyhow!(e).context("failed to fetch production order operations"))
+            })?;
+
+        for row in rows {
+            operations
+                .entry(row.production_order_id.clone())
+                .or_default()
+                .push(row.try_into()?);
+        }
+
+        Ok(operations)
+    }
+
+    async fn fetch_production_orders(&self, request: &ListRequest) -> Result<ListResponse, Error> {
+        let page_size = request.page_size();
+        let raw_filter = request.filter().as_deref().unwrap_or_default().trim();
+        let raw_order_by = request.order_by().as_deref().unwrap_or_default().trim();
+        let parameters = [raw_filter, raw_order_by];
+
+        let filter = Some(raw_filter)
+            .filter(|f| !f.is_empty())
+            .map(|f| Filter::try_from(f.to_string()))
+            .transpose()?;
+        let order_by = Some(raw_order_by)
+            .filter(|o| !o.is_empty())
+            .map(|o| OrderBy::try_from(o.to_string()))
+            .transpose()?;
+        let terms = list::resolve_order_by(PRODUCTION_ORDER_COLUMNS, order_by.as_ref())?;
+        let page_token = request
+            .page_token()
+            .as_deref()
+            .filter(|t| !t.is_empty())
+            .map(|t| PageToken::decode(t, &self.page_token_key))
+            .transpose()?;
+
+        if let Some(page_token) = &page_token {
+            page_token.verify(&parameters)?;
+        }
+
+        let mut query = QueryBuilder::<Sqlite>::new(
+            "SELECT
+                id,
+                display_name,
+                title,
+                description,
+                item_id,
+                quantity,
+                state,
+                etag,
+                uid,
+                create_time,
+                update_time
+            FROM production_order WHERE 1",
+        );
+
+        if let Some(filter) = &filter {
+            query.push(" AND ");
+            list::push_filter(&mut query, PRODUCTION_ORDER_COLUMNS, filter.expression())?;
+        }
+
+        if let Some(page_token) = &page_token {
+            query.push(" AND ");
+            list::push_cursor(&mut query, &terms, page_token.cursor())?;
+        }
+
+        list::push_order_by(&mut query, &terms);
+
+        // Fetch one more production order than requested to learn whether another page follows.
+        query.push(" LIMIT ");
+        query.push_bind(page_size + 1);
+
+        let result = query
+            .build_query_as::<ProductionOrderRow>()
+            .fetch_all(&self.db.pool())
+            .await
+            .map_err(|e| Error::from(anyhow!(e).context("failed to fetch production orders")))?;
+
+        let ids: Vec<_> = result.iter().map(|row| &row.id).collect();
+        let mut operations = self.fetch_operations(&ids).await?;
+
+        let mut production_orders = result
+            .into_iter()
+            .map(|row| {
+                let operations = operations.remove(&row.id).unwrap_or_default();
+                (row, operations).try_into()
+            })
+            .collect::<Result<Vec<ProductionOrder>, Error>>()?;
+
+        let next_page_token =
+            if production_orders.len() > usize::try_from(*page_size).unwrap_or_default() {
+                production_orders.pop();
+                production_orders.last().map(|production_order| {
+                    PageToken::new(&parameters, cursor(&terms, production_order))
+                        .encode(&self.page_token_key)
+                })
+            } else {
+                None
+            };
+
+        let mut query =
+            QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM production_order WHERE 1");
+
+        if let Some(filter) = &filter {
+            query.push(" AND ");
+            list::push_filter(&mut query, PRODUCTION_ORDER_COLUMNS, filter.expression())?;
+        }
+
+        let total_size: i64 = query
+            .build_query_scalar()
+            .fetch_one(&self.db.pool())
+            .await
+            .map_err(|e| Error::from(anyhow!(e).context("failed to count production orders")))?;
+        let total_size = i32::try_from(total_size).unwrap_or(i32::MAX);
+
+        Ok(ListResponse::new(
+            production_orders,
+            next_page_token,
+            total_size,
+        ))
+    }
+
+    async fn save_production_order(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        production_order: &ProductionOrder,
+    ) -> Result<(), Error> {
+        let id = &production_order.id.value();
+        let display_name = &production_order.display_name;
+        let title = &production_order.title;
+        let description = &production_order.description;
+        let item_id = &production_order.item.as_ref().map(Id::to_string);
+        let quantity = &production_order.quantity.to_string();
+        let state = &production_order.state.to_i64();
+        let etag = &production_order.etag.to_string();
+        let uid = &production_order.uid.to_string();
+        let create_time = &production_order.create_time.value().to_string();
+        let update_time = &production_order.update_time.value().to_string();
+
+        let query = sqlx::query!(
+            "INSERT INTO production_order (
+                id,
+                display_name,
+                title,
+                description,
+                item_id,
+                quantity,
+                state,
+                etag,
+                uid,
+                create_time,
+                update_time
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+            id,
+            display_name,
+            title,
+            description,
+            item_id,
+            quantity,
+            state,
+            etag,
+            uid,
+            create_time,
+            update_time,
+        );
+
+        tx.execute(query)
+            .await
+            .map_err(|e| match SqlxError::from(&e) {
+                SqlxError::Sqlite { inner } => match inner {
+                    SqliteError::UniqueConstraintViolationCode => {
+                        Error::from(AlreadyExistsError::new(production_order.id.clone()))
+                    }
+                    SqliteError::Unknown { message } => anyhow!(e)
+                        .context(format!(
+                            "failed to insert production order with id {:?}, with message from database: {:?}",
+                            production_order.id(),
+                            message
+                        ))
+                        .into(),
+                },
+                SqlxError::RowNotFound | SqlxError::Unknown => anyhow!(e)
+                    .context(format!("failed to insert production order with id {:?}", production_order.id()))
+                    .into(),
+            })?;
+
+        self.save_operations(tx, production_order).await
+    }
+
+    async fn save_operations(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        production_order: &ProductionOrder,
+    ) -> Result<(), Error> {
+        let production_order_id = &production_order.id.value();
+
+        for (position, operation) in production_order.operations.iter().enumerate() {
+            let position = i64::try_from(position).unwrap_or(i64::MAX);
+            let id = operation.id().value();
+            let work_center = operation.work_center();
+            let setup_time_ms = duration_to_millis(*operation.setup_time());
+            let run_time_ms = duration_to_millis(*operation.run_time());
+            let state = operation.state().to_i64();
+            let reported_quantity = operation.reported_quantity().to_string();
+            let start_time = operation
+                .start_time()
+                .as_ref()
+                .map(|t| t.value().to_string());
+            let complete_time = operation
+                .complete_time()
+                .as_ref()
+                .map(|t| t.value().to_string());
+
+            let query = sqlx::query!(
+                "INSERT INTO production_order_operation (
+                    production_order_id,
+                    position,
+                    id,
+                    work_center,
+                    setup_time_ms,
+                    run_time_ms,
+                    state,
+                    reported_quantity,
+                    start_time,
+                    complete_time
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+                production_order_id,
+                position,
+                id,
+                work_center,
+                setup_time_ms,
+                run_time_ms,
+                state,
+                reported_quantity,
+                start_time,
+                complete_time,
+            );
+
+            tx.execute(query).await.map_err(|e| {
+                Error::from(anyhow!(e).context(format!(
+                    "failed to insert operations of production order with id {production_order_id:?}"
+                )))
+            })?;
+        }
+
+        Ok(())
+    }
+
+    async fn modify_production_order(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        production_order: &ProductionOrder,
+        expected_etag: &EntityTag,
+    ) -> Result<(), Error> {
+        let id = &production_order.id.value();
+        let display_name = &production_order.display_name;
+        let title = &production_order.title;
+        let description = &production_order.description;
+        let quantity = &production_order.quantity.to_string();
+        let state = &production_order.state.to_i64();
+        let etag = &production_order.etag.to_string();
+        let uid = &production_order.uid.to_string();
+        let create_time = &production_order.create_time.value().to_string();
+        let update_time = &production_order.update_time.value().to_string();
+        let expected = &expected_etag.to_string();
+
+        // The item is immutable, and left alone so that it may have been cleared by its deletion.
+        let query = sqlx::query!(
+            "UPDATE production_order SET
+                display_name    = $2,
+                title           = $3,
+                description     = $4,
+                quantity        = $5,
+                state           = $6,
+                etag            = $7,
+                uid             = $8,
+                create_time     = $9,
+                update_time     = $10
+            WHERE id = $1 AND etag = $11",
+            id,
+            display_name,
+            title,
+            description,
+            quantity,
+            state,
+            etag,
+            uid,
+            create_time,
+            update_time,
+            expected,
+        );
+
+        let result = tx.execute(query).await.map_err(|e| {
+            Error::from(
+                anyhow!(e).context(format!("failed to update production order with id {id:?}")),
+            )
+        })?;
+
+        if result.rows_affected() > 0 {
+            let query = sqlx::query!(
+                "DELETE FROM production_order_operation WHERE production_order_id = $1",
+                id
+            );
+
+            tx.execute(query).await.map_err(|e| {
+                Error::from(anyhow!(e).context(format!(
+                    "failed to delete operations of production order with id {id:?}"
+                )))
+            })?;
+
+            return self.save_operations(tx, production_order).await;
+        }
+
+        // Nothing matched, either the production order is gone or someone else changed it first.
+        let query = sqlx::query_scalar!("SELECT COUNT(*) FROM production_order WHERE id = $1", id);
+
+        let count = query.fetch_one(&mut **tx).await.map_err(|e| {
+            Error::from(
+                anyhow!(e).context(format!("failed to fetch production order with id {id:?}")),
+            )
+        })?;
+
+        if count == 0 {
+            Err(NotFoundError::new(production_order.id.clone()).into())
+        } else {
+            Err(EtagMismatchError::new(production_order.id.clone(), expected.clone()).into())
+        }
+    }
+
+    async fn remove_production_order(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        id: &Id,
+    ) -> Result<(), Error> {
+        let value = &id.to_string();
+
+        let query = sqlx::query!("DELETE FROM production_order WHERE id = $1", value);
+
+        tx.execute(query)
+            .await
+            .map_err(|e| match SqlxError::from(&e) {
+                SqlxError::RowNotFound => NotFoundError::new(id.clone()).into(),
+                _ => Error::from(
+                    anyhow!(e).context(format!("failed to delete production order with id {id:?}")),
+                ),
+            })?;
+
+        Ok(())
+    }
+}
+
+impl<DB> Get for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn get(&self, id: &Id) -> Result<ProductionOrder, Error> {
+        let production_order = self.fetch_production_order(id).await?;
+        Ok(production_order)
+    }
+}
+
+impl<DB> List for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn list(&self, request: &ListRequest) -> Result<ListResponse, Error> {
+        let response = self.fetch_production_orders(request).await?;
+        Ok(response)
+    }
+}
+
+impl<DB> Create for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn create(&self, operation: &Operation<Metadata>) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool()
+            .begin()
+            .await
+            .with_context(|| "failed to start SQLite transaction")?;
+
+        self.save_production_order(&mut tx, operation.metadata().entity())
+            .await?;
+
+        let operation_record = OperationRecord::new(
+            operation.id().clone(),
+            operation.metadata().entity().name(),
+            operation.metadata().into(),
+        );
+        sync::repository::save_operation(&mut tx, &operation_record).await?;
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(())
+    }
+}
+
+impl<DB> Update for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn update(&self, operation: &Operation<Metadata>, etag: &EntityTag) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool()
+            .begin()
+            .await
+            .with_context(|| "failed to start SQLite transaction")?;
+
+        self.modify_production_order(&mut tx, operation.metadata().entity(), etag)
+            .await?;
+
+        let operation_record = OperationRecord::new(
+            operation.id().clone(),
+            operation.metadata().entity().name(),
+            operation.metadata().into(),
+        );
+        sync::repository::save_operation(&mut tx, &operation_record).await?;
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(())
+    }
+}
+
+impl<DB> Progress for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn progress(
+        &self,
+        production_order: &ProductionOrder,
+        etag: &EntityTag,
+    ) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool()
+            .begin()
+            .await
+            .with_context(|| "failed to start SQLite transaction")?;
+
+        self.modify_production_order(&mut tx, production_order, etag)
+            .await?;
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(())
+    }
+}
+
+impl<DB> Complete for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn complete(
+        &self,
+        operation: &Operation<Metadata>,
+        etag: &EntityTag,
+    ) -> Result<(), Error> {
+        let Some(Ok(response)) = operation.result() else {
+            return Err(Error::Unknown(anyhow!(
+                "operation with id {:?} has no successful result",
+                operation.id().value()
+            )));
+        };
+
+        let mut tx = self
+            .db
+            .pool()
+            .begin()
+            .await
+            .with_context(|| "failed to start SQLite transaction")?;
+
+        self.modify_production_order(&mut tx, response.production_order(), etag)
+            .await?;
+
+        let response = Any::from(response.clone());
+        sync::repository::complete_operation(&mut tx, operation.id(), &Ok(response)).await?;
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(())
+    }
+}
+
+impl<DB> Delete for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn delete(&self, operation: &Operation<Metadata>) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool()
+            .begin()
+            .await
+            .with_context(|| "failed to start SQLite transaction")?;
+
+        self.remove_production_order(&mut tx, operation.metadata().entity().id())
+            .await?;
+
+        let response = Any::from_msg(&()).context("failed to encode empty response")?;
+        sync::repository::complete_operation(&mut tx, operation.id(), &Ok(response)).await?;
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use derive_getters::{Dissolve, Getters};
+
+use crate::{
+    production_order,
+    sync::{OperationEntity, OperationMetadata, OperationState},
+    Id, ProductionOrder, ProductionOrderState, Timestamp,
+};
+
+impl OperationState for ProductionOrderState {
+    type NextState = Option<Self>;
+
+    fn next(&self) -> Self::NextState {
+        match self {
+            Self::Creating | Self::Updating => Some(Self::Planned),
+            Self::Releasing => Some(Self::Released),
+            Self::Completing => Some(Self::Completed),
+            Self::Finishing => Some(Self::Finished),
+            Self::Cancelling => Some(Self::Cancelled),
+            Self::Deleting
+            | Self::Planned
+            | Self::Released
+            | Self::Completed
+            | Self::Finished
+            | Self::Cancelled => None,
+        }
+    }
+}
+
+impl OperationEntity for ProductionOrder {
+    type State = ProductionOrderState;
+
+    fn id(&self) -> &Id {
+        self.id()
+    }
+
+    fn state(&self) -> &Self::State {
+        self.state()
+    }
+
+    fn name(&self) -> String {
+        format!("productionOrders/{}", self.id())
+    }
+}
+
+/// The action a production order operation performs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verb {
+    Create,
+    Update,
+    Delete,
+    Release,
+    Complete,
+    Finish,
+    Cancel,
+}
+
+impl Verb {
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Update => "update",
+            Self::Delete => "delete",
+            Self::Release => "release",
+            Self::Complete => "complete",
+            Self::Finish => "finish",
+            Self::Cancel => "cancel",
+        }
+    }
+}
+
+impl From<&ProductionOrderState> for Verb {
+    /// Operations are only started for production orders entering a transitional
+    /// state, so settled states map to the verb that last produced them.
+    fn from(value: &ProductionOrderState) -> Self {
+        match value {
+            ProductionOrderState::Creating => Self::Create,
+            ProductionOrderState::Updating | ProductionOrderState::Planned => Self::Update,
+            ProductionOrderState::Deleting => Self::Delete,
+            ProductionOrderState::Releasing | ProductionOrderState::Released => Self::Release,
+            ProductionOrderState::Completing | ProductionOrderState::Completed => Self::Complete,
+            ProductionOrderState::Finishing | ProductionOrderState::Finished => Self::Finish,
+            ProductionOrderState::Cancelling | ProductionOrderState::Cancelled => Self::Cancel,
+        }
+    }
+}
+
+#[derive(Dissolve, Getters)]
+pub struct Metadata {
+    production_order: ProductionOrder,
+    verb: Verb,
+    create_time: Timestamp,
+}
+
+impl Metadata {
+    #[must_use]
+    pub fn new(production_order: ProductionOrder) -> Self {
+        let verb = Verb::from(production_order.state());
+
+        Self {
+            production_order,
+            verb,
+            create_time: Timestamp::now(),
+        }
+    }
+}
+
+impl OperationMetadata for Metadata {
+    type Entity = ProductionOrder;
+    type Response = Response;
+    type Error = production_order::Error;
+
+    fn entity(&self) -> &Self::Entity {
+        self.production_order()
+    }
+}
+
+/// The settled production order of a finished operation, along with the verb
+/// that produced it, as each verb answers with its own response message.
+#[derive(Clone, Dissolve, Getters)]
+pub struct Response {
+    production_order: ProductionOrder,
+    verb: Verb,
+}
+
+impl Response {
+    #[must_use]
+    pub const fn new(production_order: ProductionOrder, verb: Verb) -> Self {
+        Self {
+            production_order,
+            verb,
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::sync::Arc;
+
+use tonic::Code;
+
+pub use super::Error;
+
+use crate::{
+    grpc::proto::google::rpc,
+    sync::{self, Operation, OperationRecord, Reconcile},
+    Id,
+};
+
+use super::{
+    repository,
+    sync::{Metadata, Response},
+};
+
+const PRODUCTION_ORDER_NAME_PREFIX: &str = "productionOrders/";
+
+/// The targets of the operations on production orders.
+const PRODUCTION_ORDER_TARGETS: &str = "productionOrders/*";
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+pub struct Service<
+    PR: repository::Get + repository::Complete + repository::Delete + Clone,
+    OR: sync::repository::Pending + sync::repository::Update + Clone,
+> {
+    production_order_repository: Arc<PR>,
+    operation_repository: Arc<OR>,
+}
+
+impl<PR, OR> Service<PR, OR>
+where
+    PR: repository::Get + repository::Complete + repository::Delete + Clone,
+    OR: sync::repository::Pending + sync::repository::Update + Clone,
+{
+    #[must_use]
+    pub const fn new(production_order_repository: Arc<PR>, operation_repository: Arc<OR>) -> Self {
+        Self {
+            production_order_repository,
+            operation_repository,
+        }
+    }
+
+    async fn reconcile_operation(
+        &self,
+        record: OperationRecord,
+        production_order_id: Id,
+    ) -> Result<(), Error> {
+        let production_order = match self
+            .production_order_repository
+            .get(&production_order_id)
+            .await
+        {
+            Ok(production_order) => production_order,
+            Err(Error::NotFound(err)) => {
+                let record = record.done(Err(rpc::Status {
+                    code: Code::NotFound.into(),
+                    message: err.to_string(),
+                    details: vec![],
+                }));
+
+                return Ok(self.operation_repository.update(&record).await?);
+            }
+            Err(err) => return Err(err),
+        };
+
+        let (id, ..) = record.dissolve();
+        let metadata = Metadata::new(production_order.clone());
+
+        if let Some(settled) = production_order.clone().settle()? {
+            let etag = production_order.etag().clone();
+            let response = Response::new(settled, *metadata.verb());
+            let operation = Operation::new(id, metadata, Some(Ok(response)));
+            self.production_order_repository
+                .complete(&operation, &etag)
+                .await
+        } else {
+            let operation = Operation::new(id, metadata, None);
+            self.production_order_repository.delete(&operation).await
+        }
+    }
+}
+
+impl<PR, OR> Reconcile for Service<PR, OR>
+where
+    PR: repository::Get + repository::Complete + repository::Delete + Clone,
+    OR: sync::repository::Pending + sync::repository::Update + Clone,
+{
+    type Error = Error;
+
+    async fn reconcile(&self) -> Result<usize, Error> {
+        let mut completed = 0;
+
+        for record in self
+            .operation_repository
+            .pending(PRODUCTION_ORDER_TARGETS)
+            .await?
+        {
+            let Some(production_order_id) =
+                record.target().strip_prefix(PRODUCTION_ORDER_NAME_PREFIX)
+            else {
+                continue;
+            };
+
+            let production_order_id = Id::try_from(production_order_id.to_string());
+            let failed = record.clone();
+
+            let result = match production_order_id {
+                Ok(production_order_id) => {
+                    self.reconcile_operation(record, production_order_id).await
+                }
+                Err(err) => Err(err.into()),
+            };
+            // An operation that cannot be completed is failed rather than retried, so that it
+            // does not hold up every operation pending after it.
+            if let Err(err) = result {
+                let record = failed.done(Err(err.into()));
+                self.operation_repository.update(&record).await?;
+            }
+            completed += 1;
+        }
+
+        Ok(completed)
+    }
+}
@@ -0,0 +1,318 @@
+use std::time::Duration;
+
+use derive_getters::Getters;
+use derive_more::derive::From;
+use uuid::Uuid;
+
+use crate::{
+    entity_tag, id, item, sync::OperationState, timestamp, EntityTag, Id, Item, ItemState, Routing,
+    RoutingOperation, RoutingState, ThisError, Timestamp,
+};
+
+pub mod command;
+pub mod query;
+pub mod repository;
+pub mod sync;
+pub mod worker;
+
+impl RoutingState {
+    /// The API name of the state, e.g. `ACTIVE`.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Creating => "CREATING",
+            Self::Updating => "UPDATING",
+            Self::Active => "ACTIVE",
+        }
+    }
+}
+
+impl Routing {
+    /// Lay down the operations producing an active `item`.
+    pub(crate) fn new(item: &Item, operations: Vec<RoutingOperation>) -> Result<Self, Error> {
+        if *item.state() != ItemState::Active {
+            return Err(ItemNotActiveError::new(item).into());
+        }
+
+        if operations.is_empty() {
+            return Err(NoOperationsError.into());
+        }
+
+        let now = Timestamp::now();
+
+        Ok(Self {
+            item: item.id().clone(),
+            operations,
+            state: RoutingState::Creating,
+            etag: EntityTag::new(),
+            uid: Uuid::new_v4(),
+            create_time: now.clone(),
+            update_time: now,
+        })
+    }
+
+    /// The resource name of the routing.
+    #[must_use]
+    pub fn name(&self) -> String {
+        routing_name(&self.item)
+    }
+
+    /// Replace the operations of the routing. Production orders that have already
+    /// been released keep the operations they were released with.
+    pub(crate) fn update(self, operations: Vec<RoutingOperation>) -> Result<Self, Error> {
+        if self.state != RoutingState::Active {
+            return Err(InvalidStateError::new(&self).into());
+        }
+
+        if operations.is_empty() {
+            return Err(NoOperationsError.into());
+        }
+
+        Ok(Self {
+            operations,
+            ..self.transition(RoutingState::Updating)
+        })
+    }
+
+    /// Move a transitioning routing into the state its operation leads to.
+    pub(crate) fn settle(self) -> Result<Self, Error> {
+        match self.state.next() {
+            Some(state) => Ok(self.transition(state)),
+            None => Err(InvalidStateError::new(&self).into()),
+        }
+    }
+
+    fn transition(self, state: RoutingState) -> Self {
+        Self {
+            state,
+            etag: EntityTag::new(),
+            update_time: Timestamp::now(),
+            ..self
+        }
+    }
+}
+
+impl RoutingOperation {
+    pub(crate) fn new(
+        work_center: String,
+        setup_time: Duration,
+        run_time: Duration,
+    ) -> Result<Self, Error> {
+        if work_center.trim().is_empty() {
+            return Err(EmptyWorkCenterError.into());
+        }
+
+        Ok(Self {
+            work_center,
+            setup_time,
+            run_time,
+        })
+    }
+}
+
+pub(crate) fn routing_name(item: &Id) -> String {
+    format!("items/{item}/routing")
+}
+
+#[derive(Debug, ThisError)]
+pub enum Error {
+    #[error(transparent)]
+    Empty(#[from] EmptyError),
+    #[error(transparent)]
+    InvalidName(#[from] InvalidNameError),
+    #[error(transparent)]
+    NotFound(#[from] NotFoundError),
+    #[error(transparent)]
+    EtagMismatch(#[from] EtagMismatchError),
+    #[error(transparent)]
+    InvalidState(#[from] InvalidStateError),
+    #[error(transparent)]
+    NoOperations(#[from] NoOperationsError),
+    #[error(transparent)]
+    EmptyWorkCenter(#[from] EmptyWorkCenterError),
+    #[error(transparent)]
+    InvalidDuration(#[from] InvalidDurationError),
+    #[error(transparent)]
+    ItemNotActive(#[from] ItemNotActiveError),
+    #[error(transparent)]
+    Item(#[from] item::Error),
+    #[error(transparent)]
+    Id(#[from] id::Error),
+    #[error(transparent)]
+    Etag(#[from] entity_tag::Error),
+    #[error(transparent)]
+    Timestamp(#[from] timestamp::Error),
+    #[error(transparent)]
+    Operation(#[from] crate::sync::Error),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Clone, Debug, ThisError, From)]
+#[error("routing cannot be empty")]
+pub struct EmptyError;
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("{name:?} is not a routing name, expected items/{{item}}/routing")]
+pub struct InvalidNameError {
+    name: String,
+}
+
+impl InvalidNameError {
+    #[must_use]
+    pub const fn new(name: String) -> Self {
+        Self { name }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("routing of item {item:?} not found")]
+pub struct NotFoundError {
+    item: Id,
+}
+
+impl NotFoundError {
+    #[must_use]
+    pub const fn new(item: Id) -> Self {
+        Self { item }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("etag {etag:?} does not match the current etag of the routing of item {item:?}")]
+pub struct EtagMismatchError {
+    item: Id,
+    etag: String,
+}
+
+impl EtagMismatchError {
+    #[must_use]
+    pub const fn new(item: Id, etag: String) -> Self {
+        Self { item, etag }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("routing of item {item:?} cannot be changed while it is {}", state.name())]
+pub struct InvalidStateError {
+    item: Id,
+    state: RoutingState,
+}
+
+impl InvalidStateError {
+    #[must_use]
+    pub fn new(routing: &Routing) -> Self {
+        Self {
+            item: routing.item.clone(),
+            state: routing.state.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, From)]
+#[error("routing needs at least one operation")]
+pub struct NoOperationsError;
+
+#[derive(Clone, Debug, ThisError, From)]
+#[error("work center cannot be empty")]
+pub struct EmptyWorkCenterError;
+
+#[derive(Clone, Debug, ThisError)]
+#[error("{field} must be a duration not less than zero")]
+pub struct InvalidDurationError {
+    field: &'static str,
+}
+
+impl InvalidDurationError {
+    #[must_use]
+    pub const fn new(field: &'static str) -> Self {
+        Self { field }
+    }
+
+    /// The request field holding the invalid duration.
+    #[must_use]
+    pub const fn field(&self) -> &'static str {
+        self.field
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("item {id:?} cannot be routed while it is {}", state.name())]
+pub struct ItemNotActiveError {
+    id: Id,
+    state: ItemState,
+}
+
+impl ItemNotActiveError {
+    #[must_use]
+    pub fn new(item: &Item) -> Self {
+        Self {
+            id: item.id().clone(),
+            state: item.state().clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Tracking;
+
+    use super::*;
+
+    fn item(active: bool) -> anyhow::Result<Item> {
+        let item = Item::new(
+            String::from("bike"),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::from("pcs"),
+            Tracking::None,
+        )?;
+        let item = if active { item.settle()? } else { Some(item) };
+
+        item.ok_or_else(|| anyhow::anyhow!("item was removed"))
+    }
+
+    fn operation(work_center: &str) -> anyhow::Result<RoutingOperation> {
+        Ok(RoutingOperation::new(
+            work_center.to_string(),
+            Duration::from_mins(15),
+            Duration::from_secs(90),
+        )?)
+    }
+
+    #[test]
+    fn follows_the_lifecycle() -> anyhow::Result<()> {
+        let routing = Routing::new(&item(true)?, vec![operation("assembly")?])?;
+        assert_eq!("items/bike/routing", routing.name());
+        assert!(routing.clone().update(vec![operation("paint")?]).is_err());
+
+        let active = routing.settle()?;
+        assert_eq!(&RoutingState::Active, active.state());
+
+        let updated = active.update(vec![operation("weld")?, operation("paint")?])?;
+        assert_eq!(&RoutingState::Updating, updated.state());
+        assert_eq!(2, updated.operations().len());
+        assert_eq!(&RoutingState::Active, updated.settle()?.state());
+
+        Ok(())
+    }
+
+    #[test]
+    fn validates_operations() -> anyhow::Result<()> {
+        assert!(matches!(
+            Routing::new(&item(false)?, vec![operation("assembly")?]),
+            Err(Error::ItemNotActive(_))
+        ));
+        assert!(matches!(
+            Routing::new(&item(true)?, vec![]),
+            Err(Error::NoOperations(_))
+        ));
+        assert!(matches!(
+            RoutingOperation::new(String::from(" "), Duration::ZERO, Duration::ZERO),
+            Err(Error::EmptyWorkCenter(_))
+        ));
+
+        Ok(())
+    }
+}
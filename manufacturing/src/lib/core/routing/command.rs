@@ -0,0 +1,131 @@
+pub use super::Error;
+use super::{repository, sync::Metadata, EtagMismatchError};
+
+use std::{future::Future, sync::Arc, time::Duration};
+
+use crate::{item, sync::Operation, EntityTag, Id, Routing, RoutingOperation};
+
+// MARK: Update
+
+pub trait Update: Send + Sync + 'static {
+    /// Replace the operations of an item's routing, creating the routing if the item has none.
+    #[must_use]
+    fn update(
+        &self,
+        request: UpdateRequest,
+    ) -> impl Future<Output = Result<Operation<Metadata>, Error>> + Send;
+}
+
+pub struct UpdateRequest {
+    item: String,
+    operations: Vec<OperationRequest>,
+    etag: String,
+}
+
+impl UpdateRequest {
+    #[must_use]
+    pub const fn new(item: String, operations: Vec<OperationRequest>, etag: String) -> Self {
+        Self {
+            item,
+            operations,
+            etag,
+        }
+    }
+}
+
+/// A routing operation to lay down, not to be confused with the long-running
+/// operation that lays it down.
+pub struct OperationRequest {
+    work_center: String,
+    setup_time: Duration,
+    run_time: Duration,
+}
+
+impl OperationRequest {
+    #[must_use]
+    pub const fn new(work_center: String, setup_time: Duration, run_time: Duration) -> Self {
+        Self {
+            work_center,
+            setup_time,
+            run_time,
+        }
+    }
+}
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+pub struct Service<
+    RR: repository::Get + repository::Create + repository::Update + Clone,
+    IR: item::repository::Get + Clone,
+> {
+    routing_repository: Arc<RR>,
+    item_repository: Arc<IR>,
+}
+
+impl<RR, IR> Service<RR, IR>
+where
+    RR: repository::Get + repository::Create + repository::Update + Clone,
+    IR: item::repository::Get + Clone,
+{
+    #[must_use]
+    pub const fn new(routing_repository: Arc<RR>, item_repository: Arc<IR>) -> Self {
+        Self {
+            routing_repository,
+            item_repository,
+        }
+    }
+
+    /// Validate the request, returning the operation along with the etag of the routing
+    /// it replaces, or `None` if it lays down the item's first routing.
+    async fn validate_update_request(
+        &self,
+        request: UpdateRequest,
+    ) -> Result<(Operation<Metadata>, Option<EntityTag>), Error> {
+        let item = self
+            .item_repository
+            .get(&Id::try_from(request.item)?)
+            .await?;
+
+        let operations = request
+            .operations
+            .into_iter()
+            .map(|o| RoutingOperation::new(o.work_center, o.setup_time, o.run_time))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (routing, etag) = match self.routing_repository.get(item.id()).await {
+            Ok(routing) => {
+                if request.etag != routing.etag.to_string() {
+                    return Err(EtagMismatchError::new(item.id().clone(), request.etag).into());
+                }
+
+                let etag = routing.etag.clone();
+                (routing.update(operations)?, Some(etag))
+            }
+            Err(Error::NotFound(_)) => (Routing::new(&item, operations)?, None),
+            Err(err) => return Err(err),
+        };
+
+        Ok((
+            Operation::new(Id::new(), Metadata::new(routing), None),
+            etag,
+        ))
+    }
+}
+
+impl<RR, IR> Update for Service<RR, IR>
+where
+    RR: repository::Get + repository::Create + repository::Update + Clone,
+    IR: item::repository::Get + Clone,
+{
+    async fn update(&self, request: UpdateRequest) -> Result<Operation<Metadata>, Error> {
+        let (operation, etag) = self.validate_update_request(request).await?;
+
+        match etag {
+            Some(etag) => self.routing_repository.update(&operation, &etag).await?,
+            None => self.routing_repository.create(&operation).await?,
+        }
+
+        Ok(operation)
+    }
+}
@@ -0,0 +1,58 @@
+pub use super::Error;
+
+use std::{future::Future, sync::Arc};
+
+use crate::{Id, Routing};
+
+use super::repository;
+
+// MARK: Get
+
+pub trait Get: Send + Sync + 'static {
+    fn get(&self, request: GetRequest) -> impl Future<Output = Result<Routing, Error>> + Send;
+}
+
+pub struct GetRequest {
+    item: String,
+}
+
+impl GetRequest {
+    #[must_use]
+    pub const fn new(item: String) -> Self {
+        Self { item }
+    }
+}
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+pub struct Service<RR: repository::Get + Clone> {
+    routing_repository: Arc<RR>,
+}
+
+impl<RR> Service<RR>
+where
+    RR: repository::Get + Clone,
+{
+    #[must_use]
+    pub const fn new(routing_repository: Arc<RR>) -> Self {
+        Self { routing_repository }
+    }
+}
+
+fn validate_get_request(request: GetRequest) -> Result<Id, Error> {
+    let item: Id = request.item.try_into()?;
+
+    Ok(item)
+}
+
+impl<RR> Get for Service<RR>
+where
+    RR: repository::Get + Clone,
+{
+    async fn get(&self, request: GetRequest) -> Result<Routing, Error> {
+        let item = validate_get_request(request)?;
+
+        self.routing_repository.get(&item).await
+    }
+}
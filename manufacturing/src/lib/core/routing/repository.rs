@@ -0,0 +1,451 @@
+use std::{future::Future, sync::Arc, time::Duration};
+
+use anyhow::{anyhow, Context};
+use num_traits::ToPrimitive;
+use prost_types::Any;
+use sqlx::{Executor, FromRow, Sqlite, Transaction};
+
+use crate::{
+    routing,
+    sqlx::{Error as SqlxError, SqliteConnection, SqliteError},
+    sync::{self, Operation, OperationMetadata, OperationRecord},
+    EntityTag, Id, Routing, RoutingOperation, Timestamp,
+};
+
+use super::{sync::Metadata, Error, EtagMismatchError, NotFoundError};
+
+// MARK: Get
+
+/// `Get` represents a store of routing data.
+pub trait Get: Send + Sync + 'static {
+    /// Get the [`Routing`] of an item.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::NotFound`] if the item has no [`Routing`].
+    fn get(&self, item: &Id) -> impl Future<Output = Result<Routing, Error>> + Send;
+}
+
+// MARK: Create
+
+/// `Create` represents a store of routing data.
+pub trait Create: Send + Sync + 'static {
+    /// Persist a new [`Routing`] along with its operations.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::EtagMismatch`] if the item has been given a [`Routing`] meanwhile.
+    fn create(
+        &self,
+        operation: &Operation<Metadata>,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+// MARK: Update
+
+/// `Update` represents a store of routing data.
+pub trait Update: Send + Sync + 'static {
+    /// Update a [`Routing`], provided its stored etag still equals `etag`.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::NotFound`] if the [`Routing`] does not exist.
+    /// - MUST return [`Error::EtagMismatch`] if the stored etag differs from `etag`.
+    fn update(
+        &self,
+        operation: &Operation<Metadata>,
+        etag: &EntityTag,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+// MARK: Complete
+
+/// `Complete` represents a store of routing data.
+pub trait Complete: Send + Sync + 'static {
+    /// Persist the final [`Routing`] of a finished operation and mark the operation as done.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::Unknown`] if the operation has no successful result.
+    /// - MUST return [`Error::EtagMismatch`] if the stored etag differs from `etag`.
+    /// - MUST return [`crate::id::Error::NotFound`] if the operation does not exist.
+    fn complete(
+        &self,
+        operation: &Operation<Metadata>,
+        etag: &EntityTag,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+pub struct Service<DB: SqliteConnection> {
+    db: Arc<DB>,
+}
+
+#[derive(FromRow)]
+struct RoutingRow {
+    item_id: String,
+    state: i64,
+    etag: String,
+    uid: String,
+    create_time: String,
+    update_time: String,
+}
+
+#[derive(FromRow)]
+struct RoutingOperationRow {
+    work_center: String,
+    setup_time_ms: i64,
+    run_time_ms: i64,
+}
+
+impl From<RoutingOperationRow> for RoutingOperation {
+    fn from(value: RoutingOperationRow) -> Self {
+        Self::from((
+            value.work_center,
+            duration_from_millis(value.setup_time_ms),
+            duration_from_millis(value.run_time_ms),
+        ))
+    }
+}
+
+impl TryFrom<(RoutingRow, Vec<RoutingOperation>)> for Routing {
+    type Error = Error;
+
+    fn try_from(value: (RoutingRow, Vec<RoutingOperation>)) -> Result<Self, Self::Error> {
+        let (value, operations) = value;
+        let item = Id::try_from(value.item_id)?;
+        let state = num_traits::FromPrimitive::from_i64(value.state).ok_or(Error::Unknown(
+            anyhow!(format!("invalid state {0}", value.state)),
+        ))?;
+        let etag = value.etag.try_into()?;
+        let uid =
+            uuid::Uuid::try_parse(&value.uid).map_err(|e| routing::Error::Unknown(anyhow!(e)))?;
+        let create_time = Timestamp::try_from(value.create_time)?;
+        let update_time = Timestamp::try_from(value.update_time)?;
+
+        Ok(Self::from((
+            item,
+            operations,
+            state,
+            etag,
+            uid,
+            create_time,
+            update_time,
+        )))
+    }
+}
+
+fn duration_from_millis(millis: i64) -> Duration {
+    Duration::from_millis(u64::try_from(millis).unwrap_or_default())
+}
+
+fn duration_to_millis(duration: Duration) -> i64 {
+    i64::try_from(duration.as_millis()).unwrap_or(i64::MAX)
+}
+
+impl<DB> Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    #[must_use]
+    pub const fn new(db: Arc<DB>) -> Self {
+        Self { db }
+    }
+
+    async fn fetch_routing(&self, item: &Id) -> Result<Routing, Error> {
+        let value = item.value();
+
+        let query = sqlx::query_as!(
+            RoutingRow,
+            "SELECT
+                item_id,
+                state,
+                etag,
+                uid,
+                create_time,
+                update_time
+            FROM routing WHERE item_id = $1",
+            value
+        );
+
+        let row =
+            query
+                .fetch_one(&self.db.pool())
+                .await
+                .map_err(|e| match SqlxError::from(&e) {
+                    SqlxError::RowNotFound => NotFoundError::new(item.clone()).into(),
+                    _ => Error::from(
+                        anyhow!(e)
+                            .context(format!("failed to fetch routing of item with id {item:?}")),
+                    ),
+                })?;
+
+        let query = sqlx::query_as!(
+            RoutingOperationRow,
+            "SELECT
+                work_center,
+                setup_time_ms,
+                run_time_ms
+            FROM routing_operation WHERE item_id = $1 ORDER BY position",
+            value
+        );
+
+        let operations = query.fetch_all(&self.db.pool()).await.map_err(|e| {
+            Error::from(anyhow!(e).context(format!(
+                "failed to fetch routing operations of item with id {item:?}"
+            )))
+        })?;
+
+        (row, operations.into_iter().map(Into::into).collect()).try_into()
+    }
+
+    async fn save_routing(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        routing: &Routing,
+    ) -> Result<(), Error> {
+        let item_id = &routing.item.value();
+        let state = &routing.state.to_i64();
+        let etag = &routing.etag.to_string();
+        let uid = &routing.uid.to_string();
+        let create_time = &routing.create_time.value().to_string();
+        let update_time = &routing.update_time.value().to_string();
+
+        let query = sqlx::query!(
+            "INSERT INTO routing (
+                item_id,
+                state,
+                etag,
+                uid,
+                create_time,
+                update_time
+            ) VALUES ($1, $2, $3, $4, $5, $6)",
+            item_id,
+            state,
+            etag,
+            uid,
+            create_time,
+            update_time,
+        );
+
+        tx.execute(query)
+            .await
+            .map_err(|e| match SqlxError::from(&e) {
+                // Another request laid down the item's first routing in the meantime.
+                SqlxError::Sqlite {
+                    inner: SqliteError::UniqueConstraintViolationCode,
+                } => Error::from(EtagMismatchError::new(routing.item.clone(), String::new())),
+                _ => Error::from(anyhow!(e).context(format!(
+                    "failed to insert routing of item with id {item_id:?}"
+                ))),
+            })?;
+
+        self.save_operations(tx, routing).await
+    }
+
+    async fn modify_routing(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        routing: &Routing,
+        expected_etag: &EntityTag,
+    ) -> Result<(), Error> {
+        let item_id = &routing.item.value();
+        let state = &routing.state.to_i64();
+        let etag = &routing.etag.to_string();
+        let update_time = &routing.update_time.value().to_string();
+        let expected = &expected_etag.to_string();
+
+        let query = sqlx::query!(
+            "UPDATE routing SET
+                state           = $2,
+                etag            = $3,
+                update_time     = $4
+            WHERE item_id = $1 AND etag = $5",
+            item_id,
+            state,
+            etag,
+            update_time,
+            expected,
+        );
+
+        let result = tx.execute(query).await.map_err(|e| {
+            Error::from(anyhow!(e).context(format!(
+                "failed to update routing of item with id {item_id:?}"
+            )))
+        })?;
+
+        if result.rows_affected() == 0 {
+            // Nothing matched, either the routing is gone or someone else changed it first.
+            let query =
+                sqlx::query_scalar!("SELECT COUNT(*) FROM routing WHERE item_id = $1", item_id);
+
+            let count = query.fetch_one(&mut **tx).await.map_err(|e| {
+                Error::from(anyhow!(e).context(format!(
+                    "failed to fetch routing of item with id {item_id:?}"
+                )))
+            })?;
+
+            return if count == 0 {
+                Err(NotFoundError::new(routing.item.clone()).into())
+            } else {
+                Err(EtagMismatchError::new(routing.item.clone(), expected.clone()).into())
+            };
+        }
+
+        let query = sqlx::query!("DELETE FROM routing_operation WHERE item_id = $1", item_id);
+
+        tx.execute(query).await.map_err(|e| {
+            Error::from(anyhow!(e).context(format!(
+                "failed to delete routing operations of item with id {item_id:?}"
+            )))
+        })?;
+
+        self.save_operations(tx, routing).await
+    }
+
+    async fn save_operations(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        routing: &Routing,
+    ) -> Result<(), Error> {
+        let item_id = &routing.item.value();
+
+        for (position, operation) in routing.operations.iter().enumerate() {
+            let position = i64::try_from(position).unwrap_or(i64::MAX);
+            let work_center = operation.work_center();
+            let setup_time_ms = duration_to_millis(*operation.setup_time());
+            let run_time_ms = duration_to_millis(*operation.run_time());
+
+            let query = sqlx::query!(
+                "INSERT INTO routing_operation (
+                    item_id,
+                    position,
+                    work_center,
+                    setup_time_ms,
+                    run_time_ms
+                ) VALUES ($1, $2, $3, $4, $5)",
+                item_id,
+                position,
+                work_center,
+                setup_time_ms,
+                run_time_ms,
+            );
+
+            tx.execute(query).await.map_err(|e| {
+                Error::from(anyhow!(e).context(format!(
+                    "failed to insert routing operations of item with id {item_id:?}"
+                )))
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<DB> Get for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn get(&self, item: &Id) -> Result<Routing, Error> {
+        let routing = self.fetch_routing(item).await?;
+        Ok(routing)
+    }
+}
+
+impl<DB> Create for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn create(&self, operation: &Operation<Metadata>) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool()
+            .begin()
+            .await
+            .with_context(|| "failed to start SQLite transaction")?;
+
+        self.save_routing(&mut tx, operation.metadata().entity())
+            .await?;
+
+        let operation_record = OperationRecord::new(
+            operation.id().clone(),
+            operation.metadata().entity().name(),
+            operation.metadata().into(),
+        );
+        sync::repository::save_operation(&mut tx, &operation_record).await?;
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(())
+    }
+}
+
+impl<DB> Update for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn update(&self, operation: &Operation<Metadata>, etag: &EntityTag) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool()
+            .begin()
+            .await
+            .with_context(|| "failed to start SQLite transaction")?;
+
+        self.modify_routing(&mut tx, operation.metadata().entity(), etag)
+            .await?;
+
+        let operation_record = OperationRecord::new(
+            operation.id().clone(),
+            operation.metadata().entity().name(),
+            operation.metadata().into(),
+        );
+        sync::repository::save_operation(&mut tx, &operation_record).await?;
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(())
+    }
+}
+
+impl<DB> Complete for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn complete(
+        &self,
+        operation: &Operation<Metadata>,
+        etag: &EntityTag,
+    ) -> Result<(), Error> {
+        let Some(Ok(routing)) = operation.result() else {
+            return Err(Error::Unknown(anyhow!(
+                "operation with id {:?} has no successful result",
+                operation.id().value()
+            )));
+        };
+
+        let mut tx = self
+            .db
+            .pool()
+            .begin()
+            .await
+            .with_context(|| "failed to start SQLite transaction")?;
+
+        self.modify_routing(&mut tx, routing, etag).await?;
+
+        let response = Any::from(routing.clone());
+        sync::repository::complete_operation(&mut tx, operation.id(), &Ok(response)).await?;
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(())
+    }
+}
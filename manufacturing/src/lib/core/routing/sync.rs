@@ -0,0 +1,92 @@
+use derive_getters::{Dissolve, Getters};
+
+use crate::{
+    routing,
+    sync::{OperationEntity, OperationMetadata, OperationState},
+    Id, Routing, RoutingState, Timestamp,
+};
+
+impl OperationState for RoutingState {
+    type NextState = Option<Self>;
+
+    fn next(&self) -> Self::NextState {
+        match self {
+            Self::Creating | Self::Updating => Some(Self::Active),
+            Self::Active => None,
+        }
+    }
+}
+
+impl OperationEntity for Routing {
+    type State = RoutingState;
+
+    fn id(&self) -> &Id {
+        self.item()
+    }
+
+    fn state(&self) -> &Self::State {
+        self.state()
+    }
+
+    fn name(&self) -> String {
+        Self::name(self)
+    }
+}
+
+/// The action a routing operation performs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verb {
+    Create,
+    Update,
+}
+
+impl Verb {
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Update => "update",
+        }
+    }
+}
+
+impl From<&RoutingState> for Verb {
+    /// Operations are only started for routings entering a transitional state,
+    /// so the settled state maps to the verb that last produced it.
+    fn from(value: &RoutingState) -> Self {
+        match value {
+            RoutingState::Creating => Self::Create,
+            RoutingState::Updating | RoutingState::Active => Self::Update,
+        }
+    }
+}
+
+#[derive(Dissolve, Getters)]
+pub struct Metadata {
+    routing: Routing,
+    verb: Verb,
+    create_time: Timestamp,
+}
+
+impl Metadata {
+    #[must_use]
+    pub fn new(routing: Routing) -> Self {
+        let verb = Verb::from(routing.state());
+
+        Self {
+            routing,
+            verb,
+            create_time: Timestamp::now(),
+        }
+    }
+}
+
+impl OperationMetadata for Metadata {
+    type Entity = Routing;
+    type Response = Routing;
+    type Error = routing::Error;
+
+    fn entity(&self) -> &Self::Entity {
+        self.routing()
+    }
+}
@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use tonic::Code;
+
+pub use super::Error;
+
+use crate::{
+    grpc::proto::google::rpc,
+    sync::{self, Operation, OperationRecord, Reconcile},
+    Id,
+};
+
+use super::{repository, sync::Metadata};
+
+const ITEM_NAME_PREFIX: &str = "items/";
+
+const ROUTING_NAME_SUFFIX: &str = "/routing";
+
+/// The targets of the operations on routings.
+const ROUTING_TARGETS: &str = "items/*/routing";
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+pub struct Service<
+    RR: repository::Get + repository::Complete + Clone,
+    OR: sync::repository::Pending + sync::repository::Update + Clone,
+> {
+    routing_repository: Arc<RR>,
+    operation_repository: Arc<OR>,
+}
+
+impl<RR, OR> Service<RR, OR>
+where
+    RR: repository::Get + repository::Complete + Clone,
+    OR: sync::repository::Pending + sync::repository::Update + Clone,
+{
+    #[must_use]
+    pub const fn new(routing_repository: Arc<RR>, operation_repository: Arc<OR>) -> Self {
+        Self {
+            routing_repository,
+            operation_repository,
+        }
+    }
+
+    async fn reconcile_operation(&self, record: OperationRecord, item_id: Id) -> Result<(), Error> {
+        let routing = match self.routing_repository.get(&item_id).await {
+            Ok(routing) => routing,
+            Err(Error::NotFound(err)) => {
+                let record = record.done(Err(rpc::Status {
+                    code: Code::NotFound.into(),
+                    message: err.to_string(),
+                    details: vec![],
+                }));
+
+                return Ok(self.operation_repository.update(&record).await?);
+            }
+            Err(err) => return Err(err),
+        };
+
+        let (id, ..) = record.dissolve();
+        let metadata = Metadata::new(routing.clone());
+        let etag = routing.etag().clone();
+        let settled = routing.settle()?;
+        let operation = Operation::new(id, metadata, Some(Ok(settled)));
+
+        self.routing_repository.complete(&operation, &etag).await
+    }
+}
+
+impl<RR, OR> Reconcile for Service<RR, OR>
+where
+    RR: repository::Get + repository::Complete + Clone,
+    OR: sync::repository::Pending + sync::repository::Update + Clone,
+{
+    type Error = Error;
+
+    async fn reconcile(&self) -> Result<usize, Error> {
+        let mut completed = 0;
+
+        for record in self.operation_repository.pending(ROUTING_TARGETS).await? {
+            let Some(item_id) = record
+                .target()
+                .strip_prefix(ITEM_NAME_PREFIX)
+                .and_then(|name| name.strip_suffix(ROUTING_NAME_SUFFIX))
+                .filter(|id| !id.contains('/'))
+            else {
+                continue;
+            };
+
+            let item_id = Id::try_from(item_id.to_string());
+            let failed = record.clone();
+
+            let result = match item_id {
+                Ok(item_id) => self.reconcile_operation(record, item_id).await,
+                Err(err) => Err(err.into()),
+            };
+            // An operation that cannot be completed is failed rather than retried, so that it
+            // does not hold up every operation pending after it.
+            if let Err(err) = result {
+                let record = failed.done(Err(err.into()));
+                self.operation_repository.update(&record).await?;
+            }
+            completed += 1;
+        }
+
+        Ok(completed)
+    }
+}
@@ -0,0 +1,569 @@
+use derive_getters::Getters;
+use derive_more::derive::From;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::{
+    filter, id, item, location, order_by, page_token, production_order, quantity, timestamp,
+    traceability, unit_of_measure, Id, Item, ItemState, Location, ProductionOrder,
+    ProductionOrderState, Quantity, StockBalance, StockMovement, StockMovementKind, ThisError,
+    Timestamp, Traced, Tracking,
+};
+
+pub mod command;
+pub mod query;
+pub mod repository;
+
+impl StockMovementKind {
+    /// Look up a kind by its API name, e.g. `RECEIPT`.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "RECEIPT" => Some(Self::Receipt),
+            "ISSUE" => Some(Self::Issue),
+            "TRANSFER" => Some(Self::Transfer),
+            "ADJUSTMENT" => Some(Self::Adjustment),
+            _ => None,
+        }
+    }
+
+    /// The API name of the kind, e.g. `RECEIPT`.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Receipt => "RECEIPT",
+            Self::Issue => "ISSUE",
+            Self::Transfer => "TRANSFER",
+            Self::Adjustment => "ADJUSTMENT",
+        }
+    }
+}
+
+impl StockMovement {
+    /// Prepare a movement of `quantity`, given in the base unit of `item`. The `traced` lot
+    /// or serial must be given exactly when the item is tracked by it.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        id: String,
+        kind: StockMovementKind,
+        item: &Item,
+        quantity: Quantity,
+        source_location: Option<&Location>,
+        target_location: Option<&Location>,
+        traced: Option<Traced>,
+        reason: String,
+    ) -> Result<Self, Error> {
+        if *item.state() != ItemState::Active {
+            return Err(ItemNotActiveError::new(item).into());
+        }
+
+        let source_location = source_location.map(|l| l.id().clone());
+        let target_location = target_location.map(|l| l.id().clone());
+
+        let valid_locations = match kind {
+            StockMovementKind::Receipt | StockMovementKind::Adjustment => {
+                source_location.is_none() && target_location.is_some()
+            }
+            StockMovementKind::Issue => source_location.is_some() && target_location.is_none(),
+            StockMovementKind::Transfer => {
+                source_location.is_some()
+                    && target_location.is_some()
+                    && source_location != target_location
+            }
+        };
+        if !valid_locations {
+            return Err(InvalidLocationsError::new(kind).into());
+        }
+
+        let valid_quantity = match kind {
+            StockMovementKind::Adjustment => !quantity.is_zero(),
+            _ => quantity.is_positive(),
+        };
+        if !valid_quantity {
+            return Err(InvalidQuantityError::new(kind, quantity).into());
+        }
+
+        let (lot, serial) = match (item.tracking(), traced) {
+            (Tracking::None, None) => (None, None),
+            (Tracking::Lot, Some(Traced::Lot { item: owner, lot })) if &owner == item.id() => {
+                (Some(lot), None)
+            }
+            (
+                Tracking::Serial,
+                Some(Traced::Serial {
+                    item: owner,
+                    serial,
+                }),
+            ) if &owner == item.id() => (None, Some(serial)),
+            _ => return Err(InvalidTrackingError::new(item).into()),
+        };
+        if serial.is_some() && quantity.value().abs() != Decimal::ONE {
+            return Err(SerialQuantityError::new(quantity).into());
+        }
+
+        Ok(Self {
+            id: id.try_into()?,
+            kind,
+            item: item.id().clone(),
+            quantity,
+            source_location,
+            target_location,
+            lot,
+            serial,
+            production_order: None,
+            reason,
+            uid: Uuid::new_v4(),
+            create_time: Timestamp::now(),
+        })
+    }
+
+    /// Post the movement against a production order, as an issue of its components or a
+    /// receipt of its output, while the order is being executed.
+    pub(crate) fn for_production_order(self, order: &ProductionOrder) -> Result<Self, Error> {
+        let valid = match self.kind {
+            StockMovementKind::Issue => true,
+            StockMovementKind::Receipt => order.item().as_ref() == Some(&self.item),
+            StockMovementKind::Transfer | StockMovementKind::Adjustment => false,
+        };
+        if !valid {
+            return Err(InvalidProductionOrderError::new(order.id().clone(), self.kind).into());
+        }
+
+        if !matches!(
+            order.state(),
+            ProductionOrderState::Released | ProductionOrderState::Completed
+        ) {
+            return Err(ProductionOrderNotOpenError::new(order).into());
+        }
+
+        Ok(Self {
+            production_order: Some(order.id().clone()),
+            ..self
+        })
+    }
+
+    /// The resource name of the stock movement.
+    #[must_use]
+    pub fn name(&self) -> String {
+        stock_movement_name(&self.id)
+    }
+
+    /// The lot or serial moved, if the item is tracked.
+    #[must_use]
+    pub fn traced(&self) -> Option<Traced> {
+        let lot = self.lot.clone().map(|lot| Traced::Lot {
+            item: self.item.clone(),
+            lot,
+        });
+        let serial = self.serial.clone().map(|serial| Traced::Serial {
+            item: self.item.clone(),
+            serial,
+        });
+
+        lot.or(serial)
+    }
+
+    /// How the movement changes the on-hand quantity at each location it touches.
+    #[must_use]
+    pub fn deltas(&self) -> Vec<(&Id, Quantity)> {
+        let source = self
+            .source_location
+            .as_ref()
+            .map(|location| (location, self.quantity.negated()));
+        let target = self
+            .target_location
+            .as_ref()
+            .map(|location| (location, self.quantity.clone()));
+
+        source.into_iter().chain(target).collect()
+    }
+}
+
+impl StockBalance {
+    /// The balance of an item at a location no movement has touched yet.
+    #[must_use]
+    pub const fn empty(item: Id, location: Id) -> Self {
+        Self {
+            item,
+            location,
+            quantity: Quantity::zero(),
+        }
+    }
+
+    /// The resource name of the stock balance.
+    #[must_use]
+    pub fn name(&self) -> String {
+        stock_balance_name(&self.item, &self.location)
+    }
+
+    /// Apply `delta` to the balance, refusing to go below zero unless `allow_negative`.
+    pub(crate) fn apply(self, delta: &Quantity, allow_negative: bool) -> Result<Self, Error> {
+        let quantity = self.quantity.checked_add(delta)?;
+
+        if quantity.is_negative() && !allow_negative {
+            return Err(InsufficientStockError::new(&self, delta.negated()).into());
+        }
+
+        Ok(Self { quantity, ..self })
+    }
+}
+
+pub(crate) fn stock_movement_name(id: &Id) -> String {
+    format!("stockMovements/{id}")
+}
+
+pub(crate) fn stock_balance_name(item: &Id, location: &Id) -> String {
+    format!("items/{item}/stockBalances/{location}")
+}
+
+#[derive(Debug, ThisError)]
+pub enum Error {
+    #[error(transparent)]
+    Empty(#[from] EmptyError),
+    #[error(transparent)]
+    NotFound(#[from] NotFoundError),
+    #[error(transparent)]
+    AlreadyExists(#[from] AlreadyExistsError),
+    #[error(transparent)]
+    InvalidKind(#[from] InvalidKindError),
+    #[error(transparent)]
+    InvalidLocations(#[from] InvalidLocationsError),
+    #[error(transparent)]
+    InvalidQuantity(#[from] InvalidQuantityError),
+    #[error(transparent)]
+    InsufficientStock(#[from] InsufficientStockError),
+    #[error(transparent)]
+    InvalidBalanceName(#[from] InvalidBalanceNameError),
+    #[error(transparent)]
+    ItemNotActive(#[from] ItemNotActiveError),
+    #[error(transparent)]
+    InvalidTracking(#[from] InvalidTrackingError),
+    #[error(transparent)]
+    SerialQuantity(#[from] SerialQuantityError),
+    #[error(transparent)]
+    InvalidProductionOrder(#[from] InvalidProductionOrderError),
+    #[error(transparent)]
+    ProductionOrderNotOpen(#[from] ProductionOrderNotOpenError),
+    #[error(transparent)]
+    Item(#[from] item::Error),
+    #[error(transparent)]
+    Location(#[from] location::Error),
+    #[error(transparent)]
+    Traceability(#[from] traceability::Error),
+    #[error(transparent)]
+    ProductionOrder(#[from] production_order::Error),
+    #[error(transparent)]
+    UnitOfMeasure(#[from] unit_of_measure::Error),
+    #[error(transparent)]
+    Quantity(#[from] quantity::Error),
+    #[error(transparent)]
+    Id(#[from] id::Error),
+    #[error(transparent)]
+    Timestamp(#[from] timestamp::Error),
+    #[error(transparent)]
+    Filter(#[from] filter::Error),
+    #[error(transparent)]
+    OrderBy(#[from] order_by::Error),
+    #[error(transparent)]
+    PageToken(#[from] page_token::Error),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Clone, Debug, ThisError, From)]
+#[error("stock movement cannot be empty")]
+pub struct EmptyError;
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("stock movement {id:?} not found")]
+pub struct NotFoundError {
+    id: Id,
+}
+
+impl NotFoundError {
+    #[must_use]
+    pub const fn new(id: Id) -> Self {
+        Self { id }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("stock movement {id:?} already exists")]
+pub struct AlreadyExistsError {
+    id: Id,
+}
+
+impl AlreadyExistsError {
+    #[must_use]
+    pub const fn new(id: Id) -> Self {
+        Self { id }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("stock movement kind {kind} is not supported")]
+pub struct InvalidKindError {
+    kind: i32,
+}
+
+impl InvalidKindError {
+    #[must_use]
+    pub const fn new(kind: i32) -> Self {
+        Self { kind }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("{} needs {}", kind.name(), match kind {
+    StockMovementKind::Receipt | StockMovementKind::Adjustment => "a target location and no source location",
+    StockMovementKind::Issue => "a source location and no target location",
+    StockMovementKind::Transfer => "distinct source and target locations",
+})]
+pub struct InvalidLocationsError {
+    kind: StockMovementKind,
+}
+
+impl InvalidLocationsError {
+    #[must_use]
+    pub const fn new(kind: StockMovementKind) -> Self {
+        Self { kind }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("{} needs a {} quantity, got {quantity}", kind.name(), match kind {
+    StockMovementKind::Adjustment => "non-zero",
+    _ => "positive",
+})]
+pub struct InvalidQuantityError {
+    kind: StockMovementKind,
+    quantity: Quantity,
+}
+
+impl InvalidQuantityError {
+    #[must_use]
+    pub const fn new(kind: StockMovementKind, quantity: Quantity) -> Self {
+        Self { kind, quantity }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("location {location:?} holds {on_hand} of item {item:?}, which does not cover {requested}")]
+pub struct InsufficientStockError {
+    item: Id,
+    location: Id,
+    on_hand: Quantity,
+    requested: Quantity,
+}
+
+impl InsufficientStockError {
+    #[must_use]
+    pub fn new(balance: &StockBalance, requested: Quantity) -> Self {
+        Self {
+            item: balance.item.clone(),
+            location: balance.location.clone(),
+            on_hand: balance.quantity.clone(),
+            requested,
+        }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error(
+    "stock balance name {name:?} is invalid: expected items/{{item}}/stockBalances/{{location}}"
+)]
+pub struct InvalidBalanceNameError {
+    name: String,
+}
+
+impl InvalidBalanceNameError {
+    #[must_use]
+    pub const fn new(name: String) -> Self {
+        Self { name }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("item {id:?} cannot be moved while it is {}", state.name())]
+pub struct ItemNotActiveError {
+    id: Id,
+    state: ItemState,
+}
+
+impl ItemNotActiveError {
+    #[must_use]
+    pub fn new(item: &Item) -> Self {
+        Self {
+            id: item.id().clone(),
+            state: item.state().clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("item {item:?} is tracked by {}, so a movement needs {}", tracking.name(), match tracking {
+    Tracking::None => "neither a lot nor a serial",
+    Tracking::Lot => "one of its lots",
+    Tracking::Serial => "one of its serials",
+})]
+pub struct InvalidTrackingError {
+    item: Id,
+    tracking: Tracking,
+}
+
+impl InvalidTrackingError {
+    #[must_use]
+    pub fn new(item: &Item) -> Self {
+        Self {
+            item: item.id().clone(),
+            tracking: *item.tracking(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("a serial moves a single unit, got {quantity}")]
+pub struct SerialQuantityError {
+    quantity: Quantity,
+}
+
+impl SerialQuantityError {
+    #[must_use]
+    pub const fn new(quantity: Quantity) -> Self {
+        Self { quantity }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("{} cannot be posted against production order {id:?}, which takes issues of components and receipts of its item", kind.name())]
+pub struct InvalidProductionOrderError {
+    id: Id,
+    kind: StockMovementKind,
+}
+
+impl InvalidProductionOrderError {
+    #[must_use]
+    pub const fn new(id: Id, kind: StockMovementKind) -> Self {
+        Self { id, kind }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("production order {id:?} takes no stock movements while it is {}", state.name())]
+pub struct ProductionOrderNotOpenError {
+    id: Id,
+    state: ProductionOrderState,
+}
+
+impl ProductionOrderNotOpenError {
+    #[must_use]
+    pub fn new(order: &ProductionOrder) -> Self {
+        Self {
+            id: order.id().clone(),
+            state: order.state().clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item() -> anyhow::Result<Item> {
+        let item = Item::new(
+            String::from("bike"),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::from("pcs"),
+            Tracking::None,
+        )?;
+
+        item.settle()?
+            .ok_or_else(|| anyhow::anyhow!("item was removed"))
+    }
+
+    fn location(id: &str) -> anyhow::Result<Location> {
+        Ok(Location::new(id.to_string(), String::new(), false)?)
+    }
+
+    fn quantity(value: &str) -> anyhow::Result<Quantity> {
+        Ok(Quantity::try_from(value.to_string())?)
+    }
+
+    #[test]
+    fn checks_locations_and_quantity_of_each_kind() -> anyhow::Result<()> {
+        let item = item()?;
+        let (a, b) = (location("a")?, location("b")?);
+        let movement = |kind, quantity, source, target| {
+            StockMovement::new(
+                String::from("m"),
+                kind,
+                &item,
+                quantity,
+                source,
+                target,
+                None,
+                String::new(),
+            )
+        };
+
+        let transfer = movement(
+            StockMovementKind::Transfer,
+            quantity("2")?,
+            Some(&a),
+            Some(&b),
+        )?;
+        assert_eq!(
+            vec![(a.id(), quantity("-2")?), (b.id(), quantity("2")?)],
+            transfer.deltas()
+        );
+
+        assert!(matches!(
+            movement(
+                StockMovementKind::Transfer,
+                quantity("2")?,
+                Some(&a),
+                Some(&a)
+            ),
+            Err(Error::InvalidLocations(_))
+        ));
+        assert!(matches!(
+            movement(StockMovementKind::Receipt, quantity("2")?, Some(&a), None),
+            Err(Error::InvalidLocations(_))
+        ));
+        assert!(matches!(
+            movement(StockMovementKind::Issue, quantity("-2")?, Some(&a), None),
+            Err(Error::InvalidQuantity(_))
+        ));
+        assert!(movement(
+            StockMovementKind::Adjustment,
+            quantity("-2")?,
+            None,
+            Some(&a)
+        )
+        .is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn prevents_negative_stock_unless_allowed() -> anyhow::Result<()> {
+        let balance = StockBalance::empty(
+            Id::try_from(String::from("bike"))?,
+            Id::try_from(String::from("a"))?,
+        );
+        let balance = balance.apply(&quantity("3")?, false)?;
+
+        assert!(matches!(
+            balance.clone().apply(&quantity("-3.5")?, false),
+            Err(Error::InsufficientStock(_))
+        ));
+        assert_eq!(
+            &quantity("-0.5")?,
+            balance.apply(&quantity("-3.5")?, true)?.quantity()
+        );
+
+        Ok(())
+    }
+}
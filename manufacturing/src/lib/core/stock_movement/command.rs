@@ -0,0 +1,246 @@
+use uuid::Uuid;
+
+pub use super::Error;
+use super::{repository, AlreadyExistsError, InvalidTrackingError};
+
+use std::{future::Future, sync::Arc};
+
+use crate::{
+    item, location, production_order, traceability, unit_of_measure, Id, Item, Location, Quantity,
+    StockMovement, StockMovementKind, Traced,
+};
+
+// MARK: Create
+
+pub trait Create: Send + Sync + 'static {
+    fn create(
+        &self,
+        request: CreateRequest,
+    ) -> impl Future<Output = Result<StockMovement, Error>> + Send;
+}
+
+pub struct CreateRequest {
+    id: Option<String>,
+    kind: StockMovementKind,
+    item: String,
+    quantity: String,
+    /// The unit `quantity` is given in, the base unit of the item if empty.
+    unit_of_measure: String,
+    source_location: Option<String>,
+    target_location: Option<String>,
+    /// The lot or serial moved, matching how the item is tracked.
+    lot: Option<String>,
+    serial: Option<String>,
+    production_order: Option<String>,
+    reason: String,
+}
+
+impl CreateRequest {
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    pub const fn new(
+        id: Option<String>,
+        kind: StockMovementKind,
+        item: String,
+        quantity: String,
+        unit_of_measure: String,
+        source_location: Option<String>,
+        target_location: Option<String>,
+        lot: Option<String>,
+        serial: Option<String>,
+        production_order: Option<String>,
+        reason: String,
+    ) -> Self {
+        Self {
+            id,
+            kind,
+            item,
+            quantity,
+            unit_of_measure,
+            source_location,
+            target_location,
+            lot,
+            serial,
+            production_order,
+            reason,
+        }
+    }
+}
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+#[allow(clippy::struct_field_names)]
+pub struct Service<
+    SR: repository::Get + repository::Post + Clone,
+    IR: item::repository::Get + Clone,
+    LR: location::repository::Get + Clone,
+    UR: unit_of_measure::repository::Get + Clone,
+    TR: traceability::repository::GetLot + traceability::repository::GetSerial + Clone,
+    PR: production_order::repository::Get + Clone,
+> {
+    stock_movement_repository: Arc<SR>,
+    item_repository: Arc<IR>,
+    location_repository: Arc<LR>,
+    unit_of_measure_repository: Arc<UR>,
+    traceability_repository: Arc<TR>,
+    production_order_repository: Arc<PR>,
+}
+
+impl<SR, IR, LR, UR, TR, PR> Service<SR, IR, LR, UR, TR, PR>
+where
+    SR: repository::Get + repository::Post + Clone,
+    IR: item::repository::Get + Clone,
+    LR: location::repository::Get + Clone,
+    UR: unit_of_measure::repository::Get + Clone,
+    TR: traceability::repository::GetLot + traceability::repository::GetSerial + Clone,
+    PR: production_order::repository::Get + Clone,
+{
+    #[must_use]
+    pub const fn new(
+        stock_movement_repository: Arc<SR>,
+        item_repository: Arc<IR>,
+        location_repository: Arc<LR>,
+        unit_of_measure_repository: Arc<UR>,
+        traceability_repository: Arc<TR>,
+        production_order_repository: Arc<PR>,
+    ) -> Self {
+        Self {
+            stock_movement_repository,
+            item_repository,
+            location_repository,
+            unit_of_measure_repository,
+            traceability_repository,
+            production_order_repository,
+        }
+    }
+
+    async fn location(&self, id: Option<String>) -> Result<Option<Location>, Error> {
+        match id.filter(|id| !id.is_empty()) {
+            Some(id) => {
+                let id = Id::try_from(id)?;
+                Ok(Some(self.location_repository.get(&id).await?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Resolve the lot or serial of `item` the request names, checking it exists. Whether
+    /// the item needs it is left to [`StockMovement::new`].
+    async fn traced(
+        &self,
+        item: &Item,
+        lot: Option<String>,
+        serial: Option<String>,
+    ) -> Result<Option<Traced>, Error> {
+        let lot = lot.filter(|lot| !lot.is_empty());
+        let serial = serial.filter(|serial| !serial.is_empty());
+
+        let traced = match (lot, serial) {
+            (None, None) => None,
+            (Some(lot), None) => {
+                let lot = self
+                    .traceability_repository
+                    .get_lot(item.id(), &Id::try_from(lot)?)
+                    .await?;
+                Some(Traced::from(&lot))
+            }
+            (None, Some(serial)) => {
+                let serial = self
+                    .traceability_repository
+                    .get_serial(item.id(), &Id::try_from(serial)?)
+                    .await?;
+                Some(Traced::from(&serial))
+            }
+            (Some(_), Some(_)) => return Err(InvalidTrackingError::new(item).into()),
+        };
+
+        Ok(traced)
+    }
+
+    /// Convert `quantity` from `unit_of_measure` into `base_uom`, when they differ.
+    async fn convert(
+        &self,
+        quantity: Quantity,
+        unit_of_measure: String,
+        base_uom: &Id,
+    ) -> Result<Quantity, Error> {
+        if unit_of_measure.is_empty() || &unit_of_measure == base_uom.value() {
+            return Ok(quantity);
+        }
+
+        let from = Id::try_from(unit_of_measure)?;
+        let from = self.unit_of_measure_repository.get(&from).await?;
+        let to = self.unit_of_measure_repository.get(base_uom).await?;
+
+        Ok(from.convert(&quantity, &to)?)
+    }
+
+    async fn validate_create_request(
+        &self,
+        request: CreateRequest,
+    ) -> Result<StockMovement, Error> {
+        let id = match request.id {
+            Some(id) => {
+                let id = Id::try_from(id)?;
+                match self.stock_movement_repository.get(&id).await {
+                    Ok(movement) => return Err(AlreadyExistsError::new(movement.id).into()),
+                    Err(Error::NotFound(_)) => id.to_string(),
+                    Err(err) => return Err(err),
+                }
+            }
+            None => Uuid::new_v4().to_string(),
+        };
+
+        let item = Id::try_from(request.item)?;
+        let item = self.item_repository.get(&item).await?;
+
+        let quantity = Quantity::try_from(request.quantity)?;
+        let quantity = self
+            .convert(quantity, request.unit_of_measure, item.base_uom())
+            .await?;
+
+        let source_location = self.location(request.source_location).await?;
+        let target_location = self.location(request.target_location).await?;
+        let traced = self.traced(&item, request.lot, request.serial).await?;
+
+        let movement = StockMovement::new(
+            id,
+            request.kind,
+            &item,
+            quantity,
+            source_location.as_ref(),
+            target_location.as_ref(),
+            traced,
+            request.reason,
+        )?;
+
+        match request.production_order.filter(|id| !id.is_empty()) {
+            Some(id) => {
+                let order = self
+                    .production_order_repository
+                    .get(&Id::try_from(id)?)
+                    .await?;
+                movement.for_production_order(&order)
+            }
+            None => Ok(movement),
+        }
+    }
+}
+
+impl<SR, IR, LR, UR, TR, PR> Create for Service<SR, IR, LR, UR, TR, PR>
+where
+    SR: repository::Get + repository::Post + Clone,
+    IR: item::repository::Get + Clone,
+    LR: location::repository::Get + Clone,
+    UR: unit_of_measure::repository::Get + Clone,
+    TR: traceability::repository::GetLot + traceability::repository::GetSerial + Clone,
+    PR: production_order::repository::Get + Clone,
+{
+    async fn create(&self, request: CreateRequest) -> Result<StockMovement, Error> {
+        let movement = self.validate_create_request(request).await?;
+        self.stock_movement_repository.post(&movement).await?;
+
+        Ok(movement)
+    }
+}
@@ -0,0 +1,251 @@
+use derive_getters::{Dissolve, Getters};
+
+pub use super::Error;
+
+use std::{future::Future, sync::Arc};
+
+use crate::{item, location, Id, StockBalance, StockMovement};
+
+use super::repository;
+
+// MARK: Get
+
+pub trait Get: Send + Sync + 'static {
+    fn get(&self, request: GetRequest)
+        -> impl Future<Output = Result<StockMovement, Error>> + Send;
+}
+
+pub struct GetRequest {
+    id: String,
+}
+
+impl GetRequest {
+    #[must_use]
+    pub const fn new(id: String) -> Self {
+        Self { id }
+    }
+}
+
+// MARK: List
+
+pub trait List: Send + Sync + 'static {
+    fn list(
+        &self,
+        request: ListRequest,
+    ) -> impl Future<Output = Result<ListResponse, Error>> + Send;
+}
+
+/// A page of stock movements or stock balances.
+#[derive(Getters)]
+pub struct ListRequest {
+    page_size: i32,
+    page_token: Option<String>,
+    order_by: Option<String>,
+    filter: Option<String>,
+}
+
+impl ListRequest {
+    #[must_use]
+    pub fn new(
+        page_size: Option<i32>,
+        page_token: Option<String>,
+        order_by: Option<String>,
+        filter: Option<String>,
+    ) -> Self {
+        Self {
+            page_size: page_size.filter(|s| *s > 0).unwrap_or(50).min(1000),
+            page_token,
+            order_by,
+            filter,
+        }
+    }
+}
+
+#[derive(Dissolve)]
+pub struct ListResponse {
+    stock_movements: Vec<StockMovement>,
+    next_page_token: Option<String>,
+    total_size: i32,
+}
+
+impl ListResponse {
+    #[must_use]
+    pub const fn new(
+        stock_movements: Vec<StockMovement>,
+        next_page_token: Option<String>,
+        total_size: i32,
+    ) -> Self {
+        Self {
+            stock_movements,
+            next_page_token,
+            total_size,
+        }
+    }
+}
+
+// MARK: GetBalance
+
+pub trait GetBalance: Send + Sync + 'static {
+    fn get_balance(
+        &self,
+        request: GetBalanceRequest,
+    ) -> impl Future<Output = Result<StockBalance, Error>> + Send;
+}
+
+pub struct GetBalanceRequest {
+    item: String,
+    location: String,
+}
+
+impl GetBalanceRequest {
+    #[must_use]
+    pub const fn new(item: String, location: String) -> Self {
+        Self { item, location }
+    }
+}
+
+// MARK: ListBalances
+
+pub trait ListBalances: Send + Sync + 'static {
+    fn list_balances(
+        &self,
+        request: ListRequest,
+    ) -> impl Future<Output = Result<ListBalancesResponse, Error>> + Send;
+}
+
+#[derive(Dissolve)]
+pub struct ListBalancesResponse {
+    stock_balances: Vec<StockBalance>,
+    next_page_token: Option<String>,
+    total_size: i32,
+}
+
+impl ListBalancesResponse {
+    #[must_use]
+    pub const fn new(
+        stock_balances: Vec<StockBalance>,
+        next_page_token: Option<String>,
+        total_size: i32,
+    ) -> Self {
+        Self {
+            stock_balances,
+            next_page_token,
+            total_size,
+        }
+    }
+}
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+#[allow(clippy::struct_field_names)]
+pub struct Service<
+    SR: repository::Get + repository::List + repository::GetBalance + repository::ListBalances + Clone,
+    IR: item::repository::Get + Clone,
+    LR: location::repository::Get + Clone,
+> {
+    stock_movement_repository: Arc<SR>,
+    item_repository: Arc<IR>,
+    location_repository: Arc<LR>,
+}
+
+impl<SR, IR, LR> Service<SR, IR, LR>
+where
+    SR: repository::Get
+        + repository::List
+        + repository::GetBalance
+        + repository::ListBalances
+        + Clone,
+    IR: item::repository::Get + Clone,
+    LR: location::repository::Get + Clone,
+{
+    #[must_use]
+    pub const fn new(
+        stock_movement_repository: Arc<SR>,
+        item_repository: Arc<IR>,
+        location_repository: Arc<LR>,
+    ) -> Self {
+        Self {
+            stock_movement_repository,
+            item_repository,
+            location_repository,
+        }
+    }
+}
+
+fn validate_get_request(request: GetRequest) -> Result<Id, Error> {
+    let id: Id = request.id.try_into()?;
+
+    Ok(id)
+}
+
+impl<SR, IR, LR> Get for Service<SR, IR, LR>
+where
+    SR: repository::Get
+        + repository::List
+        + repository::GetBalance
+        + repository::ListBalances
+        + Clone,
+    IR: item::repository::Get + Clone,
+    LR: location::repository::Get + Clone,
+{
+    async fn get(&self, request: GetRequest) -> Result<StockMovement, Error> {
+        let id = validate_get_request(request)?;
+
+        self.stock_movement_repository.get(&id).await
+    }
+}
+
+impl<SR, IR, LR> List for Service<SR, IR, LR>
+where
+    SR: repository::Get
+        + repository::List
+        + repository::GetBalance
+        + repository::ListBalances
+        + Clone,
+    IR: item::repository::Get + Clone,
+    LR: location::repository::Get + Clone,
+{
+    async fn list(&self, request: ListRequest) -> Result<ListResponse, Error> {
+        self.stock_movement_repository.list(&request).await
+    }
+}
+
+impl<SR, IR, LR> GetBalance for Service<SR, IR, LR>
+where
+    SR: repository::Get
+        + repository::List
+        + repository::GetBalance
+        + repository::ListBalances
+        + Clone,
+    IR: item::repository::Get + Clone,
+    LR: location::repository::Get + Clone,
+{
+    async fn get_balance(&self, request: GetBalanceRequest) -> Result<StockBalance, Error> {
+        let item = Id::try_from(request.item)?;
+        let location = Id::try_from(request.location)?;
+
+        // A balance exists for every item at every location, zero until stock moves there.
+        self.item_repository.get(&item).await?;
+        self.location_repository.get(&location).await?;
+
+        self.stock_movement_repository
+            .get_balance(&item, &location)
+            .await
+    }
+}
+
+impl<SR, IR, LR> ListBalances for Service<SR, IR, LR>
+where
+    SR: repository::Get
+        + repository::List
+        + repository::GetBalance
+        + repository::ListBalances
+        + Clone,
+    IR: item::repository::Get + Clone,
+    LR: location::repository::Get + Clone,
+{
+    async fn list_balances(&self, request: ListRequest) -> Result<ListBalancesResponse, Error> {
+        self.stock_movement_repository.list_balances(&request).await
+    }
+}
@@ -0,0 +1,676 @@
+use std::{future::Future, sync::Arc};
+
+use anyhow::{anyhow, Context};
+use num_traits::ToPrimitive;
+use sqlx::{Executor, FromRow, QueryBuilder, Sqlite, Transaction};
+
+use crate::{
+    location, page_token,
+    sqlx::{
+        list::{self, Column, Kind, OrderTerm},
+        Error as SqlxError, SqliteConnection, SqliteError,
+    },
+    traceability, Filter, Id, OrderBy, PageToken, Quantity, StockBalance, StockMovement,
+    StockMovementKind, Timestamp,
+};
+
+use super::{
+    query::{ListBalancesResponse, ListRequest, ListResponse},
+    AlreadyExistsError, Error, NotFoundError,
+};
+
+// MARK: Get
+
+/// `Get` represents a store of stock movement data.
+pub trait Get: Send + Sync + 'static {
+    /// Get a [`StockMovement`].
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::NotFound`] if a [`StockMovement`] with the given [`Id`] does not exist.
+    fn get(&self, id: &Id) -> impl Future<Output = Result<StockMovement, Error>> + Send;
+}
+
+// MARK: List
+
+/// `List` represents a store of stock movement data.
+pub trait List: Send + Sync + 'static {
+    /// List [`StockMovement`]s.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::Filter`] if the filter is invalid.
+    fn list(
+        &self,
+        request: &ListRequest,
+    ) -> impl Future<Output = Result<ListResponse, Error>> + Send;
+}
+
+// MARK: Post
+
+/// `Post` represents a store of stock movement data.
+pub trait Post: Send + Sync + 'static {
+    /// Append a [`StockMovement`] to the ledger and apply it to the [`StockBalance`]s it
+    /// touches, all or nothing.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::AlreadyExists`] if a [`StockMovement`] with the same [`Id`] already exists.
+    /// - MUST return [`Error::InsufficientStock`] if a balance would go negative at a location
+    ///   that does not allow negative stock.
+    fn post(&self, movement: &StockMovement) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+// MARK: GetBalance
+
+/// `GetBalance` represents a store of stock balance data.
+pub trait GetBalance: Send + Sync + 'static {
+    /// Get the [`StockBalance`] of an item at a location, zero if no stock moved there yet.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::Unknown`] if the balance cannot be fetched.
+    fn get_balance(
+        &self,
+        item: &Id,
+        location: &Id,
+    ) -> impl Future<Output = Result<StockBalance, Error>> + Send;
+}
+
+// MARK: ListBalances
+
+/// `ListBalances` represents a store of stock balance data.
+pub trait ListBalances: Send + Sync + 'static {
+    /// List the [`StockBalance`]s of the items and locations stock moved at.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::Filter`] if the filter is invalid.
+    fn list_balances(
+        &self,
+        request: &ListRequest,
+    ) -> impl Future<Output = Result<ListBalancesResponse, Error>> + Send;
+}
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+pub struct Service<DB: SqliteConnection> {
+    db: Arc<DB>,
+    page_token_key: page_token::Key,
+}
+
+#[derive(FromRow)]
+struct StockMovementRow {
+    id: String,
+    kind: i64,
+    item_id: String,
+    quantity: String,
+    source_location_id: Option<String>,
+    target_location_id: Option<String>,
+    lot_id: Option<String>,
+    serial_id: Option<String>,
+    production_order_id: Option<String>,
+    reason: String,
+    uid: String,
+    create_time: String,
+}
+
+impl TryFrom<StockMovementRow> for StockMovement {
+    type Error = Error;
+
+    fn try_from(value: StockMovementRow) -> Result<Self, Self::Error> {
+        let id = Id::try_from(value.id)?;
+        let kind = num_traits::FromPrimitive::from_i64(value.kind).ok_or(Error::Unknown(
+            anyhow!(format!("invalid kind {0}", value.kind)),
+        ))?;
+        let item = Id::try_from(value.item_id)?;
+        let quantity = Quantity::try_from(value.quantity)?;
+        let source_location = value.source_location_id.map(Id::try_from).transpose()?;
+        let target_location = value.target_location_id.map(Id::try_from).transpose()?;
+        let lot = value.lot_id.map(Id::try_from).transpose()?;
+        let serial = value.serial_id.map(Id::try_from).transpose()?;
+        let production_order = value.production_order_id.map(Id::try_from).transpose()?;
+        let reason = value.reason;
+        let uid = uuid::Uuid::try_parse(&value.uid).map_err(|e| Error::Unknown(anyhow!(e)))?;
+        let create_time = Timestamp::try_from(value.create_time)?;
+
+        Ok(Self::from((
+            id,
+            kind,
+            item,
+            quantity,
+            source_location,
+            target_location,
+            lot,
+            serial,
+            production_order,
+            reason,
+            uid,
+            create_time,
+        )))
+    }
+}
+
+#[derive(FromRow)]
+struct StockBalanceRow {
+    item_id: String,
+    location_id: String,
+    quantity: String,
+}
+
+impl TryFrom<StockBalanceRow> for StockBalance {
+    type Error = Error;
+
+    fn try_from(value: StockBalanceRow) -> Result<Self, Self::Error> {
+        let item = Id::try_from(value.item_id)?;
+        let location = Id::try_from(value.location_id)?;
+        let quantity = Quantity::try_from(value.quantity)?;
+
+        Ok(Self::from((item, location, quantity)))
+    }
+}
+
+// MARK: List
+
+const STOCK_MOVEMENT_COLUMNS: &[Column] = &[
+    Column::new("name", "id", Kind::Text, true),
+    Column::new("kind", "kind", Kind::State(stock_movement_kind), true),
+    Column::new("item", "item_id", Kind::Text, true),
+    Column::new("source_location", "source_location_id", Kind::Text, false),
+    Column::new("target_location", "target_location_id", Kind::Text, false),
+    Column::new("lot", "lot_id", Kind::Text, false),
+    Column::new("serial", "serial_id", Kind::Text, false),
+    Column::new("production_order", "production_order_id", Kind::Text, false),
+    Column::new("reason", "reason", Kind::Text, false),
+    Column::new("uid", "uid", Kind::Text, false),
+    Column::new("create_time", "create_time", Kind::Timestamp, true),
+];
+
+// Balances are keyed by item and location, joined into the `id` paging relies on.
+const STOCK_BALANCE_COLUMNS: &[Column] = &[
+    Column::new("name", "id", Kind::Text, false),
+    Column::new("item", "item_id", Kind::Text, true),
+    Column::new("location", "location_id", Kind::Text, true),
+];
+
+fn stock_movement_kind(name: &str) -> Option<i64> {
+    StockMovementKind::from_name(name).and_then(|k| k.to_i64())
+}
+
+fn cursor(terms: &[OrderTerm], movement: &StockMovement) -> Vec<String> {
+    terms
+        .iter()
+        .map(|(column, _)| match column.name() {
+            "kind" => movement.kind.to_i64().unwrap_or_default().to_string(),
+            "item_id" => movement.item.value().clone(),
+            "create_time" => movement.create_time.value().to_string(),
+            _ => movement.id.value().clone(),
+        })
+        .collect()
+}
+
+fn balance_cursor(terms: &[OrderTerm], balance: &StockBalance) -> Vec<String> {
+    terms
+        .iter()
+        .map(|(column, _)| match column.name() {
+            "item_id" => balance.item.value().clone(),
+            "location_id" => balance.location.value().clone(),
+            _ => format!("{}/{}", balance.item, balance.location),
+        })
+        .collect()
+}
+
+async fn fetch_balance<'e, E>(executor: E, item: &Id, location: &Id) -> Result<StockBalance, Error>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let item_id = item.value();
+    let location_id = location.value();
+
+    let query = sqlx::query_as!(
+        StockBalanceRow,
+        "SELECT item_id, location_id, quantity FROM stock_balance
+        WHERE item_id = $1 AND location_id = $2",
+        item_id,
+        location_id,
+    );
+
+    let result = query.fetch_optional(executor).await.map_err(|e| {
+        Error::from(anyhow!(e).context(format!(
+            "failed to fetch stock balance of item {item:?} at location {location:?}"
+        )))
+    })?;
+
+    result.map_or_else(
+        || Ok(StockBalance::empty(item.clone(), location.clone())),
+        TryInto::try_into,
+    )
+}
+
+impl<DB> Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    #[must_use]
+    pub const fn new(db: Arc<DB>, page_token_key: page_token::Key) -> Self {
+        Self { db, page_token_key }
+    }
+
+    async fn fetch_movement(&self, id: &Id) -> Result<StockMovement, Error> {
+        let value = id.value();
+
+        let query = sqlx::query_as!(
+            StockMovementRow,
+            "SELECT
+                id,
+                kind,
+                item_id,
+                quantity,
+                source_location_id,
+                target_location_id,
+                lot_id,
+                serial_id,
+                production_order_id,
+                reason,
+                uid,
+                create_time
+            FROM stock_movement WHERE id = $1",
+            value
+        );
+
+        let result =
+            query
+                .fetch_one(&self.db.pool())
+                .await
+                .map_err(|e| match SqlxError::from(&e) {
+                    SqlxError::RowNotFound => NotFoundError::new(id.clone()).into(),
+                    _ => Error::from(
+                        anyhow!(e)
+                            .context(format!("failed to fetch stock movement with id {id:?}")),
+                    ),
+                })?;
+
+        result.try_into()
+    }
+
+    async fn fetch_movements(&self, request: &ListRequest) -> Result<ListResponse, Error> {
+        let page_size = request.page_size();
+        let raw_filter = request.filter().as_deref().unwrap_or_default().trim();
+        let raw_order_by = request.order_by().as_deref().unwrap_or_default().trim();
+        let parameters = [raw_filter, raw_order_by];
+
+        let filter = Some(raw_filter)
+            .filter(|f| !f.is_empty())
+            .map(|f| Filter::try_from(f.to_string()))
+            .transpose()?;
+        let order_by = Some(raw_order_by)
+            .filter(|o| !o.is_empty())
+            .map(|o| OrderBy::try_from(o.to_string()))
+            .transpose()?;
+        let terms = list::resolve_order_by(STOCK_MOVEMENT_COLUMNS, order_by.as_ref())?;
+        let page_token = request
+            .page_token()
+            .as_deref()
+            .filter(|t| !t.is_empty())
+            .map(|t| PageToken::decode(t, &self.page_token_key))
+            .transpose()?;
+
+        if let Some(page_token) = &page_token {
+            page_token.verify(&parameters)?;
+        }
+
+        let mut query = QueryBuilder::<Sqlite>::new(
+            "SELECT
+                id,
+                kind,
+                item_id,
+                quantity,
+                source_location_id,
+                target_location_id,
+                lot_id,
+                serial_id,
+                production_order_id,
+                reason,
+                uid,
+                create_time
+            FROM stock_movement WHERE 1",
+        );
+
+        if let Some(filter) = &filter {
+            query.push(" AND ");
+            list::push_filter(&mut query, STOCK_MOVEMENT_COLUMNS, filter.expression())?;
+        }
+
+        if let Some(page_token) = &page_token {
+            query.push(" AND ");
+            list::push_cursor(&mut query, &terms, page_token.cursor())?;
+        }
+
+        list::push_order_by(&mut query, &terms);
+
+        // Fetch one more movement than requested to learn whether another page follows.
+        query.push(" LIMIT ");
+        query.push_bind(page_size + 1);
+
+        let result = query
+            .build_query_as::<StockMovementRow>()
+            .fetch_all(&self.db.pool())
+            .await
+            .map_err(|e| Error::from(anyhow!(e).context("failed to fetch stock movements")))?;
+
+        let mut movements = result
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<StockMovement>, Error>>()?;
+
+        let next_page_token = if movements.len() > usize::try_from(*page_size).unwrap_or_default() {
+            movements.pop();
+            movements.last().map(|movement| {
+                PageToken::new(&parameters, cursor(&terms, movement)).encode(&self.page_token_key)
+            })
+        } else {
+            None
+        };
+
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM stock_movement WHERE 1");
+
+        if let Some(filter) = &filter {
+            query.push(" AND ");
+            list::push_filter(&mut query, STOCK_MOVEMENT_COLUMNS, filter.expression())?;
+        }
+
+        let total_size: i64 = query
+            .build_query_scalar()
+            .fetch_one(&self.db.pool())
+            .await
+            .map_err(|e| Error::from(anyhow!(e).context("failed to count stock movements")))?;
+        let total_size = i32::try_from(total_size).unwrap_or(i32::MAX);
+
+        Ok(ListResponse::new(movements, next_page_token, total_size))
+    }
+
+    async fn fetch_balances(&self, request: &ListRequest) -> Result<ListBalancesResponse, Error> {
+        let page_size = request.page_size();
+        let raw_filter = request.filter().as_deref().unwrap_or_default().trim();
+        let raw_order_by = request.order_by().as_deref().unwrap_or_default().trim();
+        let parameters = [raw_filter, raw_order_by];
+
+        let filter = Some(raw_filter)
+            .filter(|f| !f.is_empty())
+            .map(|f| Filter::try_from(f.to_string()))
+            .transpose()?;
+        let order_by = Some(raw_order_by)
+            .filter(|o| !o.is_empty())
+            .map(|o| OrderBy::try_from(o.to_string()))
+            .transpose()?;
+        let terms = list::resolve_order_by(STOCK_BALANCE_COLUMNS, order_by.as_ref())?;
+        let page_token = request
+            .page_token()
+            .as_deref()
+            .filter(|t| !t.is_empty())
+            .map(|t| PageToken::decode(t, &self.page_token_key))
+            .transpose()?;
+
+        if let Some(page_token) = &page_token {
+            page_token.verify(&parameters)?;
+        }
+
+        let mut query = QueryBuilder::<Sqlite>::new(
+            "SELECT item_id, location_id, quantity FROM stock_balance WHERE 1",
+        );
+
+        if let Some(filter) = &filter {
+            query.push(" AND ");
+            list::push_filter(&mut query, STOCK_BALANCE_COLUMNS, filter.expression())?;
+        }
+
+        if let Some(page_token) = &page_token {
+            query.push(" AND ");
+            list::push_cursor(&mut query, &terms, page_token.cursor())?;
+        }
+
+        list::push_order_by(&mut query, &terms);
+
+        // Fetch one more balance than requested to learn whether another page follows.
+        query.push(" LIMIT ");
+        query.push_bind(page_size + 1);
+
+        let result = query
+            .build_query_as::<StockBalanceRow>()
+            .fetch_all(&self.db.pool())
+            .await
+            .map_err(|e| Error::from(anyhow!(e).context("failed to fetch stock balances")))?;
+
+        let mut balances = result
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<StockBalance>, Error>>()?;
+
+        let next_page_token = if balances.len() > usize::try_from(*page_size).unwrap_or_default() {
+            balances.pop();
+            balances.last().map(|balance| {
+                PageToken::new(&parameters, balance_cursor(&terms, balance))
+                    .encode(&self.page_token_key)
+            })
+        } else {
+            None
+        };
+
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM stock_balance WHERE 1");
+
+        if let Some(filter) = &filter {
+            query.push(" AND ");
+            list::push_filter(&mut query, STOCK_BALANCE_COLUMNS, filter.expression())?;
+        }
+
+        let total_size: i64 = query
+            .build_query_scalar()
+            .fetch_one(&self.db.pool())
+            .await
+            .map_err(|e| Error::from(anyhow!(e).context("failed to count stock balances")))?;
+        let total_size = i32::try_from(total_size).unwrap_or(i32::MAX);
+
+        Ok(ListBalancesResponse::new(
+            balances,
+            next_page_token,
+            total_size,
+        ))
+    }
+
+    async fn save_movement(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        movement: &StockMovement,
+    ) -> Result<(), Error> {
+        let id = &movement.id.value();
+        let kind = &movement.kind.to_i64();
+        let item_id = &movement.item.value();
+        let quantity = &movement.quantity.to_string();
+        let source_location_id = &movement.source_location.as_ref().map(Id::value);
+        let target_location_id = &movement.target_location.as_ref().map(Id::value);
+        let lot_id = &movement.lot.as_ref().map(Id::value);
+        let serial_id = &movement.serial.as_ref().map(Id::value);
+        let production_order_id = &movement.production_order.as_ref().map(Id::value);
+        let reason = &movement.reason;
+        let uid = &movement.uid.to_string();
+        let create_time = &movement.create_time.value().to_string();
+
+        let query = sqlx::query!(
+            "INSERT INTO stock_movement (
+                id,
+                kind,
+                item_id,
+                quantity,
+                source_location_id,
+                target_location_id,
+                lot_id,
+                serial_id,
+                production_order_id,
+                reason,
+                uid,
+                create_time
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+            id,
+            kind,
+            item_id,
+            quantity,
+            source_location_id,
+            target_location_id,
+            lot_id,
+            serial_id,
+            production_order_id,
+            reason,
+            uid,
+            create_time,
+        );
+
+        tx.execute(query)
+            .await
+            .map_err(|e| match SqlxError::from(&e) {
+                SqlxError::Sqlite {
+                    inner: SqliteError::UniqueConstraintViolationCode,
+                } => Error::from(AlreadyExistsError::new(movement.id.clone())),
+                _ => Error::from(anyhow!(e).context(format!(
+                    "failed to insert stock movement with id {:?}",
+                    movement.id
+                ))),
+            })?;
+
+        Ok(())
+    }
+
+    async fn save_balance(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        balance: &StockBalance,
+    ) -> Result<(), Error> {
+        let item_id = &balance.item.value();
+        let location_id = &balance.location.value();
+        let quantity = &balance.quantity.to_string();
+
+        let query = sqlx::query!(
+            "INSERT INTO stock_balance (item_id, location_id, quantity) VALUES ($1, $2, $3)
+            ON CONFLICT (item_id, location_id) DO UPDATE SET quantity = excluded.quantity",
+            item_id,
+            location_id,
+            quantity,
+        );
+
+        tx.execute(query).await.map_err(|e| {
+            Error::from(anyhow!(e).context(format!(
+                "failed to save stock balance of item {:?} at location {:?}",
+                balance.item, balance.location
+            )))
+        })?;
+
+        Ok(())
+    }
+
+    async fn allows_negative_stock(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        location: &Id,
+    ) -> Result<bool, Error> {
+        let value = location.value();
+
+        let query = sqlx::query_scalar!(
+            "SELECT allow_negative_stock FROM location WHERE id = $1",
+            value
+        );
+
+        let allow_negative_stock =
+            query
+                .fetch_one(&mut **tx)
+                .await
+                .map_err(|e| match SqlxError::from(&e) {
+                    SqlxError::RowNotFound => Error::from(location::Error::from(
+                        location::NotFoundError::new(location.clone()),
+                    )),
+                    _ => Error::from(
+                        anyhow!(e)
+                            .context(format!("failed to fetch location with id {location:?}")),
+                    ),
+                })?;
+
+        Ok(allow_negative_stock != 0)
+    }
+}
+
+impl<DB> Get for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn get(&self, id: &Id) -> Result<StockMovement, Error> {
+        let movement = self.fetch_movement(id).await?;
+        Ok(movement)
+    }
+}
+
+impl<DB> List for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn list(&self, request: &ListRequest) -> Result<ListResponse, Error> {
+        let response = self.fetch_movements(request).await?;
+        Ok(response)
+    }
+}
+
+impl<DB> GetBalance for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn get_balance(&self, item: &Id, location: &Id) -> Result<StockBalance, Error> {
+        fetch_balance(&self.db.pool(), item, location).await
+    }
+}
+
+impl<DB> ListBalances for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn list_balances(&self, request: &ListRequest) -> Result<ListBalancesResponse, Error> {
+        let response = self.fetch_balances(request).await?;
+        Ok(response)
+    }
+}
+
+impl<DB> Post for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn post(&self, movement: &StockMovement) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool()
+            .begin()
+            .await
+            .with_context(|| "failed to start SQLite transaction")?;
+
+        // Writing first takes the database write lock, so the balances read below cannot
+        // change before the transaction commits.
+        self.save_movement(&mut tx, movement).await?;
+
+        for (location, delta) in movement.deltas() {
+            let allow_negative = self.allows_negative_stock(&mut tx, location).await?;
+            let balance = fetch_balance(&mut *tx, &movement.item, location).await?;
+            let balance = balance.apply(&delta, allow_negative)?;
+            self.save_balance(&mut tx, &balance).await?;
+        }
+
+        // A serial is a single unit, so it follows the movement from location to location.
+        if let Some(serial) = &movement.serial {
+            let serial = traceability::repository::fetch_serial(&mut *tx, &movement.item, serial)
+                .await?
+                .post(movement)?;
+            traceability::repository::save_serial_location(&mut *tx, &serial).await?;
+        }
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,320 @@
+use derive_getters::Getters;
+use derive_more::derive::From;
+use uuid::Uuid;
+
+use crate::{
+    filter, id, item, order_by, page_token, timestamp, Id, Item, Lot, Serial, StockMovement,
+    StockMovementKind, ThisError, Timestamp, Traced, Tracking,
+};
+
+pub mod command;
+pub mod query;
+pub mod repository;
+
+/// The number of production orders a genealogy trace follows at most, which bounds the trace
+/// should the recorded movements ever link production orders in a cycle.
+pub const MAX_GENEALOGY_DEPTH: u32 = 100;
+
+/// Which way a genealogy trace follows the production orders linking lots and serials.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// From consumed components to the lots and serials produced from them.
+    Forward,
+    /// From produced lots and serials to the components they consumed.
+    Backward,
+}
+
+impl Lot {
+    /// Register a lot of a lot-tracked `item`.
+    pub(crate) fn new(item: &Item, id: String, description: String) -> Result<Self, Error> {
+        if *item.tracking() != Tracking::Lot {
+            return Err(TrackingMismatchError::new(item, Tracking::Lot).into());
+        }
+
+        Ok(Self {
+            item: item.id().clone(),
+            id: id.try_into()?,
+            description,
+            uid: Uuid::new_v4(),
+            create_time: Timestamp::now(),
+        })
+    }
+
+    /// The resource name of the lot.
+    #[must_use]
+    pub fn name(&self) -> String {
+        lot_name(&self.item, &self.id)
+    }
+}
+
+impl Serial {
+    /// Register a serial of a serial-tracked `item`, which is not in stock until received.
+    pub(crate) fn new(item: &Item, id: String) -> Result<Self, Error> {
+        if *item.tracking() != Tracking::Serial {
+            return Err(TrackingMismatchError::new(item, Tracking::Serial).into());
+        }
+
+        let now = Timestamp::now();
+
+        Ok(Self {
+            item: item.id().clone(),
+            id: id.try_into()?,
+            location: None,
+            uid: Uuid::new_v4(),
+            create_time: now.clone(),
+            update_time: now,
+        })
+    }
+
+    /// The resource name of the serial.
+    #[must_use]
+    pub fn name(&self) -> String {
+        serial_name(&self.item, &self.id)
+    }
+
+    /// Follow the unit to where `movement` takes it, which must start where the unit is.
+    pub(crate) fn post(self, movement: &StockMovement) -> Result<Self, Error> {
+        let (from, to) = match movement.kind() {
+            StockMovementKind::Receipt => (&None, movement.target_location()),
+            StockMovementKind::Issue => (movement.source_location(), &None),
+            StockMovementKind::Transfer => (movement.source_location(), movement.target_location()),
+            StockMovementKind::Adjustment if movement.quantity().is_positive() => {
+                (&None, movement.target_location())
+            }
+            StockMovementKind::Adjustment => (movement.target_location(), &None),
+        };
+
+        if &self.location != from {
+            return Err(SerialMisplacedError::new(&self).into());
+        }
+
+        Ok(Self {
+            location: to.clone(),
+            update_time: Timestamp::now(),
+            ..self
+        })
+    }
+}
+
+impl Traced {
+    /// The item the lot or serial belongs to.
+    #[must_use]
+    pub const fn item(&self) -> &Id {
+        match self {
+            Self::Lot { item, .. } | Self::Serial { item, .. } => item,
+        }
+    }
+
+    /// The resource name of the lot or serial.
+    #[must_use]
+    pub fn name(&self) -> String {
+        match self {
+            Self::Lot { item, lot } => lot_name(item, lot),
+            Self::Serial { item, serial } => serial_name(item, serial),
+        }
+    }
+}
+
+impl From<&Lot> for Traced {
+    fn from(value: &Lot) -> Self {
+        Self::Lot {
+            item: value.item.clone(),
+            lot: value.id.clone(),
+        }
+    }
+}
+
+impl From<&Serial> for Traced {
+    fn from(value: &Serial) -> Self {
+        Self::Serial {
+            item: value.item.clone(),
+            serial: value.id.clone(),
+        }
+    }
+}
+
+pub(crate) fn lot_name(item: &Id, lot: &Id) -> String {
+    format!("items/{item}/lots/{lot}")
+}
+
+pub(crate) fn serial_name(item: &Id, serial: &Id) -> String {
+    format!("items/{item}/serials/{serial}")
+}
+
+#[derive(Debug, ThisError)]
+pub enum Error {
+    #[error(transparent)]
+    Empty(#[from] EmptyError),
+    #[error(transparent)]
+    NotFound(#[from] NotFoundError),
+    #[error(transparent)]
+    AlreadyExists(#[from] AlreadyExistsError),
+    #[error(transparent)]
+    TrackingMismatch(#[from] TrackingMismatchError),
+    #[error(transparent)]
+    SerialMisplaced(#[from] SerialMisplacedError),
+    #[error(transparent)]
+    InvalidName(#[from] InvalidNameError),
+    #[error(transparent)]
+    Item(#[from] item::Error),
+    #[error(transparent)]
+    Id(#[from] id::Error),
+    #[error(transparent)]
+    Timestamp(#[from] timestamp::Error),
+    #[error(transparent)]
+    Filter(#[from] filter::Error),
+    #[error(transparent)]
+    OrderBy(#[from] order_by::Error),
+    #[error(transparent)]
+    PageToken(#[from] page_token::Error),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Clone, Debug, ThisError, From)]
+#[error("lot cannot be empty")]
+pub struct EmptyError;
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("{} not found", traced.name())]
+pub struct NotFoundError {
+    traced: Traced,
+}
+
+impl NotFoundError {
+    #[must_use]
+    pub const fn new(traced: Traced) -> Self {
+        Self { traced }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("{} already exists", traced.name())]
+pub struct AlreadyExistsError {
+    traced: Traced,
+}
+
+impl AlreadyExistsError {
+    #[must_use]
+    pub const fn new(traced: Traced) -> Self {
+        Self { traced }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("item {item:?} is tracked by {}, not by {}", tracking.name(), expected.name())]
+pub struct TrackingMismatchError {
+    item: Id,
+    tracking: Tracking,
+    expected: Tracking,
+}
+
+impl TrackingMismatchError {
+    #[must_use]
+    pub fn new(item: &Item, expected: Tracking) -> Self {
+        Self {
+            item: item.id().clone(),
+            tracking: *item.tracking(),
+            expected,
+        }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("serial {serial:?} of item {item:?} is {}, where the movement does not start", location
+    .as_ref()
+    .map_or_else(|| String::from("not in stock"), |l| format!("at location {l:?}")))]
+pub struct SerialMisplacedError {
+    item: Id,
+    serial: Id,
+    location: Option<Id>,
+}
+
+impl SerialMisplacedError {
+    #[must_use]
+    pub fn new(serial: &Serial) -> Self {
+        Self {
+            item: serial.item.clone(),
+            serial: serial.id.clone(),
+            location: serial.location.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("name {name:?} is invalid: expected items/{{item}}/lots/{{lot}} or items/{{item}}/serials/{{serial}}")]
+pub struct InvalidNameError {
+    name: String,
+}
+
+impl InvalidNameError {
+    #[must_use]
+    pub const fn new(name: String) -> Self {
+        Self { name }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Location, Quantity};
+
+    use super::*;
+
+    fn item(tracking: Tracking) -> anyhow::Result<Item> {
+        let item = Item::new(
+            String::from("bike"),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::from("pcs"),
+            tracking,
+        )?;
+
+        item.settle()?
+            .ok_or_else(|| anyhow::anyhow!("item was removed"))
+    }
+
+    #[test]
+    fn follows_a_serial_through_stock() -> anyhow::Result<()> {
+        let item = item(Tracking::Serial)?;
+        let serial = Serial::new(&item, String::from("sn-1"))?;
+        let traced = Traced::from(&serial);
+        let (a, b) = (
+            Location::new(String::from("a"), String::new(), false)?,
+            Location::new(String::from("b"), String::new(), false)?,
+        );
+        let one = Quantity::try_from(String::from("1"))?;
+        let movement = |kind, source, target| {
+            StockMovement::new(
+                String::from("m"),
+                kind,
+                &item,
+                one.clone(),
+                source,
+                target,
+                Some(traced.clone()),
+                String::new(),
+            )
+        };
+
+        let serial = serial.post(&movement(StockMovementKind::Receipt, None, Some(&a))?)?;
+        assert_eq!(Some(a.id()), serial.location().as_ref());
+        assert!(matches!(
+            serial
+                .clone()
+                .post(&movement(StockMovementKind::Receipt, None, Some(&b))?),
+            Err(Error::SerialMisplaced(_))
+        ));
+
+        let serial = serial.post(&movement(StockMovementKind::Transfer, Some(&a), Some(&b))?)?;
+        let serial = serial.post(&movement(StockMovementKind::Issue, Some(&b), None)?)?;
+        assert_eq!(&None, serial.location());
+
+        assert!(matches!(
+            Lot::new(&item, String::from("l-1"), String::new()),
+            Err(Error::TrackingMismatch(_))
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,168 @@
+use uuid::Uuid;
+
+pub use super::Error;
+
+use std::{future::Future, sync::Arc};
+
+use crate::{item, Id, Lot, Serial, Traced};
+
+use super::{repository, AlreadyExistsError};
+
+// MARK: CreateLot
+
+pub trait CreateLot: Send + Sync + 'static {
+    fn create_lot(
+        &self,
+        request: CreateLotRequest,
+    ) -> impl Future<Output = Result<Lot, Error>> + Send;
+}
+
+pub struct CreateLotRequest {
+    item: String,
+    id: Option<String>,
+    description: String,
+}
+
+impl CreateLotRequest {
+    #[must_use]
+    pub const fn new(item: String, id: Option<String>, description: String) -> Self {
+        Self {
+            item,
+            id,
+            description,
+        }
+    }
+}
+
+// MARK: CreateSerial
+
+pub trait CreateSerial: Send + Sync + 'static {
+    fn create_serial(
+        &self,
+        request: CreateSerialRequest,
+    ) -> impl Future<Output = Result<Serial, Error>> + Send;
+}
+
+pub struct CreateSerialRequest {
+    item: String,
+    id: Option<String>,
+}
+
+impl CreateSerialRequest {
+    #[must_use]
+    pub const fn new(item: String, id: Option<String>) -> Self {
+        Self { item, id }
+    }
+}
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+pub struct Service<
+    TR: repository::CreateLot
+        + repository::CreateSerial
+        + repository::GetLot
+        + repository::GetSerial
+        + Clone,
+    IR: item::repository::Get + Clone,
+> {
+    traceability_repository: Arc<TR>,
+    item_repository: Arc<IR>,
+}
+
+impl<TR, IR> Service<TR, IR>
+where
+    TR: repository::CreateLot
+        + repository::CreateSerial
+        + repository::GetLot
+        + repository::GetSerial
+        + Clone,
+    IR: item::repository::Get + Clone,
+{
+    #[must_use]
+    pub const fn new(traceability_repository: Arc<TR>, item_repository: Arc<IR>) -> Self {
+        Self {
+            traceability_repository,
+            item_repository,
+        }
+    }
+
+    async fn validate_create_lot_request(&self, request: CreateLotRequest) -> Result<Lot, Error> {
+        let item = Id::try_from(request.item)?;
+        let item = self.item_repository.get(&item).await?;
+
+        let id = match request.id {
+            Some(id) => {
+                let id = Id::try_from(id)?;
+                match self.traceability_repository.get_lot(item.id(), &id).await {
+                    Ok(lot) => return Err(AlreadyExistsError::new(Traced::from(&lot)).into()),
+                    Err(Error::NotFound(_)) => id.to_string(),
+                    Err(err) => return Err(err),
+                }
+            }
+            None => Uuid::new_v4().to_string(),
+        };
+
+        Lot::new(&item, id, request.description)
+    }
+
+    async fn validate_create_serial_request(
+        &self,
+        request: CreateSerialRequest,
+    ) -> Result<Serial, Error> {
+        let item = Id::try_from(request.item)?;
+        let item = self.item_repository.get(&item).await?;
+
+        let id = match request.id {
+            Some(id) => {
+                let id = Id::try_from(id)?;
+                match self
+                    .traceability_repository
+                    .get_serial(item.id(), &id)
+                    .await
+                {
+                    Ok(serial) => return Err(AlreadyExistsError::new(Traced::from(&serial)).into()),
+                    Err(Error::NotFound(_)) => id.to_string(),
+                    Err(err) => return Err(err),
+                }
+            }
+            None => Uuid::new_v4().to_string(),
+        };
+
+        Serial::new(&item, id)
+    }
+}
+
+impl<TR, IR> CreateLot for Service<TR, IR>
+where
+    TR: repository::CreateLot
+        + repository::CreateSerial
+        + repository::GetLot
+        + repository::GetSerial
+        + Clone,
+    IR: item::repository::Get + Clone,
+{
+    async fn create_lot(&self, request: CreateLotRequest) -> Result<Lot, Error> {
+        let lot = self.validate_create_lot_request(request).await?;
+        self.traceability_repository.create_lot(&lot).await?;
+
+        Ok(lot)
+    }
+}
+
+impl<TR, IR> CreateSerial for Service<TR, IR>
+where
+    TR: repository::CreateLot
+        + repository::CreateSerial
+        + repository::GetLot
+        + repository::GetSerial
+        + Clone,
+    IR: item::repository::Get + Clone,
+{
+    async fn create_serial(&self, request: CreateSerialRequest) -> Result<Serial, Error> {
+        let serial = self.validate_create_serial_request(request).await?;
+        self.traceability_repository.create_serial(&serial).await?;
+
+        Ok(serial)
+    }
+}
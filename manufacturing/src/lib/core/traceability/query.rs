@@ -0,0 +1,358 @@
+use derive_getters::{Dissolve, Getters};
+
+pub use super::Error;
+
+use std::{collections::HashSet, future::Future, sync::Arc};
+
+use crate::{item, GenealogyNode, Id, Lot, Serial, Traced, Tracking};
+
+use super::{repository, Direction, MAX_GENEALOGY_DEPTH};
+
+// MARK: GetLot
+
+pub trait GetLot: Send + Sync + 'static {
+    fn get_lot(&self, request: GetLotRequest) -> impl Future<Output = Result<Lot, Error>> + Send;
+}
+
+pub struct GetLotRequest {
+    item: String,
+    lot: String,
+}
+
+impl GetLotRequest {
+    #[must_use]
+    pub const fn new(item: String, lot: String) -> Self {
+        Self { item, lot }
+    }
+}
+
+// MARK: ListLots
+
+pub trait ListLots: Send + Sync + 'static {
+    fn list_lots(
+        &self,
+        request: ListRequest,
+    ) -> impl Future<Output = Result<ListLotsResponse, Error>> + Send;
+}
+
+/// Lists the lots or serials of the `parent` item.
+#[derive(Getters)]
+pub struct ListRequest {
+    parent: String,
+    page_size: i32,
+    page_token: Option<String>,
+    order_by: Option<String>,
+    filter: Option<String>,
+}
+
+impl ListRequest {
+    #[must_use]
+    pub fn new(
+        parent: String,
+        page_size: Option<i32>,
+        page_token: Option<String>,
+        order_by: Option<String>,
+        filter: Option<String>,
+    ) -> Self {
+        Self {
+            parent,
+            page_size: page_size.filter(|s| *s > 0).unwrap_or(50).min(1000),
+            page_token,
+            order_by,
+            filter,
+        }
+    }
+}
+
+#[derive(Dissolve)]
+pub struct ListLotsResponse {
+    lots: Vec<Lot>,
+    next_page_token: Option<String>,
+    total_size: i32,
+}
+
+impl ListLotsResponse {
+    #[must_use]
+    pub const fn new(lots: Vec<Lot>, next_page_token: Option<String>, total_size: i32) -> Self {
+        Self {
+            lots,
+            next_page_token,
+            total_size,
+        }
+    }
+}
+
+// MARK: GetSerial
+
+pub trait GetSerial: Send + Sync + 'static {
+    fn get_serial(
+        &self,
+        request: GetSerialRequest,
+    ) -> impl Future<Output = Result<Serial, Error>> + Send;
+}
+
+pub struct GetSerialRequest {
+    item: String,
+    serial: String,
+}
+
+impl GetSerialRequest {
+    #[must_use]
+    pub const fn new(item: String, serial: String) -> Self {
+        Self { item, serial }
+    }
+}
+
+// MARK: ListSerials
+
+pub trait ListSerials: Send + Sync + 'static {
+    fn list_serials(
+        &self,
+        request: ListRequest,
+    ) -> impl Future<Output = Result<ListSerialsResponse, Error>> + Send;
+}
+
+#[derive(Dissolve)]
+pub struct ListSerialsResponse {
+    serials: Vec<Serial>,
+    next_page_token: Option<String>,
+    total_size: i32,
+}
+
+impl ListSerialsResponse {
+    #[must_use]
+    pub const fn new(
+        serials: Vec<Serial>,
+        next_page_token: Option<String>,
+        total_size: i32,
+    ) -> Self {
+        Self {
+            serials,
+            next_page_token,
+            total_size,
+        }
+    }
+}
+
+// MARK: Trace
+
+pub trait Trace: Send + Sync + 'static {
+    fn trace(
+        &self,
+        request: TraceRequest,
+    ) -> impl Future<Output = Result<Vec<GenealogyNode>, Error>> + Send;
+}
+
+/// Traces the genealogy of a lot or serial of `item`.
+pub struct TraceRequest {
+    item: String,
+    tracking: Tracking,
+    id: String,
+    direction: Direction,
+}
+
+impl TraceRequest {
+    #[must_use]
+    pub const fn lot(item: String, lot: String, direction: Direction) -> Self {
+        Self {
+            item,
+            tracking: Tracking::Lot,
+            id: lot,
+            direction,
+        }
+    }
+
+    #[must_use]
+    pub const fn serial(item: String, serial: String, direction: Direction) -> Self {
+        Self {
+            item,
+            tracking: Tracking::Serial,
+            id: serial,
+            direction,
+        }
+    }
+}
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+pub struct Service<
+    TR: repository::GetLot
+        + repository::ListLots
+        + repository::GetSerial
+        + repository::ListSerials
+        + repository::Trace
+        + Clone,
+    IR: item::repository::Get + Clone,
+> {
+    traceability_repository: Arc<TR>,
+    item_repository: Arc<IR>,
+}
+
+impl<TR, IR> Service<TR, IR>
+where
+    TR: repository::GetLot
+        + repository::ListLots
+        + repository::GetSerial
+        + repository::ListSerials
+        + repository::Trace
+        + Clone,
+    IR: item::repository::Get + Clone,
+{
+    #[must_use]
+    pub const fn new(traceability_repository: Arc<TR>, item_repository: Arc<IR>) -> Self {
+        Self {
+            traceability_repository,
+            item_repository,
+        }
+    }
+
+    /// Make sure the parent item of a list exists, so listing a missing item is not mistaken
+    /// for listing an item without lots or serials.
+    async fn validate_list_request(&self, request: &ListRequest) -> Result<(), Error> {
+        let parent = Id::try_from(request.parent.clone())?;
+        self.item_repository.get(&parent).await?;
+
+        Ok(())
+    }
+
+    /// Fetch the lot or serial a trace starts from, which must exist.
+    async fn validate_trace_request(
+        &self,
+        request: TraceRequest,
+    ) -> Result<(Traced, Direction), Error> {
+        let item = Id::try_from(request.item)?;
+        let id = Id::try_from(request.id)?;
+
+        let traced = match request.tracking {
+            Tracking::Serial => {
+                Traced::from(&self.traceability_repository.get_serial(&item, &id).await?)
+            }
+            _ => Traced::from(&self.traceability_repository.get_lot(&item, &id).await?),
+        };
+
+        Ok((traced, request.direction))
+    }
+}
+
+impl<TR, IR> GetLot for Service<TR, IR>
+where
+    TR: repository::GetLot
+        + repository::ListLots
+        + repository::GetSerial
+        + repository::ListSerials
+        + repository::Trace
+        + Clone,
+    IR: item::repository::Get + Clone,
+{
+    async fn get_lot(&self, request: GetLotRequest) -> Result<Lot, Error> {
+        let item = Id::try_from(request.item)?;
+        let lot = Id::try_from(request.lot)?;
+
+        self.traceability_repository.get_lot(&item, &lot).await
+    }
+}
+
+impl<TR, IR> ListLots for Service<TR, IR>
+where
+    TR: repository::GetLot
+        + repository::ListLots
+        + repository::GetSerial
+        + repository::ListSerials
+        + repository::Trace
+        + Clone,
+    IR: item::repository::Get + Clone,
+{
+    async fn list_lots(&self, request: ListRequest) -> Result<ListLotsResponse, Error> {
+        self.validate_list_request(&request).await?;
+
+        self.traceability_repository.list_lots(&request).await
+    }
+}
+
+impl<TR, IR> GetSerial for Service<TR, IR>
+where
+    TR: repository::GetLot
+        + repository::ListLots
+        + repository::GetSerial
+        + repository::ListSerials
+        + repository::Trace
+        + Clone,
+    IR: item::repository::Get + Clone,
+{
+    async fn get_serial(&self, request: GetSerialRequest) -> Result<Serial, Error> {
+        let item = Id::try_from(request.item)?;
+        let serial = Id::try_from(request.serial)?;
+
+        self.traceability_repository
+            .get_serial(&item, &serial)
+            .await
+    }
+}
+
+impl<TR, IR> ListSerials for Service<TR, IR>
+where
+    TR: repository::GetLot
+        + repository::ListLots
+        + repository::GetSerial
+        + repository::ListSerials
+        + repository::Trace
+        + Clone,
+    IR: item::repository::Get + Clone,
+{
+    async fn list_serials(&self, request: ListRequest) -> Result<ListSerialsResponse, Error> {
+        self.validate_list_request(&request).await?;
+
+        self.traceability_repository.list_serials(&request).await
+    }
+}
+
+impl<TR, IR> Trace for Service<TR, IR>
+where
+    TR: repository::GetLot
+        + repository::ListLots
+        + repository::GetSerial
+        + repository::ListSerials
+        + repository::Trace
+        + Clone,
+    IR: item::repository::Get + Clone,
+{
+    /// Walk the genealogy breadth first, so each lot or serial is reported at the smallest
+    /// depth it is reached at.
+    async fn trace(&self, request: TraceRequest) -> Result<Vec<GenealogyNode>, Error> {
+        let (origin, direction) = self.validate_trace_request(request).await?;
+
+        let mut visited = HashSet::from([origin.clone()]);
+        let mut frontier = vec![origin];
+        let mut nodes = vec![];
+
+        for depth in 1..=MAX_GENEALOGY_DEPTH {
+            let mut next = vec![];
+
+            for traced in &frontier {
+                let links = self
+                    .traceability_repository
+                    .trace(traced, direction)
+                    .await?;
+
+                for (linked, production_order) in links {
+                    if visited.insert(linked.clone()) {
+                        nodes.push(GenealogyNode::from((
+                            linked.clone(),
+                            production_order,
+                            depth,
+                        )));
+                        next.push(linked);
+                    }
+                }
+            }
+
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+
+        Ok(nodes)
+    }
+}
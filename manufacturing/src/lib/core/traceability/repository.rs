@@ -0,0 +1,714 @@
+use std::{future::Future, sync::Arc};
+
+use anyhow::anyhow;
+use num_traits::ToPrimitive;
+use sqlx::{Executor, FromRow, QueryBuilder, Sqlite};
+
+use crate::{
+    page_token,
+    sqlx::{
+        list::{self, Column, Kind, OrderTerm},
+        Error as SqlxError, SqliteConnection, SqliteError,
+    },
+    Filter, Id, Lot, OrderBy, PageToken, Serial, StockMovementKind, Timestamp, Traced,
+};
+
+use super::{
+    query::{ListLotsResponse, ListRequest, ListSerialsResponse},
+    AlreadyExistsError, Direction, Error, NotFoundError,
+};
+
+// MARK: GetLot
+
+/// `GetLot` represents a store of lot data.
+pub trait GetLot: Send + Sync + 'static {
+    /// Get a [`Lot`] of an item.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::NotFound`] if the item has no [`Lot`] with the given [`Id`].
+    fn get_lot(&self, item: &Id, lot: &Id) -> impl Future<Output = Result<Lot, Error>> + Send;
+}
+
+// MARK: ListLots
+
+/// `ListLots` represents a store of lot data.
+pub trait ListLots: Send + Sync + 'static {
+    /// List the [`Lot`]s of an item.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::Filter`] if the filter is invalid.
+    fn list_lots(
+        &self,
+        request: &ListRequest,
+    ) -> impl Future<Output = Result<ListLotsResponse, Error>> + Send;
+}
+
+// MARK: CreateLot
+
+/// `CreateLot` represents a store of lot data.
+pub trait CreateLot: Send + Sync + 'static {
+    /// Persist a new [`Lot`].
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::AlreadyExists`] if the item already has a [`Lot`] with the same [`Id`].
+    fn create_lot(&self, lot: &Lot) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+// MARK: GetSerial
+
+/// `GetSerial` represents a store of serial data.
+pub trait GetSerial: Send + Sync + 'static {
+    /// Get a [`Serial`] of an item.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::NotFound`] if the item has no [`Serial`] with the given [`Id`].
+    fn get_serial(
+        &self,
+        item: &Id,
+        serial: &Id,
+    ) -> impl Future<Output = Result<Serial, Error>> + Send;
+}
+
+// MARK: ListSerials
+
+/// `ListSerials` represents a store of serial data.
+pub trait ListSerials: Send + Sync + 'static {
+    /// List the [`Serial`]s of an item.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::Filter`] if the filter is invalid.
+    fn list_serials(
+        &self,
+        request: &ListRequest,
+    ) -> impl Future<Output = Result<ListSerialsResponse, Error>> + Send;
+}
+
+// MARK: CreateSerial
+
+/// `CreateSerial` represents a store of serial data.
+pub trait CreateSerial: Send + Sync + 'static {
+    /// Persist a new [`Serial`].
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::AlreadyExists`] if the item already has a [`Serial`] with the same [`Id`].
+    fn create_serial(&self, serial: &Serial) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+// MARK: Trace
+
+/// `Trace` represents a store of the stock movements linking lots and serials.
+pub trait Trace: Send + Sync + 'static {
+    /// Follow the production orders `traced` was issued to or received from one step in
+    /// `direction`, returning each lot or serial reached with the production order linking it.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::Unknown`] if the movements cannot be fetched.
+    fn trace(
+        &self,
+        traced: &Traced,
+        direction: Direction,
+    ) -> impl Future<Output = Result<Vec<(Traced, Id)>, Error>> + Send;
+}
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+pub struct Service<DB: SqliteConnection> {
+    db: Arc<DB>,
+    page_token_key: page_token::Key,
+}
+
+#[derive(FromRow)]
+struct LotRow {
+    item_id: String,
+    id: String,
+    description: String,
+    uid: String,
+    create_time: String,
+}
+
+impl TryFrom<LotRow> for Lot {
+    type Error = Error;
+
+    fn try_from(value: LotRow) -> Result<Self, Self::Error> {
+        let item = Id::try_from(value.item_id)?;
+        let id = Id::try_from(value.id)?;
+        let description = value.description;
+        let uid = uuid::Uuid::try_parse(&value.uid).map_err(|e| Error::Unknown(anyhow!(e)))?;
+        let create_time = Timestamp::try_from(value.create_time)?;
+
+        Ok(Self::from((item, id, description, uid, create_time)))
+    }
+}
+
+#[derive(FromRow)]
+struct SerialRow {
+    item_id: String,
+    id: String,
+    location_id: Option<String>,
+    uid: String,
+    create_time: String,
+    update_time: String,
+}
+
+impl TryFrom<SerialRow> for Serial {
+    type Error = Error;
+
+    fn try_from(value: SerialRow) -> Result<Self, Self::Error> {
+        let item = Id::try_from(value.item_id)?;
+        let id = Id::try_from(value.id)?;
+        let location = value.location_id.map(Id::try_from).transpose()?;
+        let uid = uuid::Uuid::try_parse(&value.uid).map_err(|e| Error::Unknown(anyhow!(e)))?;
+        let create_time = Timestamp::try_from(value.create_time)?;
+        let update_time = Timestamp::try_from(value.update_time)?;
+
+        Ok(Self::from((
+            item,
+            id,
+            location,
+            uid,
+            create_time,
+            update_time,
+        )))
+    }
+}
+
+#[allow(clippy::struct_field_names)]
+struct LinkRow {
+    item_id: String,
+    lot_id: Option<String>,
+    serial_id: Option<String>,
+    production_order_id: String,
+}
+
+impl TryFrom<LinkRow> for (Traced, Id) {
+    type Error = Error;
+
+    fn try_from(value: LinkRow) -> Result<Self, Self::Error> {
+        let item = Id::try_from(value.item_id)?;
+        let traced = match (value.lot_id, value.serial_id) {
+            (Some(lot), _) => Traced::Lot {
+                item,
+                lot: lot.try_into()?,
+            },
+            (None, Some(serial)) => Traced::Serial {
+                item,
+                serial: serial.try_into()?,
+            },
+            (None, None) => return Err(anyhow!("movement of item {item:?} is not traced").into()),
+        };
+        let production_order = Id::try_from(value.production_order_id)?;
+
+        Ok((traced, production_order))
+    }
+}
+
+// MARK: List
+
+const LOT_COLUMNS: &[Column] = &[
+    Column::new("name", "id", Kind::Text, true),
+    Column::new("description", "description", Kind::Text, false),
+    Column::new("uid", "uid", Kind::Text, false),
+    Column::new("create_time", "create_time", Kind::Timestamp, true),
+];
+
+const SERIAL_COLUMNS: &[Column] = &[
+    Column::new("name", "id", Kind::Text, true),
+    Column::new("location", "location_id", Kind::Text, false),
+    Column::new("uid", "uid", Kind::Text, false),
+    Column::new("create_time", "create_time", Kind::Timestamp, true),
+    Column::new("update_time", "update_time", Kind::Timestamp, true),
+];
+
+fn lot_cursor(terms: &[OrderTerm], lot: &Lot) -> Vec<String> {
+    terms
+        .iter()
+        .map(|(column, _)| match column.name() {
+            "create_time" => lot.create_time.value().to_string(),
+            _ => lot.id.value().clone(),
+        })
+        .collect()
+}
+
+fn serial_cursor(terms: &[OrderTerm], serial: &Serial) -> Vec<String> {
+    terms
+        .iter()
+        .map(|(column, _)| match column.name() {
+            "create_time" => serial.create_time.value().to_string(),
+            "update_time" => serial.update_time.value().to_string(),
+            _ => serial.id.value().clone(),
+        })
+        .collect()
+}
+
+/// Fetch a serial, on its own or within the transaction posting a stock movement.
+pub(crate) async fn fetch_serial<'e, E>(
+    executor: E,
+    item: &Id,
+    serial: &Id,
+) -> Result<Serial, Error>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let item_id = item.value();
+    let id = serial.value();
+
+    let query = sqlx::query_as!(
+        SerialRow,
+        "SELECT item_id, id, location_id, uid, create_time, update_time FROM serial
+        WHERE item_id = $1 AND id = $2",
+        item_id,
+        id,
+    );
+
+    let result = query
+        .fetch_one(executor)
+        .await
+        .map_err(|e| match SqlxError::from(&e) {
+            SqlxError::RowNotFound => NotFoundError::new(Traced::Serial {
+                item: item.clone(),
+                serial: serial.clone(),
+            })
+            .into(),
+            _ => Error::from(anyhow!(e).context(format!(
+                "failed to fetch serial {serial:?} of item {item:?}"
+            ))),
+        })?;
+
+    result.try_into()
+}
+
+/// Record where a serial moved, within the transaction posting the stock movement.
+pub(crate) async fn save_serial_location<'e, E>(executor: E, serial: &Serial) -> Result<(), Error>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let item_id = serial.item.value();
+    let id = serial.id.value();
+    let location_id = serial.location.as_ref().map(Id::value);
+    let update_time = serial.update_time.value().to_string();
+
+    let query = sqlx::query!(
+        "UPDATE serial SET location_id = $3, update_time = $4 WHERE item_id = $1 AND id = $2",
+        item_id,
+        id,
+        location_id,
+        update_time,
+    );
+
+    query.execute(executor).await.map_err(|e| {
+        Error::from(anyhow!(e).context(format!(
+            "failed to update serial {:?} of item {:?}",
+            serial.id, serial.item
+        )))
+    })?;
+
+    Ok(())
+}
+
+impl<DB> Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    #[must_use]
+    pub const fn new(db: Arc<DB>, page_token_key: page_token::Key) -> Self {
+        Self { db, page_token_key }
+    }
+
+    async fn fetch_lot(&self, item: &Id, lot: &Id) -> Result<Lot, Error> {
+        let item_id = item.value();
+        let id = lot.value();
+
+        let query = sqlx::query_as!(
+            LotRow,
+            "SELECT item_id, id, description, uid, create_time FROM lot
+            WHERE item_id = $1 AND id = $2",
+            item_id,
+            id,
+        );
+
+        let result =
+            query
+                .fetch_one(&self.db.pool())
+                .await
+                .map_err(|e| match SqlxError::from(&e) {
+                    SqlxError::RowNotFound => NotFoundError::new(Traced::Lot {
+                        item: item.clone(),
+                        lot: lot.clone(),
+                    })
+                    .into(),
+                    _ => Error::from(
+                        anyhow!(e).context(format!("failed to fetch lot {lot:?} of item {item:?}")),
+                    ),
+                })?;
+
+        result.try_into()
+    }
+
+    async fn fetch_lots(&self, request: &ListRequest) -> Result<ListLotsResponse, Error> {
+        let page_size = request.page_size();
+        let parent = request.parent().as_str();
+        let raw_filter = request.filter().as_deref().unwrap_or_default().trim();
+        let raw_order_by = request.order_by().as_deref().unwrap_or_default().trim();
+        let parameters = [parent, raw_filter, raw_order_by];
+
+        let filter = Some(raw_filter)
+            .filter(|f| !f.is_empty())
+            .map(|f| Filter::try_from(f.to_string()))
+            .transpose()?;
+        let order_by = Some(raw_order_by)
+            .filter(|o| !o.is_empty())
+            .map(|o| OrderBy::try_from(o.to_string()))
+            .transpose()?;
+        let terms = list::resolve_order_by(LOT_COLUMNS, order_by.as_ref())?;
+        let page_token = request
+            .page_token()
+            .as_deref()
+            .filter(|t| !t.is_empty())
+            .map(|t| PageToken::decode(t, &self.page_token_key))
+            .transpose()?;
+
+        if let Some(page_token) = &page_token {
+            page_token.verify(&parameters)?;
+        }
+
+        let mut query = QueryBuilder::<Sqlite>::new(
+            "SELECT item_id, id, description, uid, create_time FROM lot WHERE item_id = ",
+        );
+        query.push_bind(parent);
+
+        if let Some(filter) = &filter {
+            query.push(" AND ");
+            list::push_filter(&mut query, LOT_COLUMNS, filter.expression())?;
+        }
+
+        if let Some(page_token) = &page_token {
+            query.push(" AND ");
+            list::push_cursor(&mut query, &terms, page_token.cursor())?;
+        }
+
+        list::push_order_by(&mut query, &terms);
+
+        // Fetch one more lot than requested to learn whether another page follows.
+        query.push(" LIMIT ");
+        query.push_bind(page_size + 1);
+
+        let result = query
+            .build_query_as::<LotRow>()
+            .fetch_all(&self.db.pool())
+            .await
+            .map_err(|e| Error::from(anyhow!(e).context("failed to fetch lots")))?;
+
+        let mut lots = result
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<Lot>, Error>>()?;
+
+        let next_page_token = if lots.len() > usize::try_from(*page_size).unwrap_or_default() {
+            lots.pop();
+            lots.last().map(|lot| {
+                PageToken::new(&parameters, lot_cursor(&terms, lot)).encode(&self.page_token_key)
+            })
+        } else {
+            None
+        };
+
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM lot WHERE item_id = ");
+        query.push_bind(parent);
+
+        if let Some(filter) = &filter {
+            query.push(" AND ");
+            list::push_filter(&mut query, LOT_COLUMNS, filter.expression())?;
+        }
+
+        let total_size: i64 = query
+            .build_query_scalar()
+            .fetch_one(&self.db.pool())
+            .await
+            .map_err(|e| Error::from(anyhow!(e).context("failed to count lots")))?;
+        let total_size = i32::try_from(total_size).unwrap_or(i32::MAX);
+
+        Ok(ListLotsResponse::new(lots, next_page_token, total_size))
+    }
+
+    async fn save_lot(&self, lot: &Lot) -> Result<(), Error> {
+        let item_id = &lot.item.value();
+        let id = &lot.id.value();
+        let description = &lot.description;
+        let uid = &lot.uid.to_string();
+        let create_time = &lot.create_time.value().to_string();
+
+        let query = sqlx::query!(
+            "INSERT INTO lot (item_id, id, description, uid, create_time)
+            VALUES ($1, $2, $3, $4, $5)",
+            item_id,
+            id,
+            description,
+            uid,
+            create_time,
+        );
+
+        query
+            .execute(&self.db.pool())
+            .await
+            .map_err(|e| match SqlxError::from(&e) {
+                SqlxError::Sqlite {
+                    inner: SqliteError::UniqueConstraintViolationCode,
+                } => Error::from(AlreadyExistsError::new(Traced::from(lot))),
+                _ => {
+                    Error::from(anyhow!(e).context(format!("failed to insert lot {}", lot.name())))
+                }
+            })?;
+
+        Ok(())
+    }
+
+    async fn fetch_serials(&self, request: &ListRequest) -> Result<ListSerialsResponse, Error> {
+        let page_size = request.page_size();
+        let parent = request.parent().as_str();
+        let raw_filter = request.filter().as_deref().unwrap_or_default().trim();
+        let raw_order_by = request.order_by().as_deref().unwrap_or_default().trim();
+        let parameters = [parent, raw_filter, raw_order_by];
+
+        let filter = Some(raw_filter)
+            .filter(|f| !f.is_empty())
+            .map(|f| Filter::try_from(f.to_string()))
+            .transpose()?;
+        let order_by = Some(raw_order_by)
+            .filter(|o| !o.is_empty())
+            .map(|o| OrderBy::try_from(o.to_string()))
+            .transpose()?;
+        let terms = list::resolve_order_by(SERIAL_COLUMNS, order_by.as_ref())?;
+        let page_token = request
+            .page_token()
+            .as_deref()
+            .filter(|t| !t.is_empty())
+            .map(|t| PageToken::decode(t, &self.page_token_key))
+            .transpose()?;
+
+        if let Some(page_token) = &page_token {
+            page_token.verify(&parameters)?;
+        }
+
+        let mut query = QueryBuilder::<Sqlite>::new(
+            "SELECT item_id, id, location_id, uid, create_time, update_time FROM serial
+            WHERE item_id = ",
+        );
+        query.push_bind(parent);
+
+        if let Some(filter) = &filter {
+            query.push(" AND ");
+            list::push_filter(&mut query, SERIAL_COLUMNS, filter.expression())?;
+        }
+
+        if let Some(page_token) = &page_token {
+            query.push(" AND ");
+            list::push_cursor(&mut query, &terms, page_token.cursor())?;
+        }
+
+        list::push_order_by(&mut query, &terms);
+
+        // Fetch one more serial than requested to learn whether another page follows.
+        query.push(" LIMIT ");
+        query.push_bind(page_size + 1);
+
+        let result = query
+            .build_query_as::<SerialRow>()
+            .fetch_all(&self.db.pool())
+            .await
+            .map_err(|e| Error::from(anyhow!(e).context("failed to fetch serials")))?;
+
+        let mut serials = result
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<Serial>, Error>>()?;
+
+        let next_page_token = if serials.len() > usize::try_from(*page_size).unwrap_or_default() {
+            serials.pop();
+            serials.last().map(|serial| {
+                PageToken::new(&parameters, serial_cursor(&terms, serial))
+                    .encode(&self.page_token_key)
+            })
+        } else {
+            None
+        };
+
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM serial WHERE item_id = ");
+        query.push_bind(parent);
+
+        if let Some(filter) = &filter {
+            query.push(" AND ");
+            list::push_filter(&mut query, SERIAL_COLUMNS, filter.expression())?;
+        }
+
+        let total_size: i64 = query
+            .build_query_scalar()
+            .fetch_one(&self.db.pool())
+            .await
+            .map_err(|e| Error::from(anyhow!(e).context("failed to count serials")))?;
+        let total_size = i32::try_from(total_size).unwrap_or(i32::MAX);
+
+        Ok(ListSerialsResponse::new(
+            serials,
+            next_page_token,
+            total_size,
+        ))
+    }
+
+    async fn save_serial(&self, serial: &Serial) -> Result<(), Error> {
+        let item_id = &serial.item.value();
+        let id = &serial.id.value();
+        let location_id = &serial.location.as_ref().map(Id::value);
+        let uid = &serial.uid.to_string();
+        let create_time = &serial.create_time.value().to_string();
+        let update_time = &serial.update_time.value().to_string();
+
+        let query = sqlx::query!(
+            "INSERT INTO serial (item_id, id, location_id, uid, create_time, update_time)
+            VALUES ($1, $2, $3, $4, $5, $6)",
+            item_id,
+            id,
+            location_id,
+            uid,
+            create_time,
+            update_time,
+        );
+
+        query
+            .execute(&self.db.pool())
+            .await
+            .map_err(|e| match SqlxError::from(&e) {
+                SqlxError::Sqlite {
+                    inner: SqliteError::UniqueConstraintViolationCode,
+                } => Error::from(AlreadyExistsError::new(Traced::from(serial))),
+                _ => Error::from(
+                    anyhow!(e).context(format!("failed to insert serial {}", serial.name())),
+                ),
+            })?;
+
+        Ok(())
+    }
+
+    async fn fetch_links(
+        &self,
+        traced: &Traced,
+        direction: Direction,
+    ) -> Result<Vec<(Traced, Id)>, Error> {
+        // Forward, the lot or serial was issued to production orders whose receipts are the
+        // next step. Backward, it was received from production orders whose issues are.
+        let (from_kind, to_kind) = match direction {
+            Direction::Forward => (StockMovementKind::Issue, StockMovementKind::Receipt),
+            Direction::Backward => (StockMovementKind::Receipt, StockMovementKind::Issue),
+        };
+        let from_kind = from_kind.to_i64();
+        let to_kind = to_kind.to_i64();
+        let (item_id, lot_id, serial_id) = match traced {
+            Traced::Lot { item, lot } => (item.value(), Some(lot.value()), None),
+            Traced::Serial { item, serial } => (item.value(), None, Some(serial.value())),
+        };
+
+        let query = sqlx::query_as!(
+            LinkRow,
+            r#"SELECT DISTINCT
+                linked.item_id AS "item_id!",
+                linked.lot_id,
+                linked.serial_id,
+                linked.production_order_id AS "production_order_id!"
+            FROM stock_movement AS origin
+            JOIN stock_movement AS linked
+                ON linked.production_order_id = origin.production_order_id
+            WHERE origin.kind = $1
+                AND origin.item_id = $2
+                AND origin.lot_id IS $3
+                AND origin.serial_id IS $4
+                AND linked.kind = $5
+                AND (linked.lot_id IS NOT NULL OR linked.serial_id IS NOT NULL)
+            ORDER BY linked.production_order_id, linked.item_id, linked.lot_id, linked.serial_id"#,
+            from_kind,
+            item_id,
+            lot_id,
+            serial_id,
+            to_kind,
+        );
+
+        let result = query.fetch_all(&self.db.pool()).await.map_err(|e| {
+            Error::from(anyhow!(e).context(format!("failed to trace {}", traced.name())))
+        })?;
+
+        result.into_iter().map(TryInto::try_into).collect()
+    }
+}
+
+impl<DB> GetLot for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn get_lot(&self, item: &Id, lot: &Id) -> Result<Lot, Error> {
+        self.fetch_lot(item, lot).await
+    }
+}
+
+impl<DB> ListLots for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn list_lots(&self, request: &ListRequest) -> Result<ListLotsResponse, Error> {
+        self.fetch_lots(request).await
+    }
+}
+
+impl<DB> CreateLot for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn create_lot(&self, lot: &Lot) -> Result<(), Error> {
+        self.save_lot(lot).await
+    }
+}
+
+impl<DB> GetSerial for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn get_serial(&self, item: &Id, serial: &Id) -> Result<Serial, Error> {
+        fetch_serial(&self.db.pool(), item, serial).await
+    }
+}
+
+impl<DB> ListSerials for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn list_serials(&self, request: &ListRequest) -> Result<ListSerialsResponse, Error> {
+        self.fetch_serials(request).await
+    }
+}
+
+impl<DB> CreateSerial for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn create_serial(&self, serial: &Serial) -> Result<(), Error> {
+        self.save_serial(serial).await
+    }
+}
+
+impl<DB> Trace for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn trace(
+        &self,
+        traced: &Traced,
+        direction: Direction,
+    ) -> Result<Vec<(Traced, Id)>, Error> {
+        self.fetch_links(traced, direction).await
+    }
+}
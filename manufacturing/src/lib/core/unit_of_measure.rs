@@ -0,0 +1,171 @@
+use derive_getters::Getters;
+
+use crate::{
+    filter, id, order_by, page_token, quantity, Dimension, Id, Quantity, ThisError, UnitOfMeasure,
+};
+
+pub mod query;
+pub mod repository;
+
+/// The unit of measure of items created without one.
+pub const DEFAULT_UNIT_OF_MEASURE: &str = "pcs";
+
+impl Dimension {
+    /// Look up a dimension by its API name, e.g. `MASS`.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "COUNT" => Some(Self::Count),
+            "MASS" => Some(Self::Mass),
+            "LENGTH" => Some(Self::Length),
+            "AREA" => Some(Self::Area),
+            "VOLUME" => Some(Self::Volume),
+            "TIME" => Some(Self::Time),
+            _ => None,
+        }
+    }
+
+    /// The API name of the dimension, e.g. `MASS`.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Count => "COUNT",
+            Self::Mass => "MASS",
+            Self::Length => "LENGTH",
+            Self::Area => "AREA",
+            Self::Volume => "VOLUME",
+            Self::Time => "TIME",
+        }
+    }
+}
+
+impl UnitOfMeasure {
+    /// The resource name of the unit of measure.
+    #[must_use]
+    pub fn name(&self) -> String {
+        unit_of_measure_name(&self.id)
+    }
+
+    /// Convert `quantity` of this unit into the unit `to`, without rounding.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::IncompatibleDimensions`] if the units measure different dimensions.
+    pub fn convert(&self, quantity: &Quantity, to: &Self) -> Result<Quantity, Error> {
+        if self.dimension != to.dimension {
+            return Err(IncompatibleDimensionsError::new(self, to).into());
+        }
+
+        if self.id == to.id {
+            return Ok(quantity.clone());
+        }
+
+        Ok(quantity
+            .checked_mul(&self.factor)?
+            .checked_div(&to.factor)?)
+    }
+}
+
+pub(crate) fn unit_of_measure_name(id: &Id) -> String {
+    format!("unitsOfMeasure/{id}")
+}
+
+#[derive(Debug, ThisError)]
+pub enum Error {
+    #[error(transparent)]
+    NotFound(#[from] NotFoundError),
+    #[error(transparent)]
+    IncompatibleDimensions(#[from] IncompatibleDimensionsError),
+    #[error(transparent)]
+    Quantity(#[from] quantity::Error),
+    #[error(transparent)]
+    Id(#[from] id::Error),
+    #[error(transparent)]
+    Filter(#[from] filter::Error),
+    #[error(transparent)]
+    OrderBy(#[from] order_by::Error),
+    #[error(transparent)]
+    PageToken(#[from] page_token::Error),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("unit of measure {id:?} not found")]
+pub struct NotFoundError {
+    id: Id,
+}
+
+impl NotFoundError {
+    #[must_use]
+    pub const fn new(id: Id) -> Self {
+        Self { id }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("{from:?} measures {} and cannot be converted into {to:?}, which measures {}", from_dimension.name(), to_dimension.name())]
+pub struct IncompatibleDimensionsError {
+    from: Id,
+    from_dimension: Dimension,
+    to: Id,
+    to_dimension: Dimension,
+}
+
+impl IncompatibleDimensionsError {
+    #[must_use]
+    pub fn new(from: &UnitOfMeasure, to: &UnitOfMeasure) -> Self {
+        Self {
+            from: from.id.clone(),
+            from_dimension: from.dimension,
+            to: to.id.clone(),
+            to_dimension: to.dimension,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(id: &str, dimension: Dimension, factor: &str) -> anyhow::Result<UnitOfMeasure> {
+        Ok(UnitOfMeasure::from((
+            Id::try_from(id.to_string())?,
+            String::new(),
+            dimension,
+            Quantity::try_from(factor.to_string())?,
+        )))
+    }
+
+    #[test]
+    fn converts_within_a_dimension() -> anyhow::Result<()> {
+        let kilogram = unit("kg", Dimension::Mass, "1")?;
+        let gram = unit("g", Dimension::Mass, "0.001")?;
+        let pound = unit("lb", Dimension::Mass, "0.45359237")?;
+
+        let quantity = Quantity::try_from(String::from("2.5"))?;
+        assert_eq!("2500", kilogram.convert(&quantity, &gram)?.to_string());
+        assert_eq!("0.0025", gram.convert(&quantity, &kilogram)?.to_string());
+        assert_eq!(
+            "0.45359237",
+            pound
+                .convert(&Quantity::try_from(String::from("1"))?, &kilogram)?
+                .to_string()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_incompatible_dimensions() -> anyhow::Result<()> {
+        let kilogram = unit("kg", Dimension::Mass, "1")?;
+        let meter = unit("m", Dimension::Length, "1")?;
+
+        assert!(matches!(
+            kilogram.convert(&Quantity::zero(), &meter),
+            Err(Error::IncompatibleDimensions(_))
+        ));
+
+        Ok(())
+    }
+}
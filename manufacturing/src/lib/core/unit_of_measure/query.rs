@@ -0,0 +1,197 @@
+use derive_getters::{Dissolve, Getters};
+
+pub use super::Error;
+
+use std::{future::Future, sync::Arc};
+
+use crate::{Id, Quantity, UnitOfMeasure};
+
+use super::repository;
+
+// MARK: Get
+
+pub trait Get: Send + Sync + 'static {
+    fn get(&self, request: GetRequest)
+        -> impl Future<Output = Result<UnitOfMeasure, Error>> + Send;
+}
+
+pub struct GetRequest {
+    id: String,
+}
+
+impl GetRequest {
+    #[must_use]
+    pub const fn new(id: String) -> Self {
+        Self { id }
+    }
+}
+
+// MARK: List
+
+pub trait List: Send + Sync + 'static {
+    fn list(
+        &self,
+        request: ListRequest,
+    ) -> impl Future<Output = Result<ListResponse, Error>> + Send;
+}
+
+#[derive(Getters)]
+pub struct ListRequest {
+    page_size: i32,
+    page_token: Option<String>,
+    order_by: Option<String>,
+    filter: Option<String>,
+}
+
+impl ListRequest {
+    #[must_use]
+    pub fn new(
+        page_size: Option<i32>,
+        page_token: Option<String>,
+        order_by: Option<String>,
+        filter: Option<String>,
+    ) -> Self {
+        Self {
+            page_size: page_size.filter(|s| *s > 0).unwrap_or(50).min(1000),
+            page_token,
+            order_by,
+            filter,
+        }
+    }
+}
+
+#[derive(Dissolve)]
+pub struct ListResponse {
+    units_of_measure: Vec<UnitOfMeasure>,
+    next_page_token: Option<String>,
+    total_size: i32,
+}
+
+impl ListResponse {
+    #[must_use]
+    pub const fn new(
+        units_of_measure: Vec<UnitOfMeasure>,
+        next_page_token: Option<String>,
+        total_size: i32,
+    ) -> Self {
+        Self {
+            units_of_measure,
+            next_page_token,
+            total_size,
+        }
+    }
+}
+
+// MARK: Convert
+
+pub trait Convert: Send + Sync + 'static {
+    fn convert(
+        &self,
+        request: ConvertRequest,
+    ) -> impl Future<Output = Result<ConvertResponse, Error>> + Send;
+}
+
+pub struct ConvertRequest {
+    quantity: String,
+    unit_of_measure: String,
+    target_unit_of_measure: String,
+}
+
+impl ConvertRequest {
+    #[must_use]
+    pub const fn new(
+        quantity: String,
+        unit_of_measure: String,
+        target_unit_of_measure: String,
+    ) -> Self {
+        Self {
+            quantity,
+            unit_of_measure,
+            target_unit_of_measure,
+        }
+    }
+}
+
+#[derive(Dissolve)]
+pub struct ConvertResponse {
+    quantity: Quantity,
+    unit_of_measure: UnitOfMeasure,
+}
+
+impl ConvertResponse {
+    #[must_use]
+    pub const fn new(quantity: Quantity, unit_of_measure: UnitOfMeasure) -> Self {
+        Self {
+            quantity,
+            unit_of_measure,
+        }
+    }
+}
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+pub struct Service<UR: repository::Get + repository::List + Clone> {
+    unit_of_measure_repository: Arc<UR>,
+}
+
+impl<UR> Service<UR>
+where
+    UR: repository::Get + repository::List + Clone,
+{
+    #[must_use]
+    pub const fn new(unit_of_measure_repository: Arc<UR>) -> Self {
+        Self {
+            unit_of_measure_repository,
+        }
+    }
+}
+
+fn validate_get_request(request: GetRequest) -> Result<Id, Error> {
+    let id: Id = request.id.try_into()?;
+
+    Ok(id)
+}
+
+fn validate_convert_request(request: ConvertRequest) -> Result<(Quantity, Id, Id), Error> {
+    let quantity = Quantity::try_from(request.quantity)?;
+    let from: Id = request.unit_of_measure.try_into()?;
+    let to: Id = request.target_unit_of_measure.try_into()?;
+
+    Ok((quantity, from, to))
+}
+
+impl<UR> Get for Service<UR>
+where
+    UR: repository::Get + repository::List + Clone,
+{
+    async fn get(&self, request: GetRequest) -> Result<UnitOfMeasure, Error> {
+        let id = validate_get_request(request)?;
+
+        self.unit_of_measure_repository.get(&id).await
+    }
+}
+
+impl<UR> List for Service<UR>
+where
+    UR: repository::Get + repository::List + Clone,
+{
+    async fn list(&self, request: ListRequest) -> Result<ListResponse, Error> {
+        self.unit_of_measure_repository.list(&request).await
+    }
+}
+
+impl<UR> Convert for Service<UR>
+where
+    UR: repository::Get + repository::List + Clone,
+{
+    async fn convert(&self, request: ConvertRequest) -> Result<ConvertResponse, Error> {
+        let (quantity, from, to) = validate_convert_request(request)?;
+
+        let from = self.unit_of_measure_repository.get(&from).await?;
+        let to = self.unit_of_measure_repository.get(&to).await?;
+        let quantity = from.convert(&quantity, &to)?;
+
+        Ok(ConvertResponse::new(quantity, to))
+    }
+}
@@ -0,0 +1,247 @@
+use std::{future::Future, sync::Arc};
+
+use anyhow::anyhow;
+use num_traits::ToPrimitive;
+use sqlx::{FromRow, QueryBuilder, Sqlite};
+
+use crate::{
+    page_token,
+    sqlx::{
+        list::{self, Column, Kind, OrderTerm},
+        Error as SqlxError, SqliteConnection,
+    },
+    Dimension, Filter, Id, OrderBy, PageToken, Quantity, UnitOfMeasure,
+};
+
+use super::{
+    query::{ListRequest, ListResponse},
+    Error, NotFoundError,
+};
+
+// MARK: Get
+
+/// `Get` represents a store of unit of measure data.
+pub trait Get: Send + Sync + 'static {
+    /// Get a [`UnitOfMeasure`].
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::NotFound`] if a [`UnitOfMeasure`] with the given [`Id`] does not exist.
+    fn get(&self, id: &Id) -> impl Future<Output = Result<UnitOfMeasure, Error>> + Send;
+}
+
+// MARK: List
+
+/// `List` represents a store of unit of measure data.
+pub trait List: Send + Sync + 'static {
+    /// List [`UnitOfMeasure`]s.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::Filter`] if the filter is invalid.
+    fn list(
+        &self,
+        request: &ListRequest,
+    ) -> impl Future<Output = Result<ListResponse, Error>> + Send;
+}
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+pub struct Service<DB: SqliteConnection> {
+    db: Arc<DB>,
+    page_token_key: page_token::Key,
+}
+
+#[derive(FromRow)]
+struct UnitOfMeasureRow {
+    id: String,
+    display_name: String,
+    dimension: i64,
+    factor: String,
+}
+
+impl TryFrom<UnitOfMeasureRow> for UnitOfMeasure {
+    type Error = Error;
+
+    fn try_from(value: UnitOfMeasureRow) -> Result<Self, Self::Error> {
+        let id = Id::try_from(value.id)?;
+        let display_name = value.display_name;
+        let dimension = num_traits::FromPrimitive::from_i64(value.dimension).ok_or(
+            Error::Unknown(anyhow!(format!("invalid dimension {0}", value.dimension))),
+        )?;
+        let factor = Quantity::try_from(value.factor)?;
+
+        Ok(Self::from((id, display_name, dimension, factor)))
+    }
+}
+
+// MARK: List
+
+const UNIT_OF_MEASURE_COLUMNS: &[Column] = &[
+    Column::new("name", "id", Kind::Text, true),
+    Column::new("display_name", "display_name", Kind::Text, true),
+    Column::new("dimension", "dimension", Kind::State(dimension), true),
+];
+
+fn dimension(name: &str) -> Option<i64> {
+    Dimension::from_name(name).and_then(|d| d.to_i64())
+}
+
+fn cursor(terms: &[OrderTerm], unit_of_measure: &UnitOfMeasure) -> Vec<String> {
+    terms
+        .iter()
+        .map(|(column, _)| match column.name() {
+            "display_name" => unit_of_measure.display_name.clone(),
+            "dimension" => unit_of_measure
+                .dimension
+                .to_i64()
+                .unwrap_or_default()
+                .to_string(),
+            _ => unit_of_measure.id.value().clone(),
+        })
+        .collect()
+}
+
+impl<DB> Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    #[must_use]
+    pub const fn new(db: Arc<DB>, page_token_key: page_token::Key) -> Self {
+        Self { db, page_token_key }
+    }
+
+    async fn fetch_unit_of_measure(&self, id: &Id) -> Result<UnitOfMeasure, Error> {
+        let value = id.value();
+
+        let query = sqlx::query_as!(
+            UnitOfMeasureRow,
+            "SELECT id, display_name, dimension, factor FROM unit_of_measure WHERE id = $1",
+            value
+        );
+
+        let result =
+            query
+                .fetch_one(&self.db.pool())
+                .await
+                .map_err(|e| match SqlxError::from(&e) {
+                    SqlxError::RowNotFound => NotFoundError::new(id.clone()).into(),
+                    _ => Error::from(
+                        anyhow!(e)
+                            .context(format!("failed to fetch unit of measure with id {id:?}")),
+                    ),
+                })?;
+
+        result.try_into()
+    }
+
+    async fn fetch_units_of_measure(&self, request: &ListRequest) -> Result<ListResponse, Error> {
+        let page_size = request.page_size();
+        let raw_filter = request.filter().as_deref().unwrap_or_default().trim();
+        let raw_order_by = request.order_by().as_deref().unwrap_or_default().trim();
+        let parameters = [raw_filter, raw_order_by];
+
+        let filter = Some(raw_filter)
+            .filter(|f| !f.is_empty())
+            .map(|f| Filter::try_from(f.to_string()))
+            .transpose()?;
+        let order_by = Some(raw_order_by)
+            .filter(|o| !o.is_empty())
+            .map(|o| OrderBy::try_from(o.to_string()))
+            .transpose()?;
+        let terms = list::resolve_order_by(UNIT_OF_MEASURE_COLUMNS, order_by.as_ref())?;
+        let page_token = request
+            .page_token()
+            .as_deref()
+            .filter(|t| !t.is_empty())
+            .map(|t| PageToken::decode(t, &self.page_token_key))
+            .transpose()?;
+
+        if let Some(page_token) = &page_token {
+            page_token.verify(&parameters)?;
+        }
+
+        let mut query = QueryBuilder::<Sqlite>::new(
+            "SELECT id, display_name, dimension, factor FROM unit_of_measure WHERE 1",
+        );
+
+        if let Some(filter) = &filter {
+            query.push(" AND ");
+            list::push_filter(&mut query, UNIT_OF_MEASURE_COLUMNS, filter.expression())?;
+        }
+
+        if let Some(page_token) = &page_token {
+            query.push(" AND ");
+            list::push_cursor(&mut query, &terms, page_token.cursor())?;
+        }
+
+        list::push_order_by(&mut query, &terms);
+
+        // Fetch one more unit than requested to learn whether another page follows.
+        query.push(" LIMIT ");
+        query.push_bind(page_size + 1);
+
+        let result = query
+            .build_query_as::<UnitOfMeasureRow>()
+            .fetch_all(&self.db.pool())
+            .await
+            .map_err(|e| Error::from(anyhow!(e).context("failed to fetch units of measure")))?;
+
+        let mut units_of_measure = result
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<UnitOfMeasure>, Error>>()?;
+
+        let next_page_token =
+            if units_of_measure.len() > usize::try_from(*page_size).unwrap_or_default() {
+                units_of_measure.pop();
+                units_of_measure.last().map(|unit_of_measure| {
+                    PageToken::new(&parameters, cursor(&terms, unit_of_measure))
+                        .encode(&self.page_token_key)
+                })
+            } else {
+                None
+            };
+
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM unit_of_measure WHERE 1");
+
+        if let Some(filter) = &filter {
+            query.push(" AND ");
+            list::push_filter(&mut query, UNIT_OF_MEASURE_COLUMNS, filter.expression())?;
+        }
+
+        let total_size: i64 = query
+            .build_query_scalar()
+            .fetch_one(&self.db.pool())
+            .await
+            .map_err(|e| Error::from(anyhow!(e).context("failed to count units of measure")))?;
+        let total_size = i32::try_from(total_size).unwrap_or(i32::MAX);
+
+        Ok(ListResponse::new(
+            units_of_measure,
+            next_page_token,
+            total_size,
+        ))
+    }
+}
+
+impl<DB> Get for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn get(&self, id: &Id) -> Result<UnitOfMeasure, Error> {
+        let unit_of_measure = self.fetch_unit_of_measure(id).await?;
+        Ok(unit_of_measure)
+    }
+}
+
+impl<DB> List for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn list(&self, request: &ListRequest) -> Result<ListResponse, Error> {
+        let response = self.fetch_units_of_measure(request).await?;
+        Ok(response)
+    }
+}
@@ -0,0 +1,444 @@
+//! An HTTP/JSON gateway to the gRPC services, transcoding requests by the `google.api.http`
+//! annotations of the methods and calling the services in process.
+
+use std::convert::Infallible;
+use std::fmt::Display;
+
+use axum::body::Body;
+use derive_getters::Getters;
+use http::{
+    header::{AUTHORIZATION, CONTENT_TYPE, TE},
+    HeaderMap, HeaderName, HeaderValue, Request, Response,
+};
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited};
+use prost::bytes::Bytes;
+use serde_json::{Map, Value};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{body::BoxBody, codegen::Service, service::Routes, Code, Status};
+
+use crate::ThisError;
+
+use descriptor::{Binding, Method, Pool};
+use template::Variables;
+
+mod descriptor;
+mod json;
+mod status;
+mod template;
+
+const APPLICATION_JSON: HeaderValue = HeaderValue::from_static("application/json");
+
+const APPLICATION_NDJSON: HeaderValue = HeaderValue::from_static("application/x-ndjson");
+
+const APPLICATION_GRPC: HeaderValue = HeaderValue::from_static("application/grpc");
+
+/// The prefix of the HTTP headers forwarded to the services as gRPC metadata, without it.
+const METADATA_PREFIX: &str = "grpc-metadata-";
+
+/// The number of messages of a server-streaming method buffered for a slow HTTP client.
+const STREAM_BUFFER_SIZE: usize = 16;
+
+/// The largest request body read, like the default body limit of axum, so that one request
+/// cannot exhaust the memory of the server.
+const MAX_BODY_SIZE: usize = 2 * 1024 * 1024;
+
+#[derive(Clone, Debug)]
+pub struct Gateway<S = Routes> {
+    pool: Pool,
+    routes: S,
+}
+
+impl<S> Gateway<S>
+where
+    S: Service<Request<BoxBody>, Response = Response<BoxBody>> + Clone + Send + Sync,
+    S::Future: Send,
+    S::Error: Display,
+{
+    /// A gateway to the services of `routes`, bound to HTTP by the annotations in the
+    /// encoded file descriptor set `descriptor_set`. The routes may be layered, e.g. to
+    /// authenticate the calls of the gateway like any other.
+    ///
+    /// # Errors
+    /// If the descriptor set does not decode or holds an invalid path template.
+    pub fn new(descriptor_set: &[u8], routes: S) -> Result<Self, Error> {
+        Ok(Self {
+            pool: Pool::decode(descriptor_set)?,
+            routes,
+        })
+    }
+
+    /// Transcode `request` into a call of the method bound to its path, and the response of
+    /// the call back into JSON. Errors are returned as AIP-193 error bodies.
+    pub async fn handle(&self, request: Request<Body>) -> Response<Body> {
+        match self.call(request).await {
+            Ok(response) => response,
+            Err(status) => status::response(&self.pool, &status),
+        }
+    }
+
+    async fn call(&self, request: Request<Body>) -> Result<Response<Body>, Status> {
+        let (parts, body) = request.into_parts();
+        let (method, binding, variables) = self
+            .pool
+            .route(&parts.method, parts.uri.path())
+            .ok_or_else(|| {
+                Status::not_found(format!(
+                    "no method is bound to {} {}",
+                    parts.method,
+                    parts.uri.path()
+                ))
+            })?;
+
+        let body = Limited::new(body, MAX_BODY_SIZE)
+            .collect()
+            .await
+            .map_err(|err| {
+                if err.is::<LengthLimitError>() {
+                    Status::invalid_argument(format!("body is larger than {MAX_BODY_SIZE} bytes"))
+                } else {
+                    Status::invalid_argument(format!("failed to read body: {err}"))
+                }
+            })?
+            .to_bytes();
+        let message = self.request_message(method, binding, &body, variables, parts.uri.query())?;
+        let message = json::from_json(&self.pool, &method.input, &message)?;
+
+        let request = grpc_request(method, &parts.headers, &message)?;
+        let response = self
+            .routes
+            .clone()
+            .call(request)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        let (parts, body) = response.into_parts();
+        if let Some(status) = Status::from_header_map(&parts.headers) {
+            if status.code() != Code::Ok {
+                return Err(status);
+            }
+        }
+
+        if method.server_streaming {
+            Ok(self.stream(method, body))
+        } else {
+            self.unary(method, binding, body).await
+        }
+    }
+
+    /// The JSON of the request message, from the body, the path variables and the query.
+    fn request_message(
+        &self,
+        method: &Method,
+        binding: &Binding,
+        body: &[u8],
+        variables: Variables,
+        query: Option<&str>,
+    ) -> Result<Value, Error> {
+        let mut message = Map::new();
+        let body = if body.is_empty() {
+            None
+        } else {
+            Some(serde_json::from_slice::<Value>(body)?)
+        };
+
+        match (binding.body.as_str(), body) {
+            ("*", Some(Value::Object(body))) => message = body,
+            ("*", Some(_)) => {
+                return Err(InvalidJsonError::new(
+                    String::new(),
+                    String::from("expected an object"),
+                )
+                .into())
+            }
+            ("", _) | (_, None) => {}
+            (field, Some(body)) => {
+                let field_path: Vec<String> = field.split('.').map(String::from).collect();
+                json::set_field(
+                    &self.pool,
+                    &method.input,
+                    &mut message,
+                    &field_path,
+                    body,
+                    false,
+                )?;
+            }
+        }
+
+        for (field_path, value) in variables {
+            json::set_field(
+                &self.pool,
+                &method.input,
+                &mut message,
+                &field_path,
+                Value::String(value),
+                false,
+            )?;
+        }
+
+        // With the whole request in the body, there are no fields left for the query.
+        if binding.body != "*" {
+            for (key, value) in query.into_iter().flat_map(query_pairs) {
+                let (key, value) = (key?, value?);
+                let field_path: Vec<String> = key.split('.').map(String::from).collect();
+                json::set_field(
+                    &self.pool,
+                    &method.input,
+                    &mut message,
+                    &field_path,
+                    Value::String(value),
+                    true,
+                )?;
+            }
+        }
+
+        Ok(Value::Object(message))
+    }
+
+    async fn unary(
+        &self,
+        method: &Method,
+        binding: &Binding,
+        mut body: BoxBody,
+    ) -> Result<Response<Body>, Status> {
+        let mut buffer = Vec::new();
+        while let Some(frame) = body.frame().await {
+            let frame = frame?;
+            if let Some(data) = frame.data_ref() {
+                buffer.extend_from_slice(data);
+            } else if let Some(status) = frame.trailers_ref().and_then(Status::from_header_map) {
+                if status.code() != Code::Ok {
+                    return Err(status);
+                }
+            }
+        }
+
+        let message = next_message(&mut buffer)
+            .ok_or_else(|| Status::internal(format!("{} returned no message", method.path)))?;
+        let mut value = json::to_json(&self.pool, &method.output, &message)?;
+        if !binding.response_body.is_empty() {
+            let key = json::json_name(&self.pool, &method.output, &binding.response_body)?;
+            value = value.get_mut(&key).map(Value::take).unwrap_or_default();
+        }
+
+        let mut response = Response::new(Body::from(value.to_string()));
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, APPLICATION_JSON);
+
+        Ok(response)
+    }
+
+    /// Stream the messages of a server-streaming method as newline-delimited
+    /// `{"result": ...}` objects, ending in an `{"error": ...}` object if the call fails.
+    fn stream(&self, method: &Method, mut body: BoxBody) -> Response<Body> {
+        let (sender, receiver) = mpsc::channel::<Result<Bytes, Infallible>>(STREAM_BUFFER_SIZE);
+        let pool = self.pool.clone();
+        let output = method.output.clone();
+
+        tokio::spawn(async move {
+            let line = |value: Value| Ok(Bytes::from(format!("{value}\n")));
+            let mut buffer = Vec::new();
+            while let Some(frame) = body.frame().await {
+                let status = match frame {
+                    Ok(frame) => match frame.into_data() {
+                        Ok(data) => {
+                            buffer.extend_from_slice(&data);
+                            None
+                        }
+                        Err(frame) => frame
+                            .trailers_ref()
+                            .and_then(Status::from_header_map)
+                            .filter(|status| status.code() != Code::Ok),
+                    },
+                    Err(status) => Some(status),
+                };
+
+                while let Some(message) = next_message(&mut buffer) {
+                    let value = match json::to_json(&pool, &output, &message) {
+                        Ok(result) => serde_json::json!({ "result": result }),
+                        Err(err) => status::error(&pool, &err.into()),
+                    };
+                    if sender.send(line(value)).await.is_err() {
+                        return;
+                    }
+                }
+                if let Some(status) = status {
+                    let _ = sender.send(line(status::error(&pool, &status))).await;
+                    return;
+                }
+            }
+        });
+
+        let mut response = Response::new(Body::from_stream(ReceiverStream::new(receiver)));
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, APPLICATION_NDJSON);
+
+        response
+    }
+}
+
+/// A gRPC request calling `method` with the encoded `message`, carrying the credentials and
+/// the `Grpc-Metadata-*` headers of the HTTP request.
+fn grpc_request(
+    method: &Method,
+    headers: &HeaderMap,
+    message: &[u8],
+) -> Result<Request<BoxBody>, Error> {
+    let len = u32::try_from(message.len()).map_err(|_| MessageTooLargeError::new(message.len()))?;
+    let mut frame = Vec::with_capacity(message.len() + 5);
+    frame.push(0);
+    frame.extend(len.to_be_bytes());
+    frame.extend_from_slice(message);
+
+    let mut request = Request::new(tonic::body::boxed(Full::new(Bytes::from(frame))));
+    *request.method_mut() = http::Method::POST;
+    *request.uri_mut() = method.path.clone();
+
+    let grpc_headers = request.headers_mut();
+    grpc_headers.insert(CONTENT_TYPE, APPLICATION_GRPC);
+    grpc_headers.insert(TE, HeaderValue::from_static("trailers"));
+    for (name, value) in headers {
+        if name == AUTHORIZATION {
+            grpc_headers.insert(name, value.clone());
+        } else if let Some(name) = name.as_str().strip_prefix(METADATA_PREFIX) {
+            if let Ok(name) = HeaderName::try_from(name) {
+                grpc_headers.append(name, value.clone());
+            }
+        }
+    }
+
+    Ok(request)
+}
+
+/// Take the next complete length-prefixed message off `buffer`.
+fn next_message(buffer: &mut Vec<u8>) -> Option<Vec<u8>> {
+    let header: [u8; 5] = buffer.get(..5)?.try_into().ok()?;
+    let len = usize::try_from(u32::from_be_bytes([
+        header[1], header[2], header[3], header[4],
+    ]))
+    .ok()?;
+    let message = buffer.get(5..5 + len)?.to_vec();
+    buffer.drain(..5 + len);
+
+    Some(message)
+}
+
+/// The percent-decoded `key=value` pairs of `query`.
+fn query_pairs(
+    query: &str,
+) -> impl Iterator<Item = (Result<String, Error>, Result<String, Error>)> + '_ {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let decode = |raw: &str| {
+                template::percent_decode(&raw.replace('+', " ")).ok_or_else(|| {
+                    InvalidJsonError::new(key.to_string(), String::from("invalid percent-encoding"))
+                        .into()
+                })
+            };
+
+            (decode(key), decode(value))
+        })
+}
+
+#[derive(Debug, ThisError)]
+pub enum Error {
+    #[error(transparent)]
+    InvalidTemplate(#[from] InvalidTemplateError),
+    #[error(transparent)]
+    InvalidJson(#[from] InvalidJsonError),
+    #[error(transparent)]
+    UnknownType(#[from] UnknownTypeError),
+    #[error(transparent)]
+    Malformed(#[from] MalformedError),
+    #[error(transparent)]
+    MessageTooLarge(#[from] MessageTooLargeError),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    InvalidUri(#[from] http::uri::InvalidUri),
+    #[error(transparent)]
+    Decode(#[from] prost::DecodeError),
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("path template {template:?} is invalid")]
+pub struct InvalidTemplateError {
+    template: String,
+}
+
+impl InvalidTemplateError {
+    #[must_use]
+    pub const fn new(template: String) -> Self {
+        Self { template }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("field {field:?} is invalid: {reason}")]
+pub struct InvalidJsonError {
+    field: String,
+    reason: String,
+}
+
+impl InvalidJsonError {
+    #[must_use]
+    pub const fn new(field: String, reason: String) -> Self {
+        Self { field, reason }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("type {name:?} is not in the descriptor set")]
+pub struct UnknownTypeError {
+    name: String,
+}
+
+impl UnknownTypeError {
+    #[must_use]
+    pub const fn new(name: String) -> Self {
+        Self { name }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("message of type {name:?} does not match its descriptor")]
+pub struct MalformedError {
+    name: String,
+}
+
+impl MalformedError {
+    #[must_use]
+    pub const fn new(name: String) -> Self {
+        Self { name }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("message of {len} bytes exceeds the gRPC frame size")]
+pub struct MessageTooLargeError {
+    len: usize,
+}
+
+impl MessageTooLargeError {
+    #[must_use]
+    pub const fn new(len: usize) -> Self {
+        Self { len }
+    }
+}
+
+impl From<Error> for Status {
+    fn from(value: Error) -> Self {
+        match value {
+            Error::InvalidJson(err) => {
+                crate::grpc::status::bad_request(err.field(), err.to_string())
+            }
+            Error::Json(err) => crate::grpc::status::bad_request("body", err.to_string()),
+            Error::MessageTooLarge(err) => Self::resource_exhausted(err.to_string()),
+            err => Self::internal(err.to_string()),
+        }
+    }
+}
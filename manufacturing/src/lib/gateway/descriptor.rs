@@ -0,0 +1,411 @@
+use std::collections::HashMap;
+
+use prost::Message as _;
+use prost_types::{
+    field_descriptor_proto::{Label, Type},
+    DescriptorProto, EnumDescriptorProto, FileDescriptorSet,
+};
+
+use crate::grpc::proto::google::api::{http_rule::Pattern, HttpRule};
+
+use super::{
+    template::{Template, Variables},
+    Error, UnknownTypeError,
+};
+
+/// The messages, enums and HTTP-bound methods of a file descriptor set.
+#[derive(Clone, Debug, Default)]
+pub struct Pool {
+    messages: HashMap<String, Message>,
+    enums: HashMap<String, Enum>,
+    methods: Vec<Method>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Message {
+    fields: Vec<Field>,
+    /// Whether the message is the synthetic entry type of a map field.
+    map_entry: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct Field {
+    pub name: String,
+    pub json_name: String,
+    pub number: u32,
+    pub kind: Kind,
+    pub repeated: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Double,
+    Float,
+    Int64,
+    Uint64,
+    Int32,
+    Fixed64,
+    Fixed32,
+    Bool,
+    String,
+    Bytes,
+    Uint32,
+    Sfixed32,
+    Sfixed64,
+    Sint32,
+    Sint64,
+    Enum(String),
+    Message(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct Enum {
+    values: Vec<(String, i32)>,
+}
+
+/// A method bound to HTTP by a `google.api.http` annotation.
+#[derive(Clone, Debug)]
+pub struct Method {
+    /// The gRPC path of the method, e.g. `/erponomics.manufacturing.v1.ItemService/GetItem`.
+    pub path: http::Uri,
+    pub input: String,
+    pub output: String,
+    pub server_streaming: bool,
+    pub bindings: Vec<Binding>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Binding {
+    pub method: http::Method,
+    pub template: Template,
+    /// The request field the HTTP body maps to, `*` for the whole request or empty for none.
+    pub body: String,
+    /// The response field the HTTP body is taken from, empty for the whole response.
+    pub response_body: String,
+}
+
+impl Pool {
+    /// Index the file descriptor set `bytes`, as written by `protoc --include_imports`.
+    pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let mut pool = Self::default();
+
+        for file in FileDescriptorSet::decode(bytes)?.file {
+            let package = file.package();
+            for message in &file.message_type {
+                pool.add_message(package, message);
+            }
+            for descriptor in &file.enum_type {
+                pool.add_enum(package, descriptor);
+            }
+        }
+
+        for file in ServiceSet::decode(bytes)?.file {
+            let package = file.package.unwrap_or_default();
+            for service in file.service {
+                let service_name = qualify(&package, &service.name.unwrap_or_default());
+                for method in service.method {
+                    pool.add_method(&service_name, method)?;
+                }
+            }
+        }
+
+        Ok(pool)
+    }
+
+    pub fn message(&self, name: &str) -> Result<&Message, Error> {
+        self.messages
+            .get(name)
+            .ok_or_else(|| UnknownTypeError::new(name.to_string()).into())
+    }
+
+    pub fn enumeration(&self, name: &str) -> Result<&Enum, Error> {
+        self.enums
+            .get(name)
+            .ok_or_else(|| UnknownTypeError::new(name.to_string()).into())
+    }
+
+    /// The entry message of `field`, if it is a map field.
+    pub fn map_entry(&self, field: &Field) -> Option<&Message> {
+        match &field.kind {
+            Kind::Message(name) if field.repeated => {
+                self.messages.get(name).filter(|message| message.map_entry)
+            }
+            _ => None,
+        }
+    }
+
+    /// The method and binding matching `method` and `path`, with the values of the
+    /// binding's variables. Bindings with a custom verb are tried first, so that
+    /// `/v1/items/x:block` is not taken for an item named `x:block`.
+    pub fn route(
+        &self,
+        method: &http::Method,
+        path: &str,
+    ) -> Option<(&Method, &Binding, Variables)> {
+        let candidates = self.methods.iter().flat_map(|m| {
+            m.bindings
+                .iter()
+                .filter(|binding| binding.method == method)
+                .map(move |binding| (m, binding))
+        });
+        let (verbs, plain): (Vec<_>, Vec<_>) =
+            candidates.partition(|(_, binding)| binding.template.verb().is_some());
+
+        verbs.into_iter().chain(plain).find_map(|(m, binding)| {
+            binding
+                .template
+                .matches(path)
+                .map(|variables| (m, binding, variables))
+        })
+    }
+
+    fn add_message(&mut self, scope: &str, descriptor: &DescriptorProto) {
+        let name = qualify(scope, descriptor.name());
+        for nested in &descriptor.nested_type {
+            self.add_message(&name, nested);
+        }
+        for nested in &descriptor.enum_type {
+            self.add_enum(&name, nested);
+        }
+
+        let fields = descriptor
+            .field
+            .iter()
+            .map(|field| Field {
+                name: field.name().to_string(),
+                json_name: field
+                    .json_name
+                    .clone()
+                    .unwrap_or_else(|| to_camel_case(field.name())),
+                number: u32::try_from(field.number()).unwrap_or_default(),
+                kind: Kind::new(field.r#type(), field.type_name()),
+                repeated: field.label() == Label::Repeated,
+            })
+            .collect();
+        let map_entry = descriptor
+            .options
+            .as_ref()
+            .is_some_and(prost_types::MessageOptions::map_entry);
+
+        self.messages.insert(name, Message { fields, map_entry });
+    }
+
+    fn add_enum(&mut self, scope: &str, descriptor: &EnumDescriptorProto) {
+        let values = descriptor
+            .value
+            .iter()
+            .map(|value| (value.name().to_string(), value.number()))
+            .collect();
+
+        self.enums
+            .insert(qualify(scope, descriptor.name()), Enum { values });
+    }
+
+    fn add_method(&mut self, service: &str, method: MethodDescriptor) -> Result<(), Error> {
+        let Some(rule) = method.options.and_then(|options| options.http) else {
+            return Ok(());
+        };
+        if method.client_streaming.unwrap_or_default() {
+            return Ok(());
+        }
+
+        let mut bindings = Vec::new();
+        for rule in std::iter::once(&rule).chain(&rule.additional_bindings) {
+            if let Some(binding) = Binding::new(rule)? {
+                bindings.push(binding);
+            }
+        }
+
+        self.methods.push(Method {
+            path: format!("/{service}/{}", method.name.unwrap_or_default()).parse()?,
+            input: unqualify(&method.input_type.unwrap_or_default()),
+            output: unqualify(&method.output_type.unwrap_or_default()),
+            server_streaming: method.server_streaming.unwrap_or_default(),
+            bindings,
+        });
+
+        Ok(())
+    }
+}
+
+impl Message {
+    pub fn field(&self, number: u32) -> Option<&Field> {
+        self.fields.iter().find(|field| field.number == number)
+    }
+
+    /// The field named `name`, by either its proto or its JSON name.
+    pub fn field_by_name(&self, name: &str) -> Option<&Field> {
+        self.fields
+            .iter()
+            .find(|field| field.json_name == name || field.name == name)
+    }
+}
+
+impl Kind {
+    fn new(r#type: Type, type_name: &str) -> Self {
+        match r#type {
+            Type::Double => Self::Double,
+            Type::Float => Self::Float,
+            Type::Int64 => Self::Int64,
+            Type::Uint64 => Self::Uint64,
+            Type::Int32 => Self::Int32,
+            Type::Fixed64 => Self::Fixed64,
+            Type::Fixed32 => Self::Fixed32,
+            Type::Bool => Self::Bool,
+            Type::String => Self::String,
+            Type::Bytes => Self::Bytes,
+            Type::Uint32 => Self::Uint32,
+            Type::Sfixed32 => Self::Sfixed32,
+            Type::Sfixed64 => Self::Sfixed64,
+            Type::Sint32 => Self::Sint32,
+            Type::Sint64 => Self::Sint64,
+            Type::Enum => Self::Enum(unqualify(type_name)),
+            Type::Message | Type::Group => Self::Message(unqualify(type_name)),
+        }
+    }
+}
+
+impl Enum {
+    pub fn name(&self, number: i32) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(_, n)| *n == number)
+            .map(|(name, _)| name.as_str())
+    }
+
+    pub fn number(&self, name: &str) -> Option<i32> {
+        self.values
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, number)| *number)
+    }
+}
+
+impl Binding {
+    fn new(rule: &HttpRule) -> Result<Option<Self>, Error> {
+        let (method, template) = match &rule.pattern {
+            Some(Pattern::Get(template)) => (http::Method::GET, template),
+            Some(Pattern::Put(template)) => (http::Method::PUT, template),
+            Some(Pattern::Post(template)) => (http::Method::POST, template),
+            Some(Pattern::Delete(template)) => (http::Method::DELETE, template),
+            Some(Pattern::Patch(template)) => (http::Method::PATCH, template),
+            Some(Pattern::Custom(custom)) => match custom.kind.parse() {
+                Ok(method) => (method, &custom.path),
+                Err(_) => return Ok(None),
+            },
+            None => return Ok(None),
+        };
+
+        Ok(Some(Self {
+            method,
+            template: template.parse()?,
+            body: rule.body.clone(),
+            response_body: rule.response_body.clone(),
+        }))
+    }
+}
+
+fn qualify(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{scope}.{name}")
+    }
+}
+
+fn unqualify(type_name: &str) -> String {
+    type_name.trim_start_matches('.').to_string()
+}
+
+pub fn to_camel_case(name: &str) -> String {
+    let mut camel = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            camel.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            camel.push(c);
+        }
+    }
+
+    camel
+}
+
+pub fn to_snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len() + 4);
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            snake.push('_');
+            snake.push(c.to_ascii_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+
+    snake
+}
+
+// MARK: Services
+//
+// `prost_types::MethodOptions` drops extensions, so the services are decoded a second time
+// through these messages, which keep the `google.api.http` option.
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ServiceSet {
+    #[prost(message, repeated, tag = "1")]
+    file: Vec<ServiceFile>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ServiceFile {
+    #[prost(string, optional, tag = "2")]
+    package: Option<String>,
+    #[prost(message, repeated, tag = "6")]
+    service: Vec<ServiceDescriptor>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ServiceDescriptor {
+    #[prost(string, optional, tag = "1")]
+    name: Option<String>,
+    #[prost(message, repeated, tag = "2")]
+    method: Vec<MethodDescriptor>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct MethodDescriptor {
+    #[prost(string, optional, tag = "1")]
+    name: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    input_type: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    output_type: Option<String>,
+    #[prost(message, optional, tag = "4")]
+    options: Option<MethodOptions>,
+    #[prost(bool, optional, tag = "5")]
+    client_streaming: Option<bool>,
+    #[prost(bool, optional, tag = "6")]
+    server_streaming: Option<bool>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct MethodOptions {
+    #[prost(message, optional, tag = "72295728")]
+    http: Option<HttpRule>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_between_snake_and_camel_case() {
+        assert_eq!("updateMask", to_camel_case("update_mask"));
+        assert_eq!("displayName", to_camel_case("display_name"));
+        assert_eq!("update_mask", to_snake_case("updateMask"));
+        assert_eq!("name", to_snake_case("name"));
+    }
+}
@@ -0,0 +1,724 @@
+//! The proto3 JSON mapping, transcoding between JSON values and the protobuf wire format
+//! of the messages in a [`Pool`].
+
+use base64::{engine::general_purpose, Engine as _};
+use prost::{
+    encoding::{decode_key, decode_varint, encode_key, encode_varint, WireType},
+    Message as _,
+};
+use serde_json::{Map, Number, Value};
+
+use super::descriptor::{to_camel_case, to_snake_case, Field, Kind, Pool};
+use super::{Error, InvalidJsonError, MalformedError};
+
+const ANY: &str = "google.protobuf.Any";
+const DURATION: &str = "google.protobuf.Duration";
+const FIELD_MASK: &str = "google.protobuf.FieldMask";
+const TIMESTAMP: &str = "google.protobuf.Timestamp";
+const WRAPPERS: [&str; 9] = [
+    "google.protobuf.DoubleValue",
+    "google.protobuf.FloatValue",
+    "google.protobuf.Int64Value",
+    "google.protobuf.UInt64Value",
+    "google.protobuf.Int32Value",
+    "google.protobuf.UInt32Value",
+    "google.protobuf.BoolValue",
+    "google.protobuf.StringValue",
+    "google.protobuf.BytesValue",
+];
+
+// MARK: Wire to JSON
+
+/// Render the wire encoding `bytes` of a message of type `name` as JSON.
+pub fn to_json(pool: &Pool, name: &str, bytes: &[u8]) -> Result<Value, Error> {
+    match name {
+        TIMESTAMP => Ok(Value::String(
+            prost_types::Timestamp::decode(bytes)?.to_string(),
+        )),
+        DURATION => Ok(Value::String(
+            prost_types::Duration::decode(bytes)?.to_string(),
+        )),
+        FIELD_MASK => {
+            let paths = prost_types::FieldMask::decode(bytes)?.paths;
+            let paths: Vec<_> = paths.iter().map(|path| to_camel_case(path)).collect();
+
+            Ok(Value::String(paths.join(",")))
+        }
+        ANY => any_to_json(pool, bytes),
+        _ if WRAPPERS.contains(&name) => {
+            let mut object = fields_to_json(pool, name, bytes)?;
+            object.remove("value").map_or_else(|| zero(pool, name), Ok)
+        }
+        _ => fields_to_json(pool, name, bytes).map(Value::Object),
+    }
+}
+
+fn any_to_json(pool: &Pool, bytes: &[u8]) -> Result<Value, Error> {
+    let any = prost_types::Any::decode(bytes)?;
+    let name = any.type_url.rsplit('/').next().unwrap_or_default();
+
+    let mut object = Map::new();
+    object.insert(String::from("@type"), Value::String(any.type_url.clone()));
+    match to_json(pool, name, &any.value)? {
+        Value::Object(fields) if !is_well_known(name) => object.extend(fields),
+        value => {
+            object.insert(String::from("value"), value);
+        }
+    }
+
+    Ok(Value::Object(object))
+}
+
+fn fields_to_json(pool: &Pool, name: &str, bytes: &[u8]) -> Result<Map<String, Value>, Error> {
+    let message = pool.message(name)?;
+    let mut object = Map::new();
+    let mut buf = bytes;
+
+    while !buf.is_empty() {
+        let (number, wire) = read_field(&mut buf, name)?;
+        let Some(field) = message.field(number) else {
+            continue;
+        };
+        let key = field.json_name.clone();
+
+        if let Some(entry) = pool.map_entry(field) {
+            let Wire::Bytes(mut bytes) = wire else {
+                return Err(MalformedError::new(name.to_string()).into());
+            };
+            let (mut key_json, mut value_json) = (None, None);
+            while !bytes.is_empty() {
+                let (number, wire) = read_field(&mut bytes, name)?;
+                match entry.field(number) {
+                    Some(f) if number == 1 => {
+                        key_json = Some(value_to_json(pool, &f.kind, wire, name)?);
+                    }
+                    Some(f) if number == 2 => {
+                        value_json = Some(value_to_json(pool, &f.kind, wire, name)?);
+                    }
+                    _ => {}
+                }
+            }
+            let key_json = match key_json {
+                Some(Value::String(key)) => key,
+                Some(key) => key.to_string(),
+                None => String::new(),
+            };
+            let value_json = match (value_json, entry.field(2)) {
+                (Some(value), _) => value,
+                (None, Some(f)) => zero_value(pool, &f.kind)?,
+                (None, None) => Value::Null,
+            };
+            if let Value::Object(map) = object
+                .entry(key)
+                .or_insert_with(|| Value::Object(Map::new()))
+            {
+                map.insert(key_json, value_json);
+            }
+        } else if field.repeated {
+            let mut values = Vec::new();
+            match (wire, packed_wire_type(&field.kind)) {
+                (Wire::Bytes(mut packed), Some(wire_type)) => {
+                    while !packed.is_empty() {
+                        let wire = read_value(&mut packed, wire_type, name)?;
+                        values.push(value_to_json(pool, &field.kind, wire, name)?);
+                    }
+                }
+                (wire, _) => values.push(value_to_json(pool, &field.kind, wire, name)?),
+            }
+            if let Value::Array(array) = object
+                .entry(key)
+                .or_insert_with(|| Value::Array(Vec::new()))
+            {
+                array.extend(values);
+            }
+        } else {
+            object.insert(key, value_to_json(pool, &field.kind, wire, name)?);
+        }
+    }
+
+    Ok(object)
+}
+
+// The wire format stores every integer in a `u64` or `u32`, reinterpreted by the field type.
+#[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+fn value_to_json(pool: &Pool, kind: &Kind, wire: Wire, message: &str) -> Result<Value, Error> {
+    let value = match (kind, wire) {
+        (Kind::Double, Wire::Fixed64(v)) => float(f64::from_bits(v)),
+        (Kind::Float, Wire::Fixed32(v)) => float(f64::from(f32::from_bits(v))),
+        (Kind::Int32, Wire::Varint(v)) => Value::from(v as i32),
+        (Kind::Uint32, Wire::Varint(v)) => Value::from(v as u32),
+        (Kind::Sint32, Wire::Varint(v)) => Value::from(zigzag(v) as i32),
+        (Kind::Fixed32, Wire::Fixed32(v)) => Value::from(v),
+        (Kind::Sfixed32, Wire::Fixed32(v)) => Value::from(v as i32),
+        (Kind::Int64, Wire::Varint(v)) | (Kind::Sfixed64, Wire::Fixed64(v)) => {
+            Value::String((v as i64).to_string())
+        }
+        (Kind::Uint64, Wire::Varint(v)) | (Kind::Fixed64, Wire::Fixed64(v)) => {
+            Value::String(v.to_string())
+        }
+        (Kind::Sint64, Wire::Varint(v)) => Value::String(zigzag(v).to_string()),
+        (Kind::Bool, Wire::Varint(v)) => Value::Bool(v != 0),
+        (Kind::String, Wire::Bytes(v)) => Value::String(
+            String::from_utf8(v.to_vec()).map_err(|_| MalformedError::new(message.to_string()))?,
+        ),
+        (Kind::Bytes, Wire::Bytes(v)) => Value::String(general_purpose::STANDARD.encode(v)),
+        (Kind::Enum(name), Wire::Varint(v)) => {
+            let number = v as i32;
+            pool.enumeration(name)?.name(number).map_or_else(
+                || Value::from(number),
+                |name| Value::String(name.to_string()),
+            )
+        }
+        (Kind::Message(name), Wire::Bytes(v)) => to_json(pool, name, v)?,
+        _ => return Err(MalformedError::new(message.to_string()).into()),
+    };
+
+    Ok(value)
+}
+
+/// The JSON of the default value of `kind`.
+fn zero_value(pool: &Pool, kind: &Kind) -> Result<Value, Error> {
+    let wire = match kind {
+        Kind::Double | Kind::Fixed64 | Kind::Sfixed64 => Wire::Fixed64(0),
+        Kind::Float | Kind::Fixed32 | Kind::Sfixed32 => Wire::Fixed32(0),
+        Kind::String | Kind::Bytes | Kind::Message(_) => Wire::Bytes(&[]),
+        _ => Wire::Varint(0),
+    };
+
+    value_to_json(pool, kind, wire, "")
+}
+
+/// The JSON of the default value of the wrapper message `name`.
+fn zero(pool: &Pool, name: &str) -> Result<Value, Error> {
+    pool.message(name)?
+        .field(1)
+        .map_or(Ok(Value::Null), |field| zero_value(pool, &field.kind))
+}
+
+fn float(value: f64) -> Value {
+    Number::from_f64(value).map_or_else(
+        || {
+            Value::String(String::from(if value.is_nan() {
+                "NaN"
+            } else if value > 0.0 {
+                "Infinity"
+            } else {
+                "-Infinity"
+            }))
+        },
+        Value::Number,
+    )
+}
+
+// Zigzag decoding keeps the magnitude within 63 bits, so the cast cannot wrap.
+#[allow(clippy::cast_possible_wrap)]
+const fn zigzag(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+// MARK: JSON to Wire
+
+/// Encode the JSON `value` as a message of type `name`.
+pub fn from_json(pool: &Pool, name: &str, value: &Value) -> Result<Vec<u8>, Error> {
+    let mut buf = Vec::new();
+    message_from_json(pool, name, value, "", &mut buf)?;
+
+    Ok(buf)
+}
+
+fn message_from_json(
+    pool: &Pool,
+    name: &str,
+    value: &Value,
+    path: &str,
+    buf: &mut Vec<u8>,
+) -> Result<(), Error> {
+    let invalid = |reason: &str| InvalidJsonError::new(path.to_string(), reason.to_string());
+
+    match name {
+        TIMESTAMP => {
+            let timestamp: prost_types::Timestamp = string(value, path)?
+                .parse()
+                .map_err(|_| invalid("expected an RFC 3339 timestamp"))?;
+            buf.extend(timestamp.encode_to_vec());
+        }
+        DURATION => {
+            let duration: prost_types::Duration = string(value, path)?
+                .parse()
+                .map_err(|_| invalid("expected a duration in seconds, e.g. \"1.5s\""))?;
+            buf.extend(duration.encode_to_vec());
+        }
+        FIELD_MASK => {
+            let paths = string(value, path)?
+                .split(',')
+                .map(str::trim)
+                .filter(|path| !path.is_empty())
+                .map(|path| {
+                    path.split('.')
+                        .map(to_snake_case)
+                        .collect::<Vec<_>>()
+                        .join(".")
+                })
+                .collect();
+            buf.extend(prost_types::FieldMask { paths }.encode_to_vec());
+        }
+        ANY => any_from_json(pool, value, path, buf)?,
+        _ if WRAPPERS.contains(&name) => {
+            if let Some(field) = pool.message(name)?.field(1) {
+                field_from_json(pool, field, value, path, buf)?;
+            }
+        }
+        _ => {
+            let message = pool.message(name)?;
+            let object = match value {
+                Value::Object(object) => object,
+                Value::Null => return Ok(()),
+                _ => return Err(invalid("expected an object").into()),
+            };
+            for (key, value) in object {
+                let path = join(path, key);
+                let field = message.field_by_name(key).ok_or_else(|| {
+                    InvalidJsonError::new(path.clone(), String::from("unknown field"))
+                })?;
+                if value.is_null() {
+                    continue;
+                }
+
+                if let Some(entry) = pool.map_entry(field) {
+                    let Value::Object(map) = value else {
+                        return Err(InvalidJsonError::new(
+                            path,
+                            String::from("expected an object"),
+                        )
+                        .into());
+                    };
+                    let (Some(key_field), Some(value_field)) = (entry.field(1), entry.field(2))
+                    else {
+                        return Err(MalformedError::new(name.to_string()).into());
+                    };
+                    for (key, value) in map {
+                        let path = join(&path, key);
+                        let mut entry = Vec::new();
+                        field_from_json(
+                            pool,
+                            key_field,
+                            &Value::String(key.clone()),
+                            &path,
+                            &mut entry,
+                        )?;
+                        field_from_json(pool, value_field, value, &path, &mut entry)?;
+                        encode_key(field.number, WireType::LengthDelimited, buf);
+                        encode_varint(entry.len() as u64, buf);
+                        buf.extend(entry);
+                    }
+                } else if let (true, Value::Array(values)) = (field.repeated, value) {
+                    for value in values {
+                        field_from_json(pool, field, value, &path, buf)?;
+                    }
+                } else {
+                    // A scalar for a repeated field is taken as a list of one, which is how
+                    // a repeated field given once as a query parameter arrives.
+                    field_from_json(pool, field, value, &path, buf)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn any_from_json(pool: &Pool, value: &Value, path: &str, buf: &mut Vec<u8>) -> Result<(), Error> {
+    let invalid = || {
+        InvalidJsonError::new(
+            path.to_string(),
+            String::from("expected an object with an \"@type\""),
+        )
+    };
+    let Value::Object(object) = value else {
+        return Err(invalid().into());
+    };
+    let type_url = object
+        .get("@type")
+        .and_then(Value::as_str)
+        .ok_or_else(invalid)?;
+    let name = type_url.rsplit('/').next().unwrap_or_default();
+
+    let value = if is_well_known(name) {
+        from_json(pool, name, object.get("value").unwrap_or(&Value::Null))?
+    } else {
+        let mut fields = object.clone();
+        fields.remove("@type");
+        from_json(pool, name, &Value::Object(fields))?
+    };
+
+    let any = prost_types::Any {
+        type_url: type_url.to_string(),
+        value,
+    };
+    buf.extend(any.encode_to_vec());
+
+    Ok(())
+}
+
+// JSON numbers are parsed into the width of the field, which the wire format then widens.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_possible_wrap
+)]
+fn field_from_json(
+    pool: &Pool,
+    field: &Field,
+    value: &Value,
+    path: &str,
+    buf: &mut Vec<u8>,
+) -> Result<(), Error> {
+    let number = field.number;
+    match &field.kind {
+        Kind::Double => {
+            encode_key(number, WireType::SixtyFourBit, buf);
+            buf.extend(parse_float(value, path)?.to_le_bytes());
+        }
+        Kind::Float => {
+            encode_key(number, WireType::ThirtyTwoBit, buf);
+            buf.extend((parse_float(value, path)? as f32).to_le_bytes());
+        }
+        Kind::Int32 => varint(number, i64::from(parse::<i32>(value, path)?) as u64, buf),
+        Kind::Int64 => varint(number, parse::<i64>(value, path)? as u64, buf),
+        Kind::Uint32 => varint(number, u64::from(parse::<u32>(value, path)?), buf),
+        Kind::Uint64 => varint(number, parse::<u64>(value, path)?, buf),
+        Kind::Sint32 => {
+            let v = i64::from(parse::<i32>(value, path)?);
+            varint(number, ((v << 1) ^ (v >> 63)) as u64, buf);
+        }
+        Kind::Sint64 => {
+            let v = parse::<i64>(value, path)?;
+            varint(number, ((v << 1) ^ (v >> 63)) as u64, buf);
+        }
+        Kind::Fixed32 => {
+            encode_key(number, WireType::ThirtyTwoBit, buf);
+            buf.extend(parse::<u32>(value, path)?.to_le_bytes());
+        }
+        Kind::Sfixed32 => {
+            encode_key(number, WireType::ThirtyTwoBit, buf);
+            buf.extend(parse::<i32>(value, path)?.to_le_bytes());
+        }
+        Kind::Fixed64 => {
+            encode_key(number, WireType::SixtyFourBit, buf);
+            buf.extend(parse::<u64>(value, path)?.to_le_bytes());
+        }
+        Kind::Sfixed64 => {
+            encode_key(number, WireType::SixtyFourBit, buf);
+            buf.extend(parse::<i64>(value, path)?.to_le_bytes());
+        }
+        Kind::Bool => {
+            let v = match value {
+                Value::Bool(v) => *v,
+                Value::String(v) if v == "true" => true,
+                Value::String(v) if v == "false" => false,
+                _ => {
+                    return Err(InvalidJsonError::new(
+                        path.to_string(),
+                        String::from("expected a boolean"),
+                    )
+                    .into())
+                }
+            };
+            varint(number, u64::from(v), buf);
+        }
+        Kind::String => bytes(number, string(value, path)?.as_bytes(), buf),
+        Kind::Bytes => {
+            let encoded = string(value, path)?;
+            let decoded = [
+                general_purpose::STANDARD,
+                general_purpose::STANDARD_NO_PAD,
+                general_purpose::URL_SAFE,
+                general_purpose::URL_SAFE_NO_PAD,
+            ]
+            .iter()
+            .find_map(|engine| engine.decode(encoded).ok())
+            .ok_or_else(|| {
+                InvalidJsonError::new(path.to_string(), String::from("expected base64"))
+            })?;
+            bytes(number, &decoded, buf);
+        }
+        Kind::Enum(name) => {
+            let v = match value {
+                Value::String(v) => pool.enumeration(name)?.number(v).or_else(|| v.parse().ok()),
+                Value::Number(v) => v.as_i64().and_then(|v| i32::try_from(v).ok()),
+                _ => None,
+            }
+            .ok_or_else(|| {
+                InvalidJsonError::new(path.to_string(), format!("expected a value of {name}"))
+            })?;
+            varint(number, i64::from(v) as u64, buf);
+        }
+        Kind::Message(name) => {
+            let mut message = Vec::new();
+            message_from_json(pool, name, value, path, &mut message)?;
+            bytes(number, &message, buf);
+        }
+    }
+
+    Ok(())
+}
+
+fn varint(number: u32, value: u64, buf: &mut Vec<u8>) {
+    encode_key(number, WireType::Varint, buf);
+    encode_varint(value, buf);
+}
+
+fn bytes(number: u32, value: &[u8], buf: &mut Vec<u8>) {
+    encode_key(number, WireType::LengthDelimited, buf);
+    encode_varint(value.len() as u64, buf);
+    buf.extend_from_slice(value);
+}
+
+fn string<'a>(value: &'a Value, path: &str) -> Result<&'a str, Error> {
+    value.as_str().ok_or_else(|| {
+        InvalidJsonError::new(path.to_string(), String::from("expected a string")).into()
+    })
+}
+
+/// An integer given as a JSON number or, as 64-bit integers are, as a string.
+fn parse<T: std::str::FromStr>(value: &Value, path: &str) -> Result<T, Error> {
+    let parsed = match value {
+        Value::Number(v) => v.to_string().parse().ok(),
+        Value::String(v) => v.parse().ok(),
+        _ => None,
+    };
+
+    parsed.ok_or_else(|| {
+        InvalidJsonError::new(
+            path.to_string(),
+            String::from("expected an integer in range"),
+        )
+        .into()
+    })
+}
+
+fn parse_float(value: &Value, path: &str) -> Result<f64, Error> {
+    let parsed = match value {
+        Value::Number(v) => v.as_f64(),
+        Value::String(v) => match v.as_str() {
+            "NaN" => Some(f64::NAN),
+            "Infinity" => Some(f64::INFINITY),
+            "-Infinity" => Some(f64::NEG_INFINITY),
+            v => v.parse().ok(),
+        },
+        _ => None,
+    };
+
+    parsed.ok_or_else(|| {
+        InvalidJsonError::new(path.to_string(), String::from("expected a number")).into()
+    })
+}
+
+// MARK: Field Paths
+
+/// Set the field at `field_path` of the JSON `object` of a message of type `name` to `value`,
+/// keyed by its JSON name. With `append`, a field set before is turned into a list.
+pub fn set_field(
+    pool: &Pool,
+    name: &str,
+    object: &mut Map<String, Value>,
+    field_path: &[String],
+    value: Value,
+    append: bool,
+) -> Result<(), Error> {
+    let Some((first, rest)) = field_path.split_first() else {
+        return Ok(());
+    };
+    let field = pool.message(name)?.field_by_name(first).ok_or_else(|| {
+        InvalidJsonError::new(field_path.join("."), String::from("unknown field"))
+    })?;
+    if field.name != field.json_name {
+        if let Some(existing) = object.remove(&field.name) {
+            object.insert(field.json_name.clone(), existing);
+        }
+    }
+
+    if rest.is_empty() {
+        match object.get_mut(&field.json_name) {
+            Some(Value::Array(values)) if append => values.push(value),
+            Some(existing) if append => {
+                let first = existing.take();
+                *existing = Value::Array(vec![first, value]);
+            }
+            _ => {
+                object.insert(field.json_name.clone(), value);
+            }
+        }
+        return Ok(());
+    }
+
+    let Kind::Message(child) = &field.kind else {
+        return Err(
+            InvalidJsonError::new(field_path.join("."), String::from("not a message")).into(),
+        );
+    };
+    let entry = object
+        .entry(field.json_name.clone())
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !entry.is_object() {
+        *entry = Value::Object(Map::new());
+    }
+    match entry {
+        Value::Object(child_object) => set_field(pool, child, child_object, rest, value, append),
+        _ => Ok(()),
+    }
+}
+
+/// The JSON name of the field at `field_path` of a message of type `name`.
+pub fn json_name(pool: &Pool, name: &str, field_path: &str) -> Result<String, Error> {
+    pool.message(name)?
+        .field_by_name(field_path)
+        .map(|field| field.json_name.clone())
+        .ok_or_else(|| {
+            InvalidJsonError::new(field_path.to_string(), String::from("unknown field")).into()
+        })
+}
+
+// MARK: Wire
+
+enum Wire<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Bytes(&'a [u8]),
+    Fixed32(u32),
+}
+
+fn read_field<'a>(buf: &mut &'a [u8], message: &str) -> Result<(u32, Wire<'a>), Error> {
+    let (number, wire_type) = decode_key(buf)?;
+    let wire = read_value(buf, wire_type, message)?;
+
+    Ok((number, wire))
+}
+
+fn read_value<'a>(
+    buf: &mut &'a [u8],
+    wire_type: WireType,
+    message: &str,
+) -> Result<Wire<'a>, Error> {
+    let malformed = || MalformedError::new(message.to_string());
+
+    match wire_type {
+        WireType::Varint => Ok(Wire::Varint(decode_varint(buf)?)),
+        WireType::SixtyFourBit => {
+            let bytes = take(buf, 8, message)?.try_into().map_err(|_| malformed())?;
+            Ok(Wire::Fixed64(u64::from_le_bytes(bytes)))
+        }
+        WireType::ThirtyTwoBit => {
+            let bytes = take(buf, 4, message)?.try_into().map_err(|_| malformed())?;
+            Ok(Wire::Fixed32(u32::from_le_bytes(bytes)))
+        }
+        WireType::LengthDelimited => {
+            let len = usize::try_from(decode_varint(buf)?).map_err(|_| malformed())?;
+            Ok(Wire::Bytes(take(buf, len, message)?))
+        }
+        WireType::StartGroup | WireType::EndGroup => Err(malformed().into()),
+    }
+}
+
+fn take<'a>(buf: &mut &'a [u8], len: usize, message: &str) -> Result<&'a [u8], Error> {
+    let (Some(head), Some(tail)) = (buf.get(..len), buf.get(len..)) else {
+        return Err(MalformedError::new(message.to_string()).into());
+    };
+    *buf = tail;
+
+    Ok(head)
+}
+
+/// The wire type of the elements of a packed repeated field of `kind`, if it can be packed.
+const fn packed_wire_type(kind: &Kind) -> Option<WireType> {
+    match kind {
+        Kind::Double | Kind::Fixed64 | Kind::Sfixed64 => Some(WireType::SixtyFourBit),
+        Kind::Float | Kind::Fixed32 | Kind::Sfixed32 => Some(WireType::ThirtyTwoBit),
+        Kind::String | Kind::Bytes | Kind::Message(_) => None,
+        _ => Some(WireType::Varint),
+    }
+}
+
+fn is_well_known(name: &str) -> bool {
+    [TIMESTAMP, DURATION, FIELD_MASK, ANY].contains(&name) || WRAPPERS.contains(&name)
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prost_types::{FieldMask, Timestamp};
+    use serde_json::json;
+
+    use crate::proto::{item, Item, UpdateItemRequest};
+
+    use super::*;
+
+    const DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("manufacturing_descriptor");
+
+    const UPDATE_ITEM_REQUEST: &str = "erponomics.manufacturing.v1.UpdateItemRequest";
+
+    #[test]
+    fn round_trips_messages_through_json() -> anyhow::Result<()> {
+        let pool = Pool::decode(DESCRIPTOR_SET)?;
+        let request = UpdateItemRequest {
+            item: Some(Item {
+                name: String::from("items/w-rim"),
+                display_name: Some(String::new()),
+                tracking: Some(item::Tracking::Lot.into()),
+                create_time: Some(Timestamp {
+                    seconds: 1_735_689_600,
+                    nanos: 500_000_000,
+                }),
+                ..Default::default()
+            }),
+            update_mask: Some(FieldMask {
+                paths: vec![String::from("display_name"), String::from("title")],
+            }),
+        };
+
+        let value = to_json(&pool, UPDATE_ITEM_REQUEST, &request.encode_to_vec())?;
+        assert_eq!(
+            json!({
+                "item": {
+                    "name": "items/w-rim",
+                    "displayName": "",
+                    "tracking": "LOT",
+                    "createTime": "2025-01-01T00:00:00.500Z",
+                },
+                "updateMask": "displayName,title",
+            }),
+            value
+        );
+
+        let bytes = from_json(&pool, UPDATE_ITEM_REQUEST, &value)?;
+        assert_eq!(request, UpdateItemRequest::decode(bytes.as_slice())?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_json_not_matching_the_message() -> anyhow::Result<()> {
+        let pool = Pool::decode(DESCRIPTOR_SET)?;
+
+        for value in [
+            json!({ "item": { "colour": "red" } }),
+            json!({ "item": { "tracking": "BY_WEIGHT" } }),
+            json!({ "item": { "createTime": "yesterday" } }),
+            json!({ "item": "items/w-rim" }),
+        ] {
+            assert!(matches!(
+                from_json(&pool, UPDATE_ITEM_REQUEST, &value),
+                Err(Error::InvalidJson(_))
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+use axum::body::Body;
+use http::{header::CONTENT_TYPE, Response, StatusCode};
+use serde_json::{json, Value};
+use tonic::{Code, Status};
+
+use super::{descriptor::Pool, json};
+
+/// The HTTP status and canonical name of a gRPC status code, as mapped by AIP-193.
+pub fn http_status(code: Code) -> (StatusCode, &'static str) {
+    match code {
+        Code::Ok => (StatusCode::OK, "OK"),
+        // 499 Client Closed Request is not registered, so `http` has no constant for it.
+        Code::Cancelled => (
+            StatusCode::from_u16(499).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            "CANCELLED",
+        ),
+        Code::Unknown => (StatusCode::INTERNAL_SERVER_ERROR, "UNKNOWN"),
+        Code::InvalidArgument => (StatusCode::BAD_REQUEST, "INVALID_ARGUMENT"),
+        Code::DeadlineExceeded => (StatusCode::GATEWAY_TIMEOUT, "DEADLINE_EXCEEDED"),
+        Code::NotFound => (StatusCode::NOT_FOUND, "NOT_FOUND"),
+        Code::AlreadyExists => (StatusCode::CONFLICT, "ALREADY_EXISTS"),
+        Code::PermissionDenied => (StatusCode::FORBIDDEN, "PERMISSION_DENIED"),
+        Code::ResourceExhausted => (StatusCode::TOO_MANY_REQUESTS, "RESOURCE_EXHAUSTED"),
+        Code::FailedPrecondition => (StatusCode::BAD_REQUEST, "FAILED_PRECONDITION"),
+        Code::Aborted => (StatusCode::CONFLICT, "ABORTED"),
+        Code::OutOfRange => (StatusCode::BAD_REQUEST, "OUT_OF_RANGE"),
+        Code::Unimplemented => (StatusCode::NOT_IMPLEMENTED, "UNIMPLEMENTED"),
+        Code::Internal => (StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL"),
+        Code::Unavailable => (StatusCode::SERVICE_UNAVAILABLE, "UNAVAILABLE"),
+        Code::DataLoss => (StatusCode::INTERNAL_SERVER_ERROR, "DATA_LOSS"),
+        Code::Unauthenticated => (StatusCode::UNAUTHORIZED, "UNAUTHENTICATED"),
+    }
+}
+
+/// The AIP-193 error body of `status`, with its details rendered as JSON.
+pub fn error(pool: &Pool, status: &Status) -> Value {
+    let (http_status, name) = http_status(status.code());
+    let details = json::to_json(pool, "google.rpc.Status", status.details())
+        .ok()
+        .and_then(|mut value| value.get_mut("details").map(Value::take))
+        .unwrap_or_else(|| Value::Array(Vec::new()));
+
+    json!({
+        "error": {
+            "code": http_status.as_u16(),
+            "message": status.message(),
+            "status": name,
+            "details": details,
+        }
+    })
+}
+
+/// The HTTP response for `status`.
+pub fn response(pool: &Pool, status: &Status) -> Response<Body> {
+    let (http_status, _) = http_status(status.code());
+    let body = error(pool, status).to_string();
+
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = http_status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, super::APPLICATION_JSON);
+
+    response
+}
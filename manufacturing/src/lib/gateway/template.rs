@@ -0,0 +1,255 @@
+use std::str::FromStr;
+
+use super::{Error, InvalidTemplateError};
+
+/// The field paths of the variables of a matched template, with their values.
+pub type Variables = Vec<(Vec<String>, String)>;
+
+/// A `google.api.http` path template, e.g. `/v1/{name=items/*}:block`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Template {
+    segments: Vec<Segment>,
+    variables: Vec<Variable>,
+    verb: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    /// `*`, matching a single path segment.
+    Single,
+    /// `**`, matching the rest of the path.
+    Rest,
+}
+
+/// A `{field.path=segments}` variable, capturing the segments from `start` to `end`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Variable {
+    field_path: Vec<String>,
+    start: usize,
+    end: usize,
+}
+
+impl Template {
+    pub fn verb(&self) -> Option<&str> {
+        self.verb.as_deref()
+    }
+
+    /// The field paths and percent-decoded values of the variables, if `path` matches.
+    pub fn matches(&self, path: &str) -> Option<Variables> {
+        let path = path.strip_prefix('/')?;
+        let path = match &self.verb {
+            Some(verb) => path.strip_suffix(verb.as_str())?.strip_suffix(':')?,
+            None => path,
+        };
+        let parts: Vec<&str> = path.split('/').collect();
+
+        // Where each segment's match starts in `parts`, plus where the last one ends.
+        let mut bounds = Vec::with_capacity(self.segments.len() + 1);
+        let mut position = 0;
+        for segment in &self.segments {
+            bounds.push(position);
+            match segment {
+                Segment::Literal(literal) => {
+                    if parts.get(position) != Some(&literal.as_str()) {
+                        return None;
+                    }
+                    position += 1;
+                }
+                Segment::Single => {
+                    if parts.get(position).is_none_or(|part| part.is_empty()) {
+                        return None;
+                    }
+                    position += 1;
+                }
+                Segment::Rest => {
+                    if position >= parts.len() {
+                        return None;
+                    }
+                    position = parts.len();
+                }
+            }
+        }
+        bounds.push(position);
+        if position != parts.len() {
+            return None;
+        }
+
+        self.variables
+            .iter()
+            .map(|variable| {
+                let value = parts.get(bounds[variable.start]..bounds[variable.end])?;
+                Some((
+                    variable.field_path.clone(),
+                    percent_decode(&value.join("/"))?,
+                ))
+            })
+            .collect()
+    }
+}
+
+impl FromStr for Template {
+    type Err = Error;
+
+    fn from_str(template: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidTemplateError::new(template.to_string());
+        let path = template.strip_prefix('/').ok_or_else(invalid)?;
+
+        let (path, verb) = match path.rfind(':') {
+            Some(i) if !path[i..].contains(['/', '}']) => {
+                (&path[..i], Some(path[i + 1..].to_string()))
+            }
+            _ => (path, None),
+        };
+
+        let mut segments = Vec::new();
+        let mut variables = Vec::new();
+        for token in split_outside_braces(path).ok_or_else(invalid)? {
+            if let Some(variable) = token.strip_prefix('{') {
+                let variable = variable.strip_suffix('}').ok_or_else(invalid)?;
+                let (field_path, pattern) = variable.split_once('=').unwrap_or((variable, "*"));
+                let start = segments.len();
+                for part in pattern.split('/') {
+                    segments.push(Segment::parse(part).ok_or_else(invalid)?);
+                }
+                variables.push(Variable {
+                    field_path: field_path.split('.').map(String::from).collect(),
+                    start,
+                    end: segments.len(),
+                });
+            } else {
+                segments.push(Segment::parse(token).ok_or_else(invalid)?);
+            }
+        }
+
+        // `**` can only match the rest of the path.
+        let rest = segments.iter().position(|s| *s == Segment::Rest);
+        if rest.is_some_and(|i| i + 1 != segments.len()) {
+            return Err(invalid().into());
+        }
+
+        Ok(Self {
+            segments,
+            variables,
+            verb,
+        })
+    }
+}
+
+impl Segment {
+    fn parse(token: &str) -> Option<Self> {
+        match token {
+            "" => None,
+            "*" => Some(Self::Single),
+            "**" => Some(Self::Rest),
+            literal if !literal.contains(['{', '}', '=']) => {
+                Some(Self::Literal(literal.to_string()))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Split `path` on the slashes that are not inside a variable.
+fn split_outside_braces(path: &str) -> Option<Vec<&str>> {
+    let mut tokens = Vec::new();
+    let mut depth = 0_u8;
+    let mut start = 0;
+    for (i, c) in path.char_indices() {
+        match c {
+            '{' if depth == 0 => depth = 1,
+            '}' if depth == 1 => depth = 0,
+            '{' | '}' => return None,
+            '/' if depth == 0 => {
+                tokens.push(&path[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if depth != 0 {
+        return None;
+    }
+    tokens.push(&path[start..]);
+
+    Some(tokens)
+}
+
+/// Decode the `%XX` escapes of `value`, or `None` if they are malformed or not UTF-8.
+pub fn percent_decode(value: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables(template: &str, path: &str) -> Option<Vec<(String, String)>> {
+        let template: Template = template.parse().ok()?;
+        let variables = template.matches(path)?;
+
+        Some(
+            variables
+                .into_iter()
+                .map(|(field_path, value)| (field_path.join("."), value))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn matches_variables_and_verbs() {
+        assert_eq!(
+            Some(vec![(String::from("name"), String::from("items/w-rim"))]),
+            variables("/v1/{name=items/*}", "/v1/items/w-rim")
+        );
+        assert_eq!(
+            Some(vec![(String::from("name"), String::from("items/w-rim"))]),
+            variables("/v1/{name=items/*}:block", "/v1/items/w-rim:block")
+        );
+        assert_eq!(
+            None,
+            variables("/v1/{name=items/*}:block", "/v1/items/w-rim")
+        );
+        assert_eq!(
+            None,
+            variables("/v1/{name=items/*}", "/v1/items/w-rim/boms/1")
+        );
+        assert_eq!(
+            Some(vec![(String::from("item.name"), String::from("items/a b"))]),
+            variables("/v1/{item.name=items/*}", "/v1/items/a%20b")
+        );
+        assert_eq!(
+            Some(vec![(String::from("parent"), String::from("items/w-rim"))]),
+            variables("/v1/{parent=items/*}/boms", "/v1/items/w-rim/boms")
+        );
+        assert_eq!(
+            Some(vec![(String::from("name"), String::from("operations/a/b"))]),
+            variables("/v1/{name=operations/**}", "/v1/operations/a/b")
+        );
+        assert_eq!(
+            None,
+            variables("/v1/{name=operations/**}", "/v1/operations")
+        );
+        assert_eq!(Some(vec![]), variables("/v1/items", "/v1/items"));
+    }
+
+    #[test]
+    fn rejects_malformed_templates() {
+        assert!("v1/items".parse::<Template>().is_err());
+        assert!("/v1/{name=items/*".parse::<Template>().is_err());
+        assert!("/v1/{name=**}/boms".parse::<Template>().is_err());
+    }
+}
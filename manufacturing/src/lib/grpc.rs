@@ -1,7 +1,18 @@
+pub mod auth;
+pub mod bill_of_materials;
+pub mod iam;
 pub mod item;
+pub mod location;
+pub mod production_order;
+pub mod routing;
 pub mod status;
+pub mod stock_movement;
 pub mod sync;
+pub mod tenant;
 pub mod timestamp;
+pub mod traceability;
+pub mod unit_of_measure;
+pub mod web;
 
 pub mod proto {
     #![allow(clippy::all, clippy::pedantic, clippy::nursery)]
@@ -20,6 +31,34 @@ pub mod proto {
         pub mod manufacturing {
             pub mod v1 {
                 tonic::include_proto!("erponomics.manufacturing.v1");
+
+                pub mod bill_of_materials {
+                    tonic::include_proto!("erponomics.manufacturing.v1.bill_of_materials");
+                }
+
+                pub mod location {
+                    tonic::include_proto!("erponomics.manufacturing.v1.location");
+                }
+
+                pub mod production_order {
+                    tonic::include_proto!("erponomics.manufacturing.v1.production_order");
+                }
+
+                pub mod routing {
+                    tonic::include_proto!("erponomics.manufacturing.v1.routing");
+                }
+
+                pub mod stock_movement {
+                    tonic::include_proto!("erponomics.manufacturing.v1.stock_movement");
+                }
+
+                pub mod traceability {
+                    tonic::include_proto!("erponomics.manufacturing.v1.traceability");
+                }
+
+                pub mod unit_of_measure {
+                    tonic::include_proto!("erponomics.manufacturing.v1.unit_of_measure");
+                }
             }
         }
     }
@@ -0,0 +1,353 @@
+//! Authentication of callers by the JWT bearer tokens issued by Identity Provisioning.
+
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use derive_getters::Getters;
+use http::{header::AUTHORIZATION, HeaderMap, Request, Response};
+use jsonwebtoken::{jwk::JwkSet, DecodingKey, Validation};
+use serde::Deserialize;
+use tonic::{body::BoxBody, codegen::BoxFuture, Status};
+
+use crate::ThisError;
+
+use super::status;
+
+const BEARER_PREFIX: &str = "Bearer ";
+
+/// The services callable without a token, so that clients can discover the API.
+const PUBLIC_PATH_PREFIX: &str = "/grpc.reflection.";
+
+/// The authenticated caller of a request, found in the extensions of the request.
+#[derive(Clone, Debug, PartialEq, Eq, Getters)]
+pub struct Principal {
+    /// The `sub` claim of the token.
+    subject: String,
+    /// The `roles` claim of the token, the roles granted to the caller.
+    roles: Vec<String>,
+    /// The `org` claim of the token, the organization the caller acts for.
+    organization: Option<String>,
+}
+
+/// Verifies tokens against the keys of a JSON Web Key Set.
+#[derive(Clone)]
+pub struct Verifier {
+    keys: JwkSet,
+    issuer: Option<String>,
+    audience: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Claims {
+    sub: String,
+    #[serde(default)]
+    roles: Vec<String>,
+    org: Option<String>,
+}
+
+impl Principal {
+    #[must_use]
+    pub const fn new(subject: String, roles: Vec<String>, organization: Option<String>) -> Self {
+        Self {
+            subject,
+            roles,
+            organization,
+        }
+    }
+}
+
+impl Verifier {
+    /// A verifier of tokens signed by a key of the JSON Web Key Set `jwks`, which are
+    /// rejected unless issued by `issuer` for `audience` where these are given.
+    ///
+    /// # Errors
+    /// If `jwks` is not a JSON Web Key Set.
+    pub fn new(
+        jwks: &[u8],
+        issuer: Option<String>,
+        audience: Option<String>,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            keys: serde_json::from_slice(jwks)?,
+            issuer,
+            audience,
+        })
+    }
+
+    /// The principal `token` was issued to.
+    ///
+    /// # Errors
+    /// If the token is malformed, expired, signed by an unknown key or not issued by the
+    /// configured issuer for the configured audience.
+    pub fn verify(&self, token: &str) -> Result<Principal, Error> {
+        let header = jsonwebtoken::decode_header(token)?;
+        // A set of a single key is used for tokens that do not name theirs.
+        let jwk = match (&header.kid, self.keys.keys.as_slice()) {
+            (Some(kid), _) => self.keys.find(kid),
+            (None, [jwk]) => Some(jwk),
+            (None, _) => None,
+        }
+        .ok_or_else(|| UnknownKeyError::new(header.kid.clone().unwrap_or_default()))?;
+        let key = DecodingKey::from_jwk(jwk)?;
+
+        let mut validation = Validation::new(header.alg);
+        if let Some(issuer) = &self.issuer {
+            validation.set_issuer(&[issuer]);
+        }
+        match &self.audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+        validation.set_required_spec_claims(&["exp", "sub"]);
+
+        let claims = jsonwebtoken::decode::<Claims>(token, &key, &validation)?.claims;
+
+        Ok(Principal::new(claims.sub, claims.roles, claims.org))
+    }
+
+    /// The principal of the bearer token in `headers`.
+    ///
+    /// # Errors
+    /// If there is no bearer token or it does not verify.
+    pub fn authenticate(&self, headers: &HeaderMap) -> Result<Principal, Error> {
+        let token = headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix(BEARER_PREFIX))
+            .ok_or(MissingTokenError)?;
+
+        self.verify(token.trim())
+    }
+}
+
+impl std::fmt::Debug for Verifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Verifier")
+            .field("keys", &self.keys.keys.len())
+            .field("issuer", &self.issuer)
+            .field("audience", &self.audience)
+            .finish()
+    }
+}
+
+/// Rejects requests without a valid bearer token, and gives the others their [`Principal`].
+#[derive(Clone, Debug)]
+pub struct Layer {
+    verifier: Arc<Verifier>,
+}
+
+impl Layer {
+    #[must_use]
+    pub fn new(verifier: Verifier) -> Self {
+        Self {
+            verifier: Arc::new(verifier),
+        }
+    }
+}
+
+impl<S> tower::Layer<S> for Layer {
+    type Service = Service<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Service {
+            inner,
+            verifier: self.verifier.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Service<S> {
+    inner: S,
+    verifier: Arc<Verifier>,
+}
+
+impl<S> tower::Service<Request<BoxBody>> for Service<S>
+where
+    S: tower::Service<Request<BoxBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<BoxBody>) -> Self::Future {
+        if !request.uri().path().starts_with(PUBLIC_PATH_PREFIX) {
+            match self.verifier.authenticate(request.headers()) {
+                Ok(principal) => {
+                    request.extensions_mut().insert(principal);
+                }
+                Err(err) => {
+                    let response = Status::from(err).into_http();
+                    return Box::pin(async { Ok(response) });
+                }
+            }
+        }
+
+        Box::pin(self.inner.call(request))
+    }
+}
+
+// MARK: Errors
+
+#[derive(Debug, ThisError)]
+pub enum Error {
+    #[error(transparent)]
+    MissingToken(#[from] MissingTokenError),
+    #[error(transparent)]
+    UnknownKey(#[from] UnknownKeyError),
+    #[error("token is invalid: {0}")]
+    InvalidToken(#[from] jsonwebtoken::errors::Error),
+    #[error(transparent)]
+    Jwks(#[from] serde_json::Error),
+}
+
+#[derive(Clone, Debug, ThisError)]
+#[error("request has no bearer token")]
+pub struct MissingTokenError;
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("token is signed by unknown key {kid:?}")]
+pub struct UnknownKeyError {
+    kid: String,
+}
+
+impl UnknownKeyError {
+    #[must_use]
+    pub const fn new(kid: String) -> Self {
+        Self { kid }
+    }
+}
+
+impl From<Error> for Status {
+    fn from(value: Error) -> Self {
+        match value {
+            Error::MissingToken(err) => status::unauthenticated("MISSING_TOKEN", err.to_string()),
+            Error::UnknownKey(err) => status::unauthenticated("UNKNOWN_KEY", err.to_string()),
+            Error::InvalidToken(err) => status::unauthenticated("INVALID_TOKEN", err.to_string()),
+            Error::Jwks(err) => Self::internal(err.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::HeaderValue;
+    use jsonwebtoken::{EncodingKey, Header};
+    use serde_json::json;
+    use tonic::Code;
+    use tower::{service_fn, Layer as _, ServiceExt as _};
+
+    use super::*;
+
+    const SECRET: &[u8] = b"manufacturing-test-secret";
+
+    fn verifier() -> anyhow::Result<Verifier> {
+        let jwks = json!({ "keys": [{
+            "kty": "oct",
+            "kid": "test",
+            "alg": "HS256",
+            "k": "bWFudWZhY3R1cmluZy10ZXN0LXNlY3JldA",
+        }] });
+
+        Ok(Verifier::new(
+            &serde_json::to_vec(&jwks)?,
+            Some(String::from("https://identity.erponomics.com")),
+            None,
+        )?)
+    }
+
+    fn token(kid: &str, issuer: &str, expires_in: i64) -> anyhow::Result<String> {
+        let header = Header {
+            kid: Some(kid.to_string()),
+            ..Header::default()
+        };
+        let claims = json!({
+            "sub": "user-1",
+            "roles": ["roles/manufacturing.itemViewer"],
+            "org": "acme",
+            "iss": issuer,
+            "exp": chrono::Utc::now().timestamp() + expires_in,
+        });
+
+        Ok(jsonwebtoken::encode(
+            &header,
+            &claims,
+            &EncodingKey::from_secret(SECRET),
+        )?)
+    }
+
+    #[test]
+    fn verifies_tokens() -> anyhow::Result<()> {
+        let verifier = verifier()?;
+        let issuer = "https://identity.erponomics.com";
+
+        let principal = verifier.verify(&token("test", issuer, 60)?)?;
+        assert_eq!("user-1", principal.subject());
+        assert_eq!(
+            &["roles/manufacturing.itemViewer"],
+            principal.roles().as_slice()
+        );
+
+        assert!(matches!(
+            verifier.verify(&token("other", issuer, 60)?),
+            Err(Error::UnknownKey(_))
+        ));
+        assert!(matches!(
+            verifier.verify(&token("test", "https://example.com", 60)?),
+            Err(Error::InvalidToken(_))
+        ));
+        assert!(matches!(
+            verifier.verify(&token("test", issuer, -600)?),
+            Err(Error::InvalidToken(_))
+        ));
+        assert!(matches!(
+            verifier.authenticate(&HeaderMap::new()),
+            Err(Error::MissingToken(_))
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn gives_requests_their_principal() -> anyhow::Result<()> {
+        async fn subject(request: Request<BoxBody>) -> Result<Response<BoxBody>, Infallible> {
+            let subject = request
+                .extensions()
+                .get::<Principal>()
+                .map(|principal| principal.subject().clone())
+                .unwrap_or_default();
+
+            Ok(Response::new(tonic::body::boxed(subject)))
+        }
+        let service = Layer::new(verifier()?).layer(service_fn(subject));
+
+        let mut request = Request::new(tonic::body::empty_body());
+        *request.uri_mut() = "/erponomics.manufacturing.v1.ItemService/GetItem".parse()?;
+        let response = service.clone().oneshot(request).await?;
+        let status = Status::from_header_map(response.headers());
+        assert_eq!(
+            Some(Code::Unauthenticated),
+            status.map(|status| status.code())
+        );
+
+        let mut request = Request::new(tonic::body::empty_body());
+        *request.uri_mut() = "/erponomics.manufacturing.v1.ItemService/GetItem".parse()?;
+        let token = token("test", "https://identity.erponomics.com", 60)?;
+        request.headers_mut().insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("{BEARER_PREFIX}{token}"))?,
+        );
+        let response = service.oneshot(request).await?;
+        let body = http_body_util::BodyExt::collect(response.into_body()).await?;
+        assert_eq!("user-1", body.to_bytes());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,435 @@
+use std::sync::Arc;
+
+use prost::Message;
+use prost_types::Any;
+use tonic::{Request, Response, Status};
+
+use crate::{
+    bill_of_materials::{
+        bill_of_materials_name,
+        command::{self, Activate, ComponentRequest, Create},
+        query::{self, Explode, Get, List},
+        sync::{self, Metadata, Verb},
+        EmptyError, Error, InvalidNameError,
+    },
+    grpc::proto::google::longrunning::Operation,
+    proto::bill_of_materials::{
+        self as proto, bill_of_materials_command_service_server::BillOfMaterialsCommandService,
+        bill_of_materials_query_service_server::BillOfMaterialsQueryService,
+        explode_bill_of_materials_response, ActivateBillOfMaterialsRequest,
+        ActivateBillOfMaterialsResponse, CreateBillOfMaterialsRequest,
+        ExplodeBillOfMaterialsRequest, ExplodeBillOfMaterialsResponse, GetBillOfMaterialsRequest,
+        ListBillsOfMaterialsRequest, ListBillsOfMaterialsResponse, OperationMetadata,
+    },
+    BillOfMaterials, BillOfMaterialsState, Component,
+};
+
+use super::{
+    item::{item_id, item_name},
+    proto::google::rpc,
+    status,
+    unit_of_measure::unit_of_measure_id,
+};
+
+const BILL_OF_MATERIALS_RESOURCE_TYPE: &str = "manufacturing.erponomics.com/BillOfMaterials";
+
+#[derive(Debug, Clone)]
+pub struct CommandService<BCS: Create + Activate + Clone> {
+    bill_of_materials_command_service: Arc<BCS>,
+}
+
+#[derive(Debug, Clone)]
+pub struct QueryService<BQS: Get + List + Explode + Clone> {
+    bill_of_materials_query_service: Arc<BQS>,
+}
+
+impl From<BillOfMaterials> for proto::BillOfMaterials {
+    fn from(value: BillOfMaterials) -> Self {
+        let name = value.name();
+        let (
+            _,
+            _,
+            version,
+            display_name,
+            description,
+            components,
+            state,
+            etag,
+            uid,
+            create_time,
+            update_time,
+        ) = value.dissolve();
+
+        Self {
+            name,
+            display_name,
+            description,
+            version,
+            components: components.into_iter().map(Into::into).collect(),
+            state: proto::bill_of_materials::State::from(state).into(),
+            etag: etag.to_string(),
+            uid: uid.to_string(),
+            create_time: create_time.into(),
+            update_time: update_time.into(),
+        }
+    }
+}
+
+impl From<Component> for proto::bill_of_materials::Component {
+    fn from(value: Component) -> Self {
+        let (item, quantity, unit_of_measure, scrap_factor) = value.dissolve();
+
+        Self {
+            item: item_name(&item),
+            quantity: quantity.to_string(),
+            unit_of_measure,
+            scrap_factor: scrap_factor.to_string(),
+        }
+    }
+}
+
+impl From<BillOfMaterialsState> for proto::bill_of_materials::State {
+    fn from(value: BillOfMaterialsState) -> Self {
+        match value {
+            BillOfMaterialsState::Creating => Self::Creating,
+            BillOfMaterialsState::Activating => Self::Activating,
+            BillOfMaterialsState::Draft => Self::Draft,
+            BillOfMaterialsState::Active => Self::Active,
+            BillOfMaterialsState::Obsolete => Self::Obsolete,
+        }
+    }
+}
+
+impl From<Error> for Status {
+    fn from(value: Error) -> Self {
+        match value {
+            Error::NotFound(err) => status::not_found(
+                BILL_OF_MATERIALS_RESOURCE_TYPE,
+                &bill_of_materials_name(err.item(), err.id()),
+                err.to_string(),
+            ),
+            Error::AlreadyExists(err) => status::already_exists(
+                BILL_OF_MATERIALS_RESOURCE_TYPE,
+                &bill_of_materials_name(err.item(), err.id()),
+                err.to_string(),
+            ),
+            Error::EtagMismatch(err) => status::etag_mismatch(
+                &bill_of_materials_name(err.item(), err.id()),
+                err.to_string(),
+            ),
+            Error::InvalidState(err) => status::invalid_state(
+                &bill_of_materials_name(err.item(), err.id()),
+                err.state().name(),
+                err.to_string(),
+            ),
+            Error::InvalidName(err) => status::bad_request("name", err.to_string()),
+            Error::NoComponents(err) => {
+                status::bad_request("bill_of_materials.components", err.to_string())
+            }
+            Error::InvalidQuantity(err) => status::bad_request("quantity", err.to_string()),
+            Error::InvalidScrapFactor(err) => {
+                status::bad_request("bill_of_materials.components.scrap_factor", err.to_string())
+            }
+            Error::EmptyUnitOfMeasure(err) => status::bad_request(
+                "bill_of_materials.components.unit_of_measure",
+                err.to_string(),
+            ),
+            Error::ItemNotActive(err) => {
+                status::invalid_state(&item_name(err.id()), err.state().name(), err.to_string())
+            }
+            Error::Cycle(err) => {
+                status::bad_request("bill_of_materials.components", err.to_string())
+            }
+            Error::Item(err) => err.into(),
+            Error::UnitOfMeasure(err) => err.into(),
+            Error::Quantity(err) => status::bad_request("quantity", err.to_string()),
+            Error::Unknown(err) => Self::unknown(err.to_string()),
+            Error::Timestamp(err) => Self::internal(err.to_string()),
+            Error::Etag(err) => status::bad_request("etag", err.to_string()),
+            Error::Id(err) => err.into(),
+            Error::Empty(err) => status::bad_request("bill_of_materials", err.to_string()),
+            Error::Filter(err) => status::bad_request("filter", err.to_string()),
+            Error::OrderBy(err) => status::bad_request("order_by", err.to_string()),
+            Error::PageToken(err) => status::bad_request("page_token", err.to_string()),
+            Error::Operation(err) => err.into(),
+        }
+    }
+}
+
+impl From<Error> for rpc::Status {
+    fn from(value: Error) -> Self {
+        let status = Status::from(value);
+
+        // Statuses built with error details carry their full `google.rpc.Status` encoding.
+        Self::decode(status.details()).unwrap_or_else(|_| Self {
+            code: status.code().into(),
+            message: status.message().to_string(),
+            details: vec![],
+        })
+    }
+}
+
+impl From<Metadata> for Option<Any> {
+    fn from(value: Metadata) -> Self {
+        Some(Any::from(&value))
+    }
+}
+
+impl From<&Metadata> for Any {
+    fn from(value: &Metadata) -> Self {
+        Self::from_msg(&OperationMetadata {
+            create_time: Some(value.create_time().clone().into()),
+            target: value.bill_of_materials().name(),
+            verb: value.verb().as_str().to_string(),
+        })
+        .unwrap_or_else(|_| Self::default())
+    }
+}
+
+impl From<sync::Response> for Any {
+    fn from(value: sync::Response) -> Self {
+        let (bill_of_materials, verb) = value.dissolve();
+        let bill_of_materials = proto::BillOfMaterials::from(bill_of_materials);
+
+        let encoded = match verb {
+            Verb::Create => Self::from_msg(&bill_of_materials),
+            Verb::Activate => Self::from_msg(&ActivateBillOfMaterialsResponse {
+                bill_of_materials: Some(bill_of_materials),
+            }),
+        };
+
+        encoded.unwrap_or_else(|_| Self::default())
+    }
+}
+
+/// Split a bill of materials name into the ids of its item and itself.
+fn parse_name(name: String) -> Result<(String, String), Error> {
+    let ids = name
+        .strip_prefix("items/")
+        .and_then(|name| name.split_once("/boms/"))
+        .map(|(item, id)| (item.to_string(), id.to_string()));
+
+    ids.ok_or_else(|| InvalidNameError::new(name).into())
+}
+
+impl<BCS> CommandService<BCS>
+where
+    BCS: Create + Activate + Clone,
+{
+    pub const fn new(bill_of_materials_command_service: Arc<BCS>) -> Self {
+        Self {
+            bill_of_materials_command_service,
+        }
+    }
+}
+
+impl<BQS> QueryService<BQS>
+where
+    BQS: Get + List + Explode + Clone,
+{
+    pub const fn new(bill_of_materials_query_service: Arc<BQS>) -> Self {
+        Self {
+            bill_of_materials_query_service,
+        }
+    }
+}
+
+// MARK: Service
+
+#[tonic::async_trait]
+impl<BCS> BillOfMaterialsCommandService for CommandService<BCS>
+where
+    BCS: Create + Activate + Clone,
+{
+    async fn create_bill_of_materials(
+        &self,
+        request: Request<CreateBillOfMaterialsRequest>,
+    ) -> Result<Response<Operation>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let operation = self
+            .bill_of_materials_command_service
+            .create(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(operation.into()))
+    }
+
+    async fn activate_bill_of_materials(
+        &self,
+        request: Request<ActivateBillOfMaterialsRequest>,
+    ) -> Result<Response<Operation>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let operation = self
+            .bill_of_materials_command_service
+            .activate(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(operation.into()))
+    }
+}
+
+#[tonic::async_trait]
+impl<BQS> BillOfMaterialsQueryService for QueryService<BQS>
+where
+    BQS: Get + List + Explode + Clone,
+{
+    async fn get_bill_of_materials(
+        &self,
+        request: Request<GetBillOfMaterialsRequest>,
+    ) -> Result<Response<proto::BillOfMaterials>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let bill_of_materials = self
+            .bill_of_materials_query_service
+            .get(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(bill_of_materials.into()))
+    }
+
+    async fn list_bills_of_materials(
+        &self,
+        request: Request<ListBillsOfMaterialsRequest>,
+    ) -> Result<Response<ListBillsOfMaterialsResponse>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let response = self
+            .bill_of_materials_query_service
+            .list(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(response.into()))
+    }
+
+    async fn explode_bill_of_materials(
+        &self,
+        request: Request<ExplodeBillOfMaterialsRequest>,
+    ) -> Result<Response<ExplodeBillOfMaterialsResponse>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let response = self
+            .bill_of_materials_query_service
+            .explode(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(response.into()))
+    }
+}
+
+impl TryFrom<Request<CreateBillOfMaterialsRequest>> for command::CreateRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<CreateBillOfMaterialsRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        match value.bill_of_materials {
+            None => Err(EmptyError.into()),
+            Some(bill_of_materials) => Ok(Self::new(
+                item_id(&value.parent).to_string(),
+                Some(value.bill_of_materials_id).filter(|id| !id.is_empty()),
+                bill_of_materials.display_name,
+                bill_of_materials.description,
+                bill_of_materials
+                    .components
+                    .into_iter()
+                    .map(|component| {
+                        ComponentRequest::new(
+                            item_id(&component.item).to_string(),
+                            component.quantity,
+                            unit_of_measure_id(&component.unit_of_measure).to_string(),
+                            component.scrap_factor,
+                        )
+                    })
+                    .collect(),
+            )),
+        }
+    }
+}
+
+impl TryFrom<Request<ActivateBillOfMaterialsRequest>> for command::ActivateRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<ActivateBillOfMaterialsRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        let (item, id) = parse_name(value.name)?;
+        Ok(Self::new(item, id, value.etag))
+    }
+}
+
+impl TryFrom<Request<GetBillOfMaterialsRequest>> for query::GetRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<GetBillOfMaterialsRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        let (item, id) = parse_name(value.name)?;
+        Ok(Self::new(item, id))
+    }
+}
+
+impl TryFrom<Request<ListBillsOfMaterialsRequest>> for query::ListRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<ListBillsOfMaterialsRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        Ok(Self::new(
+            item_id(&value.parent).to_string(),
+            Some(value.page_size),
+            Some(value.page_token),
+            Some(value.order_by),
+            Some(value.filter),
+        ))
+    }
+}
+
+impl TryFrom<Request<ExplodeBillOfMaterialsRequest>> for query::ExplodeRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<ExplodeBillOfMaterialsRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        let (item, id) = parse_name(value.name)?;
+        Ok(Self::new(item, id, value.quantity))
+    }
+}
+
+impl From<query::ListResponse> for ListBillsOfMaterialsResponse {
+    fn from(value: query::ListResponse) -> Self {
+        let (bills_of_materials, next_page_token, total_size) = value.dissolve();
+
+        Self {
+            bills_of_materials: bills_of_materials
+                .into_iter()
+                .map(BillOfMaterials::into)
+                .collect(),
+            next_page_token: next_page_token.unwrap_or_default(),
+            total_size,
+        }
+    }
+}
+
+impl From<query::ExplodeResponse> for ExplodeBillOfMaterialsResponse {
+    fn from(value: query::ExplodeResponse) -> Self {
+        let lines = value.dissolve().into_iter().map(|line| {
+            let (level, item, quantity, unit_of_measure, bill_of_materials) = line.dissolve();
+
+            explode_bill_of_materials_response::Line {
+                level,
+                bill_of_materials: bill_of_materials
+                    .map(|id| bill_of_materials_name(&item, &id))
+                    .unwrap_or_default(),
+                item: item_name(&item),
+                quantity: quantity.to_string(),
+                unit_of_measure,
+            }
+        });
+
+        Self {
+            lines: lines.collect(),
+        }
+    }
+}
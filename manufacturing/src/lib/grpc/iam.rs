@@ -0,0 +1,254 @@
+//! Authorization of callers by the permissions their roles grant.
+
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use derive_getters::Getters;
+use tonic::{Request, Status};
+
+use crate::ThisError;
+
+use super::{auth::Principal, status};
+
+/// A permission on items or on the operations changing them, checked before the RPC that
+/// needs it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Permission {
+    Get,
+    List,
+    Create,
+    Update,
+    Delete,
+    Block,
+    Unblock,
+    Annihilate,
+    GetOperation,
+    ListOperations,
+    CancelOperation,
+    DeleteOperation,
+}
+
+/// The permissions granted by each role, e.g. by `roles/manufacturing.itemViewer`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Policy {
+    roles: HashMap<String, HashSet<Permission>>,
+}
+
+impl Permission {
+    pub const ALL: [Self; 12] = [
+        Self::Get,
+        Self::List,
+        Self::Create,
+        Self::Update,
+        Self::Delete,
+        Self::Block,
+        Self::Unblock,
+        Self::Annihilate,
+        Self::GetOperation,
+        Self::ListOperations,
+        Self::CancelOperation,
+        Self::DeleteOperation,
+    ];
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Get => "manufacturing.items.get",
+            Self::List => "manufacturing.items.list",
+            Self::Create => "manufacturing.items.create",
+            Self::Update => "manufacturing.items.update",
+            Self::Delete => "manufacturing.items.delete",
+            Self::Block => "manufacturing.items.block",
+            Self::Unblock => "manufacturing.items.unblock",
+            Self::Annihilate => "manufacturing.items.annihilate",
+            Self::GetOperation => "manufacturing.operations.get",
+            Self::ListOperations => "manufacturing.operations.list",
+            Self::CancelOperation => "manufacturing.operations.cancel",
+            Self::DeleteOperation => "manufacturing.operations.delete",
+        }
+    }
+}
+
+impl FromStr for Permission {
+    type Err = UnknownPermissionError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|permission| permission.name() == name)
+            .ok_or_else(|| UnknownPermissionError::new(name.to_string()))
+    }
+}
+
+impl Policy {
+    /// The policy of the roles in `json`, an object of the permission names of each role.
+    ///
+    /// # Errors
+    /// If `json` is not such an object or names an unknown permission.
+    pub fn new(json: &[u8]) -> Result<Self, Error> {
+        let roles: HashMap<String, Vec<String>> = serde_json::from_slice(json)?;
+        let roles = roles
+            .into_iter()
+            .map(|(role, permissions)| {
+                let permissions = permissions
+                    .iter()
+                    .map(|name| name.parse())
+                    .collect::<Result<_, _>>()?;
+                Ok((role, permissions))
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Self { roles })
+    }
+
+    /// Whether any role of `principal` grants `permission`.
+    #[must_use]
+    pub fn permits(&self, principal: &Principal, permission: Permission) -> bool {
+        principal.roles().iter().any(|role| {
+            self.roles
+                .get(role)
+                .is_some_and(|permissions| permissions.contains(&permission))
+        })
+    }
+
+    /// # Errors
+    /// If no role of `principal` grants `permission`.
+    pub fn check(&self, principal: &Principal, permission: Permission) -> Result<(), Error> {
+        if self.permits(principal, permission) {
+            Ok(())
+        } else {
+            Err(PermissionDeniedError::new(permission).into())
+        }
+    }
+}
+
+/// # Errors
+/// If `policy` restricts callers and the caller of `request` lacks `permission`, which a
+/// caller that is not authenticated always does. Callers are only unrestricted without a policy.
+pub fn authorize<T>(
+    policy: Option<&Policy>,
+    request: &Request<T>,
+    permission: Permission,
+) -> Result<(), Error> {
+    let Some(policy) = policy else {
+        return Ok(());
+    };
+
+    request.extensions().get::<Principal>().map_or_else(
+        || Err(PermissionDeniedError::new(permission).into()),
+        |principal| policy.check(principal, permission),
+    )
+}
+
+// MARK: Errors
+
+#[derive(Debug, ThisError)]
+pub enum Error {
+    #[error(transparent)]
+    PermissionDenied(#[from] PermissionDeniedError),
+    #[error(transparent)]
+    UnknownPermission(#[from] UnknownPermissionError),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("permission {} is denied", permission.name())]
+pub struct PermissionDeniedError {
+    permission: Permission,
+}
+
+impl PermissionDeniedError {
+    #[must_use]
+    pub const fn new(permission: Permission) -> Self {
+        Self { permission }
+    }
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("permission {name:?} does not exist")]
+pub struct UnknownPermissionError {
+    name: String,
+}
+
+impl UnknownPermissionError {
+    #[must_use]
+    pub const fn new(name: String) -> Self {
+        Self { name }
+    }
+}
+
+impl From<Error> for Status {
+    fn from(value: Error) -> Self {
+        match value {
+            Error::PermissionDenied(err) => {
+                status::permission_denied(err.permission().name(), err.to_string())
+            }
+            err => Self::internal(err.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grants_the_permissions_of_roles() -> anyhow::Result<()> {
+        let policy = Policy::new(
+            br#"{
+                "roles/manufacturing.itemViewer": ["manufacturing.items.get", "manufacturing.items.list"],
+                "roles/manufacturing.itemBlocker": ["manufacturing.items.block"]
+            }"#,
+        )?;
+        let viewer = Principal::new(
+            String::from("viewer"),
+            vec![String::from("roles/manufacturing.itemViewer")],
+            None,
+        );
+        let nobody = Principal::new(String::from("nobody"), vec![], None);
+
+        assert!(policy.check(&viewer, Permission::Get).is_ok());
+        assert!(policy.check(&viewer, Permission::List).is_ok());
+        assert!(matches!(
+            policy.check(&viewer, Permission::Block),
+            Err(Error::PermissionDenied(_))
+        ));
+        assert!(!policy.permits(&nobody, Permission::Get));
+
+        Ok(())
+    }
+
+    #[test]
+    fn denies_callers_without_principal_under_a_policy() -> anyhow::Result<()> {
+        let policy = Policy::new(br#"{"roles/owner": ["manufacturing.items.get"]}"#)?;
+        let mut request = Request::new(());
+
+        assert!(authorize(None, &request, Permission::Annihilate).is_ok());
+        assert!(matches!(
+            authorize(Some(&policy), &request, Permission::Get),
+            Err(Error::PermissionDenied(_))
+        ));
+
+        request.extensions_mut().insert(Principal::new(
+            String::from("owner"),
+            vec![String::from("roles/owner")],
+            None,
+        ));
+        assert!(authorize(Some(&policy), &request, Permission::Get).is_ok());
+        assert!(authorize(Some(&policy), &request, Permission::CancelOperation).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_unknown_permissions() {
+        assert!(matches!(
+            Policy::new(br#"{"roles/owner": ["manufacturing.items.explode"]}"#),
+            Err(Error::UnknownPermission(_))
+        ));
+        assert!("manufacturing.items.annihilate"
+            .parse::<Permission>()
+            .is_ok());
+    }
+}
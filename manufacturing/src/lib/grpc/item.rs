@@ -1,50 +1,111 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use anyhow::anyhow;
+use prost::Message;
 use prost_types::Any;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status};
 
 use crate::{
     entity_tag,
     grpc::proto::google::longrunning::Operation,
     item::{
-        command::{self, Block, Create, Delete, Unblock, Update},
-        query::{self, Get, List},
-        sync::Metadata,
+        command::{self, Annihilate, Block, Create, Delete, Rollback, Unblock, Undelete, Update},
+        query::{self, Get, List, ListEvents, ListRevisions},
+        sync::{Metadata, Verb},
         EmptyError, Error,
     },
     proto::{
-        self, item_service_server::ItemService, BlockItemRequest, CreateItemRequest,
-        DeleteItemRequest, GetItemRequest, ListItemsRequest, ListItemsResponse, UnblockItemRequest,
-        UpdateItemRequest,
+        self, item_service_server::ItemService, AnnihilateItemRequest, BlockItemRequest,
+        CreateItemRequest, DeleteItemRequest, GetItemRequest, ListItemRevisionsRequest,
+        ListItemRevisionsResponse, ListItemsRequest, ListItemsResponse, RollbackItemRequest,
+        TestIamPermissionsRequest, TestIamPermissionsResponse, UnblockItemRequest,
+        UndeleteItemRequest, UpdateItemRequest, WatchItemsRequest,
     },
-    Item, ItemState,
+    sync::OperationEntity,
+    unit_of_measure::unit_of_measure_name,
+    FieldMask, Id, Item, ItemEvent, ItemEventKind, ItemRevision, ItemState, Tenant, Tracking,
 };
 
-use super::proto::google::rpc;
+use super::{
+    auth::Principal,
+    iam::{self, Permission, Policy},
+    proto::google::rpc,
+    status,
+    unit_of_measure::unit_of_measure_id,
+};
+
+/// The resource name of the collection of items.
+const ITEM_COLLECTION: &str = "items";
+
+const ITEM_RESOURCE_TYPE: &str = "manufacturing.erponomics.com/Item";
+
+const ITEM_MUTABLE_FIELDS: &[&str] = &["display_name", "title", "description"];
+
+const ITEM_IMMUTABLE_FIELDS: &[&str] = &[
+    "name",
+    "base_uom",
+    "tracking",
+    "state",
+    "etag",
+    "uid",
+    "create_time",
+    "update_time",
+    "delete_time",
+    "expire_time",
+];
+
+/// How long a watch waits for new item events once it has caught up.
+const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How many item events a watch buffers for a client that reads slower than they are fetched.
+const WATCH_BUFFER_SIZE: usize = 64;
 
 #[derive(Debug, Clone)]
-pub struct Service<ICS: Create + Update + Delete + Block + Unblock + Clone, IQS: Get + List + Clone>
-{
+#[allow(clippy::struct_field_names)]
+pub struct Service<
+    ICS: Create + Update + Delete + Undelete + Annihilate + Block + Unblock + Rollback + Clone,
+    IQS: Get + List + ListRevisions + ListEvents + Clone,
+> {
     item_command_service: Arc<ICS>,
     item_query_service: Arc<IQS>,
+    /// The roles of callers, without which they are not restricted.
+    policy: Option<Arc<Policy>>,
 }
 
 impl From<Item> for proto::Item {
     fn from(value: Item) -> Self {
-        let (id, display_name, title, description, state, etag, uid, create_time, update_time) =
-            value.dissolve();
+        let (
+            id,
+            display_name,
+            title,
+            description,
+            state,
+            etag,
+            uid,
+            create_time,
+            update_time,
+            base_uom,
+            tracking,
+            delete_time,
+            expire_time,
+        ) = value.dissolve();
 
         Self {
-            name: id.to_string(),
+            name: item_name(&id),
             display_name: display_name.into(),
             title: title.into(),
             description: description.into(),
+            base_uom: unit_of_measure_name(&base_uom).into(),
+            tracking: Some(proto::item::Tracking::from(tracking) as i32),
             state: state.into(),
             etag: etag.to_string().into(),
             uid: uid.to_string().into(),
             create_time: create_time.into(),
             update_time: update_time.into(),
+            delete_time: delete_time.map(Into::into),
+            expire_time: expire_time.map(Into::into),
         }
     }
 }
@@ -70,8 +131,73 @@ impl From<ItemState> for proto::item::State {
             ItemState::Annihilating => Self::Annihilating,
             ItemState::Blocking => Self::Blocking,
             ItemState::Unblocking => Self::Unblocking,
+            ItemState::Undeleting => Self::Undeleting,
             ItemState::Active => Self::Active,
             ItemState::Blocked => Self::Blocked,
+            ItemState::Deleted => Self::Deleted,
+        }
+    }
+}
+
+impl From<Tracking> for proto::item::Tracking {
+    fn from(value: Tracking) -> Self {
+        match value {
+            Tracking::None => Self::None,
+            Tracking::Lot => Self::Lot,
+            Tracking::Serial => Self::Serial,
+        }
+    }
+}
+
+/// The tracking an item is created with, none if unspecified.
+fn tracking(value: Option<i32>) -> Tracking {
+    match value.map(proto::item::Tracking::try_from) {
+        Some(Ok(proto::item::Tracking::Lot)) => Tracking::Lot,
+        Some(Ok(proto::item::Tracking::Serial)) => Tracking::Serial,
+        _ => Tracking::None,
+    }
+}
+
+impl From<ItemRevision> for proto::ItemRevision {
+    fn from(value: ItemRevision) -> Self {
+        let (item, id, operation, actor, create_time) = value.dissolve();
+
+        Self {
+            name: item_revision_name(item.id(), &id),
+            revision_id: id.into(),
+            etag: item.etag().to_string(),
+            item: Some(item.into()),
+            actor,
+            operation: operation.into(),
+            revision_create_time: create_time.into(),
+        }
+    }
+}
+
+impl From<ItemEventKind> for proto::item_event::Kind {
+    fn from(value: ItemEventKind) -> Self {
+        match value {
+            ItemEventKind::Created => Self::Created,
+            ItemEventKind::Updated => Self::Updated,
+            ItemEventKind::Deleted => Self::Deleted,
+            ItemEventKind::Annihilated => Self::Annihilated,
+            ItemEventKind::Blocked => Self::Blocked,
+            ItemEventKind::Unblocked => Self::Unblocked,
+            ItemEventKind::Undeleted => Self::Undeleted,
+        }
+    }
+}
+
+impl From<ItemEvent> for proto::ItemEvent {
+    fn from(value: ItemEvent) -> Self {
+        let (sequence, item, kind, snapshot, create_time) = value.dissolve();
+
+        Self {
+            sequence,
+            kind: proto::item_event::Kind::from(kind).into(),
+            name: item_name(&item),
+            item: snapshot.and_then(|s| s.to_msg().ok()),
+            create_time: create_time.into(),
         }
     }
 }
@@ -85,11 +211,35 @@ impl From<ItemState> for Option<i32> {
 impl From<Error> for Status {
     fn from(value: Error) -> Self {
         match value {
+            Error::NotFound(err) => {
+                status::not_found(ITEM_RESOURCE_TYPE, &item_name(err.id()), err.to_string())
+            }
+            Error::RevisionNotFound(err) => status::not_found(
+                ITEM_RESOURCE_TYPE,
+                &item_revision_name(err.id(), err.revision()),
+                err.to_string(),
+            ),
+            Error::AlreadyExists(err) => {
+                status::already_exists(ITEM_RESOURCE_TYPE, &item_name(err.id()), err.to_string())
+            }
+            Error::EtagMismatch(err) => {
+                status::etag_mismatch(&item_name(err.id()), err.to_string())
+            }
+            Error::InvalidState(err) => {
+                status::invalid_state(&item_name(err.id()), err.state().name(), err.to_string())
+            }
+            Error::InUse(err) => status::in_use(&item_name(err.id()), err.to_string()),
             Error::Unknown(err) => Self::unknown(err.to_string()),
-            Error::Timestamp(err) => Self::invalid_argument(err.to_string()),
-            Error::Etag(err) => Self::invalid_argument(err.to_string()),
-            Error::Id(err) => Self::invalid_argument(err.to_string()),
-            Error::Empty(err) => Self::invalid_argument(err.to_string()),
+            Error::Timestamp(err) => Self::internal(err.to_string()),
+            Error::Etag(err) => status::bad_request("etag", err.to_string()),
+            Error::UnitOfMeasure(err) => err.into(),
+            Error::Id(err) => err.into(),
+            Error::Empty(err) => status::bad_request("item", err.to_string()),
+            Error::FieldMask(err) => status::bad_request("update_mask", err.to_string()),
+            Error::Filter(err) => status::bad_request("filter", err.to_string()),
+            Error::OrderBy(err) => status::bad_request("order_by", err.to_string()),
+            Error::PageToken(err) => status::bad_request("page_token", err.to_string()),
+            Error::Operation(err) => err.into(),
         }
     }
 }
@@ -104,33 +254,118 @@ impl From<Error> for rpc::Status {
     fn from(value: Error) -> Self {
         let status = Status::from(value);
 
-        Self {
+        // Statuses built with error details carry their full `google.rpc.Status` encoding.
+        Self::decode(status.details()).unwrap_or_else(|_| Self {
             code: status.code().into(),
             message: status.message().to_string(),
             details: vec![],
-        }
+        })
     }
 }
 
 impl From<Metadata> for Option<Any> {
     fn from(value: Metadata) -> Self {
-        let item = value.dissolve();
+        Some(Any::from(&value))
+    }
+}
 
-        Any::from_msg(&proto::Item::from(item)).ok()
+impl From<&Metadata> for Any {
+    fn from(value: &Metadata) -> Self {
+        let create_time = Some(value.create_time().clone().into());
+        let target = value.item().name();
+        let verb = value.verb().as_str().to_string();
+
+        let encoded = match value.verb() {
+            Verb::Create => Self::from_msg(&proto::CreateItemMetadata {
+                create_time,
+                target,
+                verb,
+            }),
+            Verb::Update => Self::from_msg(&proto::UpdateItemMetadata {
+                create_time,
+                target,
+                verb,
+            }),
+            Verb::Delete => Self::from_msg(&proto::DeleteItemMetadata {
+                create_time,
+                target,
+                verb,
+            }),
+            Verb::Annihilate => Self::from_msg(&proto::AnnihilateItemMetadata {
+                create_time,
+                target,
+                verb,
+            }),
+            Verb::Block => Self::from_msg(&proto::BlockItemMetadata {
+                create_time,
+                target,
+                verb,
+            }),
+            Verb::Unblock => Self::from_msg(&proto::UnblockItemMetadata {
+                create_time,
+                target,
+                verb,
+            }),
+            Verb::Undelete => Self::from_msg(&proto::UndeleteItemMetadata {
+                create_time,
+                target,
+                verb,
+            }),
+        };
+
+        encoded.unwrap_or_else(|_| Self::default())
     }
 }
 
+/// The resource name of the item `id`, within the organization of the current tenant if
+/// there is one.
+pub(super) fn item_name(id: &Id) -> String {
+    Tenant::qualify(&format!("items/{id}"))
+}
+
+fn item_revision_name(id: &Id, revision: &Id) -> String {
+    format!("{}@{revision}", item_name(id))
+}
+
+/// The caller a change is recorded for, the subject of the request's principal. Without
+/// authentication, changes are recorded without one.
+fn actor<T>(request: &Request<T>) -> String {
+    request
+        .extensions()
+        .get::<Principal>()
+        .map(|principal| principal.subject().clone())
+        .unwrap_or_default()
+}
+
+/// The id of an item referenced by other resources, given either by its
+/// resource name or by its bare id.
+pub(super) fn item_id(name: &str) -> &str {
+    let name = Tenant::unqualify(name);
+    name.strip_prefix("items/").unwrap_or(name)
+}
+
 impl<ICS, IQS> Service<ICS, IQS>
 where
-    ICS: Create + Update + Delete + Block + Unblock + Clone,
-    IQS: Get + List + Clone,
+    ICS: Create + Update + Delete + Undelete + Annihilate + Block + Unblock + Rollback + Clone,
+    IQS: Get + List + ListRevisions + ListEvents + Clone,
 {
-    pub const fn new(item_command_service: Arc<ICS>, item_query_service: Arc<IQS>) -> Self {
+    pub const fn new(
+        item_command_service: Arc<ICS>,
+        item_query_service: Arc<IQS>,
+        policy: Option<Arc<Policy>>,
+    ) -> Self {
         Self {
             item_command_service,
             item_query_service,
+            policy,
         }
     }
+
+    /// # Errors
+    /// If the caller of `request` lacks `permission`, see [`iam::authorize`].
+    fn authorize<T>(&self, request: &Request<T>, permission: Permission) -> Result<(), iam::Error> {
+        iam::authorize(self.policy.as_deref(), request, permission)
+    }
 }
 
 // MARK: Service
@@ -138,13 +373,15 @@ where
 #[tonic::async_trait]
 impl<ICS, IQS> ItemService for Service<ICS, IQS>
 where
-    ICS: Create + Update + Delete + Block + Unblock + Clone,
-    IQS: Get + List + Clone,
+    ICS: Create + Update + Delete + Undelete + Annihilate + Block + Unblock + Rollback + Clone,
+    IQS: Get + List + ListRevisions + ListEvents + Clone,
 {
     async fn create_item(
         &self,
         request: Request<CreateItemRequest>,
     ) -> Result<Response<Operation>, Status> {
+        self.authorize(&request, Permission::Create)?;
+
         let request = request.try_into().map_err(Status::from)?;
 
         let operation = self
@@ -160,82 +397,127 @@ where
         &self,
         request: Request<UpdateItemRequest>,
     ) -> Result<Response<Operation>, Status> {
+        self.authorize(&request, Permission::Update)?;
+
         let request = request.try_into().map_err(Status::from)?;
 
-        self.item_command_service
+        let operation = self
+            .item_command_service
             .update(request)
             .await
             .map_err(Status::from)?;
 
-        Ok(Response::new(Operation {
-            name: String::new(),
-            metadata: None,
-            done: false,
-            result: None,
-        }))
+        Ok(Response::new(operation.into()))
     }
 
     async fn delete_item(
         &self,
         request: Request<DeleteItemRequest>,
     ) -> Result<Response<Operation>, Status> {
+        self.authorize(&request, Permission::Delete)?;
+
         let request = request.try_into().map_err(Status::from)?;
 
-        self.item_command_service
+        let operation = self
+            .item_command_service
             .delete(request)
             .await
             .map_err(Status::from)?;
 
-        Ok(Response::new(Operation {
-            name: String::new(),
-            metadata: None,
-            done: false,
-            result: None,
-        }))
+        Ok(Response::new(operation.into()))
+    }
+
+    async fn undelete_item(
+        &self,
+        request: Request<UndeleteItemRequest>,
+    ) -> Result<Response<Operation>, Status> {
+        self.authorize(&request, Permission::Delete)?;
+
+        let request = request.try_into().map_err(Status::from)?;
+
+        let operation = self
+            .item_command_service
+            .undelete(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(operation.into()))
+    }
+
+    async fn annihilate_item(
+        &self,
+        request: Request<AnnihilateItemRequest>,
+    ) -> Result<Response<Operation>, Status> {
+        self.authorize(&request, Permission::Annihilate)?;
+
+        let request = request.try_into().map_err(Status::from)?;
+
+        let operation = self
+            .item_command_service
+            .annihilate(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(operation.into()))
     }
 
     async fn block_item(
         &self,
         request: Request<BlockItemRequest>,
     ) -> Result<Response<Operation>, Status> {
+        self.authorize(&request, Permission::Block)?;
+
         let request = request.try_into().map_err(Status::from)?;
 
-        self.item_command_service
+        let operation = self
+            .item_command_service
             .block(request)
             .await
             .map_err(Status::from)?;
 
-        Ok(Response::new(Operation {
-            name: String::new(),
-            metadata: None,
-            done: false,
-            result: None,
-        }))
+        Ok(Response::new(operation.into()))
     }
 
     async fn unblock_item(
         &self,
         request: Request<UnblockItemRequest>,
     ) -> Result<Response<Operation>, Status> {
+        self.authorize(&request, Permission::Unblock)?;
+
         let request = request.try_into().map_err(Status::from)?;
 
-        self.item_command_service
+        let operation = self
+            .item_command_service
             .unblock(request)
             .await
             .map_err(Status::from)?;
 
-        Ok(Response::new(Operation {
-            name: String::new(),
-            metadata: None,
-            done: false,
-            result: None,
-        }))
+        Ok(Response::new(operation.into()))
+    }
+
+    async fn rollback_item(
+        &self,
+        request: Request<RollbackItemRequest>,
+    ) -> Result<Response<Operation>, Status> {
+        self.authorize(&request, Permission::Update)?;
+
+        let request = request.try_into().map_err(Status::from)?;
+
+        let operation = self
+            .item_command_service
+            .rollback(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(operation.into()))
     }
 
     async fn get_item(
         &self,
         request: Request<GetItemRequest>,
     ) -> Result<Response<proto::Item>, Status> {
+        self.authorize(&request, Permission::Get)?;
+
         let request = request.try_into().map_err(Status::from)?;
 
         let item = self
@@ -251,6 +533,8 @@ where
         &self,
         request: Request<ListItemsRequest>,
     ) -> Result<Response<ListItemsResponse>, Status> {
+        self.authorize(&request, Permission::List)?;
+
         let request = request.try_into().map_err(Status::from)?;
 
         let response = self
@@ -261,12 +545,111 @@ where
 
         Ok(Response::new(response.into()))
     }
+
+    async fn list_item_revisions(
+        &self,
+        request: Request<ListItemRevisionsRequest>,
+    ) -> Result<Response<ListItemRevisionsResponse>, Status> {
+        self.authorize(&request, Permission::Get)?;
+
+        let request = request.try_into().map_err(Status::from)?;
+
+        let response = self
+            .item_query_service
+            .list_revisions(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(response.into()))
+    }
+
+    async fn test_iam_permissions(
+        &self,
+        request: Request<TestIamPermissionsRequest>,
+    ) -> Result<Response<TestIamPermissionsResponse>, Status> {
+        let resource = &request.get_ref().resource;
+        let relative = Tenant::unqualify(resource);
+        if relative != ITEM_COLLECTION && !relative.starts_with("items/") {
+            return Err(status::bad_request(
+                "resource",
+                format!("resource {resource:?} is neither an item nor the items"),
+            ));
+        }
+
+        let permissions = request
+            .get_ref()
+            .permissions
+            .iter()
+            .filter(|name| {
+                name.parse()
+                    .is_ok_and(|permission| self.authorize(&request, permission).is_ok())
+            })
+            .cloned()
+            .collect();
+
+        Ok(Response::new(TestIamPermissionsResponse { permissions }))
+    }
+
+    type WatchItemsStream = ReceiverStream<Result<proto::ItemEvent, Status>>;
+
+    async fn watch_items(
+        &self,
+        request: Request<WatchItemsRequest>,
+    ) -> Result<Response<Self::WatchItemsStream>, Status> {
+        self.authorize(&request, Permission::List)?;
+
+        let after_sequence = request.into_inner().after_sequence;
+        let (sender, receiver) = mpsc::channel(WATCH_BUFFER_SIZE);
+
+        tokio::spawn(Tenant::scope(
+            Tenant::current(),
+            watch(self.item_query_service.clone(), after_sequence, sender),
+        ));
+
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+}
+
+/// Forward the item events after `after_sequence` to `sender`, polling for new ones until the
+/// client goes away or the events cannot be fetched.
+async fn watch<IQS: ListEvents>(
+    item_query_service: Arc<IQS>,
+    mut after_sequence: i64,
+    sender: mpsc::Sender<Result<proto::ItemEvent, Status>>,
+) {
+    loop {
+        let request = query::ListEventsRequest::new(after_sequence, None);
+        let events = match item_query_service.list_events(request).await {
+            Ok(events) => events,
+            Err(err) => {
+                // The client learns why its stream ends, unless it is gone already.
+                let _ = sender.send(Err(err.into())).await;
+                return;
+            }
+        };
+
+        if events.is_empty() {
+            if sender.is_closed() {
+                return;
+            }
+            tokio::time::sleep(WATCH_POLL_INTERVAL).await;
+            continue;
+        }
+
+        for event in events {
+            after_sequence = *event.sequence();
+            if sender.send(Ok(event.into())).await.is_err() {
+                return;
+            }
+        }
+    }
 }
 
 impl TryFrom<Request<CreateItemRequest>> for command::CreateRequest {
     type Error = Error;
 
     fn try_from(value: Request<CreateItemRequest>) -> Result<Self, Self::Error> {
+        let actor = actor(&value);
         let value = value.into_inner();
         match value.item {
             None => Err(EmptyError.into()),
@@ -275,6 +658,9 @@ impl TryFrom<Request<CreateItemRequest>> for command::CreateRequest {
                 item.display_name.unwrap_or(String::new()),
                 item.title.unwrap_or(String::new()),
                 item.description.unwrap_or(String::new()),
+                unit_of_measure_id(&item.base_uom.unwrap_or_default()).to_string(),
+                tracking(item.tracking),
+                actor,
             )),
         }
     }
@@ -284,17 +670,38 @@ impl TryFrom<Request<UpdateItemRequest>> for command::UpdateRequest {
     type Error = Error;
 
     fn try_from(value: Request<UpdateItemRequest>) -> Result<Self, Self::Error> {
+        let actor = actor(&value);
         let value = value.into_inner();
         match value.item {
             None => Err(EmptyError.into()),
-            Some(item) => Ok(Self::new(
-                item.name,
-                item.display_name,
-                item.title,
-                item.description,
-                item.etag
-                    .ok_or(Error::Etag(entity_tag::EmptyError.into()))?,
-            )),
+            Some(item) => {
+                let etag = item
+                    .etag
+                    .ok_or(Error::Etag(entity_tag::EmptyError.into()))?;
+                let paths = value.update_mask.map(|m| m.paths).unwrap_or_default();
+
+                if paths.is_empty() {
+                    return Ok(Self::new(
+                        item_id(&item.name).to_string(),
+                        item.display_name,
+                        item.title,
+                        item.description,
+                        etag,
+                        actor,
+                    ));
+                }
+
+                let mask = FieldMask::new(paths, ITEM_MUTABLE_FIELDS, ITEM_IMMUTABLE_FIELDS)?;
+
+                Ok(Self::new(
+                    item_id(&item.name).to_string(),
+                    mask.select("display_name", item.display_name),
+                    mask.select("title", item.title),
+                    mask.select("description", item.description),
+                    etag,
+                    actor,
+                ))
+            }
         }
     }
 }
@@ -303,8 +710,41 @@ impl TryFrom<Request<DeleteItemRequest>> for command::DeleteRequest {
     type Error = Error;
 
     fn try_from(value: Request<DeleteItemRequest>) -> Result<Self, Self::Error> {
+        let actor = actor(&value);
         let value = value.into_inner();
-        Ok(Self::new(value.name, value.etag))
+        Ok(Self::new(
+            item_id(&value.name).to_string(),
+            value.etag,
+            actor,
+        ))
+    }
+}
+
+impl TryFrom<Request<UndeleteItemRequest>> for command::UndeleteRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<UndeleteItemRequest>) -> Result<Self, Self::Error> {
+        let actor = actor(&value);
+        let value = value.into_inner();
+        Ok(Self::new(
+            item_id(&value.name).to_string(),
+            value.etag,
+            actor,
+        ))
+    }
+}
+
+impl TryFrom<Request<AnnihilateItemRequest>> for command::AnnihilateRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<AnnihilateItemRequest>) -> Result<Self, Self::Error> {
+        let actor = actor(&value);
+        let value = value.into_inner();
+        Ok(Self::new(
+            item_id(&value.name).to_string(),
+            value.etag,
+            actor,
+        ))
     }
 }
 
@@ -312,8 +752,13 @@ impl TryFrom<Request<BlockItemRequest>> for command::BlockRequest {
     type Error = Error;
 
     fn try_from(value: Request<BlockItemRequest>) -> Result<Self, Self::Error> {
+        let actor = actor(&value);
         let value = value.into_inner();
-        Ok(Self::new(value.name, value.etag))
+        Ok(Self::new(
+            item_id(&value.name).to_string(),
+            value.etag,
+            actor,
+        ))
     }
 }
 
@@ -321,8 +766,28 @@ impl TryFrom<Request<UnblockItemRequest>> for command::UnblockRequest {
     type Error = Error;
 
     fn try_from(value: Request<UnblockItemRequest>) -> Result<Self, Self::Error> {
+        let actor = actor(&value);
+        let value = value.into_inner();
+        Ok(Self::new(
+            item_id(&value.name).to_string(),
+            value.etag,
+            actor,
+        ))
+    }
+}
+
+impl TryFrom<Request<RollbackItemRequest>> for command::RollbackRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<RollbackItemRequest>) -> Result<Self, Self::Error> {
+        let actor = actor(&value);
         let value = value.into_inner();
-        Ok(Self::new(value.name, value.etag))
+        Ok(Self::new(
+            item_id(&value.name).to_string(),
+            value.revision_id,
+            value.etag,
+            actor,
+        ))
     }
 }
 
@@ -331,7 +796,24 @@ impl TryFrom<Request<GetItemRequest>> for query::GetRequest {
 
     fn try_from(value: Request<GetItemRequest>) -> Result<Self, Self::Error> {
         let value = value.into_inner();
-        Ok(Self::new(value.name))
+        let name = item_id(&value.name);
+        match name.split_once('@') {
+            Some((id, revision)) => Ok(Self::new(id.to_string(), Some(revision.to_string()))),
+            None => Ok(Self::new(name.to_string(), None)),
+        }
+    }
+}
+
+impl TryFrom<Request<ListItemRevisionsRequest>> for query::ListRevisionsRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<ListItemRevisionsRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        Ok(Self::new(
+            item_id(&value.name).to_string(),
+            value.page_size,
+            value.page_token,
+        ))
     }
 }
 
@@ -345,10 +827,22 @@ impl TryFrom<Request<ListItemsRequest>> for query::ListRequest {
             value.page_token,
             value.order_by,
             value.filter,
+            value.show_deleted,
         ))
     }
 }
 
+impl From<query::ListRevisionsResponse> for ListItemRevisionsResponse {
+    fn from(value: query::ListRevisionsResponse) -> Self {
+        let (revisions, next_page_token) = value.dissolve();
+
+        Self {
+            revisions: revisions.into_iter().map(ItemRevision::into).collect(),
+            next_page_token,
+        }
+    }
+}
+
 impl From<query::ListResponse> for ListItemsResponse {
     fn from(value: query::ListResponse) -> Self {
         let (items, next_page_token, total_size) = value.dissolve();
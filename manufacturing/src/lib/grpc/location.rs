@@ -0,0 +1,267 @@
+use std::sync::Arc;
+
+use tonic::{Request, Response, Status};
+
+use crate::{
+    location::{
+        command::{self, Create, Update},
+        location_name,
+        query::{self, Get, List},
+        EmptyError, Error,
+    },
+    proto::location::{
+        self as proto, location_command_service_server::LocationCommandService,
+        location_query_service_server::LocationQueryService, CreateLocationRequest,
+        GetLocationRequest, ListLocationsRequest, ListLocationsResponse, UpdateLocationRequest,
+    },
+    FieldMask, Location,
+};
+
+use super::status;
+
+const LOCATION_RESOURCE_TYPE: &str = "manufacturing.erponomics.com/Location";
+
+const LOCATION_MUTABLE_FIELDS: &[&str] = &["display_name", "allow_negative_stock"];
+
+const LOCATION_IMMUTABLE_FIELDS: &[&str] = &["name", "etag", "uid", "create_time", "update_time"];
+
+#[derive(Debug, Clone)]
+pub struct CommandService<LCS: Create + Update + Clone> {
+    location_command_service: Arc<LCS>,
+}
+
+#[derive(Debug, Clone)]
+pub struct QueryService<LQS: Get + List + Clone> {
+    location_query_service: Arc<LQS>,
+}
+
+impl From<Location> for proto::Location {
+    fn from(value: Location) -> Self {
+        let name = value.name();
+        let (_, display_name, allow_negative_stock, etag, uid, create_time, update_time) =
+            value.dissolve();
+
+        Self {
+            name,
+            display_name,
+            allow_negative_stock,
+            etag: etag.to_string(),
+            uid: uid.to_string(),
+            create_time: create_time.into(),
+            update_time: update_time.into(),
+        }
+    }
+}
+
+impl From<Error> for Status {
+    fn from(value: Error) -> Self {
+        match value {
+            Error::NotFound(err) => status::not_found(
+                LOCATION_RESOURCE_TYPE,
+                &location_name(err.id()),
+                err.to_string(),
+            ),
+            Error::AlreadyExists(err) => status::already_exists(
+                LOCATION_RESOURCE_TYPE,
+                &location_name(err.id()),
+                err.to_string(),
+            ),
+            Error::EtagMismatch(err) => {
+                status::etag_mismatch(&location_name(err.id()), err.to_string())
+            }
+            Error::Unknown(err) => Self::unknown(err.to_string()),
+            Error::Timestamp(err) => Self::internal(err.to_string()),
+            Error::Etag(err) => status::bad_request("etag", err.to_string()),
+            Error::Id(err) => err.into(),
+            Error::Empty(err) => status::bad_request("location", err.to_string()),
+            Error::FieldMask(err) => status::bad_request("update_mask", err.to_string()),
+            Error::Filter(err) => status::bad_request("filter", err.to_string()),
+            Error::OrderBy(err) => status::bad_request("order_by", err.to_string()),
+            Error::PageToken(err) => status::bad_request("page_token", err.to_string()),
+        }
+    }
+}
+
+/// The id of a location, given either by its resource name or by its bare id.
+pub(super) fn location_id(name: &str) -> &str {
+    name.strip_prefix("locations/").unwrap_or(name)
+}
+
+impl<LCS> CommandService<LCS>
+where
+    LCS: Create + Update + Clone,
+{
+    pub const fn new(location_command_service: Arc<LCS>) -> Self {
+        Self {
+            location_command_service,
+        }
+    }
+}
+
+impl<LQS> QueryService<LQS>
+where
+    LQS: Get + List + Clone,
+{
+    pub const fn new(location_query_service: Arc<LQS>) -> Self {
+        Self {
+            location_query_service,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl<LCS> LocationCommandService for CommandService<LCS>
+where
+    LCS: Create + Update + Clone,
+{
+    async fn create_location(
+        &self,
+        request: Request<CreateLocationRequest>,
+    ) -> Result<Response<proto::Location>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let location = self
+            .location_command_service
+            .create(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(location.into()))
+    }
+
+    async fn update_location(
+        &self,
+        request: Request<UpdateLocationRequest>,
+    ) -> Result<Response<proto::Location>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let location = self
+            .location_command_service
+            .update(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(location.into()))
+    }
+}
+
+#[tonic::async_trait]
+impl<LQS> LocationQueryService for QueryService<LQS>
+where
+    LQS: Get + List + Clone,
+{
+    async fn get_location(
+        &self,
+        request: Request<GetLocationRequest>,
+    ) -> Result<Response<proto::Location>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let location = self
+            .location_query_service
+            .get(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(location.into()))
+    }
+
+    async fn list_locations(
+        &self,
+        request: Request<ListLocationsRequest>,
+    ) -> Result<Response<ListLocationsResponse>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let response = self
+            .location_query_service
+            .list(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(response.into()))
+    }
+}
+
+impl TryFrom<Request<CreateLocationRequest>> for command::CreateRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<CreateLocationRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        match value.location {
+            None => Err(EmptyError.into()),
+            Some(location) => Ok(Self::new(
+                Some(value.location_id).filter(|id| !id.is_empty()),
+                location.display_name,
+                location.allow_negative_stock,
+            )),
+        }
+    }
+}
+
+impl TryFrom<Request<UpdateLocationRequest>> for command::UpdateRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<UpdateLocationRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        match value.location {
+            None => Err(EmptyError.into()),
+            Some(location) => {
+                let id = location_id(&location.name).to_string();
+                let paths = value.update_mask.map(|m| m.paths).unwrap_or_default();
+
+                if paths.is_empty() {
+                    return Ok(Self::new(
+                        id,
+                        Some(location.display_name),
+                        Some(location.allow_negative_stock),
+                        location.etag,
+                    ));
+                }
+
+                let mask =
+                    FieldMask::new(paths, LOCATION_MUTABLE_FIELDS, LOCATION_IMMUTABLE_FIELDS)?;
+
+                Ok(Self::new(
+                    id,
+                    mask.select("display_name", Some(location.display_name)),
+                    mask.select("allow_negative_stock", Some(location.allow_negative_stock)),
+                    location.etag,
+                ))
+            }
+        }
+    }
+}
+
+impl TryFrom<Request<GetLocationRequest>> for query::GetRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<GetLocationRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        Ok(Self::new(location_id(&value.name).to_string()))
+    }
+}
+
+impl TryFrom<Request<ListLocationsRequest>> for query::ListRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<ListLocationsRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        Ok(Self::new(
+            Some(value.page_size),
+            Some(value.page_token),
+            Some(value.order_by),
+            Some(value.filter),
+        ))
+    }
+}
+
+impl From<query::ListResponse> for ListLocationsResponse {
+    fn from(value: query::ListResponse) -> Self {
+        let (locations, next_page_token, total_size) = value.dissolve();
+
+        Self {
+            locations: locations.into_iter().map(Location::into).collect(),
+            next_page_token: next_page_token.unwrap_or_default(),
+            total_size,
+        }
+    }
+}
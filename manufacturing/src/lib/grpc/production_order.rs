@@ -0,0 +1,742 @@
+use std::sync::Arc;
+
+use prost::Message;
+use prost_types::Any;
+use tonic::{Request, Response, Status};
+
+use crate::{
+    grpc::proto::google::longrunning::Operation,
+    production_order::{
+        command::{
+            self, Cancel, Complete, CompleteOperation, Create, Delete, Finish, Release,
+            ReportOperation, StartOperation, Update,
+        },
+        query::{self, Get, List},
+        sync::{self, Metadata, Verb},
+        EmptyError, Error, InvalidOperationNameError,
+    },
+    proto::production_order::{
+        self as proto, production_order_command_service_server::ProductionOrderCommandService,
+        production_order_query_service_server::ProductionOrderQueryService,
+        CancelProductionOrderRequest, CancelProductionOrderResponse,
+        CompleteProductionOrderOperationRequest, CompleteProductionOrderRequest,
+        CompleteProductionOrderResponse, CreateProductionOrderRequest,
+        DeleteProductionOrderRequest, FinishProductionOrderRequest, FinishProductionOrderResponse,
+        GetProductionOrderRequest, ListProductionOrdersRequest, ListProductionOrdersResponse,
+        OperationMetadata, ReleaseProductionOrderRequest, ReleaseProductionOrderResponse,
+        ReportProductionOrderOperationRequest, StartProductionOrderOperationRequest,
+        UpdateProductionOrderRequest,
+    },
+    sync::OperationEntity,
+    FieldMask, Id, ProductionOrder, ProductionOrderOperation, ProductionOrderOperationState,
+    ProductionOrderState,
+};
+
+use super::{
+    item::{item_id, item_name},
+    proto::google::rpc,
+    routing::duration,
+    status,
+};
+
+const PRODUCTION_ORDER_RESOURCE_TYPE: &str = "manufacturing.erponomics.com/ProductionOrder";
+
+const PRODUCTION_ORDER_OPERATION_RESOURCE_TYPE: &str =
+    "manufacturing.erponomics.com/ProductionOrderOperation";
+
+const PRODUCTION_ORDER_MUTABLE_FIELDS: &[&str] =
+    &["display_name", "title", "description", "quantity"];
+
+const PRODUCTION_ORDER_IMMUTABLE_FIELDS: &[&str] = &[
+    "name",
+    "item",
+    "operations",
+    "state",
+    "etag",
+    "uid",
+    "create_time",
+    "update_time",
+];
+
+#[derive(Debug, Clone)]
+pub struct CommandService<
+    PCS: Create
+        + Update
+        + Delete
+        + Release
+        + Complete
+        + Finish
+        + Cancel
+        + StartOperation
+        + ReportOperation
+        + CompleteOperation
+        + Clone,
+> {
+    production_order_command_service: Arc<PCS>,
+}
+
+#[derive(Debug, Clone)]
+pub struct QueryService<PQS: Get + List + Clone> {
+    production_order_query_service: Arc<PQS>,
+}
+
+impl From<ProductionOrder> for proto::ProductionOrder {
+    fn from(value: ProductionOrder) -> Self {
+        let (
+            id,
+            display_name,
+            title,
+            description,
+            item,
+            quantity,
+            operations,
+            state,
+            etag,
+            uid,
+            create_time,
+            update_time,
+        ) = value.dissolve();
+
+        Self {
+            operations: operations
+                .into_iter()
+                .map(|operation| {
+                    let mut operation = proto::production_order::Operation::from(operation);
+                    operation.name = operation_name(&id, &operation.name);
+                    operation
+                })
+                .collect(),
+            name: id.to_string(),
+            display_name,
+            title,
+            description,
+            item: item.as_ref().map(item_name).unwrap_or_default(),
+            quantity: quantity.to_string(),
+            state: proto::production_order::State::from(state).into(),
+            etag: etag.to_string(),
+            uid: uid.to_string(),
+            create_time: create_time.into(),
+            update_time: update_time.into(),
+        }
+    }
+}
+
+/// Converts an operation, leaving its bare id as the name for the production order to qualify.
+impl From<ProductionOrderOperation> for proto::production_order::Operation {
+    fn from(value: ProductionOrderOperation) -> Self {
+        let (
+            id,
+            work_center,
+            setup_time,
+            run_time,
+            state,
+            reported_quantity,
+            start_time,
+            complete_time,
+        ) = value.dissolve();
+
+        Self {
+            name: id.to_string(),
+            work_center,
+            setup_time: duration(setup_time),
+            run_time: duration(run_time),
+            state: proto::production_order::operation::State::from(state).into(),
+            reported_quantity: reported_quantity.to_string(),
+            start_time: start_time.map(Into::into),
+            complete_time: complete_time.map(Into::into),
+        }
+    }
+}
+
+impl From<ProductionOrderOperationState> for proto::production_order::operation::State {
+    fn from(value: ProductionOrderOperationState) -> Self {
+        match value {
+            ProductionOrderOperationState::Pending => Self::Pending,
+            ProductionOrderOperationState::Started => Self::Started,
+            ProductionOrderOperationState::Completed => Self::Completed,
+        }
+    }
+}
+
+impl From<ProductionOrder> for Any {
+    fn from(value: ProductionOrder) -> Self {
+        Self::from_msg(&proto::ProductionOrder::from(value)).unwrap_or_else(|_| Self::default())
+    }
+}
+
+impl From<ProductionOrderState> for proto::production_order::State {
+    fn from(value: ProductionOrderState) -> Self {
+        match value {
+            ProductionOrderState::Creating => Self::Creating,
+            ProductionOrderState::Updating => Self::Updating,
+            ProductionOrderState::Deleting => Self::Deleting,
+            ProductionOrderState::Releasing => Self::Releasing,
+            ProductionOrderState::Completing => Self::Completing,
+            ProductionOrderState::Finishing => Self::Finishing,
+            ProductionOrderState::Cancelling => Self::Cancelling,
+            ProductionOrderState::Planned => Self::Planned,
+            ProductionOrderState::Released => Self::Released,
+            ProductionOrderState::Completed => Self::Completed,
+            ProductionOrderState::Finished => Self::Finished,
+            ProductionOrderState::Cancelled => Self::Cancelled,
+        }
+    }
+}
+
+impl From<Error> for Status {
+    fn from(value: Error) -> Self {
+        match value {
+            Error::NotFound(err) => status::not_found(
+                PRODUCTION_ORDER_RESOURCE_TYPE,
+                &production_order_name(err.id()),
+                err.to_string(),
+            ),
+            Error::AlreadyExists(err) => status::already_exists(
+                PRODUCTION_ORDER_RESOURCE_TYPE,
+                &production_order_name(err.id()),
+                err.to_string(),
+            ),
+            Error::EtagMismatch(err) => {
+                status::etag_mismatch(&production_order_name(err.id()), err.to_string())
+            }
+            Error::InvalidState(err) => status::invalid_state(
+                &production_order_name(err.id()),
+                err.state().name(),
+                err.to_string(),
+            ),
+            Error::InvalidQuantity(err) => {
+                status::bad_request("production_order.quantity", err.to_string())
+            }
+            Error::Quantity(err) => {
+                status::bad_request("production_order.quantity", err.to_string())
+            }
+            Error::ItemNotActive(err) => {
+                status::invalid_state(&item_name(err.id()), err.state().name(), err.to_string())
+            }
+            Error::OperationNotFound(err) => status::not_found(
+                PRODUCTION_ORDER_OPERATION_RESOURCE_TYPE,
+                &operation_name(err.id(), err.operation()),
+                err.to_string(),
+            ),
+            Error::InvalidOperationState(err) => status::invalid_state(
+                &operation_name(err.id(), err.operation()),
+                err.state().name(),
+                err.to_string(),
+            ),
+            Error::OperationsPending(err) => status::invalid_state(
+                &production_order_name(err.id()),
+                ProductionOrderState::Released.name(),
+                err.to_string(),
+            ),
+            Error::InvalidOperationName(err) => status::bad_request("name", err.to_string()),
+            Error::Item(err) => err.into(),
+            Error::Routing(err) => err.into(),
+            Error::Unknown(err) => Self::unknown(err.to_string()),
+            Error::Timestamp(err) => Self::internal(err.to_string()),
+            Error::Etag(err) => status::bad_request("etag", err.to_string()),
+            Error::Id(err) => err.into(),
+            Error::Empty(err) => status::bad_request("production_order", err.to_string()),
+            Error::FieldMask(err) => status::bad_request("update_mask", err.to_string()),
+            Error::Filter(err) => status::bad_request("filter", err.to_string()),
+            Error::OrderBy(err) => status::bad_request("order_by", err.to_string()),
+            Error::PageToken(err) => status::bad_request("page_token", err.to_string()),
+            Error::Operation(err) => err.into(),
+        }
+    }
+}
+
+impl From<Error> for rpc::Status {
+    fn from(value: Error) -> Self {
+        let status = Status::from(value);
+
+        // Statuses built with error details carry their full `google.rpc.Status` encoding.
+        Self::decode(status.details()).unwrap_or_else(|_| Self {
+            code: status.code().into(),
+            message: status.message().to_string(),
+            details: vec![],
+        })
+    }
+}
+
+impl From<Metadata> for Option<Any> {
+    fn from(value: Metadata) -> Self {
+        Some(Any::from(&value))
+    }
+}
+
+impl From<&Metadata> for Any {
+    fn from(value: &Metadata) -> Self {
+        Self::from_msg(&OperationMetadata {
+            create_time: Some(value.create_time().clone().into()),
+            target: value.production_order().name(),
+            verb: value.verb().as_str().to_string(),
+        })
+        .unwrap_or_else(|_| Self::default())
+    }
+}
+
+impl From<sync::Response> for Any {
+    fn from(value: sync::Response) -> Self {
+        let (production_order, verb) = value.dissolve();
+        let production_order = proto::ProductionOrder::from(production_order);
+
+        let encoded = match verb {
+            Verb::Create | Verb::Update | Verb::Delete => Self::from_msg(&production_order),
+            Verb::Release => Self::from_msg(&ReleaseProductionOrderResponse {
+                production_order: Some(production_order),
+            }),
+            Verb::Complete => Self::from_msg(&CompleteProductionOrderResponse {
+                production_order: Some(production_order),
+            }),
+            Verb::Finish => Self::from_msg(&FinishProductionOrderResponse {
+                production_order: Some(production_order),
+            }),
+            Verb::Cancel => Self::from_msg(&CancelProductionOrderResponse {
+                production_order: Some(production_order),
+            }),
+        };
+
+        encoded.unwrap_or_else(|_| Self::default())
+    }
+}
+
+pub(super) fn production_order_name(id: &Id) -> String {
+    format!("productionOrders/{id}")
+}
+
+/// The id of a production order, given either by its resource name or by its bare id.
+pub(super) fn production_order_id(name: &str) -> &str {
+    name.strip_prefix("productionOrders/").unwrap_or(name)
+}
+
+fn operation_name(id: &Id, operation: impl std::fmt::Display) -> String {
+    format!("productionOrders/{id}/operations/{operation}")
+}
+
+/// Split an operation name into the ids of its production order and itself.
+fn parse_operation_name(name: String) -> Result<(String, String), Error> {
+    let ids = name
+        .strip_prefix("productionOrders/")
+        .unwrap_or(&name)
+        .split_once("/operations/")
+        .map(|(id, operation)| (id.to_string(), operation.to_string()));
+
+    ids.ok_or_else(|| InvalidOperationNameError::new(name).into())
+}
+
+impl<PCS> CommandService<PCS>
+where
+    PCS: Create
+        + Update
+        + Delete
+        + Release
+        + Complete
+        + Finish
+        + Cancel
+        + StartOperation
+        + ReportOperation
+        + CompleteOperation
+        + Clone,
+{
+    pub const fn new(production_order_command_service: Arc<PCS>) -> Self {
+        Self {
+            production_order_command_service,
+        }
+    }
+}
+
+impl<PQS> QueryService<PQS>
+where
+    PQS: Get + List + Clone,
+{
+    pub const fn new(production_order_query_service: Arc<PQS>) -> Self {
+        Self {
+            production_order_query_service,
+        }
+    }
+}
+
+// MARK: Service
+
+#[tonic::async_trait]
+impl<PCS> ProductionOrderCommandService for CommandService<PCS>
+where
+    PCS: Create
+        + Update
+        + Delete
+        + Release
+        + Complete
+        + Finish
+        + Cancel
+        + StartOperation
+        + ReportOperation
+        + CompleteOperation
+        + Clone,
+{
+    async fn create_production_order(
+        &self,
+        request: Request<CreateProductionOrderRequest>,
+    ) -> Result<Response<Operation>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let operation = self
+            .production_order_command_service
+            .create(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(operation.into()))
+    }
+
+    async fn update_production_order(
+        &self,
+        request: Request<UpdateProductionOrderRequest>,
+    ) -> Result<Response<Operation>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let operation = self
+            .production_order_command_service
+            .update(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(operation.into()))
+    }
+
+    async fn delete_production_order(
+        &self,
+        request: Request<DeleteProductionOrderRequest>,
+    ) -> Result<Response<Operation>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let operation = self
+            .production_order_command_service
+            .delete(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(operation.into()))
+    }
+
+    async fn release_production_order(
+        &self,
+        request: Request<ReleaseProductionOrderRequest>,
+    ) -> Result<Response<Operation>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let operation = self
+            .production_order_command_service
+            .release(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(operation.into()))
+    }
+
+    async fn complete_production_order(
+        &self,
+        request: Request<CompleteProductionOrderRequest>,
+    ) -> Result<Response<Operation>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let operation = self
+            .production_order_command_service
+            .complete(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(operation.into()))
+    }
+
+    async fn finish_production_order(
+        &self,
+        request: Request<FinishProductionOrderRequest>,
+    ) -> Result<Response<Operation>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let operation = self
+            .production_order_command_service
+            .finish(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(operation.into()))
+    }
+
+    async fn cancel_production_order(
+        &self,
+        request: Request<CancelProductionOrderRequest>,
+    ) -> Result<Response<Operation>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let operation = self
+            .production_order_command_service
+            .cancel(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(operation.into()))
+    }
+
+    async fn start_production_order_operation(
+        &self,
+        request: Request<StartProductionOrderOperationRequest>,
+    ) -> Result<Response<proto::ProductionOrder>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let production_order = self
+            .production_order_command_service
+            .start_operation(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(production_order.into()))
+    }
+
+    async fn report_production_order_operation(
+        &self,
+        request: Request<ReportProductionOrderOperationRequest>,
+    ) -> Result<Response<proto::ProductionOrder>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let production_order = self
+            .production_order_command_service
+            .report_operation(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(production_order.into()))
+    }
+
+    async fn complete_production_order_operation(
+        &self,
+        request: Request<CompleteProductionOrderOperationRequest>,
+    ) -> Result<Response<proto::ProductionOrder>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let production_order = self
+            .production_order_command_service
+            .complete_operation(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(production_order.into()))
+    }
+}
+
+#[tonic::async_trait]
+impl<PQS> ProductionOrderQueryService for QueryService<PQS>
+where
+    PQS: Get + List + Clone,
+{
+    async fn get_production_order(
+        &self,
+        request: Request<GetProductionOrderRequest>,
+    ) -> Result<Response<proto::ProductionOrder>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let production_order = self
+            .production_order_query_service
+            .get(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(production_order.into()))
+    }
+
+    async fn list_production_orders(
+        &self,
+        request: Request<ListProductionOrdersRequest>,
+    ) -> Result<Response<ListProductionOrdersResponse>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let response = self
+            .production_order_query_service
+            .list(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(response.into()))
+    }
+}
+
+impl TryFrom<Request<CreateProductionOrderRequest>> for command::CreateRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<CreateProductionOrderRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        match value.production_order {
+            None => Err(EmptyError.into()),
+            Some(production_order) => Ok(Self::new(
+                Some(value.production_order_id).filter(|id| !id.is_empty()),
+                item_id(&production_order.item).to_string(),
+                production_order.quantity,
+                production_order.display_name,
+                production_order.title,
+                production_order.description,
+            )),
+        }
+    }
+}
+
+impl TryFrom<Request<UpdateProductionOrderRequest>> for command::UpdateRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<UpdateProductionOrderRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        match value.production_order {
+            None => Err(EmptyError.into()),
+            Some(production_order) => {
+                let paths = value.update_mask.map(|m| m.paths).unwrap_or_default();
+
+                // Without a mask, only the fields that were set are replaced.
+                if paths.is_empty() {
+                    let set = |value: String| Some(value).filter(|v| !v.is_empty());
+
+                    return Ok(Self::new(
+                        production_order.name,
+                        set(production_order.display_name),
+                        set(production_order.title),
+                        set(production_order.description),
+                        set(production_order.quantity),
+                        value.etag,
+                    ));
+                }
+
+                let mask = FieldMask::new(
+                    paths,
+                    PRODUCTION_ORDER_MUTABLE_FIELDS,
+                    PRODUCTION_ORDER_IMMUTABLE_FIELDS,
+                )?;
+
+                Ok(Self::new(
+                    production_order.name,
+                    mask.select("display_name", Some(production_order.display_name)),
+                    mask.select("title", Some(production_order.title)),
+                    mask.select("description", Some(production_order.description)),
+                    mask.select("quantity", Some(production_order.quantity)),
+                    value.etag,
+                ))
+            }
+        }
+    }
+}
+
+impl TryFrom<Request<DeleteProductionOrderRequest>> for command::DeleteRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<DeleteProductionOrderRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        Ok(Self::new(value.name, value.etag))
+    }
+}
+
+impl TryFrom<Request<ReleaseProductionOrderRequest>> for command::ReleaseRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<ReleaseProductionOrderRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        Ok(Self::new(value.name, value.etag))
+    }
+}
+
+impl TryFrom<Request<CompleteProductionOrderRequest>> for command::CompleteRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<CompleteProductionOrderRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        Ok(Self::new(value.name, value.etag))
+    }
+}
+
+impl TryFrom<Request<FinishProductionOrderRequest>> for command::FinishRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<FinishProductionOrderRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        Ok(Self::new(value.name, value.etag, value.force))
+    }
+}
+
+impl TryFrom<Request<CancelProductionOrderRequest>> for command::CancelRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<CancelProductionOrderRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        Ok(Self::new(value.name, value.etag, value.force))
+    }
+}
+
+impl TryFrom<Request<StartProductionOrderOperationRequest>> for command::StartOperationRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<StartProductionOrderOperationRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        let (id, operation) = parse_operation_name(value.name)?;
+        Ok(Self::new(id, operation, value.etag))
+    }
+}
+
+impl TryFrom<Request<ReportProductionOrderOperationRequest>> for command::ReportOperationRequest {
+    type Error = Error;
+
+    fn try_from(
+        value: Request<ReportProductionOrderOperationRequest>,
+    ) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        let (id, operation) = parse_operation_name(value.name)?;
+        Ok(Self::new(id, operation, value.quantity, value.etag))
+    }
+}
+
+impl TryFrom<Request<CompleteProductionOrderOperationRequest>>
+    for command::CompleteOperationRequest
+{
+    type Error = Error;
+
+    fn try_from(
+        value: Request<CompleteProductionOrderOperationRequest>,
+    ) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        let (id, operation) = parse_operation_name(value.name)?;
+        Ok(Self::new(id, operation, value.etag))
+    }
+}
+
+impl TryFrom<Request<GetProductionOrderRequest>> for query::GetRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<GetProductionOrderRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        Ok(Self::new(value.name))
+    }
+}
+
+impl TryFrom<Request<ListProductionOrdersRequest>> for query::ListRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<ListProductionOrdersRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        Ok(Self::new(
+            Some(value.page_size),
+            Some(value.page_token),
+            Some(value.order_by),
+            Some(value.filter),
+        ))
+    }
+}
+
+impl From<query::ListResponse> for ListProductionOrdersResponse {
+    fn from(value: query::ListResponse) -> Self {
+        let (production_orders, next_page_token, total_size) = value.dissolve();
+
+        Self {
+            production_orders: production_orders
+                .into_iter()
+                .map(ProductionOrder::into)
+                .collect(),
+            next_page_token: next_page_token.unwrap_or_default(),
+            total_size,
+        }
+    }
+}
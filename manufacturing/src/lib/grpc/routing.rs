@@ -0,0 +1,272 @@
+use std::{sync::Arc, time::Duration};
+
+use prost::Message;
+use prost_types::Any;
+use tonic::{Request, Response, Status};
+
+use crate::{
+    grpc::proto::google::longrunning::Operation,
+    proto::routing::{
+        self as proto, routing_command_service_server::RoutingCommandService,
+        routing_query_service_server::RoutingQueryService, GetRoutingRequest, OperationMetadata,
+        UpdateRoutingRequest,
+    },
+    routing::{
+        command::{self, OperationRequest, Update},
+        query::{self, Get},
+        routing_name,
+        sync::Metadata,
+        EmptyError, Error, InvalidDurationError, InvalidNameError,
+    },
+    Routing, RoutingOperation, RoutingState,
+};
+
+use super::{item::item_name, proto::google::rpc, status};
+
+const ROUTING_RESOURCE_TYPE: &str = "manufacturing.erponomics.com/Routing";
+
+#[derive(Debug, Clone)]
+pub struct CommandService<RCS: Update + Clone> {
+    routing_command_service: Arc<RCS>,
+}
+
+#[derive(Debug, Clone)]
+pub struct QueryService<RQS: Get + Clone> {
+    routing_query_service: Arc<RQS>,
+}
+
+impl From<Routing> for proto::Routing {
+    fn from(value: Routing) -> Self {
+        let name = value.name();
+        let (_, operations, state, etag, uid, create_time, update_time) = value.dissolve();
+
+        Self {
+            name,
+            operations: operations.into_iter().map(Into::into).collect(),
+            state: proto::routing::State::from(state).into(),
+            etag: etag.to_string(),
+            uid: uid.to_string(),
+            create_time: create_time.into(),
+            update_time: update_time.into(),
+        }
+    }
+}
+
+impl From<RoutingOperation> for proto::routing::Operation {
+    fn from(value: RoutingOperation) -> Self {
+        let (work_center, setup_time, run_time) = value.dissolve();
+
+        Self {
+            work_center,
+            setup_time: duration(setup_time),
+            run_time: duration(run_time),
+        }
+    }
+}
+
+impl From<RoutingState> for proto::routing::State {
+    fn from(value: RoutingState) -> Self {
+        match value {
+            RoutingState::Creating => Self::Creating,
+            RoutingState::Updating => Self::Updating,
+            RoutingState::Active => Self::Active,
+        }
+    }
+}
+
+impl From<Error> for Status {
+    fn from(value: Error) -> Self {
+        match value {
+            Error::NotFound(err) => status::not_found(
+                ROUTING_RESOURCE_TYPE,
+                &routing_name(err.item()),
+                err.to_string(),
+            ),
+            Error::EtagMismatch(err) => {
+                status::etag_mismatch(&routing_name(err.item()), err.to_string())
+            }
+            Error::InvalidState(err) => status::invalid_state(
+                &routing_name(err.item()),
+                err.state().name(),
+                err.to_string(),
+            ),
+            Error::InvalidName(err) => status::bad_request("routing.name", err.to_string()),
+            Error::NoOperations(err) => status::bad_request("routing.operations", err.to_string()),
+            Error::EmptyWorkCenter(err) => {
+                status::bad_request("routing.operations.work_center", err.to_string())
+            }
+            Error::InvalidDuration(err) => status::bad_request(err.field(), err.to_string()),
+            Error::ItemNotActive(err) => {
+                status::invalid_state(&item_name(err.id()), err.state().name(), err.to_string())
+            }
+            Error::Item(err) => err.into(),
+            Error::Unknown(err) => Self::unknown(err.to_string()),
+            Error::Timestamp(err) => Self::internal(err.to_string()),
+            Error::Etag(err) => status::bad_request("etag", err.to_string()),
+            Error::Id(err) => err.into(),
+            Error::Empty(err) => status::bad_request("routing", err.to_string()),
+            Error::Operation(err) => err.into(),
+        }
+    }
+}
+
+impl From<Error> for rpc::Status {
+    fn from(value: Error) -> Self {
+        let status = Status::from(value);
+
+        // Statuses built with error details carry their full `google.rpc.Status` encoding.
+        Self::decode(status.details()).unwrap_or_else(|_| Self {
+            code: status.code().into(),
+            message: status.message().to_string(),
+            details: vec![],
+        })
+    }
+}
+
+impl From<Metadata> for Option<Any> {
+    fn from(value: Metadata) -> Self {
+        Some(Any::from(&value))
+    }
+}
+
+impl From<&Metadata> for Any {
+    fn from(value: &Metadata) -> Self {
+        Self::from_msg(&OperationMetadata {
+            create_time: Some(value.create_time().clone().into()),
+            target: value.routing().name(),
+            verb: value.verb().as_str().to_string(),
+        })
+        .unwrap_or_else(|_| Self::default())
+    }
+}
+
+impl From<Routing> for Any {
+    fn from(value: Routing) -> Self {
+        Self::from_msg(&proto::Routing::from(value)).unwrap_or_else(|_| Self::default())
+    }
+}
+
+/// Extract the item id from a routing name.
+fn parse_name(name: String) -> Result<String, Error> {
+    let item = name
+        .strip_prefix("items/")
+        .and_then(|name| name.strip_suffix("/routing"))
+        .filter(|item| !item.contains('/'))
+        .map(ToString::to_string);
+
+    item.ok_or_else(|| InvalidNameError::new(name).into())
+}
+
+pub(crate) fn duration(value: Duration) -> Option<prost_types::Duration> {
+    prost_types::Duration::try_from(value).ok()
+}
+
+/// Convert an optional API duration, treating an absent one as zero.
+pub(crate) fn try_duration(
+    value: Option<prost_types::Duration>,
+    field: &'static str,
+) -> Result<Duration, InvalidDurationError> {
+    value.map_or(Ok(Duration::ZERO), |value| {
+        Duration::try_from(value).map_err(|_| InvalidDurationError::new(field))
+    })
+}
+
+impl<RCS> CommandService<RCS>
+where
+    RCS: Update + Clone,
+{
+    pub const fn new(routing_command_service: Arc<RCS>) -> Self {
+        Self {
+            routing_command_service,
+        }
+    }
+}
+
+impl<RQS> QueryService<RQS>
+where
+    RQS: Get + Clone,
+{
+    pub const fn new(routing_query_service: Arc<RQS>) -> Self {
+        Self {
+            routing_query_service,
+        }
+    }
+}
+
+// MARK: Service
+
+#[tonic::async_trait]
+impl<RCS> RoutingCommandService for CommandService<RCS>
+where
+    RCS: Update + Clone,
+{
+    async fn update_routing(
+        &self,
+        request: Request<UpdateRoutingRequest>,
+    ) -> Result<Response<Operation>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let operation = self
+            .routing_command_service
+            .update(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(operation.into()))
+    }
+}
+
+#[tonic::async_trait]
+impl<RQS> RoutingQueryService for QueryService<RQS>
+where
+    RQS: Get + Clone,
+{
+    async fn get_routing(
+        &self,
+        request: Request<GetRoutingRequest>,
+    ) -> Result<Response<proto::Routing>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let routing = self
+            .routing_query_service
+            .get(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(routing.into()))
+    }
+}
+
+impl TryFrom<Request<UpdateRoutingRequest>> for command::UpdateRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<UpdateRoutingRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        let Some(routing) = value.routing else {
+            return Err(EmptyError.into());
+        };
+
+        let operations = routing
+            .operations
+            .into_iter()
+            .map(|operation| {
+                Ok(OperationRequest::new(
+                    operation.work_center,
+                    try_duration(operation.setup_time, "routing.operations.setup_time")?,
+                    try_duration(operation.run_time, "routing.operations.run_time")?,
+                ))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(Self::new(parse_name(routing.name)?, operations, value.etag))
+    }
+}
+
+impl TryFrom<Request<GetRoutingRequest>> for query::GetRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<GetRoutingRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        Ok(Self::new(parse_name(value.name)?))
+    }
+}
@@ -1,16 +1,22 @@
+use std::collections::HashMap;
+
 use anyhow::anyhow;
 use tonic::{Code, Status};
+use tonic_types::{ErrorDetails, StatusExt};
 
 use crate::id;
 
+/// The domain reported in the `google.rpc.ErrorInfo` of every error.
+pub const ERROR_DOMAIN: &str = "manufacturing.erponomics.com";
+
 impl From<id::Error> for Status {
     fn from(value: id::Error) -> Self {
         match value {
             id::Error::Unknown(err) => Self::unknown(err.to_string()),
             id::Error::NotFound(err) => Self::not_found(err.to_string()),
-            id::Error::Duplicate(err) => Self::invalid_argument(err.to_string()),
-            id::Error::InvalidFormat(err) => Self::invalid_argument(err.to_string()),
-            id::Error::Empty(err) => Self::invalid_argument(err.to_string()),
+            id::Error::Duplicate(err) => Self::already_exists(err.to_string()),
+            id::Error::InvalidFormat(err) => bad_request("name", err.to_string()),
+            id::Error::Empty(err) => bad_request("name", err.to_string()),
         }
     }
 }
@@ -26,3 +32,112 @@ impl TryFrom<Status> for id::Error {
         }
     }
 }
+
+// MARK: Details
+
+/// `INVALID_ARGUMENT` with a `google.rpc.BadRequest` violation for `field`.
+#[must_use]
+pub fn bad_request(field: &str, message: String) -> Status {
+    let mut details = error_info("INVALID_ARGUMENT", &[("field", field)]);
+    details.add_bad_request_violation(field, message.clone());
+
+    Status::with_error_details(Code::InvalidArgument, message, details)
+}
+
+/// `NOT_FOUND` with a `google.rpc.ResourceInfo` describing the missing resource.
+#[must_use]
+pub fn not_found(resource_type: &str, resource_name: &str, message: String) -> Status {
+    let mut details = error_info("RESOURCE_NOT_FOUND", &[("resource", resource_name)]);
+    details.set_resource_info(resource_type, resource_name, "", message.clone());
+
+    Status::with_error_details(Code::NotFound, message, details)
+}
+
+/// `ALREADY_EXISTS` with a `google.rpc.ResourceInfo` describing the conflicting resource.
+#[must_use]
+pub fn already_exists(resource_type: &str, resource_name: &str, message: String) -> Status {
+    let mut details = error_info("RESOURCE_ALREADY_EXISTS", &[("resource", resource_name)]);
+    details.set_resource_info(resource_type, resource_name, "", message.clone());
+
+    Status::with_error_details(Code::AlreadyExists, message, details)
+}
+
+/// `ABORTED` with a `google.rpc.PreconditionFailure` for a stale etag.
+#[must_use]
+pub fn etag_mismatch(resource_name: &str, message: String) -> Status {
+    let mut details = error_info("ETAG_MISMATCH", &[("resource", resource_name)]);
+    details.add_precondition_failure_violation("ETAG", resource_name, message.clone());
+
+    Status::with_error_details(Code::Aborted, message, details)
+}
+
+/// `FAILED_PRECONDITION` with a `google.rpc.PreconditionFailure` for a resource
+/// whose state does not allow the requested change.
+#[must_use]
+pub fn invalid_state(resource_name: &str, state: &str, message: String) -> Status {
+    let mut details = error_info(
+        "INVALID_STATE",
+        &[("resource", resource_name), ("state", state)],
+    );
+    details.add_precondition_failure_violation("STATE", resource_name, message.clone());
+
+    Status::with_error_details(Code::FailedPrecondition, message, details)
+}
+
+/// `FAILED_PRECONDITION` with a `google.rpc.PreconditionFailure` for a resource
+/// that other resources still depend on.
+#[must_use]
+pub fn in_use(resource_name: &str, message: String) -> Status {
+    let mut details = error_info("RESOURCE_IN_USE", &[("resource", resource_name)]);
+    details.add_precondition_failure_violation("REFERENCE", resource_name, message.clone());
+
+    Status::with_error_details(Code::FailedPrecondition, message, details)
+}
+
+/// `FAILED_PRECONDITION` with a `google.rpc.PreconditionFailure` for a quantity
+/// that cannot be converted between units measuring different dimensions.
+#[must_use]
+pub fn incompatible_dimensions(from: &str, to: &str, message: String) -> Status {
+    let mut details = error_info(
+        "INCOMPATIBLE_DIMENSIONS",
+        &[("unit_of_measure", from), ("target_unit_of_measure", to)],
+    );
+    details.add_precondition_failure_violation("DIMENSION", to, message.clone());
+
+    Status::with_error_details(Code::FailedPrecondition, message, details)
+}
+
+/// `FAILED_PRECONDITION` with a `google.rpc.PreconditionFailure` for a stock
+/// movement taking more than is on hand.
+#[must_use]
+pub fn insufficient_stock(resource_name: &str, message: String) -> Status {
+    let mut details = error_info("INSUFFICIENT_STOCK", &[("resource", resource_name)]);
+    details.add_precondition_failure_violation("STOCK", resource_name, message.clone());
+
+    Status::with_error_details(Code::FailedPrecondition, message, details)
+}
+
+/// `UNAUTHENTICATED` with a `google.rpc.ErrorInfo` giving why the credentials were refused.
+#[must_use]
+pub fn unauthenticated(reason: &str, message: String) -> Status {
+    let details = error_info(reason, &[]);
+
+    Status::with_error_details(Code::Unauthenticated, message, details)
+}
+
+/// `PERMISSION_DENIED` with a `google.rpc.ErrorInfo` naming the missing permission.
+#[must_use]
+pub fn permission_denied(permission: &str, message: String) -> Status {
+    let details = error_info("IAM_PERMISSION_DENIED", &[("permission", permission)]);
+
+    Status::with_error_details(Code::PermissionDenied, message, details)
+}
+
+fn error_info(reason: &str, metadata: &[(&str, &str)]) -> ErrorDetails {
+    let metadata: HashMap<String, String> = metadata
+        .iter()
+        .map(|(key, value)| ((*key).to_string(), (*value).to_string()))
+        .collect();
+
+    ErrorDetails::with_error_info(reason, ERROR_DOMAIN, metadata)
+}
@@ -0,0 +1,408 @@
+use std::sync::Arc;
+
+use tonic::{Request, Response, Status};
+
+use crate::{
+    location::location_name,
+    proto::stock_movement::{
+        self as proto, stock_movement_command_service_server::StockMovementCommandService,
+        stock_movement_query_service_server::StockMovementQueryService, CreateStockMovementRequest,
+        GetStockBalanceRequest, GetStockMovementRequest, ListStockBalancesRequest,
+        ListStockBalancesResponse, ListStockMovementsRequest, ListStockMovementsResponse,
+    },
+    stock_movement::{
+        command::{self, Create},
+        query::{self, Get, GetBalance, List, ListBalances},
+        stock_balance_name, stock_movement_name, EmptyError, Error, InvalidBalanceNameError,
+        InvalidKindError,
+    },
+    traceability::{lot_name, serial_name},
+    StockBalance, StockMovement, StockMovementKind, Tracking,
+};
+
+use super::{
+    item::{item_id, item_name},
+    location::location_id,
+    production_order::{production_order_id, production_order_name},
+    status,
+    traceability::{lot_id, serial_id},
+    unit_of_measure::unit_of_measure_id,
+};
+
+const STOCK_MOVEMENT_RESOURCE_TYPE: &str = "manufacturing.erponomics.com/StockMovement";
+
+#[derive(Debug, Clone)]
+pub struct CommandService<SCS: Create + Clone> {
+    stock_movement_command_service: Arc<SCS>,
+}
+
+#[derive(Debug, Clone)]
+pub struct QueryService<SQS: Get + List + GetBalance + ListBalances + Clone> {
+    stock_movement_query_service: Arc<SQS>,
+}
+
+impl From<StockMovement> for proto::StockMovement {
+    fn from(value: StockMovement) -> Self {
+        let name = value.name();
+        let (
+            _,
+            kind,
+            item,
+            quantity,
+            source_location,
+            target_location,
+            lot,
+            serial,
+            production_order,
+            reason,
+            uid,
+            create_time,
+        ) = value.dissolve();
+
+        Self {
+            name,
+            kind: proto::stock_movement::Kind::from(kind).into(),
+            item: item_name(&item),
+            quantity: quantity.into(),
+            unit_of_measure: String::new(),
+            source_location: source_location
+                .map(|l| location_name(&l))
+                .unwrap_or_default(),
+            target_location: target_location
+                .map(|l| location_name(&l))
+                .unwrap_or_default(),
+            lot: lot.map(|l| lot_name(&item, &l)).unwrap_or_default(),
+            serial: serial.map(|s| serial_name(&item, &s)).unwrap_or_default(),
+            production_order: production_order
+                .map(|p| production_order_name(&p))
+                .unwrap_or_default(),
+            reason,
+            uid: uid.to_string(),
+            create_time: create_time.into(),
+        }
+    }
+}
+
+impl From<StockBalance> for proto::StockBalance {
+    fn from(value: StockBalance) -> Self {
+        let name = value.name();
+        let (item, location, quantity) = value.dissolve();
+
+        Self {
+            name,
+            item: item_name(&item),
+            location: location_name(&location),
+            quantity: quantity.into(),
+        }
+    }
+}
+
+impl From<StockMovementKind> for proto::stock_movement::Kind {
+    fn from(value: StockMovementKind) -> Self {
+        match value {
+            StockMovementKind::Receipt => Self::Receipt,
+            StockMovementKind::Issue => Self::Issue,
+            StockMovementKind::Transfer => Self::Transfer,
+            StockMovementKind::Adjustment => Self::Adjustment,
+        }
+    }
+}
+
+fn try_kind(value: i32) -> Result<StockMovementKind, Error> {
+    match proto::stock_movement::Kind::try_from(value) {
+        Ok(proto::stock_movement::Kind::Receipt) => Ok(StockMovementKind::Receipt),
+        Ok(proto::stock_movement::Kind::Issue) => Ok(StockMovementKind::Issue),
+        Ok(proto::stock_movement::Kind::Transfer) => Ok(StockMovementKind::Transfer),
+        Ok(proto::stock_movement::Kind::Adjustment) => Ok(StockMovementKind::Adjustment),
+        _ => Err(InvalidKindError::new(value).into()),
+    }
+}
+
+impl From<Error> for Status {
+    fn from(value: Error) -> Self {
+        match value {
+            Error::NotFound(err) => status::not_found(
+                STOCK_MOVEMENT_RESOURCE_TYPE,
+                &stock_movement_name(err.id()),
+                err.to_string(),
+            ),
+            Error::AlreadyExists(err) => status::already_exists(
+                STOCK_MOVEMENT_RESOURCE_TYPE,
+                &stock_movement_name(err.id()),
+                err.to_string(),
+            ),
+            Error::InvalidKind(err) => status::bad_request("stock_movement.kind", err.to_string()),
+            Error::InvalidLocations(err) => {
+                let field = match err.kind() {
+                    StockMovementKind::Issue => "stock_movement.source_location",
+                    _ => "stock_movement.target_location",
+                };
+                status::bad_request(field, err.to_string())
+            }
+            Error::InvalidQuantity(err) => {
+                status::bad_request("stock_movement.quantity", err.to_string())
+            }
+            Error::InsufficientStock(err) => status::insufficient_stock(
+                &stock_balance_name(err.item(), err.location()),
+                err.to_string(),
+            ),
+            Error::InvalidBalanceName(err) => status::bad_request("name", err.to_string()),
+            Error::ItemNotActive(err) => {
+                status::invalid_state(&item_name(err.id()), err.state().name(), err.to_string())
+            }
+            Error::InvalidTracking(err) => {
+                let field = match err.tracking() {
+                    Tracking::Serial => "stock_movement.serial",
+                    _ => "stock_movement.lot",
+                };
+                status::bad_request(field, err.to_string())
+            }
+            Error::SerialQuantity(err) => {
+                status::bad_request("stock_movement.quantity", err.to_string())
+            }
+            Error::InvalidProductionOrder(err) => {
+                status::bad_request("stock_movement.production_order", err.to_string())
+            }
+            Error::ProductionOrderNotOpen(err) => status::invalid_state(
+                &production_order_name(err.id()),
+                err.state().name(),
+                err.to_string(),
+            ),
+            Error::Item(err) => err.into(),
+            Error::Location(err) => err.into(),
+            Error::Traceability(err) => err.into(),
+            Error::ProductionOrder(err) => err.into(),
+            Error::UnitOfMeasure(err) => err.into(),
+            Error::Quantity(err) => status::bad_request("stock_movement.quantity", err.to_string()),
+            Error::Unknown(err) => Self::unknown(err.to_string()),
+            Error::Timestamp(err) => Self::internal(err.to_string()),
+            Error::Id(err) => err.into(),
+            Error::Empty(err) => status::bad_request("stock_movement", err.to_string()),
+            Error::Filter(err) => status::bad_request("filter", err.to_string()),
+            Error::OrderBy(err) => status::bad_request("order_by", err.to_string()),
+            Error::PageToken(err) => status::bad_request("page_token", err.to_string()),
+        }
+    }
+}
+
+/// Split `items/{item}/stockBalances/{location}` into the item and location ids.
+fn parse_balance_name(name: String) -> Result<(String, String), Error> {
+    let ids = name
+        .strip_prefix("items/")
+        .and_then(|name| name.split_once("/stockBalances/"))
+        .filter(|(item, location)| !item.contains('/') && !location.contains('/'))
+        .map(|(item, location)| (item.to_string(), location.to_string()));
+
+    ids.ok_or_else(|| InvalidBalanceNameError::new(name).into())
+}
+
+impl<SCS> CommandService<SCS>
+where
+    SCS: Create + Clone,
+{
+    pub const fn new(stock_movement_command_service: Arc<SCS>) -> Self {
+        Self {
+            stock_movement_command_service,
+        }
+    }
+}
+
+impl<SQS> QueryService<SQS>
+where
+    SQS: Get + List + GetBalance + ListBalances + Clone,
+{
+    pub const fn new(stock_movement_query_service: Arc<SQS>) -> Self {
+        Self {
+            stock_movement_query_service,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl<SCS> StockMovementCommandService for CommandService<SCS>
+where
+    SCS: Create + Clone,
+{
+    async fn create_stock_movement(
+        &self,
+        request: Request<CreateStockMovementRequest>,
+    ) -> Result<Response<proto::StockMovement>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let movement = self
+            .stock_movement_command_service
+            .create(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(movement.into()))
+    }
+}
+
+#[tonic::async_trait]
+impl<SQS> StockMovementQueryService for QueryService<SQS>
+where
+    SQS: Get + List + GetBalance + ListBalances + Clone,
+{
+    async fn get_stock_movement(
+        &self,
+        request: Request<GetStockMovementRequest>,
+    ) -> Result<Response<proto::StockMovement>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let movement = self
+            .stock_movement_query_service
+            .get(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(movement.into()))
+    }
+
+    async fn list_stock_movements(
+        &self,
+        request: Request<ListStockMovementsRequest>,
+    ) -> Result<Response<ListStockMovementsResponse>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let response = self
+            .stock_movement_query_service
+            .list(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(response.into()))
+    }
+
+    async fn get_stock_balance(
+        &self,
+        request: Request<GetStockBalanceRequest>,
+    ) -> Result<Response<proto::StockBalance>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let balance = self
+            .stock_movement_query_service
+            .get_balance(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(balance.into()))
+    }
+
+    async fn list_stock_balances(
+        &self,
+        request: Request<ListStockBalancesRequest>,
+    ) -> Result<Response<ListStockBalancesResponse>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let response = self
+            .stock_movement_query_service
+            .list_balances(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(response.into()))
+    }
+}
+
+impl TryFrom<Request<CreateStockMovementRequest>> for command::CreateRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<CreateStockMovementRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        match value.stock_movement {
+            None => Err(EmptyError.into()),
+            Some(movement) => Ok(Self::new(
+                Some(value.stock_movement_id).filter(|id| !id.is_empty()),
+                try_kind(movement.kind)?,
+                item_id(&movement.item).to_string(),
+                movement.quantity,
+                unit_of_measure_id(&movement.unit_of_measure).to_string(),
+                Some(location_id(&movement.source_location).to_string()),
+                Some(location_id(&movement.target_location).to_string()),
+                Some(lot_id(&movement.lot).to_string()),
+                Some(serial_id(&movement.serial).to_string()),
+                Some(production_order_id(&movement.production_order).to_string()),
+                movement.reason,
+            )),
+        }
+    }
+}
+
+impl TryFrom<Request<GetStockMovementRequest>> for query::GetRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<GetStockMovementRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        let id = value
+            .name
+            .strip_prefix("stockMovements/")
+            .unwrap_or(&value.name);
+        Ok(Self::new(id.to_string()))
+    }
+}
+
+impl TryFrom<Request<ListStockMovementsRequest>> for query::ListRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<ListStockMovementsRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        Ok(Self::new(
+            Some(value.page_size),
+            Some(value.page_token),
+            Some(value.order_by),
+            Some(value.filter),
+        ))
+    }
+}
+
+impl From<query::ListResponse> for ListStockMovementsResponse {
+    fn from(value: query::ListResponse) -> Self {
+        let (stock_movements, next_page_token, total_size) = value.dissolve();
+
+        Self {
+            stock_movements: stock_movements
+                .into_iter()
+                .map(StockMovement::into)
+                .collect(),
+            next_page_token: next_page_token.unwrap_or_default(),
+            total_size,
+        }
+    }
+}
+
+impl TryFrom<Request<GetStockBalanceRequest>> for query::GetBalanceRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<GetStockBalanceRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        let (item, location) = parse_balance_name(value.name)?;
+        Ok(Self::new(item, location))
+    }
+}
+
+impl TryFrom<Request<ListStockBalancesRequest>> for query::ListRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<ListStockBalancesRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        Ok(Self::new(
+            Some(value.page_size),
+            Some(value.page_token),
+            Some(value.order_by),
+            Some(value.filter),
+        ))
+    }
+}
+
+impl From<query::ListBalancesResponse> for ListStockBalancesResponse {
+    fn from(value: query::ListBalancesResponse) -> Self {
+        let (stock_balances, next_page_token, total_size) = value.dissolve();
+
+        Self {
+            stock_balances: stock_balances.into_iter().map(StockBalance::into).collect(),
+            next_page_token: next_page_token.unwrap_or_default(),
+            total_size,
+        }
+    }
+}
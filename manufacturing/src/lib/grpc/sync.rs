@@ -1,8 +1,16 @@
+use std::sync::Arc;
+
 use prost_types::Any;
 use tonic::{Request, Response, Status};
 
-use crate::sync::{self, OperationMetadata};
+use crate::sync::{
+    self,
+    command::{self, Cancel, Delete},
+    query::{self, Get, List, Wait},
+    Error, OperationMetadata, OperationRecord,
+};
 
+use super::iam::{self, Permission, Policy};
 use super::proto::google::{
     longrunning::{
         operation, operations_server::Operations, CancelOperationRequest, DeleteOperationRequest,
@@ -12,7 +20,14 @@ use super::proto::google::{
     rpc,
 };
 
-pub struct Service;
+#[derive(Debug, Clone)]
+#[allow(clippy::struct_field_names)]
+pub struct Service<OCS: Delete + Cancel + Clone, OQS: Get + List + Wait + Clone> {
+    operation_command_service: Arc<OCS>,
+    operation_query_service: Arc<OQS>,
+    /// The roles of callers, without which they are not restricted.
+    policy: Option<Arc<Policy>>,
+}
 
 impl<T> From<sync::Operation<T>> for Operation
 where
@@ -40,42 +55,212 @@ where
     }
 }
 
+impl From<OperationRecord> for Operation {
+    fn from(value: OperationRecord) -> Self {
+        let (id, _, metadata, result, _, _) = value.dissolve();
+
+        let result = result.map(|res| match res {
+            Ok(response) => operation::Result::Response(response),
+            Err(status) => operation::Result::Error(status),
+        });
+        let done = result.is_some();
+
+        Self {
+            name: id.into(),
+            metadata: Some(metadata),
+            done,
+            result,
+        }
+    }
+}
+
+impl From<Error> for Status {
+    fn from(value: Error) -> Self {
+        match value {
+            Error::Unknown(err) => Self::unknown(err.to_string()),
+            Error::Timestamp(err) => Self::internal(err.to_string()),
+            Error::Filter(err) => Self::invalid_argument(err.to_string()),
+            Error::Uncancellable(err) => Self::unimplemented(err.to_string()),
+            Error::Id(err) => err.into(),
+        }
+    }
+}
+
+impl<OCS, OQS> Service<OCS, OQS>
+where
+    OCS: Delete + Cancel + Clone,
+    OQS: Get + List + Wait + Clone,
+{
+    pub const fn new(
+        operation_command_service: Arc<OCS>,
+        operation_query_service: Arc<OQS>,
+        policy: Option<Arc<Policy>>,
+    ) -> Self {
+        Self {
+            operation_command_service,
+            operation_query_service,
+            policy,
+        }
+    }
+
+    /// # Errors
+    /// If the caller of `request` lacks `permission`, see [`iam::authorize`].
+    fn authorize<T>(&self, request: &Request<T>, permission: Permission) -> Result<(), iam::Error> {
+        iam::authorize(self.policy.as_deref(), request, permission)
+    }
+}
+
 // MARK: Service
 
 #[tonic::async_trait]
-impl Operations for Service {
+impl<OCS, OQS> Operations for Service<OCS, OQS>
+where
+    OCS: Delete + Cancel + Clone,
+    OQS: Get + List + Wait + Clone,
+{
     async fn list_operations(
         &self,
-        _request: Request<ListOperationsRequest>,
+        request: Request<ListOperationsRequest>,
     ) -> Result<Response<ListOperationsResponse>, Status> {
-        todo!()
+        self.authorize(&request, Permission::ListOperations)?;
+
+        let request = request.into();
+
+        let response = self
+            .operation_query_service
+            .list(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(response.into()))
     }
 
     async fn get_operation(
         &self,
-        _request: Request<GetOperationRequest>,
+        request: Request<GetOperationRequest>,
     ) -> Result<Response<Operation>, Status> {
-        todo!()
+        self.authorize(&request, Permission::GetOperation)?;
+
+        let request = request.into();
+
+        let operation = self
+            .operation_query_service
+            .get(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(operation.into()))
     }
 
     async fn delete_operation(
         &self,
-        _request: Request<DeleteOperationRequest>,
+        request: Request<DeleteOperationRequest>,
     ) -> Result<Response<()>, Status> {
-        todo!()
+        self.authorize(&request, Permission::DeleteOperation)?;
+
+        let request = request.into();
+
+        self.operation_command_service
+            .delete(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(()))
     }
 
     async fn cancel_operation(
         &self,
-        _request: Request<CancelOperationRequest>,
+        request: Request<CancelOperationRequest>,
     ) -> Result<Response<()>, Status> {
-        todo!()
+        self.authorize(&request, Permission::CancelOperation)?;
+
+        let request = request.into();
+
+        self.operation_command_service
+            .cancel(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(()))
     }
 
     async fn wait_operation(
         &self,
-        _request: Request<WaitOperationRequest>,
+        request: Request<WaitOperationRequest>,
     ) -> Result<Response<Operation>, Status> {
-        todo!()
+        self.authorize(&request, Permission::GetOperation)?;
+
+        let request = request.try_into()?;
+
+        let operation = self
+            .operation_query_service
+            .wait(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(operation.into()))
+    }
+}
+
+/// The id of an operation, given either by its resource name or by its bare id.
+fn operation_id(name: &str) -> String {
+    name.strip_prefix("operations/").unwrap_or(name).to_string()
+}
+
+impl From<Request<GetOperationRequest>> for query::GetRequest {
+    fn from(value: Request<GetOperationRequest>) -> Self {
+        let value = value.into_inner();
+        Self::new(operation_id(&value.name))
+    }
+}
+
+impl From<Request<ListOperationsRequest>> for query::ListRequest {
+    fn from(value: Request<ListOperationsRequest>) -> Self {
+        let value = value.into_inner();
+        Self::new(
+            Some(value.page_size),
+            Some(value.page_token),
+            Some(value.filter),
+        )
+    }
+}
+
+impl TryFrom<Request<WaitOperationRequest>> for query::WaitRequest {
+    type Error = Status;
+
+    fn try_from(value: Request<WaitOperationRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        let timeout = value
+            .timeout
+            .map(TryInto::try_into)
+            .transpose()
+            .map_err(|e| Status::invalid_argument(format!("invalid timeout: {e}")))?;
+
+        Ok(Self::new(operation_id(&value.name), timeout))
+    }
+}
+
+impl From<Request<DeleteOperationRequest>> for command::DeleteRequest {
+    fn from(value: Request<DeleteOperationRequest>) -> Self {
+        let value = value.into_inner();
+        Self::new(operation_id(&value.name))
+    }
+}
+
+impl From<Request<CancelOperationRequest>> for command::CancelRequest {
+    fn from(value: Request<CancelOperationRequest>) -> Self {
+        let value = value.into_inner();
+        Self::new(operation_id(&value.name))
+    }
+}
+
+impl From<query::ListResponse> for ListOperationsResponse {
+    fn from(value: query::ListResponse) -> Self {
+        let (operations, next_page_token) = value.dissolve();
+
+        Self {
+            operations: operations.into_iter().map(OperationRecord::into).collect(),
+            next_page_token: next_page_token.unwrap_or_default(),
+        }
     }
 }
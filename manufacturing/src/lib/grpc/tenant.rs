@@ -0,0 +1,326 @@
+//! Resolution of the tenant of each request, which the request is then served for.
+
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use http::{Request, Response};
+use tonic::{body::BoxBody, codegen::BoxFuture, Status};
+
+use derive_getters::Getters;
+
+use crate::{id, sqlx::Tenants, Tenant, ThisError};
+
+use super::{auth::Principal, status};
+
+/// The metadata naming the organization of a caller where callers are not authenticated.
+///
+/// The organization of an authenticated caller is the one of its token instead.
+pub const ORGANIZATION_HEADER: &str = "erponomics-organization";
+
+/// The services callable outside of any tenant, so that clients can discover the API.
+const PUBLIC_PATH_PREFIX: &str = "/grpc.reflection.";
+
+/// Serves each request for the organization of its caller.
+///
+/// Organizations that are not provisioned are refused, as are authenticated callers outside
+/// of any organization. Other callers outside of any organization are served from the default
+/// database.
+#[derive(Debug)]
+pub struct Layer<T> {
+    tenants: Arc<T>,
+    /// Whether the organization is taken from [`ORGANIZATION_HEADER`] of callers without a
+    /// token.
+    header: bool,
+}
+
+impl<T> Layer<T> {
+    #[must_use]
+    pub const fn new(tenants: Arc<T>, header: bool) -> Self {
+        Self { tenants, header }
+    }
+}
+
+impl<T> Clone for Layer<T> {
+    fn clone(&self) -> Self {
+        Self {
+            tenants: self.tenants.clone(),
+            header: self.header,
+        }
+    }
+}
+
+impl<S, T> tower::Layer<S> for Layer<T> {
+    type Service = Service<S, T>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Service {
+            inner,
+            tenants: self.tenants.clone(),
+            header: self.header,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Service<S, T> {
+    inner: S,
+    tenants: Arc<T>,
+    header: bool,
+}
+
+impl<S: Clone, T> Clone for Service<S, T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            tenants: self.tenants.clone(),
+            header: self.header,
+        }
+    }
+}
+
+impl<S, T> tower::Service<Request<BoxBody>> for Service<S, T>
+where
+    S: tower::Service<Request<BoxBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    T: Tenants,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<BoxBody>) -> Self::Future {
+        let tenant = if request.uri().path().starts_with(PUBLIC_PATH_PREFIX) {
+            Ok(None)
+        } else {
+            resolve(&request, self.header)
+        };
+        let tenant = tenant.and_then(|tenant| match tenant {
+            Some(tenant) if !self.tenants.serves(&tenant) => {
+                Err(UnknownOrganizationError::new(tenant).into())
+            }
+            tenant => Ok(tenant),
+        });
+        let tenant = match tenant {
+            Ok(tenant) => tenant,
+            Err(err) => {
+                let response = Status::from(err).into_http();
+                return Box::pin(async { Ok(response) });
+            }
+        };
+
+        // The ready service is taken for the call, leaving a clone to be made ready next.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(Tenant::scope(tenant, inner.call(request)))
+    }
+}
+
+/// The tenant of the caller of `request`, by its token if it is authenticated and by its
+/// metadata otherwise, if `header` accepts it.
+fn resolve<B>(request: &Request<B>, header: bool) -> Result<Option<Tenant>, Error> {
+    if let Some(principal) = request.extensions().get::<Principal>() {
+        let organization = principal
+            .organization()
+            .clone()
+            .ok_or(Error::MissingOrganization)?;
+
+        return Tenant::try_from(organization)
+            .map(Some)
+            .map_err(Error::InvalidToken);
+    }
+
+    if !header {
+        return Ok(None);
+    }
+
+    request
+        .headers()
+        .get(ORGANIZATION_HEADER)
+        .map(|value| {
+            let value = value
+                .to_str()
+                .map_err(|err| Error::InvalidMetadata(anyhow::anyhow!(err)))?;
+            Tenant::try_from(value.to_string()).map_err(|err| Error::InvalidMetadata(err.into()))
+        })
+        .transpose()
+}
+
+// MARK: Errors
+
+#[derive(Debug, ThisError)]
+pub enum Error {
+    /// The organization of the caller's token is not a valid id.
+    #[error("organization of token is invalid: {0}")]
+    InvalidToken(id::Error),
+    /// The caller's token names no organization.
+    #[error("token names no organization")]
+    MissingOrganization,
+    /// The organization of the caller's metadata is not a valid id.
+    #[error("{ORGANIZATION_HEADER} is invalid: {0}")]
+    InvalidMetadata(anyhow::Error),
+    #[error(transparent)]
+    UnknownOrganization(#[from] UnknownOrganizationError),
+}
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("organization {tenant} is not provisioned")]
+pub struct UnknownOrganizationError {
+    tenant: Tenant,
+}
+
+impl UnknownOrganizationError {
+    #[must_use]
+    pub const fn new(tenant: Tenant) -> Self {
+        Self { tenant }
+    }
+}
+
+impl From<Error> for Status {
+    fn from(value: Error) -> Self {
+        match value {
+            err @ Error::InvalidToken(_) => {
+                status::unauthenticated("INVALID_ORGANIZATION", err.to_string())
+            }
+            err @ Error::InvalidMetadata(_) => {
+                status::bad_request(ORGANIZATION_HEADER, err.to_string())
+            }
+            err @ (Error::MissingOrganization | Error::UnknownOrganization(_)) => {
+                Self::permission_denied(err.to_string())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::HeaderValue;
+    use tonic::Code;
+    use tower::{service_fn, Layer as _, ServiceExt as _};
+
+    use super::*;
+
+    /// The tenants provisioned, without any database.
+    struct Provisioned(Vec<Tenant>);
+
+    impl Tenants for Provisioned {
+        fn serves(&self, tenant: &Tenant) -> bool {
+            self.0.contains(tenant)
+        }
+
+        fn tenants(&self) -> Vec<Tenant> {
+            self.0.clone()
+        }
+    }
+
+    /// A service answering with the tenant it serves the request for.
+    async fn tenant(_request: Request<BoxBody>) -> Result<Response<BoxBody>, Infallible> {
+        let tenant = Tenant::current()
+            .map(|tenant| tenant.to_string())
+            .unwrap_or_default();
+
+        Ok(Response::new(tonic::body::boxed(tenant)))
+    }
+
+    fn request(
+        organization: Option<&'static str>,
+        principal: Option<Principal>,
+    ) -> anyhow::Result<Request<BoxBody>> {
+        let mut request = Request::new(tonic::body::empty_body());
+        *request.uri_mut() = "/erponomics.manufacturing.v1.ItemService/GetItem".parse()?;
+        if let Some(organization) = organization {
+            request
+                .headers_mut()
+                .insert(ORGANIZATION_HEADER, HeaderValue::from_static(organization));
+        }
+        if let Some(principal) = principal {
+            request.extensions_mut().insert(principal);
+        }
+
+        Ok(request)
+    }
+
+    async fn body(response: Response<BoxBody>) -> anyhow::Result<String> {
+        let body = http_body_util::BodyExt::collect(response.into_body()).await?;
+        Ok(String::from_utf8(body.to_bytes().to_vec())?)
+    }
+
+    fn code(response: &Response<BoxBody>) -> Option<Code> {
+        Status::from_header_map(response.headers()).map(|status| status.code())
+    }
+
+    fn provisioned() -> anyhow::Result<Arc<Provisioned>> {
+        Ok(Arc::new(Provisioned(vec![
+            Tenant::try_from(String::from("acme"))?,
+            Tenant::try_from(String::from("globex"))?,
+        ])))
+    }
+
+    #[tokio::test]
+    async fn serves_requests_for_the_tenant_of_their_caller() -> anyhow::Result<()> {
+        let service = Layer::new(provisioned()?, true).layer(service_fn(tenant));
+
+        let response = service
+            .clone()
+            .oneshot(request(Some("acme"), None)?)
+            .await?;
+        assert_eq!("acme", body(response).await?);
+
+        // The token of an authenticated caller decides over its metadata.
+        let principal =
+            Principal::new(String::from("user-1"), vec![], Some(String::from("globex")));
+        let response = service
+            .clone()
+            .oneshot(request(Some("acme"), Some(principal))?)
+            .await?;
+        assert_eq!("globex", body(response).await?);
+
+        let response = service.clone().oneshot(request(None, None)?).await?;
+        assert_eq!("", body(response).await?);
+
+        let response = service
+            .clone()
+            .oneshot(request(Some("Acme Inc"), None)?)
+            .await?;
+        assert_eq!(Some(Code::InvalidArgument), code(&response));
+
+        let response = service.oneshot(request(Some("initech"), None)?).await?;
+        assert_eq!(Some(Code::PermissionDenied), code(&response));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn ignores_metadata_where_callers_are_authenticated() -> anyhow::Result<()> {
+        let service = Layer::new(provisioned()?, false).layer(service_fn(tenant));
+
+        let response = service
+            .clone()
+            .oneshot(request(Some("acme"), None)?)
+            .await?;
+        assert_eq!("", body(response).await?);
+
+        let principal = Principal::new(String::from("user-1"), vec![], Some(String::from("acme")));
+        let response = service
+            .clone()
+            .oneshot(request(None, Some(principal))?)
+            .await?;
+        assert_eq!("acme", body(response).await?);
+
+        // An authenticated caller outside of any organization is not served the default
+        // database, whatever its metadata says.
+        let principal = Principal::new(String::from("user-2"), vec![], None);
+        let response = service
+            .oneshot(request(Some("acme"), Some(principal))?)
+            .await?;
+        assert_eq!(Some(Code::PermissionDenied), code(&response));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,404 @@
+use std::sync::Arc;
+
+use tonic::{Request, Response, Status};
+
+use crate::{
+    location::location_name,
+    proto::traceability::{
+        self as proto, trace_genealogy_request,
+        traceability_command_service_server::TraceabilityCommandService,
+        traceability_query_service_server::TraceabilityQueryService, CreateLotRequest,
+        CreateSerialRequest, GetLotRequest, GetSerialRequest, ListLotsRequest, ListLotsResponse,
+        ListSerialsRequest, ListSerialsResponse, TraceGenealogyRequest, TraceGenealogyResponse,
+    },
+    traceability::{
+        command::{self, CreateLot, CreateSerial},
+        query::{self, GetLot, GetSerial, ListLots, ListSerials, Trace},
+        serial_name, Direction, EmptyError, Error, InvalidNameError,
+    },
+    GenealogyNode, Lot, Serial, Traced,
+};
+
+use super::{item::item_id, production_order::production_order_name, status};
+
+const LOT_RESOURCE_TYPE: &str = "manufacturing.erponomics.com/Lot";
+
+const SERIAL_RESOURCE_TYPE: &str = "manufacturing.erponomics.com/Serial";
+
+#[derive(Debug, Clone)]
+pub struct CommandService<TCS: CreateLot + CreateSerial + Clone> {
+    traceability_command_service: Arc<TCS>,
+}
+
+#[derive(Debug, Clone)]
+pub struct QueryService<TQS: GetLot + ListLots + GetSerial + ListSerials + Trace + Clone> {
+    traceability_query_service: Arc<TQS>,
+}
+
+impl From<Lot> for proto::Lot {
+    fn from(value: Lot) -> Self {
+        let name = value.name();
+        let (_, _, description, uid, create_time) = value.dissolve();
+
+        Self {
+            name,
+            description,
+            uid: uid.to_string(),
+            create_time: create_time.into(),
+        }
+    }
+}
+
+impl From<Serial> for proto::Serial {
+    fn from(value: Serial) -> Self {
+        let name = value.name();
+        let (_, _, location, uid, create_time, update_time) = value.dissolve();
+
+        Self {
+            name,
+            location: location.map(|l| location_name(&l)).unwrap_or_default(),
+            uid: uid.to_string(),
+            create_time: create_time.into(),
+            update_time: update_time.into(),
+        }
+    }
+}
+
+impl From<GenealogyNode> for proto::GenealogyNode {
+    fn from(value: GenealogyNode) -> Self {
+        let (traced, production_order, depth) = value.dissolve();
+
+        Self {
+            name: traced.name(),
+            production_order: production_order_name(&production_order),
+            depth,
+        }
+    }
+}
+
+const fn resource_type(traced: &Traced) -> &'static str {
+    match traced {
+        Traced::Lot { .. } => LOT_RESOURCE_TYPE,
+        Traced::Serial { .. } => SERIAL_RESOURCE_TYPE,
+    }
+}
+
+impl From<Error> for Status {
+    fn from(value: Error) -> Self {
+        match value {
+            Error::NotFound(err) => status::not_found(
+                resource_type(err.traced()),
+                &err.traced().name(),
+                err.to_string(),
+            ),
+            Error::AlreadyExists(err) => status::already_exists(
+                resource_type(err.traced()),
+                &err.traced().name(),
+                err.to_string(),
+            ),
+            Error::TrackingMismatch(err) => status::bad_request("parent", err.to_string()),
+            Error::SerialMisplaced(err) => status::invalid_state(
+                &serial_name(err.item(), err.serial()),
+                err.location()
+                    .as_ref()
+                    .map_or("NOT_IN_STOCK", |_| "IN_STOCK"),
+                err.to_string(),
+            ),
+            Error::InvalidName(err) => status::bad_request("name", err.to_string()),
+            Error::Item(err) => err.into(),
+            Error::Unknown(err) => Self::unknown(err.to_string()),
+            Error::Timestamp(err) => Self::internal(err.to_string()),
+            Error::Id(err) => err.into(),
+            Error::Empty(err) => status::bad_request("lot", err.to_string()),
+            Error::Filter(err) => status::bad_request("filter", err.to_string()),
+            Error::OrderBy(err) => status::bad_request("order_by", err.to_string()),
+            Error::PageToken(err) => status::bad_request("page_token", err.to_string()),
+        }
+    }
+}
+
+/// The id of a lot, given either by its resource name or by its bare id.
+pub(super) fn lot_id(name: &str) -> &str {
+    name.rsplit_once("/lots/").map_or(name, |(_, lot)| lot)
+}
+
+/// The id of a serial, given either by its resource name or by its bare id.
+pub(super) fn serial_id(name: &str) -> &str {
+    name.rsplit_once("/serials/")
+        .map_or(name, |(_, serial)| serial)
+}
+
+/// Split `items/{item}{separator}{id}` into the item id and the id of its lot or serial.
+fn split_name(name: &str, separator: &str) -> Option<(String, String)> {
+    name.strip_prefix("items/")
+        .and_then(|name| name.split_once(separator))
+        .filter(|(item, id)| !item.contains('/') && !id.contains('/'))
+        .map(|(item, id)| (item.to_string(), id.to_string()))
+}
+
+impl<TCS> CommandService<TCS>
+where
+    TCS: CreateLot + CreateSerial + Clone,
+{
+    pub const fn new(traceability_command_service: Arc<TCS>) -> Self {
+        Self {
+            traceability_command_service,
+        }
+    }
+}
+
+impl<TQS> QueryService<TQS>
+where
+    TQS: GetLot + ListLots + GetSerial + ListSerials + Trace + Clone,
+{
+    pub const fn new(traceability_query_service: Arc<TQS>) -> Self {
+        Self {
+            traceability_query_service,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl<TCS> TraceabilityCommandService for CommandService<TCS>
+where
+    TCS: CreateLot + CreateSerial + Clone,
+{
+    async fn create_lot(
+        &self,
+        request: Request<CreateLotRequest>,
+    ) -> Result<Response<proto::Lot>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let lot = self
+            .traceability_command_service
+            .create_lot(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(lot.into()))
+    }
+
+    async fn create_serial(
+        &self,
+        request: Request<CreateSerialRequest>,
+    ) -> Result<Response<proto::Serial>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let serial = self
+            .traceability_command_service
+            .create_serial(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(serial.into()))
+    }
+}
+
+#[tonic::async_trait]
+impl<TQS> TraceabilityQueryService for QueryService<TQS>
+where
+    TQS: GetLot + ListLots + GetSerial + ListSerials + Trace + Clone,
+{
+    async fn get_lot(
+        &self,
+        request: Request<GetLotRequest>,
+    ) -> Result<Response<proto::Lot>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let lot = self
+            .traceability_query_service
+            .get_lot(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(lot.into()))
+    }
+
+    async fn list_lots(
+        &self,
+        request: Request<ListLotsRequest>,
+    ) -> Result<Response<ListLotsResponse>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let response = self
+            .traceability_query_service
+            .list_lots(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(response.into()))
+    }
+
+    async fn get_serial(
+        &self,
+        request: Request<GetSerialRequest>,
+    ) -> Result<Response<proto::Serial>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let serial = self
+            .traceability_query_service
+            .get_serial(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(serial.into()))
+    }
+
+    async fn list_serials(
+        &self,
+        request: Request<ListSerialsRequest>,
+    ) -> Result<Response<ListSerialsResponse>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let response = self
+            .traceability_query_service
+            .list_serials(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(response.into()))
+    }
+
+    async fn trace_genealogy(
+        &self,
+        request: Request<TraceGenealogyRequest>,
+    ) -> Result<Response<TraceGenealogyResponse>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let nodes = self
+            .traceability_query_service
+            .trace(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(TraceGenealogyResponse {
+            nodes: nodes.into_iter().map(GenealogyNode::into).collect(),
+        }))
+    }
+}
+
+impl TryFrom<Request<CreateLotRequest>> for command::CreateLotRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<CreateLotRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        match value.lot {
+            None => Err(EmptyError.into()),
+            Some(lot) => Ok(Self::new(
+                item_id(&value.parent).to_string(),
+                Some(value.lot_id).filter(|id| !id.is_empty()),
+                lot.description,
+            )),
+        }
+    }
+}
+
+impl TryFrom<Request<CreateSerialRequest>> for command::CreateSerialRequest {
+    type Error = Error;
+
+    /// A serial has no fields to set on creation, so it may be omitted.
+    fn try_from(value: Request<CreateSerialRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        Ok(Self::new(
+            item_id(&value.parent).to_string(),
+            Some(value.serial_id).filter(|id| !id.is_empty()),
+        ))
+    }
+}
+
+impl TryFrom<Request<GetLotRequest>> for query::GetLotRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<GetLotRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        split_name(&value.name, "/lots/")
+            .map(|(item, lot)| Self::new(item, lot))
+            .ok_or_else(|| InvalidNameError::new(value.name).into())
+    }
+}
+
+impl TryFrom<Request<GetSerialRequest>> for query::GetSerialRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<GetSerialRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        split_name(&value.name, "/serials/")
+            .map(|(item, serial)| Self::new(item, serial))
+            .ok_or_else(|| InvalidNameError::new(value.name).into())
+    }
+}
+
+impl TryFrom<Request<ListLotsRequest>> for query::ListRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<ListLotsRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        Ok(Self::new(
+            item_id(&value.parent).to_string(),
+            Some(value.page_size),
+            Some(value.page_token),
+            Some(value.order_by),
+            Some(value.filter),
+        ))
+    }
+}
+
+impl TryFrom<Request<ListSerialsRequest>> for query::ListRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<ListSerialsRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        Ok(Self::new(
+            item_id(&value.parent).to_string(),
+            Some(value.page_size),
+            Some(value.page_token),
+            Some(value.order_by),
+            Some(value.filter),
+        ))
+    }
+}
+
+impl TryFrom<Request<TraceGenealogyRequest>> for query::TraceRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<TraceGenealogyRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        let direction = match trace_genealogy_request::Direction::try_from(value.direction) {
+            Ok(trace_genealogy_request::Direction::Backward) => Direction::Backward,
+            _ => Direction::Forward,
+        };
+
+        let lot = split_name(&value.name, "/lots/");
+        let serial = split_name(&value.name, "/serials/");
+
+        match (lot, serial) {
+            (Some((item, lot)), _) => Ok(Self::lot(item, lot, direction)),
+            (None, Some((item, serial))) => Ok(Self::serial(item, serial, direction)),
+            (None, None) => Err(InvalidNameError::new(value.name).into()),
+        }
+    }
+}
+
+impl From<query::ListLotsResponse> for ListLotsResponse {
+    fn from(value: query::ListLotsResponse) -> Self {
+        let (lots, next_page_token, total_size) = value.dissolve();
+
+        Self {
+            lots: lots.into_iter().map(Lot::into).collect(),
+            next_page_token: next_page_token.unwrap_or_default(),
+            total_size,
+        }
+    }
+}
+
+impl From<query::ListSerialsResponse> for ListSerialsResponse {
+    fn from(value: query::ListSerialsResponse) -> Self {
+        let (serials, next_page_token, total_size) = value.dissolve();
+
+        Self {
+            serials: serials.into_iter().map(Serial::into).collect(),
+            next_page_token: next_page_token.unwrap_or_default(),
+            total_size,
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use std::sync::Arc;
+
+use tonic::{Request, Response, Status};
+
+use crate::{
+    proto::unit_of_measure::{
+        self as proto, unit_of_measure_query_service_server::UnitOfMeasureQueryService,
+        ConvertQuantityRequest, ConvertQuantityResponse, GetUnitOfMeasureRequest,
+        ListUnitsOfMeasureRequest, ListUnitsOfMeasureResponse,
+    },
+    unit_of_measure::{
+        query::{self, Convert, Get, List},
+        unit_of_measure_name, Error,
+    },
+    Dimension, UnitOfMeasure,
+};
+
+use super::status;
+
+const UNIT_OF_MEASURE_RESOURCE_TYPE: &str = "manufacturing.erponomics.com/UnitOfMeasure";
+
+#[derive(Debug, Clone)]
+pub struct QueryService<UQS: Get + List + Convert + Clone> {
+    unit_of_measure_query_service: Arc<UQS>,
+}
+
+impl From<UnitOfMeasure> for proto::UnitOfMeasure {
+    fn from(value: UnitOfMeasure) -> Self {
+        let name = value.name();
+        let (_, display_name, dimension, factor) = value.dissolve();
+
+        Self {
+            name,
+            display_name,
+            dimension: proto::unit_of_measure::Dimension::from(dimension).into(),
+            factor: factor.into(),
+        }
+    }
+}
+
+impl From<Dimension> for proto::unit_of_measure::Dimension {
+    fn from(value: Dimension) -> Self {
+        match value {
+            Dimension::Count => Self::Count,
+            Dimension::Mass => Self::Mass,
+            Dimension::Length => Self::Length,
+            Dimension::Area => Self::Area,
+            Dimension::Volume => Self::Volume,
+            Dimension::Time => Self::Time,
+        }
+    }
+}
+
+impl From<Error> for Status {
+    fn from(value: Error) -> Self {
+        match value {
+            Error::NotFound(err) => status::not_found(
+                UNIT_OF_MEASURE_RESOURCE_TYPE,
+                &unit_of_measure_name(err.id()),
+                err.to_string(),
+            ),
+            Error::IncompatibleDimensions(err) => status::incompatible_dimensions(
+                &unit_of_measure_name(err.from()),
+                &unit_of_measure_name(err.to()),
+                err.to_string(),
+            ),
+            Error::Quantity(err) => status::bad_request("quantity", err.to_string()),
+            Error::Id(err) => err.into(),
+            Error::Filter(err) => status::bad_request("filter", err.to_string()),
+            Error::OrderBy(err) => status::bad_request("order_by", err.to_string()),
+            Error::PageToken(err) => status::bad_request("page_token", err.to_string()),
+            Error::Unknown(err) => Self::unknown(err.to_string()),
+        }
+    }
+}
+
+/// The id of a unit of measure, given either by its resource name or by its
+/// bare id.
+pub(super) fn unit_of_measure_id(name: &str) -> &str {
+    name.strip_prefix("unitsOfMeasure/").unwrap_or(name)
+}
+
+impl<UQS> QueryService<UQS>
+where
+    UQS: Get + List + Convert + Clone,
+{
+    pub const fn new(unit_of_measure_query_service: Arc<UQS>) -> Self {
+        Self {
+            unit_of_measure_query_service,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl<UQS> UnitOfMeasureQueryService for QueryService<UQS>
+where
+    UQS: Get + List + Convert + Clone,
+{
+    async fn get_unit_of_measure(
+        &self,
+        request: Request<GetUnitOfMeasureRequest>,
+    ) -> Result<Response<proto::UnitOfMeasure>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let unit_of_measure = self
+            .unit_of_measure_query_service
+            .get(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(unit_of_measure.into()))
+    }
+
+    async fn list_units_of_measure(
+        &self,
+        request: Request<ListUnitsOfMeasureRequest>,
+    ) -> Result<Response<ListUnitsOfMeasureResponse>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let response = self
+            .unit_of_measure_query_service
+            .list(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(response.into()))
+    }
+
+    async fn convert_quantity(
+        &self,
+        request: Request<ConvertQuantityRequest>,
+    ) -> Result<Response<ConvertQuantityResponse>, Status> {
+        let request = request.try_into().map_err(Status::from)?;
+
+        let response = self
+            .unit_of_measure_query_service
+            .convert(request)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(response.into()))
+    }
+}
+
+impl TryFrom<Request<GetUnitOfMeasureRequest>> for query::GetRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<GetUnitOfMeasureRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        Ok(Self::new(unit_of_measure_id(&value.name).to_string()))
+    }
+}
+
+impl TryFrom<Request<ListUnitsOfMeasureRequest>> for query::ListRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<ListUnitsOfMeasureRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        Ok(Self::new(
+            Some(value.page_size),
+            Some(value.page_token),
+            Some(value.order_by),
+            Some(value.filter),
+        ))
+    }
+}
+
+impl From<query::ListResponse> for ListUnitsOfMeasureResponse {
+    fn from(value: query::ListResponse) -> Self {
+        let (units_of_measure, next_page_token, total_size) = value.dissolve();
+
+        Self {
+            units_of_measure: units_of_measure
+                .into_iter()
+                .map(UnitOfMeasure::into)
+                .collect(),
+            next_page_token: next_page_token.unwrap_or_default(),
+            total_size,
+        }
+    }
+}
+
+impl TryFrom<Request<ConvertQuantityRequest>> for query::ConvertRequest {
+    type Error = Error;
+
+    fn try_from(value: Request<ConvertQuantityRequest>) -> Result<Self, Self::Error> {
+        let value = value.into_inner();
+        Ok(Self::new(
+            value.quantity,
+            unit_of_measure_id(&value.unit_of_measure).to_string(),
+            unit_of_measure_id(&value.target_unit_of_measure).to_string(),
+        ))
+    }
+}
+
+impl From<query::ConvertResponse> for ConvertQuantityResponse {
+    fn from(value: query::ConvertResponse) -> Self {
+        let (quantity, unit_of_measure) = value.dissolve();
+
+        Self {
+            quantity: quantity.into(),
+            unit_of_measure: unit_of_measure.name(),
+        }
+    }
+}
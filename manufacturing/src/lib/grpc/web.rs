@@ -0,0 +1,240 @@
+//! gRPC-Web, translated to and from gRPC in front of the services, for browsers that can
+//! neither choose HTTP/2 nor read trailers.
+
+use std::task::{Context, Poll};
+
+use base64::{engine::general_purpose, Engine as _};
+use http::{
+    header::{CONTENT_LENGTH, CONTENT_TYPE, TE},
+    HeaderMap, HeaderValue, Method, Request, Response, StatusCode,
+};
+use http_body::Frame;
+use http_body_util::{BodyExt, Full};
+use prost::bytes::{BufMut, Bytes, BytesMut};
+use tonic::{body::BoxBody, codegen::BoxFuture, Status};
+
+const GRPC: HeaderValue = HeaderValue::from_static("application/grpc");
+
+const GRPC_WEB: HeaderValue = HeaderValue::from_static("application/grpc-web+proto");
+
+const GRPC_WEB_TEXT: HeaderValue = HeaderValue::from_static("application/grpc-web-text+proto");
+
+/// The flag of a length-prefixed message carrying the trailers instead of a message.
+const TRAILERS_FLAG: u8 = 0x80;
+
+/// Translates gRPC-Web requests into gRPC and their responses back, passing gRPC through.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Layer;
+
+impl<S> tower::Layer<S> for Layer {
+    type Service = Service<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Service { inner }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Service<S> {
+    inner: S,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Encoding {
+    /// `application/grpc-web`, framed like gRPC.
+    Binary,
+    /// `application/grpc-web-text`, the binary framing encoded in base64.
+    Text,
+}
+
+impl<S> tower::Service<Request<BoxBody>> for Service<S>
+where
+    S: tower::Service<Request<BoxBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Send,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<BoxBody>) -> Self::Future {
+        let Some(encoding) = Encoding::of(request.headers()) else {
+            return Box::pin(self.inner.call(request));
+        };
+        if request.method() != Method::POST {
+            let mut response = Response::new(tonic::body::empty_body());
+            *response.status_mut() = StatusCode::METHOD_NOT_ALLOWED;
+            return Box::pin(async { Ok(response) });
+        }
+
+        // The ready service is taken for the call, leaving a clone to be made ready next.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            match into_grpc(request, encoding).await {
+                Ok(request) => {
+                    let response = inner.call(request).await?;
+                    Ok(into_grpc_web(response, encoding))
+                }
+                Err(status) => Ok(into_grpc_web(status.into_http(), encoding)),
+            }
+        })
+    }
+}
+
+impl Encoding {
+    fn of(headers: &HeaderMap) -> Option<Self> {
+        let content_type = headers.get(CONTENT_TYPE)?.to_str().ok()?;
+        let (content_type, _) = content_type.split_once(';').unwrap_or((content_type, ""));
+
+        match content_type.trim() {
+            "application/grpc-web" | "application/grpc-web+proto" => Some(Self::Binary),
+            "application/grpc-web-text" | "application/grpc-web-text+proto" => Some(Self::Text),
+            _ => None,
+        }
+    }
+
+    const fn content_type(self) -> HeaderValue {
+        match self {
+            Self::Binary => GRPC_WEB,
+            Self::Text => GRPC_WEB_TEXT,
+        }
+    }
+}
+
+async fn into_grpc(
+    request: Request<BoxBody>,
+    encoding: Encoding,
+) -> Result<Request<BoxBody>, Status> {
+    let (mut parts, body) = request.into_parts();
+    parts.headers.insert(CONTENT_TYPE, GRPC);
+    parts
+        .headers
+        .insert(TE, HeaderValue::from_static("trailers"));
+    parts.headers.remove(CONTENT_LENGTH);
+
+    let body = match encoding {
+        Encoding::Binary => body,
+        // Browsers send whole requests, as gRPC-Web has no client streaming.
+        Encoding::Text => {
+            let text = body.collect().await?.to_bytes();
+            let text: Vec<u8> = text
+                .into_iter()
+                .filter(|b| !b.is_ascii_whitespace())
+                .collect();
+            let decoded = general_purpose::STANDARD.decode(text).map_err(|err| {
+                Status::invalid_argument(format!("invalid grpc-web-text body: {err}"))
+            })?;
+            tonic::body::boxed(Full::new(Bytes::from(decoded)))
+        }
+    };
+
+    Ok(Request::from_parts(parts, body))
+}
+
+fn into_grpc_web(response: Response<BoxBody>, encoding: Encoding) -> Response<BoxBody> {
+    let (mut parts, body) = response.into_parts();
+    parts.headers.insert(CONTENT_TYPE, encoding.content_type());
+
+    let body = body
+        .map_frame(move |frame| {
+            let data = match frame.into_data() {
+                Ok(data) => data,
+                Err(frame) => frame
+                    .into_trailers()
+                    .map_or_else(|_| Bytes::new(), |trailers| encode_trailers(&trailers)),
+            };
+            match encoding {
+                Encoding::Binary => Frame::data(data),
+                Encoding::Text => Frame::data(Bytes::from(general_purpose::STANDARD.encode(data))),
+            }
+        })
+        .boxed_unsync();
+
+    Response::from_parts(parts, body)
+}
+
+/// The trailers as the length-prefixed trailers message that ends a gRPC-Web body.
+fn encode_trailers(trailers: &HeaderMap) -> Bytes {
+    let mut block = BytesMut::new();
+    for (name, value) in trailers {
+        block.put_slice(name.as_str().as_bytes());
+        block.put_slice(b":");
+        block.put_slice(value.as_bytes());
+        block.put_slice(b"\r\n");
+    }
+
+    let mut message = BytesMut::with_capacity(block.len() + 5);
+    message.put_u8(TRAILERS_FLAG);
+    message.put_u32(u32::try_from(block.len()).unwrap_or(u32::MAX));
+    message.put_slice(&block);
+
+    message.freeze()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use tower::{service_fn, Layer as _, ServiceExt as _};
+
+    use super::*;
+
+    /// A service echoing the request body, with a `grpc-status` of 0 in the trailers.
+    async fn echo(request: Request<BoxBody>) -> Result<Response<BoxBody>, Infallible> {
+        assert_eq!(Some(&GRPC), request.headers().get(CONTENT_TYPE));
+
+        let mut trailers = HeaderMap::new();
+        trailers.insert("grpc-status", HeaderValue::from_static("0"));
+        let body = request
+            .into_body()
+            .collect()
+            .await
+            .map(http_body_util::Collected::to_bytes)
+            .unwrap_or_default();
+        let body = http_body_util::StreamBody::new(tokio_stream::iter([
+            Ok(Frame::data(body)),
+            Ok(Frame::trailers(trailers)),
+        ]));
+
+        Ok(Response::new(BodyExt::boxed_unsync(body)))
+    }
+
+    async fn call(
+        content_type: &'static str,
+        body: &'static [u8],
+    ) -> anyhow::Result<(HeaderMap, Bytes)> {
+        let mut request = Request::new(tonic::body::boxed(Full::new(Bytes::from_static(body))));
+        *request.method_mut() = Method::POST;
+        request
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+
+        let response = Layer.layer(service_fn(echo)).oneshot(request).await?;
+        let (parts, body) = response.into_parts();
+
+        Ok((parts.headers, body.collect().await?.to_bytes()))
+    }
+
+    #[tokio::test]
+    async fn translates_grpc_web_into_grpc_and_back() -> anyhow::Result<()> {
+        let (headers, body) = call("application/grpc-web", b"\0\0\0\0\x01a").await?;
+        assert_eq!(Some(&GRPC_WEB), headers.get(CONTENT_TYPE));
+        assert_eq!(
+            Bytes::from_static(b"\0\0\0\0\x01a\x80\0\0\0\x0fgrpc-status:0\r\n"),
+            body
+        );
+
+        let (headers, body) = call("application/grpc-web-text", b"AAAAAAFh").await?;
+        assert_eq!(Some(&GRPC_WEB_TEXT), headers.get(CONTENT_TYPE));
+        let body = std::str::from_utf8(&body)?;
+        assert!(body.starts_with("AAAAAAFh"));
+
+        Ok(())
+    }
+}
@@ -8,6 +8,7 @@ use thiserror::Error as ThisError;
 
 pub(crate) mod base;
 pub(crate) mod core;
+pub mod gateway;
 pub mod grpc;
 pub mod sqlx;
 pub mod sync;
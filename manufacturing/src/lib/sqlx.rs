@@ -1,9 +1,25 @@
 use sqlx::{error::DatabaseError, SqlitePool};
 
+use crate::Tenant;
+
+pub mod list;
+
 const UNIQUE_CONSTRAINT_VIOLATION_CODE: &str = "2067";
 
 pub trait SqliteConnection: Send + Sync + 'static {
-    fn pool(&self) -> &SqlitePool;
+    /// The pool of the database of [`Tenant::current`], or of the default database outside of
+    /// any tenant.
+    fn pool(&self) -> SqlitePool;
+}
+
+/// A connection keeping one database per provisioned tenant besides the default database.
+pub trait Tenants: Send + Sync + 'static {
+    /// Whether `tenant` is provisioned, so that [`SqliteConnection::pool`] selects its
+    /// database within [`Tenant::scope`].
+    fn serves(&self, tenant: &Tenant) -> bool;
+
+    /// The provisioned tenants.
+    fn tenants(&self) -> Vec<Tenant>;
 }
 
 pub enum Error {
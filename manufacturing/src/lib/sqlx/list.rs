@@ -0,0 +1,304 @@
+use chrono::DateTime;
+use sqlx::{QueryBuilder, Sqlite};
+
+use crate::{
+    filter::{self, Comparator, Expression, Restriction},
+    order_by, page_token, OrderBy, Timestamp,
+};
+
+/// How the values of a [`Column`] are stored and compared.
+#[derive(Clone, Copy, Debug)]
+pub enum Kind {
+    Text,
+    Integer,
+    Timestamp,
+    /// An enum stored as its integer, looked up by its API name, e.g. `ACTIVE`.
+    State(fn(&str) -> Option<i64>),
+}
+
+/// A resource field that can be filtered on, and optionally ordered by.
+#[derive(Clone, Copy, Debug)]
+pub struct Column {
+    field: &'static str,
+    name: &'static str,
+    kind: Kind,
+    orderable: bool,
+}
+
+impl Column {
+    #[must_use]
+    pub const fn new(field: &'static str, name: &'static str, kind: Kind, orderable: bool) -> Self {
+        Self {
+            field,
+            name,
+            kind,
+            orderable,
+        }
+    }
+
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+pub type OrderTerm = (&'static Column, bool);
+
+// MARK: Filter
+
+/// Append the SQL condition of a filter `expression` over `columns`.
+///
+/// # Errors
+///
+/// Returns an error if a restriction names an unknown field or does not fit its column.
+pub fn push_filter(
+    query: &mut QueryBuilder<'_, Sqlite>,
+    columns: &[Column],
+    expression: &Expression,
+) -> Result<(), filter::Error> {
+    match expression {
+        Expression::And(expressions) | Expression::Or(expressions) => {
+            let separator = if matches!(expression, Expression::And(_)) {
+                " AND "
+            } else {
+                " OR "
+            };
+
+            query.push("(");
+            for (i, expression) in expressions.iter().enumerate() {
+                if i > 0 {
+                    query.push(separator);
+                }
+                push_filter(query, columns, expression)?;
+            }
+            query.push(")");
+        }
+        Expression::Not(expression) => {
+            query.push("NOT (");
+            push_filter(query, columns, expression)?;
+            query.push(")");
+        }
+        Expression::Restriction(restriction) => push_restriction(query, columns, restriction)?,
+    }
+
+    Ok(())
+}
+
+fn push_restriction(
+    query: &mut QueryBuilder<'_, Sqlite>,
+    columns: &[Column],
+    restriction: &Restriction,
+) -> Result<(), filter::Error> {
+    let column = columns
+        .iter()
+        .find(|c| c.field == restriction.field())
+        .ok_or_else(|| filter::UnknownFieldError::new(restriction))?;
+
+    match column.kind {
+        Kind::Text => push_text_restriction(query, column.name, restriction),
+        Kind::Integer => push_integer_restriction(query, column.name, restriction),
+        Kind::Timestamp => push_timestamp_restriction(query, column.name, restriction),
+        Kind::State(from_name) => {
+            push_state_restriction(query, column.name, from_name, restriction)
+        }
+    }
+}
+
+fn push_text_restriction(
+    query: &mut QueryBuilder<'_, Sqlite>,
+    column: &str,
+    restriction: &Restriction,
+) -> Result<(), filter::Error> {
+    let value = restriction.value();
+
+    if !value.contains('*') {
+        query.push(column);
+        query.push(sql_comparator(*restriction.comparator()));
+        query.push_bind(value.clone());
+        return Ok(());
+    }
+
+    let operator = match restriction.comparator() {
+        Comparator::Equals | Comparator::Has => " LIKE ",
+        Comparator::NotEquals => " NOT LIKE ",
+        _ => return Err(filter::InvalidComparatorError::new(restriction).into()),
+    };
+    let pattern = value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+        .replace('*', "%");
+
+    query.push(column);
+    query.push(operator);
+    query.push_bind(pattern);
+    query.push(" ESCAPE '\\'");
+
+    Ok(())
+}
+
+fn push_integer_restriction(
+    query: &mut QueryBuilder<'_, Sqlite>,
+    column: &str,
+    restriction: &Restriction,
+) -> Result<(), filter::Error> {
+    if *restriction.comparator() == Comparator::Has {
+        return Err(filter::InvalidComparatorError::new(restriction).into());
+    }
+
+    let value = restriction
+        .value()
+        .parse::<i64>()
+        .map_err(|_| filter::InvalidValueError::new(restriction))?;
+
+    query.push(column);
+    query.push(sql_comparator(*restriction.comparator()));
+    query.push_bind(value);
+
+    Ok(())
+}
+
+fn push_state_restriction(
+    query: &mut QueryBuilder<'_, Sqlite>,
+    column: &str,
+    from_name: fn(&str) -> Option<i64>,
+    restriction: &Restriction,
+) -> Result<(), filter::Error> {
+    if !matches!(
+        restriction.comparator(),
+        Comparator::Equals | Comparator::NotEquals | Comparator::Has
+    ) {
+        return Err(filter::InvalidComparatorError::new(restriction).into());
+    }
+
+    let state = from_name(restriction.value())
+        .ok_or_else(|| filter::InvalidValueError::new(restriction))?;
+
+    query.push(column);
+    query.push(sql_comparator(*restriction.comparator()));
+    query.push_bind(state);
+
+    Ok(())
+}
+
+fn push_timestamp_restriction(
+    query: &mut QueryBuilder<'_, Sqlite>,
+    column: &str,
+    restriction: &Restriction,
+) -> Result<(), filter::Error> {
+    if *restriction.comparator() == Comparator::Has {
+        return Err(filter::InvalidComparatorError::new(restriction).into());
+    }
+
+    let timestamp = DateTime::parse_from_rfc3339(restriction.value())
+        .map_err(|_| filter::InvalidValueError::new(restriction))?;
+    let timestamp = Timestamp::new(timestamp.to_utc());
+
+    query.push(column);
+    query.push(sql_comparator(*restriction.comparator()));
+    query.push_bind(timestamp.value().to_string());
+
+    Ok(())
+}
+
+// MARK: Order
+
+/// Resolve `order_by` against the orderable `columns`, always ending with the
+/// `id` column so that paging is deterministic.
+///
+/// # Errors
+///
+/// Returns an error if an ordering names a field that cannot be ordered by.
+pub fn resolve_order_by(
+    columns: &'static [Column],
+    order_by: Option<&OrderBy>,
+) -> Result<Vec<OrderTerm>, order_by::Error> {
+    let orderings = order_by.map_or(&[][..], OrderBy::orderings);
+    let mut terms = Vec::with_capacity(orderings.len() + 1);
+
+    for ordering in orderings {
+        let column = columns
+            .iter()
+            .filter(|c| c.orderable)
+            .find(|c| c.field == ordering.field() || c.name == ordering.field())
+            .ok_or_else(|| order_by::UnknownFieldError::new(ordering))?;
+
+        terms.push((column, *ordering.descending()));
+    }
+
+    // Ids are unique, so ordering by them last makes paging deterministic.
+    if !terms.iter().any(|(column, _)| column.name == "id") {
+        if let Some(id) = columns.iter().find(|c| c.name == "id") {
+            terms.push((id, false));
+        }
+    }
+
+    Ok(terms)
+}
+
+pub fn push_order_by(query: &mut QueryBuilder<'_, Sqlite>, terms: &[OrderTerm]) {
+    query.push(" ORDER BY ");
+    for (i, (column, descending)) in terms.iter().enumerate() {
+        if i > 0 {
+            query.push(", ");
+        }
+        query.push(column.name);
+        query.push(if *descending { " DESC" } else { " ASC" });
+    }
+}
+
+/// Restrict the query to rows sorting strictly after `cursor`, e.g.
+/// `(a > ? OR (a = ? AND b < ?))` for `order_by = "a, b desc"`.
+///
+/// # Errors
+///
+/// Returns an error if the cursor does not fit the `terms`.
+pub fn push_cursor(
+    query: &mut QueryBuilder<'_, Sqlite>,
+    terms: &[OrderTerm],
+    cursor: &[String],
+) -> Result<(), page_token::Error> {
+    if terms.len() != cursor.len() {
+        return Err(page_token::InvalidError.into());
+    }
+
+    query.push("(");
+    for i in 0..terms.len() {
+        if i > 0 {
+            query.push(" OR ");
+        }
+        query.push("(");
+        for (j, ((column, descending), value)) in terms.iter().zip(cursor).enumerate().take(i + 1) {
+            if j > 0 {
+                query.push(" AND ");
+            }
+            query.push(column.name);
+            query.push(match (j == i, descending) {
+                (false, _) => " = ",
+                (true, false) => " > ",
+                (true, true) => " < ",
+            });
+            if matches!(column.kind, Kind::Integer | Kind::State(_)) {
+                let value = value.parse::<i64>().map_err(|_| page_token::InvalidError)?;
+                query.push_bind(value);
+            } else {
+                query.push_bind(value.clone());
+            }
+        }
+        query.push(")");
+    }
+    query.push(")");
+
+    Ok(())
+}
+
+const fn sql_comparator(comparator: Comparator) -> &'static str {
+    match comparator {
+        Comparator::Equals | Comparator::Has => " = ",
+        Comparator::NotEquals => " <> ",
+        Comparator::LessThan => " < ",
+        Comparator::LessEquals => " <= ",
+        Comparator::GreaterThan => " > ",
+        Comparator::GreaterEquals => " >= ",
+    }
+}
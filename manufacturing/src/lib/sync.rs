@@ -1,6 +1,14 @@
+use std::{fmt::Debug, future::Future};
+
 use derive_getters::{Dissolve, Getters};
+use derive_more::From;
+use prost_types::Any;
+
+use crate::{grpc::proto::google::rpc, id, timestamp, Id, ThisError, Timestamp};
 
-use crate::Id;
+pub mod command;
+pub mod query;
+pub mod repository;
 
 pub trait OperationState {
     type NextState;
@@ -39,6 +47,18 @@ pub trait OperationEntity {
 
     fn id(&self) -> &Id;
     fn state(&self) -> &Self::State;
+
+    /// The resource name of the entity, e.g. `items/{item}`.
+    fn name(&self) -> String;
+}
+
+/// A worker driving the pending operations of one kind of entity to completion.
+pub trait Reconcile: Send + Sync + 'static {
+    type Error: Debug + Send;
+
+    /// Drive every pending operation to completion.
+    /// Returns the number of operations that were completed.
+    fn reconcile(&self) -> impl Future<Output = Result<usize, Self::Error>> + Send;
 }
 
 pub trait OperationMetadata {
@@ -66,3 +86,80 @@ impl<T: OperationMetadata> Operation<T> {
         }
     }
 }
+
+/// An [`Operation`] as kept by the operation store, with its metadata and
+/// result already packed into their wire representation. The target is the
+/// resource name of the entity the operation acts on.
+#[derive(Clone, Debug, From, Dissolve, Getters)]
+pub struct OperationRecord {
+    id: Id,
+    target: String,
+    metadata: Any,
+    result: Option<Result<Any, rpc::Status>>,
+    create_time: Timestamp,
+    update_time: Timestamp,
+}
+
+impl OperationRecord {
+    #[must_use]
+    pub fn new(id: Id, target: String, metadata: Any) -> Self {
+        let now = Timestamp::now();
+
+        Self {
+            id,
+            target,
+            metadata,
+            result: None,
+            create_time: now.clone(),
+            update_time: now,
+        }
+    }
+
+    #[must_use]
+    pub const fn is_done(&self) -> bool {
+        self.result.is_some()
+    }
+
+    #[must_use]
+    pub fn done(self, result: Result<Any, rpc::Status>) -> Self {
+        Self {
+            id: self.id,
+            target: self.target,
+            metadata: self.metadata,
+            result: Some(result),
+            create_time: self.create_time,
+            update_time: Timestamp::now(),
+        }
+    }
+}
+
+#[derive(Debug, ThisError)]
+pub enum Error {
+    #[error(transparent)]
+    Id(#[from] id::Error),
+    #[error(transparent)]
+    Timestamp(#[from] timestamp::Error),
+    #[error(transparent)]
+    Filter(#[from] FilterError),
+    #[error(transparent)]
+    Uncancellable(#[from] UncancellableError),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Clone, Debug, ThisError)]
+#[error("operation filter is not supported")]
+pub struct FilterError;
+
+#[derive(Clone, Debug, ThisError, Getters)]
+#[error("operation {id:?} cannot be cancelled, cancellation is not supported")]
+pub struct UncancellableError {
+    id: Id,
+}
+
+impl UncancellableError {
+    #[must_use]
+    pub const fn new(id: Id) -> Self {
+        Self { id }
+    }
+}
@@ -0,0 +1,87 @@
+pub use super::Error;
+
+use std::{future::Future, sync::Arc};
+
+use crate::Id;
+
+use super::{repository, UncancellableError};
+
+// MARK: Delete
+
+pub trait Delete: Send + Sync + 'static {
+    #[must_use]
+    fn delete(&self, request: DeleteRequest) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+pub struct DeleteRequest {
+    id: String,
+}
+
+impl DeleteRequest {
+    #[must_use]
+    pub const fn new(id: String) -> Self {
+        Self { id }
+    }
+}
+
+// MARK: Cancel
+
+pub trait Cancel: Send + Sync + 'static {
+    /// Refuse to cancel an operation, as cancellation is not supported. The resource of an
+    /// operation is already in a transitional state that only the worker settles.
+    #[must_use]
+    fn cancel(&self, request: CancelRequest) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+pub struct CancelRequest {
+    id: String,
+}
+
+impl CancelRequest {
+    #[must_use]
+    pub const fn new(id: String) -> Self {
+        Self { id }
+    }
+}
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+pub struct Service<OR: repository::Get + repository::Delete + Clone> {
+    operation_repository: Arc<OR>,
+}
+
+impl<OR> Service<OR>
+where
+    OR: repository::Get + repository::Delete + Clone,
+{
+    #[must_use]
+    pub const fn new(operation_repository: Arc<OR>) -> Self {
+        Self {
+            operation_repository,
+        }
+    }
+}
+
+impl<OR> Delete for Service<OR>
+where
+    OR: repository::Get + repository::Delete + Clone,
+{
+    async fn delete(&self, request: DeleteRequest) -> Result<(), Error> {
+        let id = Id::try_from(request.id)?;
+
+        self.operation_repository.delete(&id).await
+    }
+}
+
+impl<OR> Cancel for Service<OR>
+where
+    OR: repository::Get + repository::Delete + Clone,
+{
+    async fn cancel(&self, request: CancelRequest) -> Result<(), Error> {
+        let id = Id::try_from(request.id)?;
+        self.operation_repository.get(&id).await?;
+
+        Err(UncancellableError::new(id).into())
+    }
+}
@@ -0,0 +1,161 @@
+use derive_getters::{Dissolve, Getters};
+
+pub use super::Error;
+
+use std::{future::Future, sync::Arc, time::Duration};
+
+use tokio::time::{sleep, Instant};
+
+use crate::Id;
+
+use super::{repository, OperationRecord};
+
+const DEFAULT_WAIT_TIMEOUT: Duration = Duration::from_mins(1);
+
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+// MARK: Get
+
+pub trait Get: Send + Sync + 'static {
+    fn get(
+        &self,
+        request: GetRequest,
+    ) -> impl Future<Output = Result<OperationRecord, Error>> + Send;
+}
+
+pub struct GetRequest {
+    id: String,
+}
+
+impl GetRequest {
+    #[must_use]
+    pub const fn new(id: String) -> Self {
+        Self { id }
+    }
+}
+
+// MARK: List
+
+pub trait List: Send + Sync + 'static {
+    fn list(
+        &self,
+        request: ListRequest,
+    ) -> impl Future<Output = Result<ListResponse, Error>> + Send;
+}
+
+#[derive(Getters)]
+pub struct ListRequest {
+    page_size: i32,
+    page_token: Option<String>,
+    filter: Option<String>,
+}
+
+impl ListRequest {
+    #[must_use]
+    pub fn new(page_size: Option<i32>, page_token: Option<String>, filter: Option<String>) -> Self {
+        Self {
+            page_size: page_size.filter(|s| *s > 0).unwrap_or(50).min(1000),
+            page_token: page_token.filter(|t| !t.is_empty()),
+            filter,
+        }
+    }
+}
+
+#[derive(Dissolve)]
+pub struct ListResponse {
+    operations: Vec<OperationRecord>,
+    next_page_token: Option<String>,
+}
+
+impl ListResponse {
+    #[must_use]
+    pub const fn new(operations: Vec<OperationRecord>, next_page_token: Option<String>) -> Self {
+        Self {
+            operations,
+            next_page_token,
+        }
+    }
+}
+
+// MARK: Wait
+
+pub trait Wait: Send + Sync + 'static {
+    fn wait(
+        &self,
+        request: WaitRequest,
+    ) -> impl Future<Output = Result<OperationRecord, Error>> + Send;
+}
+
+pub struct WaitRequest {
+    id: String,
+    timeout: Duration,
+}
+
+impl WaitRequest {
+    #[must_use]
+    pub fn new(id: String, timeout: Option<Duration>) -> Self {
+        Self {
+            id,
+            timeout: timeout.unwrap_or(DEFAULT_WAIT_TIMEOUT),
+        }
+    }
+}
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+pub struct Service<OR: repository::Get + repository::List + Clone> {
+    operation_repository: Arc<OR>,
+}
+
+impl<OR> Service<OR>
+where
+    OR: repository::Get + repository::List + Clone,
+{
+    #[must_use]
+    pub const fn new(operation_repository: Arc<OR>) -> Self {
+        Self {
+            operation_repository,
+        }
+    }
+}
+
+impl<OR> Get for Service<OR>
+where
+    OR: repository::Get + repository::List + Clone,
+{
+    async fn get(&self, request: GetRequest) -> Result<OperationRecord, Error> {
+        let id = Id::try_from(request.id)?;
+
+        self.operation_repository.get(&id).await
+    }
+}
+
+impl<OR> List for Service<OR>
+where
+    OR: repository::Get + repository::List + Clone,
+{
+    async fn list(&self, request: ListRequest) -> Result<ListResponse, Error> {
+        self.operation_repository.list(&request).await
+    }
+}
+
+impl<OR> Wait for Service<OR>
+where
+    OR: repository::Get + repository::List + Clone,
+{
+    async fn wait(&self, request: WaitRequest) -> Result<OperationRecord, Error> {
+        let id = Id::try_from(request.id)?;
+        let deadline = Instant::now() + request.timeout;
+
+        loop {
+            let operation = self.operation_repository.get(&id).await?;
+
+            if operation.is_done() || Instant::now() >= deadline {
+                return Ok(operation);
+            }
+
+            sleep(WAIT_POLL_INTERVAL.min(deadline.saturating_duration_since(Instant::now()))).await;
+        }
+    }
+}
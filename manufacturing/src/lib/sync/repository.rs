@@ -0,0 +1,621 @@
+use std::{future::Future, sync::Arc};
+
+use anyhow::{anyhow, Context};
+use prost::Message;
+use prost_types::Any;
+use sqlx::{Executor, Sqlite, Transaction};
+
+use crate::{
+    grpc::proto::google::rpc,
+    id,
+    sqlx::{Error as SqlxError, SqliteConnection, SqliteError},
+    Id, Timestamp,
+};
+
+use super::{
+    query::{ListRequest, ListResponse},
+    Error, OperationRecord,
+};
+
+/// The most pending operations a worker takes on in one go; the rest wait for its next tick.
+/// Each worker only takes on the operations of its own targets, see [`Pending::pending`].
+pub const PENDING_OPERATIONS_LIMIT: i64 = 100;
+
+// MARK: Get
+
+/// `Get` represents a store of operation data.
+pub trait Get: Send + Sync + 'static {
+    /// Get an [`OperationRecord`].
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`id::Error::NotFound`] if an operation with the given [`Id`] does not exist.
+    fn get(&self, id: &Id) -> impl Future<Output = Result<OperationRecord, Error>> + Send;
+}
+
+// MARK: List
+
+/// `List` represents a store of operation data.
+pub trait List: Send + Sync + 'static {
+    /// List [`OperationRecord`]s.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::Filter`] if the filter is not supported.
+    fn list(
+        &self,
+        request: &ListRequest,
+    ) -> impl Future<Output = Result<ListResponse, Error>> + Send;
+}
+
+// MARK: Pending
+
+/// `Pending` represents a store of operation data.
+pub trait Pending: Send + Sync + 'static {
+    /// List the oldest [`OperationRecord`]s that are not done yet, at most
+    /// [`PENDING_OPERATIONS_LIMIT`], oldest first.
+    ///
+    /// Only operations whose target matches `targets` are listed, a GLOB pattern with a `*`
+    /// for each id, e.g. `items/*/routing`. Operations on resources nested below a matching
+    /// target are left out, so that they cannot crowd out the ones the caller reconciles.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`Error::Unknown`] if the operations cannot be fetched.
+    fn pending(
+        &self,
+        targets: &str,
+    ) -> impl Future<Output = Result<Vec<OperationRecord>, Error>> + Send;
+}
+
+// MARK: Update
+
+/// `Update` represents a store of operation data.
+pub trait Update: Send + Sync + 'static {
+    /// Store the result of a done [`OperationRecord`].
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`id::Error::NotFound`] if an operation with the given [`Id`] does not exist.
+    fn update(&self, operation: &OperationRecord)
+        -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+// MARK: Delete
+
+/// `Delete` represents a store of operation data.
+pub trait Delete: Send + Sync + 'static {
+    /// Delete an [`OperationRecord`].
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [`id::Error::NotFound`] if an operation with the given [`Id`] does not exist.
+    fn delete(&self, id: &Id) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+// MARK: Service
+
+#[derive(Debug, Clone)]
+pub struct Service<DB: SqliteConnection> {
+    db: Arc<DB>,
+}
+
+struct Row {
+    id: String,
+    target: String,
+    metadata_type: String,
+    metadata: Vec<u8>,
+    done: i64,
+    response_type: Option<String>,
+    response: Option<Vec<u8>>,
+    error_code: Option<i64>,
+    error_message: Option<String>,
+    error_status: Option<Vec<u8>>,
+    create_time: String,
+    update_time: String,
+}
+
+impl TryFrom<Row> for OperationRecord {
+    type Error = Error;
+
+    fn try_from(value: Row) -> Result<Self, Self::Error> {
+        let id = Id::try_from(value.id)?;
+        let target = value.target;
+        let metadata = Any {
+            type_url: value.metadata_type,
+            value: value.metadata,
+        };
+        let result = match (value.done, value.error_code, value.error_status) {
+            (0, ..) => None,
+            // The encoded status keeps its details, which the code and message alone lose.
+            (_, Some(_), Some(status)) => Some(Err(rpc::Status::decode(status.as_slice())
+                .map_err(|e| Error::Unknown(anyhow!("invalid error status: {e}")))?)),
+            (_, Some(code), None) => Some(Err(rpc::Status {
+                code: code
+                    .try_into()
+                    .map_err(|e| Error::Unknown(anyhow!("invalid error code {code}: {e}")))?,
+                message: value.error_message.unwrap_or_default(),
+                details: vec![],
+            })),
+            (_, None, _) => Some(Ok(Any {
+                type_url: value.response_type.unwrap_or_default(),
+                value: value.response.unwrap_or_default(),
+            })),
+        };
+        let create_time = Timestamp::try_from(value.create_time)?;
+        let update_time = Timestamp::try_from(value.update_time)?;
+
+        Ok(Self::from((
+            id,
+            target,
+            metadata,
+            result,
+            create_time,
+            update_time,
+        )))
+    }
+}
+
+/// Persist a new [`OperationRecord`] as part of an ongoing transaction, so an
+/// entity and the operation issued for it are stored together.
+///
+/// # Errors
+///
+/// - MUST return [`id::Error::Duplicate`] if an operation with the same [`Id`] already exists.
+pub(crate) async fn save_operation(
+    tx: &mut Transaction<'_, Sqlite>,
+    operation: &OperationRecord,
+) -> Result<(), Error> {
+    let id = operation.id().value();
+    let target = operation.target();
+    let metadata_type = &operation.metadata().type_url;
+    let metadata = &operation.metadata().value;
+    let done = i64::from(operation.is_done());
+    let (response_type, response, error_code, error_message, error_status) =
+        result_columns(operation.result().as_ref());
+    let create_time = &operation.create_time().value().to_string();
+    let update_time = &operation.update_time().value().to_string();
+
+    let query = sqlx::query!(
+        "INSERT INTO operation (
+            id,
+            target,
+            metadata_type,
+            metadata,
+            done,
+            response_type,
+            response,
+            error_code,
+            error_message,
+            error_status,
+            create_time,
+            update_time
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+        id,
+        target,
+        metadata_type,
+        metadata,
+        done,
+        response_type,
+        response,
+        error_code,
+        error_message,
+        error_status,
+        create_time,
+        update_time,
+    );
+
+    tx.execute(query)
+        .await
+        .map_err(|e| match SqlxError::from(&e) {
+            SqlxError::Sqlite { inner } => match inner {
+                SqliteError::UniqueConstraintViolationCode => {
+                    Error::Id(id::DuplicateError(operation.id().clone()).into())
+                }
+                SqliteError::Unknown { message } => anyhow!(e)
+                    .context(format!(
+                        "failed to insert operation with id {id:?}, with message from database: {message:?}"
+                    ))
+                    .into(),
+            },
+            SqlxError::RowNotFound | SqlxError::Unknown => anyhow!(e)
+                .context(format!("failed to insert operation with id {id:?}"))
+                .into(),
+        })?;
+
+    Ok(())
+}
+
+/// Mark an operation as done with the given result as part of an ongoing
+/// transaction, so an entity and the outcome of its operation are stored
+/// together.
+///
+/// # Errors
+///
+/// - MUST return [`id::Error::NotFound`] if an operation with the given [`Id`] does not exist.
+pub(crate) async fn complete_operation(
+    tx: &mut Transaction<'_, Sqlite>,
+    id: &Id,
+    result: &Result<Any, rpc::Status>,
+) -> Result<(), Error> {
+    let id = id.value();
+    let (response_type, response, error_code, error_message, error_status) =
+        result_columns(Some(result));
+    let update_time = &Timestamp::now().value().to_string();
+
+    let query = sqlx::query!(
+        "UPDATE operation SET
+            done            = 1,
+            response_type   = $2,
+            response        = $3,
+            error_code      = $4,
+            error_message   = $5,
+            error_status    = $6,
+            update_time     = $7
+        WHERE id = $1",
+        id,
+        response_type,
+        response,
+        error_code,
+        error_message,
+        error_status,
+        update_time,
+    );
+
+    let result = tx
+        .execute(query)
+        .await
+        .map_err(|e| anyhow!(e).context(format!("failed to update operation with id {id:?}")))?;
+
+    if result.rows_affected() == 0 {
+        return Err(Error::Id(id::NotFoundError.into()));
+    }
+
+    Ok(())
+}
+
+/// Remove every operation recorded for `target` except `keep` as part of an
+/// ongoing transaction, so an entity can be purged together with its history.
+///
+/// # Errors
+///
+/// Returns an error if the operations cannot be deleted.
+pub(crate) async fn purge_operations(
+    tx: &mut Transaction<'_, Sqlite>,
+    target: &str,
+    keep: &Id,
+) -> Result<(), Error> {
+    let keep = keep.value();
+
+    let query = sqlx::query!(
+        "DELETE FROM operation WHERE target = $1 AND id <> $2",
+        target,
+        keep,
+    );
+
+    tx.execute(query).await.map_err(|e| {
+        anyhow!(e).context(format!("failed to purge operations of target {target:?}"))
+    })?;
+
+    Ok(())
+}
+
+type ResultColumns<'a> = (
+    Option<&'a str>,
+    Option<&'a [u8]>,
+    Option<i64>,
+    Option<&'a str>,
+    Option<Vec<u8>>,
+);
+
+fn result_columns(result: Option<&Result<Any, rpc::Status>>) -> ResultColumns<'_> {
+    match result {
+        None => (None, None, None, None, None),
+        Some(Ok(response)) => (
+            Some(response.type_url.as_str()),
+            Some(response.value.as_slice()),
+            None,
+            None,
+            None,
+        ),
+        Some(Err(status)) => (
+            None,
+            None,
+            Some(i64::from(status.code)),
+            Some(status.message.as_str()),
+            Some(status.encode_to_vec()),
+        ),
+    }
+}
+
+impl<DB> Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    #[must_use]
+    pub const fn new(db: Arc<DB>) -> Self {
+        Self { db }
+    }
+
+    async fn fetch_operation(&self, id: &Id) -> Result<OperationRecord, Error> {
+        let id = id.value();
+
+        let query = sqlx::query_as!(
+            Row,
+            "SELECT
+                id,
+                target,
+                metadata_type,
+                metadata,
+                done,
+                response_type,
+                response,
+                error_code,
+                error_message,
+                error_status,
+                create_time,
+                update_time
+            FROM operation WHERE id = $1",
+            id
+        );
+
+        let result =
+            query
+                .fetch_one(&self.db.pool())
+                .await
+                .map_err(|e| match SqlxError::from(&e) {
+                    SqlxError::RowNotFound => Error::Id(id::NotFoundError.into()),
+                    _ => Error::from(
+                        anyhow!(e).context(format!("failed to fetch operation with id {id:?}")),
+                    ),
+                })?;
+
+        result.try_into()
+    }
+
+    async fn fetch_operations(&self, request: &ListRequest) -> Result<ListResponse, Error> {
+        if request
+            .filter()
+            .as_deref()
+            .is_some_and(|f| !f.trim().is_empty())
+        {
+            return Err(super::FilterError.into());
+        }
+
+        let page_size = *request.page_size();
+        let limit = page_size + 1;
+        let page_token = request.page_token();
+
+        let query = sqlx::query_as!(
+            Row,
+            "SELECT
+                id,
+                target,
+                metadata_type,
+                metadata,
+                done,
+                response_type,
+                response,
+                error_code,
+                error_message,
+                error_status,
+                create_time,
+                update_time
+            FROM operation WHERE $1 IS NULL OR id > $1
+            ORDER BY id
+            LIMIT $2",
+            page_token,
+            limit
+        );
+
+        let result = query
+            .fetch_all(&self.db.pool())
+            .await
+            .map_err(|e| Error::from(anyhow!(e).context("failed to fetch operations")))?;
+
+        let mut operations: Vec<OperationRecord> = vec![];
+
+        for r in result {
+            operations.push(r.try_into()?);
+        }
+
+        let next_page_token = if operations.len() > usize::try_from(page_size).unwrap_or(0) {
+            operations.pop();
+            operations.last().map(|o| o.id().to_string())
+        } else {
+            None
+        };
+
+        Ok(ListResponse::new(operations, next_page_token))
+    }
+
+    async fn fetch_pending_operations(&self, targets: &str) -> Result<Vec<OperationRecord>, Error> {
+        let query = sqlx::query_as!(
+            Row,
+            "SELECT
+                id,
+                target,
+                metadata_type,
+                metadata,
+                done,
+                response_type,
+                response,
+                error_code,
+                error_message,
+                error_status,
+                create_time,
+                update_time
+            FROM operation
+            WHERE done = 0 AND target GLOB $1 AND target NOT GLOB $1 || '/*'
+            ORDER BY create_time, id
+            LIMIT $2",
+            targets,
+            PENDING_OPERATIONS_LIMIT,
+        );
+
+        let result = query
+            .fetch_all(&self.db.pool())
+            .await
+            .map_err(|e| Error::from(anyhow!(e).context("failed to fetch pending operations")))?;
+
+        result.into_iter().map(TryInto::try_into).collect()
+    }
+
+    async fn remove_operation(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        id: &Id,
+    ) -> Result<(), Error> {
+        let id = id.value();
+
+        let query = sqlx::query!("DELETE FROM operation WHERE id = $1", id);
+
+        let result = tx.execute(query).await.map_err(|e| {
+            Error::from(anyhow!(e).context(format!("failed to delete operation with id {id:?}")))
+        })?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::Id(id::NotFoundError.into()));
+        }
+
+        Ok(())
+    }
+}
+
+impl<DB> Get for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn get(&self, id: &Id) -> Result<OperationRecord, Error> {
+        let operation = self.fetch_operation(id).await?;
+        Ok(operation)
+    }
+}
+
+impl<DB> List for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn list(&self, request: &ListRequest) -> Result<ListResponse, Error> {
+        let response = self.fetch_operations(request).await?;
+        Ok(response)
+    }
+}
+
+impl<DB> Pending for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn pending(&self, targets: &str) -> Result<Vec<OperationRecord>, Error> {
+        let operations = self.fetch_pending_operations(targets).await?;
+        Ok(operations)
+    }
+}
+
+impl<DB> Update for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn update(&self, operation: &OperationRecord) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool()
+            .begin()
+            .await
+            .with_context(|| "failed to start SQLite transaction")?;
+
+        let result = operation
+            .result()
+            .as_ref()
+            .ok_or_else(|| anyhow!("operation with id {:?} is not done", operation.id().value()))?;
+        complete_operation(&mut tx, operation.id(), result).await?;
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(())
+    }
+}
+
+impl<DB> Delete for Service<DB>
+where
+    DB: SqliteConnection + Clone,
+{
+    async fn delete(&self, id: &Id) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool()
+            .begin()
+            .await
+            .with_context(|| "failed to start SQLite transaction")?;
+
+        self.remove_operation(&mut tx, id).await?;
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grpc::status;
+
+    use super::*;
+
+    /// The row an operation done with `result` is stored as.
+    fn row(result: &Result<Any, rpc::Status>) -> Row {
+        let (response_type, response, error_code, error_message, error_status) =
+            result_columns(Some(result));
+        let now = Timestamp::now().value().to_string();
+
+        Row {
+            id: String::from("op-1"),
+            target: String::from("items/bike"),
+            metadata_type: String::new(),
+            metadata: vec![],
+            done: 1,
+            response_type: response_type.map(ToString::to_string),
+            response: response.map(<[u8]>::to_vec),
+            error_code,
+            error_message: error_message.map(ToString::to_string),
+            error_status,
+            create_time: now.clone(),
+            update_time: now,
+        }
+    }
+
+    #[test]
+    fn keeps_the_details_of_errors() -> anyhow::Result<()> {
+        let status = status::bad_request("name", String::from("name is empty"));
+        let error = rpc::Status::decode(status.details())?;
+        assert!(!error.details.is_empty());
+
+        let result = Err(error);
+        let operation = OperationRecord::try_from(row(&result))?;
+        assert_eq!(&Some(result), operation.result());
+
+        Ok(())
+    }
+
+    #[test]
+    fn reads_errors_stored_without_their_status() -> anyhow::Result<()> {
+        let result = Err(rpc::Status {
+            code: 5,
+            message: String::from("item not found"),
+            details: vec![],
+        });
+        let row = Row {
+            error_status: None,
+            ..row(&result)
+        };
+
+        let operation = OperationRecord::try_from(row)?;
+        assert_eq!(&Some(result), operation.result());
+
+        Ok(())
+    }
+}
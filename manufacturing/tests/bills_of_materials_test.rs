@@ -0,0 +1,444 @@
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+use manufacturing::{
+    grpc::proto::google::longrunning::{
+        operation, operations_client::OperationsClient, Operation, WaitOperationRequest,
+    },
+    proto::bill_of_materials::{
+        bill_of_materials::{Component, State},
+        bill_of_materials_command_service_client::BillOfMaterialsCommandServiceClient,
+        bill_of_materials_query_service_client::BillOfMaterialsQueryServiceClient,
+        ActivateBillOfMaterialsRequest, BillOfMaterials, CreateBillOfMaterialsRequest,
+        ExplodeBillOfMaterialsRequest, GetBillOfMaterialsRequest, ListBillsOfMaterialsRequest,
+    },
+    proto::{
+        item_service_client::ItemServiceClient, AnnihilateItemRequest, CreateItemRequest,
+        GetItemRequest, Item,
+    },
+};
+use tonic::{transport::Channel, Code, Request};
+
+const PATH: &str = "http://localhost:8081";
+
+async fn wait(
+    operations_client: &mut OperationsClient<Channel>,
+    operation: Operation,
+) -> Result<Operation, Box<dyn std::error::Error>> {
+    let request = WaitOperationRequest {
+        name: operation.name,
+        timeout: None,
+    };
+    let request = Request::new(request);
+
+    let operation = operations_client
+        .wait_operation(request)
+        .await?
+        .into_inner();
+    assert!(operation.done);
+
+    Ok(operation)
+}
+
+async fn create_items(
+    operations_client: &mut OperationsClient<Channel>,
+    ids: &[&str],
+) -> Result<(), Box<dyn std::error::Error>> {
+    create_items_in(operations_client, ids, None).await
+}
+
+/// Create items counted in `base_uom`, pieces if unset.
+async fn create_items_in(
+    operations_client: &mut OperationsClient<Channel>,
+    ids: &[&str],
+    base_uom: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut item_client = ItemServiceClient::connect(PATH).await?;
+
+    for id in ids {
+        let request = CreateItemRequest {
+            item_id: Some((*id).to_string()),
+            item: Some(Item {
+                base_uom: base_uom.map(|uom| format!("unitsOfMeasure/{uom}")),
+                ..Item::default()
+            }),
+        };
+        let request = Request::new(request);
+
+        let operation = item_client.create_item(request).await?.into_inner();
+        wait(operations_client, operation).await?;
+    }
+    drop(item_client);
+
+    Ok(())
+}
+
+fn component(item: &str, quantity: &str, scrap_factor: &str) -> Component {
+    component_in(item, quantity, "pcs", scrap_factor)
+}
+
+fn component_in(
+    item: &str,
+    quantity: &str,
+    unit_of_measure: &str,
+    scrap_factor: &str,
+) -> Component {
+    Component {
+        item: format!("items/{item}"),
+        quantity: quantity.to_string(),
+        unit_of_measure: unit_of_measure.to_string(),
+        scrap_factor: scrap_factor.to_string(),
+    }
+}
+
+/// Create a bill of materials of `item` and, unless it is a draft, activate it.
+async fn create(
+    command_client: &mut BillOfMaterialsCommandServiceClient<Channel>,
+    operations_client: &mut OperationsClient<Channel>,
+    name: &str,
+    components: Vec<Component>,
+    activate: bool,
+) -> Result<BillOfMaterials, Box<dyn std::error::Error>> {
+    let (parent, id) = name.split_once("/boms/").ok_or("invalid name")?;
+
+    let request = CreateBillOfMaterialsRequest {
+        parent: parent.to_string(),
+        bill_of_materials_id: id.to_string(),
+        bill_of_materials: Some(BillOfMaterials {
+            components,
+            ..Default::default()
+        }),
+    };
+    let request = Request::new(request);
+
+    let operation = command_client
+        .create_bill_of_materials(request)
+        .await?
+        .into_inner();
+    let operation = wait(operations_client, operation).await?;
+
+    let Some(operation::Result::Response(response)) = operation.result else {
+        return Err("operation has no response".into());
+    };
+    let bill_of_materials = response.to_msg::<BillOfMaterials>()?;
+
+    if !activate {
+        return Ok(bill_of_materials);
+    }
+
+    let request = ActivateBillOfMaterialsRequest {
+        name: name.to_string(),
+        etag: bill_of_materials.etag,
+    };
+    let request = Request::new(request);
+
+    let operation = command_client
+        .activate_bill_of_materials(request)
+        .await?
+        .into_inner();
+    wait(operations_client, operation).await?;
+
+    get(name).await
+}
+
+async fn get(name: &str) -> Result<BillOfMaterials, Box<dyn std::error::Error>> {
+    let mut query_client = BillOfMaterialsQueryServiceClient::connect(PATH).await?;
+
+    let request = GetBillOfMaterialsRequest {
+        name: name.to_string(),
+    };
+    let request = Request::new(request);
+
+    Ok(query_client
+        .get_bill_of_materials(request)
+        .await?
+        .into_inner())
+}
+
+#[tokio::test]
+async fn it_activates_versions_of_bill_of_materials() -> Result<(), Box<dyn std::error::Error>> {
+    let mut command_client = BillOfMaterialsCommandServiceClient::connect(PATH).await?;
+    let mut query_client = BillOfMaterialsQueryServiceClient::connect(PATH).await?;
+    let mut operations_client = OperationsClient::connect(PATH).await?;
+    let mut item_client = ItemServiceClient::connect(PATH).await?;
+
+    create_items(&mut operations_client, &["bom-frame", "bom-frame-tube"]).await?;
+
+    let first = create(
+        &mut command_client,
+        &mut operations_client,
+        "items/bom-frame/boms/first",
+        vec![component("bom-frame-tube", "3", "0")],
+        true,
+    )
+    .await?;
+    assert_eq!(State::Active as i32, first.state);
+    assert_eq!(1, first.version);
+    assert_eq!("items/bom-frame-tube", first.components[0].item);
+
+    let second = create(
+        &mut command_client,
+        &mut operations_client,
+        "items/bom-frame/boms/second",
+        vec![component("bom-frame-tube", "4", "0.1")],
+        false,
+    )
+    .await?;
+    assert_eq!(State::Draft as i32, second.state);
+    assert_eq!(2, second.version);
+
+    let request = ActivateBillOfMaterialsRequest {
+        name: second.name.clone(),
+        etag: second.etag.clone(),
+    };
+    let operation = command_client
+        .activate_bill_of_materials(Request::new(request))
+        .await?
+        .into_inner();
+    wait(&mut operations_client, operation).await?;
+
+    let request = ListBillsOfMaterialsRequest {
+        parent: String::from("items/bom-frame"),
+        order_by: String::from("version desc"),
+        ..Default::default()
+    };
+    let response = query_client
+        .list_bills_of_materials(Request::new(request))
+        .await?
+        .into_inner();
+    let states: Vec<_> = response
+        .bills_of_materials
+        .iter()
+        .map(|b| b.state)
+        .collect();
+    assert_eq!(vec![State::Active as i32, State::Obsolete as i32], states);
+
+    // The tube is a component, even of an obsolete bill of materials, so it cannot go.
+    let item = item_client
+        .get_item(Request::new(GetItemRequest {
+            name: String::from("bom-frame-tube"),
+        }))
+        .await?
+        .into_inner();
+    let request = AnnihilateItemRequest {
+        name: item.name,
+        etag: item.etag.ok_or("item has no etag")?,
+    };
+    let error = item_client
+        .annihilate_item(Request::new(request))
+        .await
+        .err()
+        .ok_or("a component was annihilated")?;
+    assert_eq!(Code::FailedPrecondition, error.code());
+
+    drop(command_client);
+    drop(query_client);
+    drop(operations_client);
+    drop(item_client);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_rejects_cyclic_bill_of_materials() -> Result<(), Box<dyn std::error::Error>> {
+    let mut command_client = BillOfMaterialsCommandServiceClient::connect(PATH).await?;
+    let mut operations_client = OperationsClient::connect(PATH).await?;
+
+    create_items(&mut operations_client, &["bom-cycle-a", "bom-cycle-b"]).await?;
+
+    create(
+        &mut command_client,
+        &mut operations_client,
+        "items/bom-cycle-a/boms/standard",
+        vec![component("bom-cycle-b", "1", "0")],
+        true,
+    )
+    .await?;
+
+    for (name, item) in [
+        ("items/bom-cycle-b/boms/standard", "bom-cycle-a"),
+        ("items/bom-cycle-a/boms/itself", "bom-cycle-a"),
+    ] {
+        let status = create(
+            &mut command_client,
+            &mut operations_client,
+            name,
+            vec![component(item, "1", "0")],
+            false,
+        )
+        .await
+        .err()
+        .ok_or("a cycle was accepted")?
+        .downcast::<tonic::Status>()
+        .map_err(|_| "not a status")?;
+        assert_eq!(Code::InvalidArgument, status.code());
+    }
+
+    drop(command_client);
+    drop(operations_client);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_explodes_bill_of_materials() -> Result<(), Box<dyn std::error::Error>> {
+    let mut command_client = BillOfMaterialsCommandServiceClient::connect(PATH).await?;
+    let mut query_client = BillOfMaterialsQueryServiceClient::connect(PATH).await?;
+    let mut operations_client = OperationsClient::connect(PATH).await?;
+
+    create_items(
+        &mut operations_client,
+        &[
+            "bom-bike",
+            "bom-bike-frame",
+            "bom-bike-tube",
+            "bom-bike-wheel",
+        ],
+    )
+    .await?;
+
+    create(
+        &mut command_client,
+        &mut operations_client,
+        "items/bom-bike-frame/boms/standard",
+        vec![component("bom-bike-tube", "3", "0")],
+        true,
+    )
+    .await?;
+    create(
+        &mut command_client,
+        &mut operations_client,
+        "items/bom-bike/boms/standard",
+        vec![
+            component("bom-bike-frame", "1", "0"),
+            component("bom-bike-wheel", "2", "0.5"),
+        ],
+        true,
+    )
+    .await?;
+
+    let request = ExplodeBillOfMaterialsRequest {
+        name: String::from("items/bom-bike/boms/standard"),
+        quantity: String::from("2"),
+    };
+    let lines = query_client
+        .explode_bill_of_materials(Request::new(request))
+        .await?
+        .into_inner()
+        .lines;
+
+    let lines: Vec<_> = lines
+        .iter()
+        .map(|l| {
+            (
+                l.level,
+                l.item.as_str(),
+                l.quantity.as_str(),
+                l.bill_of_materials.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            (
+                1,
+                "items/bom-bike-frame",
+                "2",
+                "items/bom-bike-frame/boms/standard"
+            ),
+            (2, "items/bom-bike-tube", "6", ""),
+            (1, "items/bom-bike-wheel", "6", ""),
+        ],
+        lines
+    );
+
+    drop(command_client);
+    drop(query_client);
+    drop(operations_client);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_explodes_components_in_the_base_unit_of_their_item(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut command_client = BillOfMaterialsCommandServiceClient::connect(PATH).await?;
+    let mut query_client = BillOfMaterialsQueryServiceClient::connect(PATH).await?;
+    let mut operations_client = OperationsClient::connect(PATH).await?;
+
+    create_items(&mut operations_client, &["bom-paint"]).await?;
+    create_items_in(&mut operations_client, &["bom-paint-base"], Some("kg")).await?;
+    create_items_in(&mut operations_client, &["bom-paint-pigment"], Some("g")).await?;
+
+    // The base is given per kilogram, and consumed by the paint in grams.
+    create(
+        &mut command_client,
+        &mut operations_client,
+        "items/bom-paint-base/boms/standard",
+        vec![component_in("bom-paint-pigment", "20", "g", "0")],
+        true,
+    )
+    .await?;
+    create(
+        &mut command_client,
+        &mut operations_client,
+        "items/bom-paint/boms/standard",
+        vec![component_in(
+            "bom-paint-base",
+            "500",
+            "unitsOfMeasure/g",
+            "0.1",
+        )],
+        true,
+    )
+    .await?;
+
+    let request = ExplodeBillOfMaterialsRequest {
+        name: String::from("items/bom-paint/boms/standard"),
+        quantity: String::from("3"),
+    };
+    let lines = query_client
+        .explode_bill_of_materials(Request::new(request))
+        .await?
+        .into_inner()
+        .lines;
+
+    let lines: Vec<_> = lines
+        .iter()
+        .map(|l| {
+            (
+                l.item.as_str(),
+                l.quantity.as_str(),
+                l.unit_of_measure.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            ("items/bom-paint-base", "1650", "g"),
+            ("items/bom-paint-pigment", "33", "g"),
+        ],
+        lines
+    );
+
+    // A component must be given in a unit that converts into the base unit of its item.
+    let request = CreateBillOfMaterialsRequest {
+        parent: String::from("items/bom-paint"),
+        bill_of_materials_id: String::from("metres"),
+        bill_of_materials: Some(BillOfMaterials {
+            components: vec![component_in("bom-paint-base", "1", "m", "0")],
+            ..Default::default()
+        }),
+    };
+    let status = command_client
+        .create_bill_of_materials(Request::new(request))
+        .await
+        .err()
+        .ok_or("component in an incompatible unit was accepted")?;
+    assert_eq!(Code::FailedPrecondition, status.code());
+
+    drop(command_client);
+    drop(query_client);
+    drop(operations_client);
+
+    Ok(())
+}
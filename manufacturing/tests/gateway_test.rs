@@ -0,0 +1,128 @@
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::thread;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+const ADDR: &str = "localhost:8082";
+
+/// Send `method path` with an optional JSON `body` and return the status and JSON body
+/// of the response.
+fn send(
+    method: &str,
+    path: &str,
+    body: Option<&Value>,
+) -> Result<(u16, Value), Box<dyn std::error::Error>> {
+    let body = body.map(Value::to_string).unwrap_or_default();
+    let mut stream = TcpStream::connect(ADDR)?;
+    write!(
+        stream,
+        "{method} {path} HTTP/1.1\r\nHost: {ADDR}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or("response has no body")?;
+    let status = head
+        .split(' ')
+        .nth(1)
+        .ok_or("response has no status")?
+        .parse()?;
+
+    Ok((status, serde_json::from_str(body)?))
+}
+
+/// Poll the operation `name` until it is done.
+fn wait(name: &str) -> Result<Value, Box<dyn std::error::Error>> {
+    for _ in 0..50 {
+        let (status, operation) = send("GET", &format!("/v1/operations/{name}"), None)?;
+        assert_eq!(200, status);
+        if operation["done"] == json!(true) {
+            return Ok(operation);
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+
+    Err(format!("operation {name} did not complete").into())
+}
+
+#[test]
+fn it_transcodes_http_bindings() -> Result<(), Box<dyn std::error::Error>> {
+    let item = json!({ "displayName": "Rim", "tracking": "LOT" });
+    let (status, operation) = send("POST", "/v1/items?itemId=gw-rim", Some(&item))?;
+    assert_eq!(200, status);
+    assert!(operation["metadata"]["@type"]
+        .as_str()
+        .is_some_and(|t| t.ends_with("CreateItemMetadata")));
+    let operation = wait(operation["name"].as_str().ok_or("operation has no name")?)?;
+    assert_eq!("items/gw-rim", operation["response"]["name"]);
+
+    let (status, item) = send("GET", "/v1/items/gw-rim", None)?;
+    assert_eq!(200, status);
+    assert_eq!("Rim", item["displayName"]);
+    assert_eq!("LOT", item["tracking"]);
+    assert_eq!("ACTIVE", item["state"]);
+    assert!(item["createTime"]
+        .as_str()
+        .is_some_and(|t| t.ends_with('Z')));
+
+    let update = json!({ "title": "Wheel rim", "displayName": "Ignored", "etag": item["etag"] });
+    let (status, operation) = send("PATCH", "/v1/items/gw-rim?updateMask=title", Some(&update))?;
+    assert_eq!(200, status);
+    wait(operation["name"].as_str().ok_or("operation has no name")?)?;
+
+    let (_, item) = send("GET", "/v1/items/gw-rim", None)?;
+    assert_eq!("Wheel rim", item["title"]);
+    assert_eq!("Rim", item["displayName"]);
+
+    let (status, revisions) = send("GET", "/v1/items/gw-rim:listRevisions?pageSize=1", None)?;
+    assert_eq!(200, status);
+    assert_eq!(1, revisions["revisions"].as_array().map_or(0, Vec::len));
+
+    Ok(())
+}
+
+#[test]
+fn it_maps_errors_to_http_status() -> Result<(), Box<dyn std::error::Error>> {
+    let (status, body) = send("GET", "/v1/items/gw-missing", None)?;
+    assert_eq!(404, status);
+    assert_eq!(404, body["error"]["code"]);
+    assert_eq!("NOT_FOUND", body["error"]["status"]);
+    let details = body["error"]["details"]
+        .as_array()
+        .ok_or("error has no details")?;
+    assert!(details.iter().any(|detail| detail["@type"]
+        == "type.googleapis.com/google.rpc.ResourceInfo"
+        && detail["resourceName"] == "items/gw-missing"));
+
+    let (status, body) = send("POST", "/v1/items", Some(&json!({ "colour": "red" })))?;
+    assert_eq!(400, status);
+    assert_eq!("INVALID_ARGUMENT", body["error"]["status"]);
+
+    let (status, body) = send("POST", "/v1/items/gw-missing:explode", None)?;
+    assert_eq!(404, status);
+    assert_eq!("NOT_FOUND", body["error"]["status"]);
+
+    let (status, body) = send(
+        "POST",
+        "/v1/items?itemId=gw-blocked",
+        Some(&json!({ "displayName": "Blocked" })),
+    )?;
+    assert_eq!(200, status);
+    wait(body["name"].as_str().ok_or("operation has no name")?)?;
+    let (status, body) = send(
+        "POST",
+        "/v1/items/gw-blocked:block",
+        Some(&json!({ "etag": "\"stale\"" })),
+    )?;
+    assert_eq!(409, status);
+    assert_eq!("ABORTED", body["error"]["status"]);
+
+    Ok(())
+}
@@ -0,0 +1,116 @@
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+
+const ADDR: &str = "localhost:8081";
+
+const ORIGIN: &str = "http://localhost:3000";
+
+const LIST_ITEMS: &str = "/erponomics.manufacturing.v1.ItemService/ListItems";
+
+const GET_ITEM: &str = "/erponomics.manufacturing.v1.ItemService/GetItem";
+
+/// Send an HTTP/1.1 request with `headers` and `body`, and return the response head and body.
+fn send(
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+    body: &[u8],
+) -> Result<(String, Vec<u8>), Box<dyn std::error::Error>> {
+    let mut stream = TcpStream::connect(ADDR)?;
+    write!(
+        stream,
+        "{method} {path} HTTP/1.1\r\nHost: {ADDR}\r\nOrigin: {ORIGIN}\r\n"
+    )?;
+    for (name, value) in headers {
+        write!(stream, "{name}: {value}\r\n")?;
+    }
+    write!(
+        stream,
+        "Content-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(body)?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+    let split = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or("response has no body")?;
+    let head = String::from_utf8(response[..split].to_vec())?.to_lowercase();
+
+    Ok((head, response[split + 4..].to_vec()))
+}
+
+/// The gRPC frame of the message `payload`.
+fn frame(payload: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0];
+    frame.extend(
+        u32::try_from(payload.len())
+            .unwrap_or_default()
+            .to_be_bytes(),
+    );
+    frame.extend(payload);
+    frame
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+#[test]
+fn it_serves_grpc_web() -> Result<(), Box<dyn std::error::Error>> {
+    let (head, body) = send(
+        "POST",
+        LIST_ITEMS,
+        &[
+            ("Content-Type", "application/grpc-web+proto"),
+            ("X-Grpc-Web", "1"),
+        ],
+        &frame(&[]),
+    )?;
+    assert!(head.starts_with("http/1.1 200"));
+    assert!(head.contains("content-type: application/grpc-web+proto"));
+    assert!(head.contains(&format!("access-control-allow-origin: {ORIGIN}")));
+    assert!(contains(&body, b"\x80\0\0\0"));
+    assert!(contains(&body, b"grpc-status:0\r\n"));
+
+    // GetItemRequest { name: "web-missing" }
+    let (head, _) = send(
+        "POST",
+        GET_ITEM,
+        &[
+            ("Content-Type", "application/grpc-web-text"),
+            ("X-Grpc-Web", "1"),
+        ],
+        b"AAAAAA0KC3dlYi1taXNzaW5n",
+    )?;
+    assert!(head.starts_with("http/1.1 200"));
+    assert!(head.contains("content-type: application/grpc-web-text+proto"));
+    assert!(head.contains("grpc-status: 5"));
+
+    Ok(())
+}
+
+#[test]
+fn it_answers_cors_preflight_requests() -> Result<(), Box<dyn std::error::Error>> {
+    let (head, _) = send(
+        "OPTIONS",
+        GET_ITEM,
+        &[
+            ("Access-Control-Request-Method", "POST"),
+            ("Access-Control-Request-Headers", "content-type,x-grpc-web"),
+        ],
+        &[],
+    )?;
+    assert!(head.starts_with("http/1.1 200"));
+    assert!(head.contains(&format!("access-control-allow-origin: {ORIGIN}")));
+    assert!(head.contains("access-control-allow-methods: post"));
+    assert!(head.contains("access-control-max-age: 86400"));
+
+    Ok(())
+}
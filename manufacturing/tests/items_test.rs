@@ -1,15 +1,27 @@
 #![warn(clippy::all, clippy::pedantic, clippy::nursery)]
 
-use manufacturing::proto::{
-    item_service_client::ItemServiceClient, CreateItemRequest, DeleteItemRequest, GetItemRequest,
-    Item,
+use manufacturing::{
+    grpc::proto::google::longrunning::{
+        operations_client::OperationsClient, GetOperationRequest, WaitOperationRequest,
+    },
+    proto::{
+        item, item_event, item_service_client::ItemServiceClient, AnnihilateItemMetadata,
+        AnnihilateItemRequest, BlockItemRequest, CreateItemRequest, DeleteItemMetadata,
+        DeleteItemRequest, GetItemRequest, Item, ItemEvent, ListItemRevisionsRequest,
+        ListItemRevisionsResponse, ListItemsRequest, RollbackItemRequest,
+        TestIamPermissionsRequest, UndeleteItemRequest, UpdateItemRequest, WatchItemsRequest,
+    },
 };
-use tonic::Request;
+use prost_types::FieldMask;
+use std::time::Duration;
+use tonic::{codec::Streaming, transport::Channel, Code, Request};
+use tonic_types::StatusExt;
 
 #[tokio::test]
 async fn it_does_not_create_duplicate_item() -> Result<(), Box<dyn std::error::Error>> {
     let path = "http://localhost:8081";
     let mut item_client = ItemServiceClient::connect(path).await?;
+    let mut operations_client = OperationsClient::connect(path).await?;
 
     let id = String::from("b-max");
     let name = String::new();
@@ -29,18 +41,47 @@ async fn it_does_not_create_duplicate_item() -> Result<(), Box<dyn std::error::E
             display_name: display_name.clone(),
             title: title.clone(),
             description: description.clone(),
-            state: state.clone(),
+            base_uom: None,
+            tracking: None,
+            state,
             etag: etag.clone(),
             uid: uid.clone(),
-            create_time: create_time.clone(),
-            update_time: update_time.clone(),
+            create_time,
+            update_time,
+            delete_time: None,
+            expire_time: None,
         }
         .into(),
     };
 
     let request = Request::new(request);
 
-    item_client.create_item(request).await?;
+    let operation = item_client.create_item(request).await?.into_inner();
+
+    let request = WaitOperationRequest {
+        name: operation.name,
+        timeout: None,
+    };
+    let request = Request::new(request);
+
+    let operation = operations_client
+        .wait_operation(request)
+        .await?
+        .into_inner();
+    assert!(operation.done);
+
+    let request = CreateItemRequest {
+        item_id: id.clone().into(),
+        item: Item::default().into(),
+    };
+    let request = Request::new(request);
+
+    let status = item_client
+        .create_item(request)
+        .await
+        .err()
+        .ok_or("duplicate item was created")?;
+    assert_eq!(Code::AlreadyExists, status.code());
 
     let request = GetItemRequest { name: id.clone() };
     let request = Request::new(request);
@@ -48,31 +89,1042 @@ async fn it_does_not_create_duplicate_item() -> Result<(), Box<dyn std::error::E
     let response = item_client.get_item(request).await?;
     let item = response.into_inner();
 
-    //    assert_eq!(id, item.name.clone().unwrap());
-    //    assert_eq!(display_name, item.display_name.clone());
-    //    assert_eq!(title, item.title.clone());
-    //    assert_eq!(description, item.description.clone());
-    //    assert_eq!(ItemState::Creating.to_i32(), item.state.clone());
-    //    assert!(!item.etag.clone().unwrap().is_empty());
-    //    assert!(!uuid::Uuid::try_parse(&item.uid.clone().unwrap())
-    //        .unwrap()
-    //        .is_nil());
+    assert_eq!(display_name, item.display_name.clone());
+    assert_eq!(description, item.description.clone());
+    assert_eq!(Some(item::State::Active as i32), item.state);
 
     let request = DeleteItemRequest {
         name: id.clone(),
-        etag: item.etag.clone().unwrap(),
+        etag: item.etag.clone().ok_or("item has no etag")?,
     };
     let request = Request::new(request);
 
-    item_client.delete_item(request).await?;
+    let operation = item_client.delete_item(request).await?.into_inner();
+
+    let metadata = operation
+        .metadata
+        .ok_or("delete operation has no metadata")?
+        .to_msg::<DeleteItemMetadata>()?;
+    assert!(!operation.name.is_empty());
+    assert_eq!(format!("items/{id}"), metadata.target);
+    assert_eq!("delete", metadata.verb);
+    assert!(metadata.create_time.is_some());
+
+    let request = WaitOperationRequest {
+        name: operation.name,
+        timeout: None,
+    };
+    let request = Request::new(request);
+
+    let operation = operations_client
+        .wait_operation(request)
+        .await?
+        .into_inner();
+    drop(operations_client);
+    assert!(operation.done);
 
     let request = GetItemRequest { name: id };
     let request = Request::new(request);
 
-    let response = item_client.get_item(request).await?;
-    let _item = response.into_inner();
+    let item = item_client.get_item(request).await?.into_inner();
+    drop(item_client);
+    assert_eq!(Some(item::State::Deleted as i32), item.state);
+    assert!(item.delete_time.is_some());
+    assert!(item.expire_time.is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_filters_listed_items() -> Result<(), Box<dyn std::error::Error>> {
+    let path = "http://localhost:8081";
+    let mut item_client = ItemServiceClient::connect(path).await?;
+    let mut operations_client = OperationsClient::connect(path).await?;
+
+    let request = CreateItemRequest {
+        item_id: Some(String::from("f-unicycle")),
+        item: Item {
+            display_name: Some(String::from("Unicycle")),
+            ..Default::default()
+        }
+        .into(),
+    };
+    let request = Request::new(request);
+
+    let operation = item_client.create_item(request).await?.into_inner();
+
+    let request = WaitOperationRequest {
+        name: operation.name,
+        timeout: None,
+    };
+    let request = Request::new(request);
+
+    operations_client.wait_operation(request).await?;
+    drop(operations_client);
+
+    let request = ListItemsRequest {
+        filter: Some(String::from(
+            r#"state = ACTIVE AND (display_name : "Uni*" OR NOT name = "f-unicycle")"#,
+        )),
+        ..Default::default()
+    };
+    let request = Request::new(request);
+
+    let items = item_client.list_items(request).await?.into_inner().items;
+    assert!(items.iter().any(|item| item.name == "items/f-unicycle"));
+    assert!(items
+        .iter()
+        .all(|item| item.display_name.as_deref() != Some("Bike")));
+
+    let request = ListItemsRequest {
+        filter: Some(String::from("state = ACTIVE AND")),
+        ..Default::default()
+    };
+    let request = Request::new(request);
+
+    let status = item_client
+        .list_items(request)
+        .await
+        .err()
+        .ok_or("invalid filter was accepted")?;
+    assert_eq!(Code::InvalidArgument, status.code());
+
+    let request = ListItemsRequest {
+        filter: Some(String::from("color = \"red\"")),
+        ..Default::default()
+    };
+    let request = Request::new(request);
+
+    let status = item_client
+        .list_items(request)
+        .await
+        .err()
+        .ok_or("unknown field was accepted")?;
+    drop(item_client);
+    assert_eq!(Code::InvalidArgument, status.code());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_orders_listed_items() -> Result<(), Box<dyn std::error::Error>> {
+    let path = "http://localhost:8081";
+    let mut item_client = ItemServiceClient::connect(path).await?;
+
+    for (id, display_name) in [
+        ("o-frame", "Frame"),
+        ("o-axle", "Axle"),
+        ("o-chain", "Chain"),
+    ] {
+        let request = CreateItemRequest {
+            item_id: Some(String::from(id)),
+            item: Item {
+                display_name: Some(String::from(display_name)),
+                ..Default::default()
+            }
+            .into(),
+        };
+        let request = Request::new(request);
+
+        item_client.create_item(request).await?;
+    }
+
+    let request = ListItemsRequest {
+        filter: Some(String::from(r#"name = "o-*""#)),
+        order_by: Some(String::from("display_name desc")),
+        ..Default::default()
+    };
+    let request = Request::new(request);
+
+    let items = item_client.list_items(request).await?.into_inner().items;
+    let names: Vec<_> = items.iter().map(|item| item.name.as_str()).collect();
+    assert_eq!(
+        vec!["items/o-frame", "items/o-chain", "items/o-axle"],
+        names
+    );
+
+    let request = ListItemsRequest {
+        order_by: Some(String::from("color")),
+        ..Default::default()
+    };
+    let request = Request::new(request);
+
+    let status = item_client
+        .list_items(request)
+        .await
+        .err()
+        .ok_or("unknown order_by field was accepted")?;
+    drop(item_client);
+    assert_eq!(Code::InvalidArgument, status.code());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_pages_through_listed_items() -> Result<(), Box<dyn std::error::Error>> {
+    let path = "http://localhost:8081";
+    let mut item_client = ItemServiceClient::connect(path).await?;
+
+    for id in ["p-pedal", "p-saddle", "p-spoke"] {
+        let request = CreateItemRequest {
+            item_id: Some(String::from(id)),
+            item: Item::default().into(),
+        };
+        let request = Request::new(request);
+
+        item_client.create_item(request).await?;
+    }
+
+    let filter = Some(String::from(r#"name = "p-*""#));
+    let order_by = Some(String::from("name desc"));
+
+    let request = ListItemsRequest {
+        page_size: Some(2),
+        filter: filter.clone(),
+        order_by: order_by.clone(),
+        ..Default::default()
+    };
+    let request = Request::new(request);
+
+    let response = item_client.list_items(request).await?.into_inner();
+    let names: Vec<_> = response
+        .items
+        .iter()
+        .map(|item| item.name.as_str())
+        .collect();
+    assert_eq!(vec!["items/p-spoke", "items/p-saddle"], names);
+    assert_eq!(3, response.total_size);
+
+    let page_token = response
+        .next_page_token
+        .ok_or("first page has no next page token")?;
+
+    let request = ListItemsRequest {
+        page_size: Some(2),
+        page_token: Some(page_token.clone()),
+        filter: filter.clone(),
+        order_by,
+        show_deleted: false,
+    };
+    let request = Request::new(request);
+
+    let response = item_client.list_items(request).await?.into_inner();
+    let names: Vec<_> = response
+        .items
+        .iter()
+        .map(|item| item.name.as_str())
+        .collect();
+    assert_eq!(vec!["items/p-pedal"], names);
+    assert_eq!(None, response.next_page_token);
+
+    let request = ListItemsRequest {
+        page_size: Some(2),
+        page_token: Some(page_token),
+        filter,
+        order_by: None,
+        show_deleted: false,
+    };
+    let request = Request::new(request);
+
+    let status = item_client
+        .list_items(request)
+        .await
+        .err()
+        .ok_or("page token was reused with a different order_by")?;
+    assert_eq!(Code::InvalidArgument, status.code());
+
+    let request = ListItemsRequest {
+        page_token: Some(String::from("tampered")),
+        ..Default::default()
+    };
+    let request = Request::new(request);
+
+    let status = item_client
+        .list_items(request)
+        .await
+        .err()
+        .ok_or("tampered page token was accepted")?;
+    drop(item_client);
+    assert_eq!(Code::InvalidArgument, status.code());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_rejects_invalid_update_mask() -> Result<(), Box<dyn std::error::Error>> {
+    let path = "http://localhost:8081";
+    let mut item_client = ItemServiceClient::connect(path).await?;
+
+    for paths in [vec!["state"], vec!["color"], vec!["*", "title"]] {
+        let request = UpdateItemRequest {
+            item: Item {
+                name: String::from("u-bell"),
+                title: Some(String::from("Bell")),
+                etag: Some(String::from("\"etag\"")),
+                ..Default::default()
+            }
+            .into(),
+            update_mask: Some(FieldMask {
+                paths: paths.into_iter().map(String::from).collect(),
+            }),
+        };
+        let request = Request::new(request);
+
+        let status = item_client
+            .update_item(request)
+            .await
+            .err()
+            .ok_or("invalid update mask was accepted")?;
+        assert_eq!(Code::InvalidArgument, status.code());
+    }
+    drop(item_client);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_reports_error_details() -> Result<(), Box<dyn std::error::Error>> {
+    let path = "http://localhost:8081";
+    let mut item_client = ItemServiceClient::connect(path).await?;
+
+    let request = GetItemRequest {
+        name: String::from("e-missing"),
+    };
+    let request = Request::new(request);
+
+    let status = item_client
+        .get_item(request)
+        .await
+        .err()
+        .ok_or("missing item was found")?;
+    let details = status.get_error_details();
+    assert_eq!(Code::NotFound, status.code());
+    assert_eq!(
+        Some("RESOURCE_NOT_FOUND"),
+        details.error_info().map(|info| info.reason.as_str())
+    );
+    assert_eq!(
+        Some("items/e-missing"),
+        details
+            .resource_info()
+            .map(|info| info.resource_name.as_str())
+    );
+
+    let request = CreateItemRequest {
+        item_id: Some(String::from("e-horn")),
+        item: Item::default().into(),
+    };
+    let request = Request::new(request);
+
+    item_client.create_item(request).await?;
+
+    let request = DeleteItemRequest {
+        name: String::from("e-horn"),
+        etag: String::from("\"stale\""),
+    };
+    let request = Request::new(request);
+
+    let status = item_client
+        .delete_item(request)
+        .await
+        .err()
+        .ok_or("item was deleted with a stale etag")?;
+    drop(item_client);
+    let details = status.get_error_details();
+    assert_eq!(Code::Aborted, status.code());
+    assert_eq!(
+        Some("ETAG_MISMATCH"),
+        details.error_info().map(|info| info.reason.as_str())
+    );
+    assert!(details.has_precondition_failure_violations());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_updates_only_the_given_item() -> Result<(), Box<dyn std::error::Error>> {
+    let path = "http://localhost:8081";
+    let mut item_client = ItemServiceClient::connect(path).await?;
+    let mut operations_client = OperationsClient::connect(path).await?;
+
+    for (id, title) in [("w-rim", "Rim"), ("w-hub", "Hub")] {
+        let request = CreateItemRequest {
+            item_id: Some(String::from(id)),
+            item: Item {
+                title: Some(String::from(title)),
+                ..Default::default()
+            }
+            .into(),
+        };
+        let request = Request::new(request);
+
+        let operation = item_client.create_item(request).await?.into_inner();
+
+        let request = WaitOperationRequest {
+            name: operation.name,
+            timeout: None,
+        };
+        let request = Request::new(request);
+
+        let operation = operations_client
+            .wait_operation(request)
+            .await?
+            .into_inner();
+        assert!(operation.done);
+    }
+
+    let request = GetItemRequest {
+        name: String::from("w-rim"),
+    };
+    let request = Request::new(request);
+
+    let item = item_client.get_item(request).await?.into_inner();
+
+    let request = UpdateItemRequest {
+        item: Item {
+            name: String::from("w-rim"),
+            title: Some(String::from("Carbon Rim")),
+            etag: item.etag.clone(),
+            ..Default::default()
+        }
+        .into(),
+        update_mask: Some(FieldMask {
+            paths: vec![String::from("title")],
+        }),
+    };
+    let request = Request::new(request);
+
+    let operation = item_client.update_item(request).await?.into_inner();
+
+    let request = WaitOperationRequest {
+        name: operation.name,
+        timeout: None,
+    };
+    let request = Request::new(request);
+
+    let operation = operations_client
+        .wait_operation(request)
+        .await?
+        .into_inner();
+    drop(operations_client);
+    assert!(operation.done);
+
+    let request = UpdateItemRequest {
+        item: Item {
+            name: String::from("w-rim"),
+            title: Some(String::from("Steel Rim")),
+            etag: item.etag,
+            ..Default::default()
+        }
+        .into(),
+        update_mask: None,
+    };
+    let request = Request::new(request);
+
+    let status = item_client
+        .update_item(request)
+        .await
+        .err()
+        .ok_or("item was updated with a stale etag")?;
+    assert_eq!(Code::Aborted, status.code());
+
+    for (id, title) in [("w-rim", "Carbon Rim"), ("w-hub", "Hub")] {
+        let request = GetItemRequest {
+            name: String::from(id),
+        };
+        let request = Request::new(request);
+
+        let item = item_client.get_item(request).await?.into_inner();
+        assert_eq!(format!("items/{id}"), item.name);
+        assert_eq!(Some(String::from(title)), item.title);
+        assert_eq!(Some(item::State::Active as i32), item.state);
+    }
+    drop(item_client);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_annihilates_item_with_its_history() -> Result<(), Box<dyn std::error::Error>> {
+    let path = "http://localhost:8081";
+    let mut item_client = ItemServiceClient::connect(path).await?;
+    let mut operations_client = OperationsClient::connect(path).await?;
+
+    let id = String::from("a-ghost");
+
+    let request = CreateItemRequest {
+        item_id: Some(id.clone()),
+        item: Item::default().into(),
+    };
+    let request = Request::new(request);
+
+    let create_operation = item_client.create_item(request).await?.into_inner();
+
+    let request = WaitOperationRequest {
+        name: create_operation.name.clone(),
+        timeout: None,
+    };
+    let request = Request::new(request);
+
+    let operation = operations_client
+        .wait_operation(request)
+        .await?
+        .into_inner();
+    assert!(operation.done);
+
+    let request = GetItemRequest { name: id.clone() };
+    let request = Request::new(request);
+
+    let item = item_client.get_item(request).await?.into_inner();
+
+    let request = AnnihilateItemRequest {
+        name: id.clone(),
+        etag: item.etag.ok_or("item has no etag")?,
+    };
+    let request = Request::new(request);
+
+    let operation = item_client.annihilate_item(request).await?.into_inner();
+
+    let metadata = operation
+        .metadata
+        .ok_or("annihilate operation has no metadata")?
+        .to_msg::<AnnihilateItemMetadata>()?;
+    assert_eq!(format!("items/{id}"), metadata.target);
+    assert_eq!("annihilate", metadata.verb);
+
+    let request = WaitOperationRequest {
+        name: operation.name,
+        timeout: None,
+    };
+    let request = Request::new(request);
+
+    let operation = operations_client
+        .wait_operation(request)
+        .await?
+        .into_inner();
+    assert!(operation.done);
+
+    let request = GetItemRequest { name: id.clone() };
+    let request = Request::new(request);
+
+    let status = item_client
+        .get_item(request)
+        .await
+        .err()
+        .ok_or("annihilated item is still found")?;
+    assert_eq!(Code::NotFound, status.code());
+
+    // Only the tombstone of the item is left of its events, without a snapshot.
+    let request = WatchItemsRequest { after_sequence: 0 };
+    let mut stream = item_client
+        .watch_items(Request::new(request))
+        .await?
+        .into_inner();
+    drop(item_client);
+    let event = next_event(&mut stream, &format!("items/{id}")).await?;
+    drop(stream);
+    assert_eq!(item_event::Kind::Annihilated as i32, event.kind);
+    assert_eq!(None, event.item);
+
+    let request = GetOperationRequest {
+        name: create_operation.name,
+    };
+    let request = Request::new(request);
+
+    let status = operations_client
+        .get_operation(request)
+        .await
+        .err()
+        .ok_or("operation of an annihilated item is still found")?;
+    assert_eq!(Code::NotFound, status.code());
+
+    let request = GetOperationRequest {
+        name: operation.name,
+    };
+    let request = Request::new(request);
+
+    let operation = operations_client.get_operation(request).await?.into_inner();
+    drop(operations_client);
+    assert!(operation.done);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_hides_and_restores_deleted_item() -> Result<(), Box<dyn std::error::Error>> {
+    let path = "http://localhost:8081";
+    let mut item_client = ItemServiceClient::connect(path).await?;
+    let mut operations_client = OperationsClient::connect(path).await?;
+
+    let id = String::from("sd-chain");
+
+    let request = CreateItemRequest {
+        item_id: Some(id.clone()),
+        item: Item::default().into(),
+    };
+    let request = Request::new(request);
+
+    let operation = item_client.create_item(request).await?.into_inner();
+
+    let request = WaitOperationRequest {
+        name: operation.name,
+        timeout: None,
+    };
+    let request = Request::new(request);
+
+    operations_client.wait_operation(request).await?;
+
+    let request = GetItemRequest { name: id.clone() };
+    let request = Request::new(request);
+
+    let item = item_client.get_item(request).await?.into_inner();
+
+    let request = DeleteItemRequest {
+        name: id.clone(),
+        etag: item.etag.ok_or("item has no etag")?,
+    };
+    let request = Request::new(request);
+
+    let operation = item_client.delete_item(request).await?.into_inner();
+
+    let request = WaitOperationRequest {
+        name: operation.name,
+        timeout: None,
+    };
+    let request = Request::new(request);
+
+    operations_client.wait_operation(request).await?;
+
+    for (show_deleted, count) in [(false, 0), (true, 1)] {
+        let request = ListItemsRequest {
+            filter: Some(format!("name = {id:?}")),
+            show_deleted,
+            ..Default::default()
+        };
+        let request = Request::new(request);
+
+        let response = item_client.list_items(request).await?.into_inner();
+        assert_eq!(count, response.items.len());
+        assert_eq!(i32::try_from(count)?, response.total_size);
+    }
+
+    let request = GetItemRequest { name: id.clone() };
+    let request = Request::new(request);
+
+    let item = item_client.get_item(request).await?.into_inner();
+    assert_eq!(Some(item::State::Deleted as i32), item.state);
+
+    let request = UndeleteItemRequest {
+        name: id.clone(),
+        etag: item.etag.ok_or("item has no etag")?,
+    };
+    let request = Request::new(request);
+
+    let operation = item_client.undelete_item(request).await?.into_inner();
+
+    let request = WaitOperationRequest {
+        name: operation.name,
+        timeout: None,
+    };
+    let request = Request::new(request);
+
+    let operation = operations_client
+        .wait_operation(request)
+        .await?
+        .into_inner();
+    drop(operations_client);
+    assert!(operation.done);
+
+    let request = GetItemRequest { name: id };
+    let request = Request::new(request);
+
+    let item = item_client.get_item(request).await?.into_inner();
+    drop(item_client);
+    assert_eq!(Some(item::State::Active as i32), item.state);
+    assert_eq!(None, item.delete_time);
+    assert_eq!(None, item.expire_time);
+
+    Ok(())
+}
+
+/// The next event of the item named `name`, skipping the events of other items.
+async fn next_event(
+    stream: &mut Streaming<ItemEvent>,
+    name: &str,
+) -> Result<ItemEvent, Box<dyn std::error::Error>> {
+    loop {
+        let event = tokio::time::timeout(Duration::from_secs(5), stream.message())
+            .await??
+            .ok_or("item event stream ended")?;
+
+        if event.name == name {
+            return Ok(event);
+        }
+    }
+}
+
+#[tokio::test]
+async fn it_streams_item_events_in_order() -> Result<(), Box<dyn std::error::Error>> {
+    let path = "http://localhost:8081";
+    let mut item_client = ItemServiceClient::connect(path).await?;
+    let mut operations_client = OperationsClient::connect(path).await?;
+
+    let request = CreateItemRequest {
+        item_id: Some(String::from("ev-bell")),
+        item: Item {
+            title: Some(String::from("Bell")),
+            ..Default::default()
+        }
+        .into(),
+    };
+    let operation = item_client
+        .create_item(Request::new(request))
+        .await?
+        .into_inner();
+
+    let request = WaitOperationRequest {
+        name: operation.name,
+        timeout: None,
+    };
+    operations_client
+        .wait_operation(Request::new(request))
+        .await?;
+
+    let request = WatchItemsRequest { after_sequence: 0 };
+    let mut stream = item_client
+        .watch_items(Request::new(request))
+        .await?
+        .into_inner();
 
-    //    assert_eq!(ItemState::Deleting.to_i32(), item.state.clone());
+    // The item is created once, by the event of the completed operation.
+    let created = next_event(&mut stream, "items/ev-bell").await?;
+    assert_eq!(item_event::Kind::Created as i32, created.kind);
+    let item = created.item.ok_or("created event has no snapshot")?;
+    assert_eq!(Some(item::State::Active as i32), item.state);
+    drop(stream);
+
+    let request = WatchItemsRequest {
+        after_sequence: created.sequence - 1,
+    };
+    let mut stream = item_client
+        .watch_items(Request::new(request))
+        .await?
+        .into_inner();
+    let resumed = next_event(&mut stream, "items/ev-bell").await?;
+    assert_eq!(created.sequence, resumed.sequence);
+
+    let request = UpdateItemRequest {
+        item: Item {
+            name: String::from("ev-bell"),
+            title: Some(String::from("Brass Bell")),
+            etag: item.etag,
+            ..Default::default()
+        }
+        .into(),
+        update_mask: Some(FieldMask {
+            paths: vec![String::from("title")],
+        }),
+    };
+    item_client.update_item(Request::new(request)).await?;
+    drop(item_client);
+    drop(operations_client);
+
+    let updated = next_event(&mut stream, "items/ev-bell").await?;
+    drop(stream);
+    assert_eq!(item_event::Kind::Updated as i32, updated.kind);
+    assert!(updated.sequence > created.sequence);
+    let item = updated.item.ok_or("updated event has no snapshot")?;
+    assert_eq!(Some(item::State::Active as i32), item.state);
+
+    Ok(())
+}
+
+/// Wait for the operation named `name` to complete.
+async fn wait(
+    operations_client: &mut OperationsClient<Channel>,
+    name: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let request = WaitOperationRequest {
+        name,
+        timeout: None,
+    };
+    let operation = operations_client
+        .wait_operation(Request::new(request))
+        .await?
+        .into_inner();
+    assert!(operation.done);
+
+    Ok(())
+}
+
+async fn list_revisions(
+    item_client: &mut ItemServiceClient<Channel>,
+    page_size: Option<i32>,
+    page_token: Option<String>,
+) -> Result<ListItemRevisionsResponse, Box<dyn std::error::Error>> {
+    let request = ListItemRevisionsRequest {
+        name: String::from("rev-saddle"),
+        page_size,
+        page_token,
+    };
+    let response = item_client
+        .list_item_revisions(Request::new(request))
+        .await?
+        .into_inner();
+
+    Ok(response)
+}
+
+#[tokio::test]
+async fn it_rolls_back_to_a_revision() -> Result<(), Box<dyn std::error::Error>> {
+    let path = "http://localhost:8081";
+    let mut item_client = ItemServiceClient::connect(path).await?;
+    let mut operations_client = OperationsClient::connect(path).await?;
+
+    let request = CreateItemRequest {
+        item_id: Some(String::from("rev-saddle")),
+        item: Item {
+            title: Some(String::from("Saddle")),
+            ..Default::default()
+        }
+        .into(),
+    };
+    let operation = item_client
+        .create_item(Request::new(request))
+        .await?
+        .into_inner();
+    wait(&mut operations_client, operation.name).await?;
+
+    let request = GetItemRequest {
+        name: String::from("rev-saddle"),
+    };
+    let item = item_client
+        .get_item(Request::new(request))
+        .await?
+        .into_inner();
+
+    let request = UpdateItemRequest {
+        item: Item {
+            name: String::from("rev-saddle"),
+            title: Some(String::from("Gel Saddle")),
+            etag: item.etag,
+            ..Default::default()
+        }
+        .into(),
+        update_mask: Some(FieldMask {
+            paths: vec![String::from("title")],
+        }),
+    };
+    let operation = item_client
+        .update_item(Request::new(request))
+        .await?
+        .into_inner();
+    wait(&mut operations_client, operation.name).await?;
+
+    let response = list_revisions(&mut item_client, Some(1), None).await?;
+    let [updated] = response.revisions.as_slice() else {
+        return Err("expected one revision per page".into());
+    };
+    assert_eq!(
+        Some(String::from("Gel Saddle")),
+        updated.item.as_ref().and_then(|i| i.title.clone())
+    );
+
+    let response = list_revisions(&mut item_client, Some(1), response.next_page_token).await?;
+    assert_eq!(None, response.next_page_token);
+    let [created] = response.revisions.as_slice() else {
+        return Err("expected the creation on the last page".into());
+    };
+    assert_eq!(
+        format!("items/rev-saddle@{}", created.revision_id),
+        created.name
+    );
+    let item = created.item.clone().ok_or("revision has no item")?;
+    assert_eq!(Some(item::State::Active as i32), item.state);
+    assert_eq!(item.etag, Some(created.etag.clone()));
+
+    let request = GetItemRequest {
+        name: format!("rev-saddle@{}", created.revision_id),
+    };
+    let item = item_client
+        .get_item(Request::new(request))
+        .await?
+        .into_inner();
+    assert_eq!(Some(String::from("Saddle")), item.title);
+
+    let request = GetItemRequest {
+        name: String::from("rev-saddle@missing"),
+    };
+    let status = item_client
+        .get_item(Request::new(request))
+        .await
+        .err()
+        .ok_or("missing revision was found")?;
+    assert_eq!(Code::NotFound, status.code());
+
+    let request = RollbackItemRequest {
+        name: String::from("rev-saddle"),
+        revision_id: created.revision_id.clone(),
+        etag: updated.etag.clone(),
+    };
+    let operation = item_client
+        .rollback_item(Request::new(request))
+        .await?
+        .into_inner();
+    wait(&mut operations_client, operation.name).await?;
+    drop(operations_client);
+
+    let request = GetItemRequest {
+        name: String::from("rev-saddle"),
+    };
+    let item = item_client
+        .get_item(Request::new(request))
+        .await?
+        .into_inner();
+    assert_eq!(Some(String::from("Saddle")), item.title);
+
+    let response = list_revisions(&mut item_client, None, None).await?;
+    drop(item_client);
+    assert_eq!(3, response.revisions.len());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_keeps_blocked_item_blocked() -> Result<(), Box<dyn std::error::Error>> {
+    let path = "http://localhost:8081";
+    let mut item_client = ItemServiceClient::connect(path).await?;
+    let mut operations_client = OperationsClient::connect(path).await?;
+
+    let request = CreateItemRequest {
+        item_id: Some(String::from("blk-bell")),
+        item: Item {
+            title: Some(String::from("Bell")),
+            ..Default::default()
+        }
+        .into(),
+    };
+    let operation = item_client
+        .create_item(Request::new(request))
+        .await?
+        .into_inner();
+    wait(&mut operations_client, operation.name).await?;
+
+    let request = ListItemRevisionsRequest {
+        name: String::from("blk-bell"),
+        page_size: None,
+        page_token: None,
+    };
+    let response = item_client
+        .list_item_revisions(Request::new(request))
+        .await?
+        .into_inner();
+    let [created] = response.revisions.as_slice() else {
+        return Err("expected the creation revision".into());
+    };
+
+    let request = BlockItemRequest {
+        name: String::from("blk-bell"),
+        etag: created.etag.clone(),
+    };
+    let operation = item_client
+        .block_item(Request::new(request))
+        .await?
+        .into_inner();
+    wait(&mut operations_client, operation.name).await?;
+    drop(operations_client);
+
+    let request = GetItemRequest {
+        name: String::from("blk-bell"),
+    };
+    let item = item_client
+        .get_item(Request::new(request))
+        .await?
+        .into_inner();
+    assert_eq!(Some(item::State::Blocked as i32), item.state);
+
+    let request = UpdateItemRequest {
+        item: Item {
+            name: String::from("blk-bell"),
+            title: Some(String::from("Brass Bell")),
+            etag: item.etag.clone(),
+            ..Default::default()
+        }
+        .into(),
+        update_mask: Some(FieldMask {
+            paths: vec![String::from("title")],
+        }),
+    };
+    let status = item_client
+        .update_item(Request::new(request))
+        .await
+        .err()
+        .ok_or("blocked item was updated")?;
+    assert_eq!(Code::FailedPrecondition, status.code());
+
+    let request = RollbackItemRequest {
+        name: String::from("blk-bell"),
+        revision_id: created.revision_id.clone(),
+        etag: item.etag.clone().ok_or("item has no etag")?,
+    };
+    let status = item_client
+        .rollback_item(Request::new(request))
+        .await
+        .err()
+        .ok_or("blocked item was rolled back")?;
+    assert_eq!(Code::FailedPrecondition, status.code());
+
+    let request = GetItemRequest {
+        name: String::from("blk-bell"),
+    };
+    let unchanged = item_client
+        .get_item(Request::new(request))
+        .await?
+        .into_inner();
+    drop(item_client);
+    assert_eq!(Some(item::State::Blocked as i32), unchanged.state);
+    assert_eq!(item.etag, unchanged.etag);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_tests_item_permissions() -> Result<(), Box<dyn std::error::Error>> {
+    let path = "http://localhost:8081";
+    let mut item_client = ItemServiceClient::connect(path).await?;
+
+    // Callers are not restricted without authentication, so every permission is granted.
+    let request = TestIamPermissionsRequest {
+        resource: String::from("items/b-max"),
+        permissions: vec![
+            String::from("manufacturing.items.get"),
+            String::from("manufacturing.items.block"),
+            String::from("manufacturing.items.explode"),
+        ],
+    };
+    let response = item_client
+        .test_iam_permissions(Request::new(request))
+        .await?
+        .into_inner();
+    assert_eq!(
+        vec![
+            String::from("manufacturing.items.get"),
+            String::from("manufacturing.items.block"),
+        ],
+        response.permissions
+    );
+
+    let request = TestIamPermissionsRequest {
+        resource: String::from("routings/r-1"),
+        permissions: vec![String::from("manufacturing.items.get")],
+    };
+    let status = item_client
+        .test_iam_permissions(Request::new(request))
+        .await
+        .err()
+        .ok_or("foreign resource was accepted")?;
+    drop(item_client);
+    assert_eq!(Code::InvalidArgument, status.code());
 
     Ok(())
 }
@@ -0,0 +1,93 @@
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+use manufacturing::{
+    grpc::proto::google::longrunning::{
+        operation, operations_client::OperationsClient, CancelOperationRequest,
+        DeleteOperationRequest, GetOperationRequest, WaitOperationRequest,
+    },
+    proto::{item_service_client::ItemServiceClient, CreateItemRequest, Item},
+};
+use tonic::{Code, Request};
+
+#[tokio::test]
+async fn it_tracks_operation_of_created_item() -> Result<(), Box<dyn std::error::Error>> {
+    let path = "http://localhost:8081";
+    let mut item_client = ItemServiceClient::connect(path).await?;
+    let mut operations_client = OperationsClient::connect(path).await?;
+
+    let request = CreateItemRequest {
+        item_id: None,
+        item: Item {
+            display_name: Some(String::from("Wheel")),
+            ..Default::default()
+        }
+        .into(),
+    };
+    let request = Request::new(request);
+
+    let operation = item_client.create_item(request).await?.into_inner();
+    drop(item_client);
+
+    let request = GetOperationRequest {
+        name: operation.name.clone(),
+    };
+    let request = Request::new(request);
+
+    let fetched = operations_client.get_operation(request).await?.into_inner();
+    assert_eq!(operation.name, fetched.name);
+    assert_eq!(operation.metadata, fetched.metadata);
+
+    // The item is already being created, so the operation runs to completion.
+    let request = CancelOperationRequest {
+        name: operation.name.clone(),
+    };
+    let request = Request::new(request);
+
+    let status = operations_client
+        .cancel_operation(request)
+        .await
+        .err()
+        .ok_or("operation was cancelled")?;
+    assert_eq!(Code::Unimplemented, status.code());
+
+    let request = WaitOperationRequest {
+        name: operation.name.clone(),
+        timeout: Some(prost_types::Duration {
+            seconds: 1,
+            nanos: 0,
+        }),
+    };
+    let request = Request::new(request);
+
+    let waited = operations_client
+        .wait_operation(request)
+        .await?
+        .into_inner();
+    assert!(waited.done);
+    assert!(matches!(
+        waited.result,
+        Some(operation::Result::Response(_))
+    ));
+
+    let request = DeleteOperationRequest {
+        name: operation.name.clone(),
+    };
+    let request = Request::new(request);
+
+    operations_client.delete_operation(request).await?;
+
+    let request = GetOperationRequest {
+        name: operation.name,
+    };
+    let request = Request::new(request);
+
+    let status = operations_client
+        .get_operation(request)
+        .await
+        .err()
+        .ok_or("deleted operation is still found")?;
+    drop(operations_client);
+    assert_eq!(Code::NotFound, status.code());
+
+    Ok(())
+}
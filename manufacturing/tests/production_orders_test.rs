@@ -0,0 +1,379 @@
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+use manufacturing::{
+    grpc::proto::google::longrunning::{
+        operation, operations_client::OperationsClient, Operation, WaitOperationRequest,
+    },
+    proto::production_order::{
+        production_order::State,
+        production_order_command_service_client::ProductionOrderCommandServiceClient,
+        production_order_query_service_client::ProductionOrderQueryServiceClient,
+        CancelProductionOrderRequest, CompleteProductionOrderRequest, CreateProductionOrderRequest,
+        FinishProductionOrderRequest, GetProductionOrderRequest, ListProductionOrdersRequest,
+        OperationMetadata, ProductionOrder, ReleaseProductionOrderRequest,
+        ReleaseProductionOrderResponse,
+    },
+    proto::{
+        item_service_client::ItemServiceClient, AnnihilateItemRequest, BlockItemRequest,
+        CreateItemRequest, DeleteItemRequest, GetItemRequest, Item,
+    },
+};
+use tonic::{transport::Channel, Code, Request};
+
+const PATH: &str = "http://localhost:8081";
+
+async fn wait(
+    operations_client: &mut OperationsClient<Channel>,
+    operation: Operation,
+) -> Result<Operation, Box<dyn std::error::Error>> {
+    let request = WaitOperationRequest {
+        name: operation.name,
+        timeout: None,
+    };
+    let request = Request::new(request);
+
+    let operation = operations_client
+        .wait_operation(request)
+        .await?
+        .into_inner();
+    assert!(operation.done);
+
+    Ok(operation)
+}
+
+async fn create_item(
+    operations_client: &mut OperationsClient<Channel>,
+    id: &str,
+) -> Result<Item, Box<dyn std::error::Error>> {
+    let mut item_client = ItemServiceClient::connect(PATH).await?;
+
+    let request = CreateItemRequest {
+        item_id: Some(id.to_string()),
+        item: Some(Item::default()),
+    };
+    let request = Request::new(request);
+
+    let operation = item_client.create_item(request).await?.into_inner();
+    wait(operations_client, operation).await?;
+
+    let request = GetItemRequest {
+        name: id.to_string(),
+    };
+    let request = Request::new(request);
+
+    Ok(item_client.get_item(request).await?.into_inner())
+}
+
+async fn create(
+    command_client: &mut ProductionOrderCommandServiceClient<Channel>,
+    operations_client: &mut OperationsClient<Channel>,
+    id: &str,
+    production_order: ProductionOrder,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let request = CreateProductionOrderRequest {
+        production_order_id: id.to_string(),
+        production_order: Some(production_order),
+    };
+    let request = Request::new(request);
+
+    let operation = command_client
+        .create_production_order(request)
+        .await?
+        .into_inner();
+    wait(operations_client, operation).await?;
+
+    Ok(())
+}
+
+async fn get(
+    query_client: &mut ProductionOrderQueryServiceClient<Channel>,
+    id: &str,
+) -> Result<ProductionOrder, Box<dyn std::error::Error>> {
+    let request = GetProductionOrderRequest {
+        name: id.to_string(),
+    };
+    let request = Request::new(request);
+
+    Ok(query_client
+        .get_production_order(request)
+        .await?
+        .into_inner())
+}
+
+#[tokio::test]
+async fn it_moves_production_order_through_its_lifecycle() -> Result<(), Box<dyn std::error::Error>>
+{
+    let mut command_client = ProductionOrderCommandServiceClient::connect(PATH).await?;
+    let mut query_client = ProductionOrderQueryServiceClient::connect(PATH).await?;
+    let mut operations_client = OperationsClient::connect(PATH).await?;
+
+    let id = String::from("po-lifecycle");
+    create_item(&mut operations_client, "po-lifecycle-bike").await?;
+
+    let production_order = ProductionOrder {
+        display_name: String::from("Bikes for spring"),
+        item: String::from("items/po-lifecycle-bike"),
+        quantity: String::from("12"),
+        ..Default::default()
+    };
+    create(
+        &mut command_client,
+        &mut operations_client,
+        &id,
+        production_order,
+    )
+    .await?;
+
+    let production_order = get(&mut query_client, &id).await?;
+    assert_eq!(State::Planned as i32, production_order.state);
+    assert_eq!("items/po-lifecycle-bike", production_order.item);
+    assert_eq!("12", production_order.quantity);
+
+    let request = CompleteProductionOrderRequest {
+        name: id.clone(),
+        etag: production_order.etag.clone(),
+    };
+    let request = Request::new(request);
+
+    let status = command_client
+        .complete_production_order(request)
+        .await
+        .err()
+        .ok_or("planned production order was completed")?;
+    assert_eq!(Code::FailedPrecondition, status.code());
+
+    let request = ReleaseProductionOrderRequest {
+        name: id.clone(),
+        etag: production_order.etag,
+    };
+    let request = Request::new(request);
+
+    let operation = command_client
+        .release_production_order(request)
+        .await?
+        .into_inner();
+
+    let metadata = operation
+        .metadata
+        .clone()
+        .ok_or("release operation has no metadata")?
+        .to_msg::<OperationMetadata>()?;
+    assert_eq!(format!("productionOrders/{id}"), metadata.target);
+    assert_eq!("release", metadata.verb);
+
+    let operation = wait(&mut operations_client, operation).await?;
+    let Some(operation::Result::Response(response)) = operation.result else {
+        return Err("release operation has no response".into());
+    };
+    let response = response.to_msg::<ReleaseProductionOrderResponse>()?;
+    let production_order = response
+        .production_order
+        .ok_or("release response has no production order")?;
+    assert_eq!(State::Released as i32, production_order.state);
+
+    let request = CompleteProductionOrderRequest {
+        name: id.clone(),
+        etag: production_order.etag,
+    };
+    let request = Request::new(request);
+
+    let operation = command_client
+        .complete_production_order(request)
+        .await?
+        .into_inner();
+    wait(&mut operations_client, operation).await?;
+
+    let production_order = get(&mut query_client, &id).await?;
+    assert_eq!(State::Completed as i32, production_order.state);
+
+    let request = FinishProductionOrderRequest {
+        name: id.clone(),
+        etag: production_order.etag,
+        force: false,
+    };
+    let request = Request::new(request);
+
+    let operation = command_client
+        .finish_production_order(request)
+        .await?
+        .into_inner();
+    wait(&mut operations_client, operation).await?;
+
+    drop(operations_client);
+
+    let production_order = get(&mut query_client, &id).await?;
+    drop(query_client);
+    assert_eq!(State::Finished as i32, production_order.state);
+
+    let request = CancelProductionOrderRequest {
+        name: id,
+        etag: production_order.etag,
+        force: true,
+    };
+    let request = Request::new(request);
+
+    let status = command_client
+        .cancel_production_order(request)
+        .await
+        .err()
+        .ok_or("finished production order was cancelled")?;
+    drop(command_client);
+    assert_eq!(Code::FailedPrecondition, status.code());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_filters_listed_production_orders() -> Result<(), Box<dyn std::error::Error>> {
+    let mut command_client = ProductionOrderCommandServiceClient::connect(PATH).await?;
+    let mut query_client = ProductionOrderQueryServiceClient::connect(PATH).await?;
+    let mut operations_client = OperationsClient::connect(PATH).await?;
+
+    create_item(&mut operations_client, "po-f-bike").await?;
+
+    for (id, title) in [
+        ("po-f-1", "Frames"),
+        ("po-f-2", "Forks"),
+        ("po-f-3", "Wheels"),
+    ] {
+        let production_order = ProductionOrder {
+            title: title.to_string(),
+            item: String::from("po-f-bike"),
+            quantity: String::from("1"),
+            ..Default::default()
+        };
+        create(
+            &mut command_client,
+            &mut operations_client,
+            id,
+            production_order,
+        )
+        .await?;
+    }
+    drop(operations_client);
+    drop(command_client);
+
+    let request = ListProductionOrdersRequest {
+        filter: String::from(r#"item = "po-f-bike" AND title = "F*""#),
+        order_by: String::from("title desc"),
+        ..Default::default()
+    };
+    let request = Request::new(request);
+
+    let response = query_client
+        .list_production_orders(request)
+        .await?
+        .into_inner();
+    drop(query_client);
+
+    let names: Vec<_> = response
+        .production_orders
+        .iter()
+        .map(|p| p.name.as_str())
+        .collect();
+    assert_eq!(vec!["po-f-1", "po-f-2"], names);
+    assert_eq!(2, response.total_size);
+    assert!(response.next_page_token.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_keeps_production_orders_and_items_consistent() -> Result<(), Box<dyn std::error::Error>>
+{
+    let mut command_client = ProductionOrderCommandServiceClient::connect(PATH).await?;
+    let mut query_client = ProductionOrderQueryServiceClient::connect(PATH).await?;
+    let mut item_client = ItemServiceClient::connect(PATH).await?;
+    let mut operations_client = OperationsClient::connect(PATH).await?;
+
+    let production_order = |item: &str| ProductionOrder {
+        item: item.to_string(),
+        quantity: String::from("1"),
+        ..Default::default()
+    };
+
+    let request = CreateProductionOrderRequest {
+        production_order_id: String::from("po-c-missing"),
+        production_order: production_order("items/po-c-missing").into(),
+    };
+    let status = command_client
+        .create_production_order(Request::new(request))
+        .await
+        .err()
+        .ok_or("production order of a missing item was created")?;
+    assert_eq!(Code::NotFound, status.code());
+
+    let item = create_item(&mut operations_client, "po-c-blocked").await?;
+    let request = BlockItemRequest {
+        name: String::from("po-c-blocked"),
+        etag: item.etag.ok_or("item has no etag")?,
+    };
+    let operation = item_client
+        .block_item(Request::new(request))
+        .await?
+        .into_inner();
+    wait(&mut operations_client, operation).await?;
+
+    let request = CreateProductionOrderRequest {
+        production_order_id: String::from("po-c-blocked"),
+        production_order: production_order("items/po-c-blocked").into(),
+    };
+    let status = command_client
+        .create_production_order(Request::new(request))
+        .await
+        .err()
+        .ok_or("production order of a blocked item was created")?;
+    assert_eq!(Code::FailedPrecondition, status.code());
+
+    let item = create_item(&mut operations_client, "po-c-bike").await?;
+    create(
+        &mut command_client,
+        &mut operations_client,
+        "po-c-open",
+        production_order("items/po-c-bike"),
+    )
+    .await?;
+
+    let request = DeleteItemRequest {
+        name: String::from("po-c-bike"),
+        etag: item.etag.clone().ok_or("item has no etag")?,
+    };
+    let status = item_client
+        .delete_item(Request::new(request))
+        .await
+        .err()
+        .ok_or("item of an open production order was deleted")?;
+    assert_eq!(Code::FailedPrecondition, status.code());
+
+    let open = get(&mut query_client, "po-c-open").await?;
+    let request = CancelProductionOrderRequest {
+        name: String::from("po-c-open"),
+        etag: open.etag,
+        force: false,
+    };
+    let operation = command_client
+        .cancel_production_order(Request::new(request))
+        .await?
+        .into_inner();
+    drop(command_client);
+    wait(&mut operations_client, operation).await?;
+
+    let request = AnnihilateItemRequest {
+        name: String::from("po-c-bike"),
+        etag: item.etag.ok_or("item has no etag")?,
+    };
+    let operation = item_client
+        .annihilate_item(Request::new(request))
+        .await?
+        .into_inner();
+    drop(item_client);
+    wait(&mut operations_client, operation).await?;
+    drop(operations_client);
+
+    let cancelled = get(&mut query_client, "po-c-open").await?;
+    drop(query_client);
+    assert_eq!(State::Cancelled as i32, cancelled.state);
+    assert!(cancelled.item.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,323 @@
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+use manufacturing::{
+    grpc::proto::google::longrunning::{
+        operation, operations_client::OperationsClient, Operation, WaitOperationRequest,
+    },
+    proto::production_order::{
+        production_order::{operation::State as OperationState, State},
+        production_order_command_service_client::ProductionOrderCommandServiceClient,
+        production_order_query_service_client::ProductionOrderQueryServiceClient,
+        CompleteProductionOrderOperationRequest, CreateProductionOrderRequest,
+        GetProductionOrderRequest, ProductionOrder, ReleaseProductionOrderRequest,
+        ReportProductionOrderOperationRequest, StartProductionOrderOperationRequest,
+    },
+    proto::routing::{
+        routing::{self, State as RoutingState},
+        routing_command_service_client::RoutingCommandServiceClient,
+        routing_query_service_client::RoutingQueryServiceClient,
+        GetRoutingRequest, Routing, UpdateRoutingRequest,
+    },
+    proto::{item_service_client::ItemServiceClient, CreateItemRequest, Item},
+};
+use tonic::{transport::Channel, Code, Request};
+
+const PATH: &str = "http://localhost:8081";
+
+async fn wait(
+    operations_client: &mut OperationsClient<Channel>,
+    operation: Operation,
+) -> Result<Operation, Box<dyn std::error::Error>> {
+    let request = WaitOperationRequest {
+        name: operation.name,
+        timeout: None,
+    };
+    let request = Request::new(request);
+
+    let operation = operations_client
+        .wait_operation(request)
+        .await?
+        .into_inner();
+    assert!(operation.done);
+
+    Ok(operation)
+}
+
+async fn create_item(
+    operations_client: &mut OperationsClient<Channel>,
+    id: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut item_client = ItemServiceClient::connect(PATH).await?;
+
+    let request = CreateItemRequest {
+        item_id: Some(id.to_string()),
+        item: Some(Item::default()),
+    };
+    let request = Request::new(request);
+
+    let operation = item_client.create_item(request).await?.into_inner();
+    wait(operations_client, operation).await?;
+    drop(item_client);
+
+    Ok(())
+}
+
+fn operation(work_center: &str, minutes: i64) -> routing::Operation {
+    routing::Operation {
+        work_center: work_center.to_string(),
+        setup_time: Some(prost_types::Duration {
+            seconds: minutes * 60,
+            nanos: 0,
+        }),
+        run_time: Some(prost_types::Duration {
+            seconds: 30,
+            nanos: 0,
+        }),
+    }
+}
+
+/// Update the routing of `item`, returning the settled routing.
+async fn update(
+    command_client: &mut RoutingCommandServiceClient<Channel>,
+    operations_client: &mut OperationsClient<Channel>,
+    item: &str,
+    operations: Vec<routing::Operation>,
+    etag: String,
+) -> Result<Routing, Box<dyn std::error::Error>> {
+    let request = UpdateRoutingRequest {
+        routing: Some(Routing {
+            name: format!("items/{item}/routing"),
+            operations,
+            ..Default::default()
+        }),
+        etag,
+    };
+    let request = Request::new(request);
+
+    let operation = command_client.update_routing(request).await?.into_inner();
+    let operation = wait(operations_client, operation).await?;
+
+    let Some(operation::Result::Response(response)) = operation.result else {
+        return Err("operation has no response".into());
+    };
+
+    Ok(response.to_msg::<Routing>()?)
+}
+
+/// Release the production order `routing-po`, returning the released production order.
+async fn release(
+    command_client: &mut ProductionOrderCommandServiceClient<Channel>,
+    query_client: &mut ProductionOrderQueryServiceClient<Channel>,
+    operations_client: &mut OperationsClient<Channel>,
+    etag: String,
+) -> Result<ProductionOrder, Box<dyn std::error::Error>> {
+    let request = ReleaseProductionOrderRequest {
+        name: String::from("routing-po"),
+        etag,
+    };
+    let operation = command_client
+        .release_production_order(Request::new(request))
+        .await?
+        .into_inner();
+    wait(operations_client, operation).await?;
+
+    let request = GetProductionOrderRequest {
+        name: String::from("routing-po"),
+    };
+
+    Ok(query_client
+        .get_production_order(Request::new(request))
+        .await?
+        .into_inner())
+}
+
+/// Poll the state of the production order `routing-po` until it left `Completing`.
+async fn settled_state(
+    query_client: &mut ProductionOrderQueryServiceClient<Channel>,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    let mut state = State::Completing as i32;
+
+    for _ in 0..50 {
+        let request = GetProductionOrderRequest {
+            name: String::from("routing-po"),
+        };
+        state = query_client
+            .get_production_order(Request::new(request))
+            .await?
+            .into_inner()
+            .state;
+        if state != State::Completing as i32 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+
+    Ok(state)
+}
+
+#[tokio::test]
+async fn it_updates_routing() -> Result<(), Box<dyn std::error::Error>> {
+    let mut command_client = RoutingCommandServiceClient::connect(PATH).await?;
+    let mut query_client = RoutingQueryServiceClient::connect(PATH).await?;
+    let mut operations_client = OperationsClient::connect(PATH).await?;
+
+    create_item(&mut operations_client, "routing-frame").await?;
+
+    let created = update(
+        &mut command_client,
+        &mut operations_client,
+        "routing-frame",
+        vec![operation("weld", 20)],
+        String::new(),
+    )
+    .await?;
+    assert_eq!(RoutingState::Active as i32, created.state);
+    assert_eq!("items/routing-frame/routing", created.name);
+
+    let status = update(
+        &mut command_client,
+        &mut operations_client,
+        "routing-frame",
+        vec![operation("paint", 10)],
+        String::from("stale"),
+    )
+    .await
+    .err()
+    .ok_or("a stale etag was accepted")?
+    .downcast::<tonic::Status>()
+    .map_err(|_| "not a status")?;
+    assert_eq!(Code::Aborted, status.code());
+
+    update(
+        &mut command_client,
+        &mut operations_client,
+        "routing-frame",
+        vec![operation("weld", 20), operation("paint", 10)],
+        created.etag,
+    )
+    .await?;
+
+    let request = GetRoutingRequest {
+        name: String::from("items/routing-frame/routing"),
+    };
+    let routing = query_client
+        .get_routing(Request::new(request))
+        .await?
+        .into_inner();
+    let work_centers: Vec<_> = routing
+        .operations
+        .iter()
+        .map(|o| o.work_center.as_str())
+        .collect();
+    assert_eq!(vec!["weld", "paint"], work_centers);
+
+    drop(command_client);
+    drop(query_client);
+    drop(operations_client);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_completes_production_order_with_its_operations(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut routing_client = RoutingCommandServiceClient::connect(PATH).await?;
+    let mut command_client = ProductionOrderCommandServiceClient::connect(PATH).await?;
+    let mut query_client = ProductionOrderQueryServiceClient::connect(PATH).await?;
+    let mut operations_client = OperationsClient::connect(PATH).await?;
+
+    create_item(&mut operations_client, "routing-bike").await?;
+    update(
+        &mut routing_client,
+        &mut operations_client,
+        "routing-bike",
+        vec![operation("assembly", 15), operation("inspection", 5)],
+        String::new(),
+    )
+    .await?;
+
+    let request = CreateProductionOrderRequest {
+        production_order_id: String::from("routing-po"),
+        production_order: Some(ProductionOrder {
+            item: String::from("items/routing-bike"),
+            quantity: String::from("2"),
+            ..Default::default()
+        }),
+    };
+    let operation = command_client
+        .create_production_order(Request::new(request))
+        .await?
+        .into_inner();
+    let operation = wait(&mut operations_client, operation).await?;
+    let Some(operation::Result::Response(response)) = operation.result else {
+        return Err("operation has no response".into());
+    };
+    let production_order = response.to_msg::<ProductionOrder>()?;
+    assert!(production_order.operations.is_empty());
+
+    let mut production_order = release(
+        &mut command_client,
+        &mut query_client,
+        &mut operations_client,
+        production_order.etag,
+    )
+    .await?;
+    let names: Vec<_> = production_order
+        .operations
+        .iter()
+        .map(|o| o.name.as_str())
+        .collect();
+    assert_eq!(
+        vec![
+            "productionOrders/routing-po/operations/1",
+            "productionOrders/routing-po/operations/2"
+        ],
+        names
+    );
+
+    for name in ["routing-po/operations/1", "routing-po/operations/2"] {
+        let request = StartProductionOrderOperationRequest {
+            name: name.to_string(),
+            etag: production_order.etag,
+        };
+        production_order = command_client
+            .start_production_order_operation(Request::new(request))
+            .await?
+            .into_inner();
+
+        let request = ReportProductionOrderOperationRequest {
+            name: name.to_string(),
+            quantity: String::from("2"),
+            etag: production_order.etag,
+        };
+        production_order = command_client
+            .report_production_order_operation(Request::new(request))
+            .await?
+            .into_inner();
+
+        let request = CompleteProductionOrderOperationRequest {
+            name: name.to_string(),
+            etag: production_order.etag,
+        };
+        production_order = command_client
+            .complete_production_order_operation(Request::new(request))
+            .await?
+            .into_inner();
+    }
+    assert_eq!(State::Completing as i32, production_order.state);
+    assert!(production_order
+        .operations
+        .iter()
+        .all(|o| o.state == OperationState::Completed as i32));
+
+    // The worker completes the production order like an explicit completion would.
+    let state = settled_state(&mut query_client).await?;
+    assert_eq!(State::Completed as i32, state);
+
+    drop(routing_client);
+    drop(command_client);
+    drop(query_client);
+    drop(operations_client);
+
+    Ok(())
+}
@@ -0,0 +1,377 @@
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+use manufacturing::{
+    grpc::proto::google::longrunning::{operations_client::OperationsClient, WaitOperationRequest},
+    proto::location::{
+        location_command_service_client::LocationCommandServiceClient,
+        location_query_service_client::LocationQueryServiceClient, CreateLocationRequest,
+        GetLocationRequest, Location, UpdateLocationRequest,
+    },
+    proto::stock_movement::{
+        stock_movement::Kind,
+        stock_movement_command_service_client::StockMovementCommandServiceClient,
+        stock_movement_query_service_client::StockMovementQueryServiceClient,
+        CreateStockMovementRequest, GetStockBalanceRequest, ListStockBalancesRequest,
+        ListStockMovementsRequest, StockMovement,
+    },
+    proto::{
+        item_service_client::ItemServiceClient, CreateItemRequest, DeleteItemRequest,
+        GetItemRequest, Item,
+    },
+};
+use prost_types::FieldMask;
+use tonic::{transport::Channel, Code, Request};
+
+const PATH: &str = "http://localhost:8081";
+
+async fn create_item(id: &str, base_uom: Option<&str>) -> Result<Item, Box<dyn std::error::Error>> {
+    let mut item_client = ItemServiceClient::connect(PATH).await?;
+    let mut operations_client = OperationsClient::connect(PATH).await?;
+
+    let request = CreateItemRequest {
+        item_id: Some(id.to_string()),
+        item: Item {
+            base_uom: base_uom.map(ToString::to_string),
+            ..Default::default()
+        }
+        .into(),
+    };
+    let operation = item_client
+        .create_item(Request::new(request))
+        .await?
+        .into_inner();
+
+    let request = WaitOperationRequest {
+        name: operation.name,
+        timeout: None,
+    };
+    operations_client
+        .wait_operation(Request::new(request))
+        .await?;
+    drop(operations_client);
+
+    let request = GetItemRequest {
+        name: id.to_string(),
+    };
+    let item = item_client
+        .get_item(Request::new(request))
+        .await?
+        .into_inner();
+    drop(item_client);
+
+    Ok(item)
+}
+
+async fn create_location(
+    id: &str,
+    allow_negative_stock: bool,
+) -> Result<Location, Box<dyn std::error::Error>> {
+    let mut command_client = LocationCommandServiceClient::connect(PATH).await?;
+
+    let request = CreateLocationRequest {
+        location: Some(Location {
+            display_name: id.to_uppercase(),
+            allow_negative_stock,
+            ..Default::default()
+        }),
+        location_id: id.to_string(),
+    };
+    let location = command_client
+        .create_location(Request::new(request))
+        .await?
+        .into_inner();
+    drop(command_client);
+
+    Ok(location)
+}
+
+fn movement(kind: Kind, item: &str, quantity: &str, source: &str, target: &str) -> StockMovement {
+    StockMovement {
+        kind: kind.into(),
+        item: format!("items/{item}"),
+        quantity: quantity.to_string(),
+        source_location: source.to_string(),
+        target_location: target.to_string(),
+        ..Default::default()
+    }
+}
+
+async fn post(
+    command_client: &mut StockMovementCommandServiceClient<Channel>,
+    stock_movement: StockMovement,
+) -> Result<StockMovement, tonic::Status> {
+    let request = CreateStockMovementRequest {
+        stock_movement: Some(stock_movement),
+        stock_movement_id: String::new(),
+    };
+
+    command_client
+        .create_stock_movement(Request::new(request))
+        .await
+        .map(tonic::Response::into_inner)
+}
+
+async fn on_hand(
+    query_client: &mut StockMovementQueryServiceClient<Channel>,
+    item: &str,
+    location: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let request = GetStockBalanceRequest {
+        name: format!("items/{item}/stockBalances/{location}"),
+    };
+    let balance = query_client
+        .get_stock_balance(Request::new(request))
+        .await?
+        .into_inner();
+
+    Ok(balance.quantity)
+}
+
+#[tokio::test]
+async fn it_creates_and_updates_locations() -> Result<(), Box<dyn std::error::Error>> {
+    let location = create_location("sm-a-dock", false).await?;
+    assert_eq!("locations/sm-a-dock", location.name);
+    assert_eq!("SM-A-DOCK", location.display_name);
+    assert!(!location.allow_negative_stock);
+
+    let mut command_client = LocationCommandServiceClient::connect(PATH).await?;
+    let request = UpdateLocationRequest {
+        location: Some(Location {
+            name: location.name.clone(),
+            allow_negative_stock: true,
+            etag: location.etag.clone(),
+            ..Default::default()
+        }),
+        update_mask: Some(FieldMask {
+            paths: vec![String::from("allow_negative_stock")],
+        }),
+    };
+    let updated = command_client
+        .update_location(Request::new(request))
+        .await?
+        .into_inner();
+    assert!(updated.allow_negative_stock);
+    assert_eq!("SM-A-DOCK", updated.display_name);
+    assert_ne!(location.etag, updated.etag);
+
+    let request = UpdateLocationRequest {
+        location: Some(Location {
+            name: location.name.clone(),
+            display_name: String::from("Dock"),
+            etag: location.etag,
+            ..Default::default()
+        }),
+        update_mask: None,
+    };
+    let status = command_client
+        .update_location(Request::new(request))
+        .await
+        .err()
+        .ok_or("location was updated with a stale etag")?;
+    drop(command_client);
+    assert_eq!(Code::Aborted, status.code());
+
+    let mut query_client = LocationQueryServiceClient::connect(PATH).await?;
+    let request = GetLocationRequest {
+        name: String::from("sm-a-dock"),
+    };
+    let location = query_client
+        .get_location(Request::new(request))
+        .await?
+        .into_inner();
+    drop(query_client);
+    assert_eq!(updated, location);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_posts_movements_and_tracks_balances() -> Result<(), Box<dyn std::error::Error>> {
+    create_item("sm-b-bolt", None).await?;
+    create_location("sm-b-main", false).await?;
+    create_location("sm-b-line", false).await?;
+
+    let mut command_client = StockMovementCommandServiceClient::connect(PATH).await?;
+    let mut query_client = StockMovementQueryServiceClient::connect(PATH).await?;
+
+    let receipt = post(
+        &mut command_client,
+        movement(Kind::Receipt, "sm-b-bolt", "10", "", "locations/sm-b-main"),
+    )
+    .await?;
+    assert!(receipt.name.starts_with("stockMovements/"));
+    assert_eq!("items/sm-b-bolt", receipt.item);
+    assert_eq!("locations/sm-b-main", receipt.target_location);
+
+    post(
+        &mut command_client,
+        movement(Kind::Transfer, "sm-b-bolt", "4", "sm-b-main", "sm-b-line"),
+    )
+    .await?;
+    post(
+        &mut command_client,
+        movement(Kind::Issue, "sm-b-bolt", "1.5", "sm-b-line", ""),
+    )
+    .await?;
+
+    assert_eq!(
+        "6",
+        on_hand(&mut query_client, "sm-b-bolt", "sm-b-main").await?
+    );
+    assert_eq!(
+        "2.5",
+        on_hand(&mut query_client, "sm-b-bolt", "sm-b-line").await?
+    );
+
+    let status = post(
+        &mut command_client,
+        movement(Kind::Issue, "sm-b-bolt", "3", "sm-b-line", ""),
+    )
+    .await
+    .err()
+    .ok_or("more than on hand was issued")?;
+    assert_eq!(Code::FailedPrecondition, status.code());
+    assert_eq!(
+        "2.5",
+        on_hand(&mut query_client, "sm-b-bolt", "sm-b-line").await?
+    );
+
+    let status = post(
+        &mut command_client,
+        movement(Kind::Transfer, "sm-b-bolt", "1", "sm-b-main", "sm-b-main"),
+    )
+    .await
+    .err()
+    .ok_or("transfer to the same location was posted")?;
+    assert_eq!(Code::InvalidArgument, status.code());
+
+    post(
+        &mut command_client,
+        movement(Kind::Adjustment, "sm-b-bolt", "-0.5", "", "sm-b-line"),
+    )
+    .await?;
+    drop(command_client);
+    assert_eq!(
+        "2",
+        on_hand(&mut query_client, "sm-b-bolt", "sm-b-line").await?
+    );
+
+    let request = ListStockMovementsRequest {
+        filter: String::from(r#"item = "sm-b-bolt""#),
+        ..Default::default()
+    };
+    let response = query_client
+        .list_stock_movements(Request::new(request))
+        .await?
+        .into_inner();
+    assert_eq!(4, response.total_size);
+
+    let request = ListStockBalancesRequest {
+        filter: String::from(r#"item = "sm-b-bolt""#),
+        order_by: String::from("location"),
+        ..Default::default()
+    };
+    let response = query_client
+        .list_stock_balances(Request::new(request))
+        .await?
+        .into_inner();
+    drop(query_client);
+    let balances = response
+        .stock_balances
+        .iter()
+        .map(|balance| (balance.location.as_str(), balance.quantity.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![("locations/sm-b-line", "2"), ("locations/sm-b-main", "6")],
+        balances
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_allows_negative_stock_where_configured() -> Result<(), Box<dyn std::error::Error>> {
+    create_item("sm-c-flour", Some("kg")).await?;
+    create_location("sm-c-bakery", true).await?;
+
+    let mut command_client = StockMovementCommandServiceClient::connect(PATH).await?;
+    let mut query_client = StockMovementQueryServiceClient::connect(PATH).await?;
+
+    let status = on_hand(&mut query_client, "sm-c-flour", "sm-c-bakery").await?;
+    assert_eq!("0", status);
+
+    post(
+        &mut command_client,
+        movement(Kind::Issue, "sm-c-flour", "2", "sm-c-bakery", ""),
+    )
+    .await?;
+
+    let receipt = post(
+        &mut command_client,
+        StockMovement {
+            unit_of_measure: String::from("unitsOfMeasure/g"),
+            ..movement(Kind::Receipt, "sm-c-flour", "500", "", "sm-c-bakery")
+        },
+    )
+    .await?;
+    assert_eq!("0.5", receipt.quantity);
+
+    let status = post(
+        &mut command_client,
+        StockMovement {
+            unit_of_measure: String::from("m"),
+            ..movement(Kind::Receipt, "sm-c-flour", "1", "", "sm-c-bakery")
+        },
+    )
+    .await
+    .err()
+    .ok_or("quantity in an incompatible unit was posted")?;
+    drop(command_client);
+    assert_eq!(Code::FailedPrecondition, status.code());
+
+    assert_eq!(
+        "-1.5",
+        on_hand(&mut query_client, "sm-c-flour", "sm-c-bakery").await?
+    );
+    drop(query_client);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_keeps_items_with_movements() -> Result<(), Box<dyn std::error::Error>> {
+    let item = create_item("sm-d-nut", None).await?;
+    create_location("sm-d-store", false).await?;
+
+    let mut command_client = StockMovementCommandServiceClient::connect(PATH).await?;
+    post(
+        &mut command_client,
+        movement(Kind::Receipt, "sm-d-nut", "1", "", "sm-d-store"),
+    )
+    .await?;
+
+    let status = post(
+        &mut command_client,
+        movement(Kind::Receipt, "sm-d-missing", "1", "", "sm-d-store"),
+    )
+    .await
+    .err()
+    .ok_or("movement of an unknown item was posted")?;
+    drop(command_client);
+    assert_eq!(Code::NotFound, status.code());
+
+    let mut item_client = ItemServiceClient::connect(PATH).await?;
+    let request = DeleteItemRequest {
+        name: String::from("sm-d-nut"),
+        etag: item.etag.ok_or("item has no etag")?,
+    };
+    let status = item_client
+        .delete_item(Request::new(request))
+        .await
+        .err()
+        .ok_or("item with stock movements was deleted")?;
+    drop(item_client);
+    assert_eq!(Code::FailedPrecondition, status.code());
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+use manufacturing::{
+    grpc::{
+        proto::google::longrunning::{operations_client::OperationsClient, WaitOperationRequest},
+        tenant::ORGANIZATION_HEADER,
+    },
+    proto::{
+        item_service_client::ItemServiceClient, CreateItemMetadata, CreateItemRequest,
+        GetItemRequest, Item, ListItemsRequest,
+    },
+};
+use tonic::{metadata::MetadataValue, Code, Request};
+
+/// `message` as sent by a caller of `organization`.
+fn request<T>(message: T, organization: Option<&'static str>) -> Request<T> {
+    let mut request = Request::new(message);
+    if let Some(organization) = organization {
+        request.metadata_mut().insert(
+            ORGANIZATION_HEADER,
+            MetadataValue::from_static(organization),
+        );
+    }
+    request
+}
+
+#[tokio::test]
+async fn it_serves_organizations_from_their_own_database() -> Result<(), Box<dyn std::error::Error>>
+{
+    let path = "http://localhost:8081";
+    let mut item_client = ItemServiceClient::connect(path).await?;
+    let mut operations_client = OperationsClient::connect(path).await?;
+
+    let message = CreateItemRequest {
+        item_id: Some(String::from("t-anvil")),
+        item: Some(Item {
+            display_name: Some(String::from("Anvil")),
+            ..Item::default()
+        }),
+    };
+    let operation = item_client
+        .create_item(request(message, Some("acme")))
+        .await?
+        .into_inner();
+    let message = WaitOperationRequest {
+        name: operation.name,
+        timeout: None,
+    };
+    let operation = operations_client
+        .wait_operation(request(message, Some("acme")))
+        .await?
+        .into_inner();
+    drop(operations_client);
+    assert!(operation.done);
+
+    // Operations name their target within the organization, like the names the API returns.
+    let metadata = operation
+        .metadata
+        .ok_or("create operation has no metadata")?
+        .to_msg::<CreateItemMetadata>()?;
+    assert_eq!("organizations/acme/items/t-anvil", metadata.target);
+
+    let get = |name: &str| GetItemRequest {
+        name: String::from(name),
+    };
+
+    let item = item_client
+        .get_item(request(get("items/t-anvil"), Some("acme")))
+        .await?
+        .into_inner();
+    assert_eq!(Some(String::from("Anvil")), item.display_name);
+    assert_eq!("organizations/acme/items/t-anvil", item.name);
+
+    // The name the API returns leads back to the item.
+    let item = item_client
+        .get_item(request(get(&item.name), Some("acme")))
+        .await?
+        .into_inner();
+    assert_eq!(Some(String::from("Anvil")), item.display_name);
+
+    // Another organization, or a caller outside of any, cannot see the item.
+    for organization in [Some("globex"), None] {
+        let status = item_client
+            .get_item(request(get("items/t-anvil"), organization))
+            .await
+            .err()
+            .ok_or("item of another organization was found")?;
+        assert_eq!(Code::NotFound, status.code());
+    }
+
+    // Naming another organization does not reach into its database.
+    let status = item_client
+        .get_item(request(
+            get("organizations/acme/items/t-anvil"),
+            Some("globex"),
+        ))
+        .await
+        .err()
+        .ok_or("item of another organization was found by name")?;
+    assert_ne!(Code::Ok, status.code());
+
+    let items = item_client
+        .list_items(request(ListItemsRequest::default(), Some("globex")))
+        .await?
+        .into_inner()
+        .items;
+    assert!(items
+        .iter()
+        .all(|item| item.name.starts_with("organizations/globex/items/")));
+    assert!(items
+        .iter()
+        .all(|item| item.name != "organizations/globex/items/t-anvil"));
+
+    let items = item_client
+        .list_items(request(ListItemsRequest::default(), Some("acme")))
+        .await?
+        .into_inner()
+        .items;
+    let names: Vec<_> = items.iter().map(|item| item.name.as_str()).collect();
+    assert_eq!(vec!["organizations/acme/items/t-anvil"], names);
+
+    let status = item_client
+        .get_item(request(get("items/t-anvil"), Some("Acme Inc")))
+        .await
+        .err()
+        .ok_or("invalid organization was accepted")?;
+    assert_eq!(Code::InvalidArgument, status.code());
+
+    // Organizations that are not provisioned get no database.
+    let status = item_client
+        .get_item(request(get("items/t-anvil"), Some("initech")))
+        .await
+        .err()
+        .ok_or("organization that is not provisioned was accepted")?;
+    drop(item_client);
+    assert_eq!(Code::PermissionDenied, status.code());
+
+    Ok(())
+}
@@ -0,0 +1,402 @@
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+use manufacturing::{
+    grpc::proto::google::longrunning::{operations_client::OperationsClient, WaitOperationRequest},
+    proto::location::{
+        location_command_service_client::LocationCommandServiceClient, CreateLocationRequest,
+        Location,
+    },
+    proto::production_order::{
+        production_order_command_service_client::ProductionOrderCommandServiceClient,
+        production_order_query_service_client::ProductionOrderQueryServiceClient,
+        CreateProductionOrderRequest, GetProductionOrderRequest, ProductionOrder,
+        ReleaseProductionOrderRequest,
+    },
+    proto::stock_movement::{
+        stock_movement::Kind,
+        stock_movement_command_service_client::StockMovementCommandServiceClient,
+        CreateStockMovementRequest, StockMovement,
+    },
+    proto::traceability::{
+        trace_genealogy_request::Direction,
+        traceability_command_service_client::TraceabilityCommandServiceClient,
+        traceability_query_service_client::TraceabilityQueryServiceClient, CreateLotRequest,
+        CreateSerialRequest, GetSerialRequest, ListLotsRequest, Lot, TraceGenealogyRequest,
+    },
+    proto::{
+        item::Tracking, item_service_client::ItemServiceClient, CreateItemRequest, GetItemRequest,
+        Item,
+    },
+};
+use tonic::{transport::Channel, Code, Request};
+
+const PATH: &str = "http://localhost:8081";
+
+async fn create_item(id: &str, tracking: Tracking) -> Result<Item, Box<dyn std::error::Error>> {
+    let mut item_client = ItemServiceClient::connect(PATH).await?;
+    let mut operations_client = OperationsClient::connect(PATH).await?;
+
+    let request = CreateItemRequest {
+        item_id: Some(id.to_string()),
+        item: Item {
+            tracking: Some(tracking as i32),
+            ..Default::default()
+        }
+        .into(),
+    };
+    let operation = item_client
+        .create_item(Request::new(request))
+        .await?
+        .into_inner();
+
+    let request = WaitOperationRequest {
+        name: operation.name,
+        timeout: None,
+    };
+    operations_client
+        .wait_operation(Request::new(request))
+        .await?;
+    drop(operations_client);
+
+    let request = GetItemRequest {
+        name: id.to_string(),
+    };
+    let item = item_client
+        .get_item(Request::new(request))
+        .await?
+        .into_inner();
+    drop(item_client);
+
+    Ok(item)
+}
+
+async fn create_location(id: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut command_client = LocationCommandServiceClient::connect(PATH).await?;
+
+    let request = CreateLocationRequest {
+        location: Some(Location::default()),
+        location_id: id.to_string(),
+    };
+    command_client
+        .create_location(Request::new(request))
+        .await?;
+    drop(command_client);
+
+    Ok(())
+}
+
+async fn create_lot(item: &str, lot: &str) -> Result<Lot, tonic::Status> {
+    let mut command_client = TraceabilityCommandServiceClient::connect(PATH)
+        .await
+        .map_err(|e| tonic::Status::unavailable(e.to_string()))?;
+
+    let request = CreateLotRequest {
+        parent: format!("items/{item}"),
+        lot: Some(Lot::default()),
+        lot_id: lot.to_string(),
+    };
+    let lot = command_client
+        .create_lot(Request::new(request))
+        .await
+        .map(tonic::Response::into_inner);
+    drop(command_client);
+
+    lot
+}
+
+async fn create_serials(item: &str, serials: &[&str]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut command_client = TraceabilityCommandServiceClient::connect(PATH).await?;
+
+    for serial in serials {
+        let request = CreateSerialRequest {
+            parent: format!("items/{item}"),
+            serial: None,
+            serial_id: (*serial).to_string(),
+        };
+        command_client.create_serial(Request::new(request)).await?;
+    }
+    drop(command_client);
+
+    Ok(())
+}
+
+async fn release_production_order(id: &str, item: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut command_client = ProductionOrderCommandServiceClient::connect(PATH).await?;
+    let mut query_client = ProductionOrderQueryServiceClient::connect(PATH).await?;
+    let mut operations_client = OperationsClient::connect(PATH).await?;
+
+    let request = CreateProductionOrderRequest {
+        production_order_id: id.to_string(),
+        production_order: Some(ProductionOrder {
+            item: format!("items/{item}"),
+            quantity: String::from("2"),
+            ..Default::default()
+        }),
+    };
+    let operation = command_client
+        .create_production_order(Request::new(request))
+        .await?
+        .into_inner();
+    let request = WaitOperationRequest {
+        name: operation.name,
+        timeout: None,
+    };
+    operations_client
+        .wait_operation(Request::new(request))
+        .await?;
+
+    let request = GetProductionOrderRequest {
+        name: id.to_string(),
+    };
+    let production_order = query_client
+        .get_production_order(Request::new(request))
+        .await?
+        .into_inner();
+    drop(query_client);
+
+    let request = ReleaseProductionOrderRequest {
+        name: id.to_string(),
+        etag: production_order.etag,
+    };
+    let operation = command_client
+        .release_production_order(Request::new(request))
+        .await?
+        .into_inner();
+    drop(command_client);
+    let request = WaitOperationRequest {
+        name: operation.name,
+        timeout: None,
+    };
+    operations_client
+        .wait_operation(Request::new(request))
+        .await?;
+    drop(operations_client);
+
+    Ok(())
+}
+
+async fn post(
+    command_client: &mut StockMovementCommandServiceClient<Channel>,
+    stock_movement: StockMovement,
+) -> Result<StockMovement, tonic::Status> {
+    let request = CreateStockMovementRequest {
+        stock_movement: Some(stock_movement),
+        stock_movement_id: String::new(),
+    };
+
+    command_client
+        .create_stock_movement(Request::new(request))
+        .await
+        .map(tonic::Response::into_inner)
+}
+
+/// The names and depths of the lots and serials reached from `name`, sorted by name.
+async fn trace(
+    name: &str,
+    direction: Direction,
+) -> Result<Vec<(String, u32)>, Box<dyn std::error::Error>> {
+    let mut query_client = TraceabilityQueryServiceClient::connect(PATH).await?;
+
+    let request = TraceGenealogyRequest {
+        name: name.to_string(),
+        direction: direction.into(),
+    };
+    let response = query_client
+        .trace_genealogy(Request::new(request))
+        .await?
+        .into_inner();
+    drop(query_client);
+
+    let mut nodes = response
+        .nodes
+        .into_iter()
+        .inspect(|node| assert!(node.production_order.starts_with("productionOrders/")))
+        .map(|node| (node.name, node.depth))
+        .collect::<Vec<_>>();
+    nodes.sort_unstable();
+
+    Ok(nodes)
+}
+
+fn receipt(item: &str, quantity: &str, location: &str) -> StockMovement {
+    StockMovement {
+        kind: Kind::Receipt.into(),
+        item: format!("items/{item}"),
+        quantity: quantity.to_string(),
+        target_location: location.to_string(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn it_requires_lots_and_serials_of_tracked_items() -> Result<(), Box<dyn std::error::Error>> {
+    create_item("tr-a-steel", Tracking::Lot).await?;
+    let frame = create_item("tr-a-frame", Tracking::Serial).await?;
+    assert_eq!(Some(Tracking::Serial as i32), frame.tracking);
+    create_location("tr-a-store").await?;
+
+    let lot = create_lot("tr-a-steel", "heat-1").await?;
+    assert_eq!("items/tr-a-steel/lots/heat-1", lot.name);
+    let status = create_lot("tr-a-frame", "heat-1")
+        .await
+        .err()
+        .ok_or("lot of a serial-tracked item was created")?;
+    assert_eq!(Code::InvalidArgument, status.code());
+    create_serials("tr-a-frame", &["sn-1"]).await?;
+
+    let mut command_client = StockMovementCommandServiceClient::connect(PATH).await?;
+    let status = post(
+        &mut command_client,
+        receipt("tr-a-steel", "10", "tr-a-store"),
+    )
+    .await
+    .err()
+    .ok_or("lot-tracked item was received without a lot")?;
+    assert_eq!(Code::InvalidArgument, status.code());
+
+    let movement = post(
+        &mut command_client,
+        StockMovement {
+            lot: String::from("heat-1"),
+            ..receipt("tr-a-steel", "10", "tr-a-store")
+        },
+    )
+    .await?;
+    assert_eq!("items/tr-a-steel/lots/heat-1", movement.lot);
+
+    let status = post(
+        &mut command_client,
+        StockMovement {
+            serial: String::from("sn-1"),
+            ..receipt("tr-a-frame", "2", "tr-a-store")
+        },
+    )
+    .await
+    .err()
+    .ok_or("two units were received under one serial")?;
+    assert_eq!(Code::InvalidArgument, status.code());
+
+    post(
+        &mut command_client,
+        StockMovement {
+            serial: String::from("items/tr-a-frame/serials/sn-1"),
+            ..receipt("tr-a-frame", "1", "tr-a-store")
+        },
+    )
+    .await?;
+    let status = post(
+        &mut command_client,
+        StockMovement {
+            serial: String::from("sn-1"),
+            ..receipt("tr-a-frame", "1", "tr-a-store")
+        },
+    )
+    .await
+    .err()
+    .ok_or("a serial in stock was received again")?;
+    drop(command_client);
+    assert_eq!(Code::FailedPrecondition, status.code());
+
+    let mut query_client = TraceabilityQueryServiceClient::connect(PATH).await?;
+    let request = GetSerialRequest {
+        name: String::from("items/tr-a-frame/serials/sn-1"),
+    };
+    let serial = query_client
+        .get_serial(Request::new(request))
+        .await?
+        .into_inner();
+    assert_eq!("locations/tr-a-store", serial.location);
+
+    let request = ListLotsRequest {
+        parent: String::from("items/tr-a-steel"),
+        ..Default::default()
+    };
+    let response = query_client
+        .list_lots(Request::new(request))
+        .await?
+        .into_inner();
+    drop(query_client);
+    assert_eq!(1, response.total_size);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_traces_genealogy_through_production_orders() -> Result<(), Box<dyn std::error::Error>> {
+    create_item("tr-b-steel", Tracking::Lot).await?;
+    create_item("tr-b-frame", Tracking::Serial).await?;
+    create_location("tr-b-line").await?;
+    create_lot("tr-b-steel", "heat-1").await?;
+    create_serials("tr-b-frame", &["sn-1", "sn-2"]).await?;
+
+    let mut command_client = StockMovementCommandServiceClient::connect(PATH).await?;
+    post(
+        &mut command_client,
+        StockMovement {
+            lot: String::from("heat-1"),
+            ..receipt("tr-b-steel", "10", "tr-b-line")
+        },
+    )
+    .await?;
+
+    let issue = StockMovement {
+        kind: Kind::Issue.into(),
+        item: String::from("items/tr-b-steel"),
+        quantity: String::from("4"),
+        source_location: String::from("tr-b-line"),
+        lot: String::from("heat-1"),
+        production_order: String::from("productionOrders/tr-b-po"),
+        ..Default::default()
+    };
+    let status = post(&mut command_client, issue.clone())
+        .await
+        .err()
+        .ok_or("stock was issued to an unknown production order")?;
+    assert_eq!(Code::NotFound, status.code());
+
+    release_production_order("tr-b-po", "tr-b-frame").await?;
+    post(&mut command_client, issue).await?;
+
+    for serial in ["sn-1", "sn-2"] {
+        post(
+            &mut command_client,
+            StockMovement {
+                serial: serial.to_string(),
+                production_order: String::from("tr-b-po"),
+                ..receipt("tr-b-frame", "1", "tr-b-line")
+            },
+        )
+        .await?;
+    }
+
+    let status = post(
+        &mut command_client,
+        StockMovement {
+            lot: String::from("heat-1"),
+            production_order: String::from("tr-b-po"),
+            ..receipt("tr-b-steel", "1", "tr-b-line")
+        },
+    )
+    .await
+    .err()
+    .ok_or("a component was received from a production order")?;
+    drop(command_client);
+    assert_eq!(Code::InvalidArgument, status.code());
+
+    let nodes = trace("items/tr-b-steel/lots/heat-1", Direction::Forward).await?;
+    assert_eq!(
+        vec![
+            (String::from("items/tr-b-frame/serials/sn-1"), 1),
+            (String::from("items/tr-b-frame/serials/sn-2"), 1),
+        ],
+        nodes
+    );
+
+    let nodes = trace("items/tr-b-frame/serials/sn-2", Direction::Backward).await?;
+    assert_eq!(
+        vec![(String::from("items/tr-b-steel/lots/heat-1"), 1)],
+        nodes
+    );
+
+    Ok(())
+}
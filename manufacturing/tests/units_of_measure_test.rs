@@ -0,0 +1,192 @@
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+use manufacturing::{
+    grpc::proto::google::longrunning::{operations_client::OperationsClient, WaitOperationRequest},
+    proto::unit_of_measure::{
+        unit_of_measure::Dimension,
+        unit_of_measure_query_service_client::UnitOfMeasureQueryServiceClient,
+        ConvertQuantityRequest, GetUnitOfMeasureRequest, ListUnitsOfMeasureRequest,
+    },
+    proto::{
+        item_service_client::ItemServiceClient, CreateItemRequest, GetItemRequest, Item,
+        ListItemsRequest, UpdateItemRequest,
+    },
+};
+use prost_types::FieldMask;
+use tonic::{Code, Request};
+
+const PATH: &str = "http://localhost:8081";
+
+#[tokio::test]
+async fn it_converts_quantities_between_units() -> Result<(), Box<dyn std::error::Error>> {
+    let mut unit_of_measure_client = UnitOfMeasureQueryServiceClient::connect(PATH).await?;
+
+    let request = GetUnitOfMeasureRequest {
+        name: String::from("unitsOfMeasure/kg"),
+    };
+    let request = Request::new(request);
+
+    let kilogram = unit_of_measure_client
+        .get_unit_of_measure(request)
+        .await?
+        .into_inner();
+    assert_eq!("unitsOfMeasure/kg", kilogram.name);
+    assert_eq!(Dimension::Mass as i32, kilogram.dimension);
+    assert_eq!("1", kilogram.factor);
+
+    let request = ListUnitsOfMeasureRequest {
+        filter: String::from("dimension = MASS"),
+        order_by: String::from("name"),
+        ..Default::default()
+    };
+    let request = Request::new(request);
+
+    let response = unit_of_measure_client
+        .list_units_of_measure(request)
+        .await?
+        .into_inner();
+    assert_eq!(6, response.total_size);
+    assert!(response
+        .units_of_measure
+        .iter()
+        .all(|unit| unit.dimension == Dimension::Mass as i32));
+
+    for (quantity, from, to, expected) in [
+        ("2.5", "kg", "g", "2500"),
+        ("1", "lb", "unitsOfMeasure/kg", "0.45359237"),
+        ("1", "g", "oz", "0.0352739619495804129156758082"),
+        ("90", "min", "h", "1.5"),
+    ] {
+        let request = ConvertQuantityRequest {
+            quantity: quantity.to_string(),
+            unit_of_measure: from.to_string(),
+            target_unit_of_measure: to.to_string(),
+        };
+        let request = Request::new(request);
+
+        let response = unit_of_measure_client
+            .convert_quantity(request)
+            .await?
+            .into_inner();
+        assert_eq!(expected, response.quantity);
+    }
+
+    let request = ConvertQuantityRequest {
+        quantity: String::from("1"),
+        unit_of_measure: String::from("kg"),
+        target_unit_of_measure: String::from("m"),
+    };
+    let request = Request::new(request);
+
+    let status = unit_of_measure_client
+        .convert_quantity(request)
+        .await
+        .err()
+        .ok_or("incompatible units were converted")?;
+    assert_eq!(Code::FailedPrecondition, status.code());
+
+    let request = ConvertQuantityRequest {
+        quantity: String::from("one"),
+        unit_of_measure: String::from("kg"),
+        target_unit_of_measure: String::from("g"),
+    };
+    let request = Request::new(request);
+
+    let status = unit_of_measure_client
+        .convert_quantity(request)
+        .await
+        .err()
+        .ok_or("invalid quantity was converted")?;
+    drop(unit_of_measure_client);
+    assert_eq!(Code::InvalidArgument, status.code());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_counts_items_in_their_base_unit() -> Result<(), Box<dyn std::error::Error>> {
+    let mut item_client = ItemServiceClient::connect(PATH).await?;
+    let mut operations_client = OperationsClient::connect(PATH).await?;
+
+    for (id, base_uom) in [("u-flour", Some("unitsOfMeasure/kg")), ("u-bolt", None)] {
+        let request = CreateItemRequest {
+            item_id: Some(id.to_string()),
+            item: Item {
+                base_uom: base_uom.map(ToString::to_string),
+                ..Default::default()
+            }
+            .into(),
+        };
+        let request = Request::new(request);
+
+        let operation = item_client.create_item(request).await?.into_inner();
+
+        let request = WaitOperationRequest {
+            name: operation.name,
+            timeout: None,
+        };
+        let request = Request::new(request);
+
+        operations_client.wait_operation(request).await?;
+    }
+    drop(operations_client);
+
+    let request = GetItemRequest {
+        name: String::from("u-bolt"),
+    };
+    let request = Request::new(request);
+
+    let item = item_client.get_item(request).await?.into_inner();
+    assert_eq!(Some("unitsOfMeasure/pcs"), item.base_uom.as_deref());
+
+    let request = ListItemsRequest {
+        filter: Some(String::from(r#"base_uom = "kg" AND name = "u-*""#)),
+        ..Default::default()
+    };
+    let request = Request::new(request);
+
+    let response = item_client.list_items(request).await?.into_inner();
+    assert_eq!(1, response.total_size);
+    assert_eq!("items/u-flour", response.items[0].name);
+
+    let request = UpdateItemRequest {
+        item: Item {
+            name: String::from("u-flour"),
+            base_uom: Some(String::from("unitsOfMeasure/g")),
+            etag: response.items[0].etag.clone(),
+            ..Default::default()
+        }
+        .into(),
+        update_mask: Some(FieldMask {
+            paths: vec![String::from("base_uom")],
+        }),
+    };
+    let request = Request::new(request);
+
+    let status = item_client
+        .update_item(request)
+        .await
+        .err()
+        .ok_or("base unit was changed")?;
+    assert_eq!(Code::InvalidArgument, status.code());
+
+    let request = CreateItemRequest {
+        item_id: Some(String::from("u-rope")),
+        item: Item {
+            base_uom: Some(String::from("unitsOfMeasure/furlong")),
+            ..Default::default()
+        }
+        .into(),
+    };
+    let request = Request::new(request);
+
+    let status = item_client
+        .create_item(request)
+        .await
+        .err()
+        .ok_or("unknown unit was accepted")?;
+    drop(item_client);
+    assert_eq!(Code::NotFound, status.code());
+
+    Ok(())
+}